@@ -8,19 +8,23 @@ use crate::commands::bench::agent_generator;
 use crate::commands::configure::handle_configure;
 use crate::commands::info::handle_info;
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
-use crate::commands::recipe::{handle_deeplink, handle_list, handle_open, handle_validate};
+use crate::commands::recipe::{
+    handle_deeplink, handle_list, handle_open, handle_test, handle_validate,
+};
 
 use crate::commands::schedule::{
-    handle_schedule_add, handle_schedule_cron_help, handle_schedule_list, handle_schedule_remove,
-    handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
-    handle_schedule_sessions,
+    handle_schedule_add, handle_schedule_cron_help, handle_schedule_history, handle_schedule_list,
+    handle_schedule_remove, handle_schedule_run_now, handle_schedule_services_status,
+    handle_schedule_services_stop, handle_schedule_sessions,
 };
 use crate::commands::session::{handle_session_list, handle_session_remove};
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
 use crate::session::{build_session, SessionBuilderConfig, SessionSettings};
+use goose::scheduler::{OverlapPolicy, RunPolicy};
+use goose::scheduler_triggers::{ChainCondition, JobTrigger};
 use goose::session::session_manager::SessionType;
-use goose::session::SessionManager;
+use goose::session::{Budget, SessionManager};
 use goose_bench::bench_config::BenchRunConfig;
 use goose_bench::runners::bench_runner::BenchRunner;
 use goose_bench::runners::eval_runner::EvalRunner;
@@ -68,6 +72,157 @@ jsonl' -> '20250325_200615')."
     pub path: Option<PathBuf>,
 }
 
+/// Spending limits for a session, on top of any global or recipe budget
+#[derive(Args, Debug, Clone, Default)]
+pub struct BudgetArgs {
+    #[arg(
+        long = "budget-usd",
+        value_name = "USD",
+        help = "Stop the session once its estimated cost reaches this many USD"
+    )]
+    pub max_cost_usd: Option<f64>,
+
+    #[arg(
+        long = "budget-tokens",
+        value_name = "NUMBER",
+        help = "Stop the session once it has used this many tokens"
+    )]
+    pub max_tokens: Option<i64>,
+}
+
+impl BudgetArgs {
+    fn budget(&self) -> Option<Budget> {
+        let budget = Budget {
+            max_cost_usd: self.max_cost_usd,
+            max_tokens: self.max_tokens,
+        };
+        (!budget.is_empty()).then_some(budget)
+    }
+}
+
+/// Events that run a scheduled job, in addition to or instead of its cron expression
+#[derive(Args, Debug, Clone, Default)]
+pub struct TriggerArgs {
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Run the job when files in this directory change",
+        long_help = "Run the job when files in this directory or its subdirectories are created, modified or removed. Hidden and git-ignored files are skipped. The changed paths are passed as the 'changed_files' recipe parameter."
+    )]
+    pub watch: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "GLOB",
+        requires = "watch",
+        default_value = "**/*",
+        help = "Only watch files matching this glob, relative to the watched directory"
+    )]
+    pub watch_pattern: String,
+
+    #[arg(
+        long,
+        help = "Run the job when POST /schedule/<id>/webhook is called on goose-server",
+        long_help = "Run the job when POST /schedule/<id>/webhook is called on goose-server. The fields of the JSON payload are passed as recipe parameters."
+    )]
+    pub webhook: bool,
+
+    #[arg(
+        long,
+        value_name = "JOB_ID",
+        help = "Run the job after this scheduled job succeeds (can be repeated)"
+    )]
+    pub on_success: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "JOB_ID",
+        help = "Run the job after this scheduled job fails (can be repeated)"
+    )]
+    pub on_failure: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Wait this long for further events before a trigger runs the job (default: 2)"
+    )]
+    pub debounce: Option<u64>,
+}
+
+impl TriggerArgs {
+    fn triggers(&self) -> Vec<JobTrigger> {
+        let mut triggers = Vec::new();
+        if let Some(path) = &self.watch {
+            // Jobs run in the scheduler's process, which may have another working directory
+            let path = std::path::absolute(path)
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_else(|_| path.clone());
+            triggers.push(JobTrigger::FileWatch {
+                path,
+                pattern: self.watch_pattern.clone(),
+            });
+        }
+        if self.webhook {
+            triggers.push(JobTrigger::Webhook);
+        }
+        for (job_ids, on) in [
+            (&self.on_success, ChainCondition::Success),
+            (&self.on_failure, ChainCondition::Failure),
+        ] {
+            triggers.extend(job_ids.iter().map(|job_id| JobTrigger::JobCompleted {
+                job_id: job_id.clone(),
+                on,
+            }));
+        }
+        triggers
+    }
+}
+
+/// Limits on how a scheduled job runs
+#[derive(Args, Debug, Clone, Default)]
+pub struct RunPolicyArgs {
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop a run that takes longer than this and record it as timed out"
+    )]
+    pub max_runtime: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "NUMBER",
+        default_value_t = 0,
+        help = "Retry a failed or timed out run this many times"
+    )]
+    pub retries: u32,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Wait this long before the first retry, doubling for each further retry (default: 60)"
+    )]
+    pub retry_backoff: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value_t = OverlapPolicy::Skip,
+        help = "What a cron tick does while the previous run is still going: skip, queue or cancel-previous"
+    )]
+    pub overlap: OverlapPolicy,
+}
+
+impl RunPolicyArgs {
+    fn run_policy(&self) -> RunPolicy {
+        RunPolicy {
+            max_runtime_seconds: self.max_runtime,
+            retries: self.retries,
+            retry_backoff_seconds: self.retry_backoff,
+            overlap: self.overlap,
+        }
+    }
+}
+
 async fn get_or_create_session_id(
     identifier: Option<Identifier>,
     resume: bool,
@@ -239,6 +394,49 @@ enum SessionCommand {
         #[arg(short = 'o', long)]
         output: Option<PathBuf>,
     },
+    #[command(about = "Search the messages of all sessions")]
+    Search {
+        #[arg(help = "Words to search for; all of them must appear in a message")]
+        query: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+
+        #[arg(
+            short = 'w',
+            long = "working_dir",
+            help = "Only search sessions whose working directory contains this path"
+        )]
+        working_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Only match messages on or after this date (YYYY-MM-DD or RFC 3339)"
+        )]
+        after: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Only match messages on or before this date (YYYY-MM-DD or RFC 3339)"
+        )]
+        before: Option<String>,
+
+        #[arg(short = 'l', long = "limit", help = "Limit the number of sessions")]
+        limit: Option<usize>,
+    },
+    #[command(
+        name = "index-embeddings",
+        about = "Build the embedding index used for semantic chat recall",
+        long_about = "Embed all stored messages that are not in the embedding index yet. Requires GOOSE_EMBEDDING_PROVIDER and GOOSE_EMBEDDING_MODEL to be configured, e.g. ollama and nomic-embed-text to keep everything local. New messages are indexed automatically once configured."
+    )]
+    IndexEmbeddings,
 }
 
 #[derive(Subcommand, Debug)]
@@ -254,14 +452,20 @@ enum SchedulerCommand {
         #[arg(
             long,
             help = "Cron expression for the schedule",
-            long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight"
+            long_help = "Cron expression for when to run the job. Can be left out when the job has a trigger. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight"
         )]
-        cron: String,
+        cron: Option<String>,
         #[arg(
             long,
             help = "Recipe source (path to file, or base64 encoded recipe string)"
         )]
         recipe_source: String,
+        #[command(flatten)]
+        budget: BudgetArgs,
+        #[command(flatten)]
+        triggers: Box<TriggerArgs>,
+        #[command(flatten)]
+        run_policy: RunPolicyArgs,
     },
     #[command(about = "List all scheduled jobs")]
     List {},
@@ -283,6 +487,15 @@ enum SchedulerCommand {
         #[arg(short = 'l', long, help = "Maximum number of sessions to return")]
         limit: Option<usize>,
     },
+    /// Show the run history of a schedule
+    #[command(about = "Show the run history of a schedule")]
+    History {
+        /// ID of the schedule
+        #[arg(long = "schedule-id", alias = "id", help = "ID of the schedule")]
+        schedule_id: String,
+        #[arg(short = 'l', long, help = "Maximum number of runs to show")]
+        limit: Option<usize>,
+    },
     #[command(about = "Run a scheduled job immediately")]
     RunNow {
         /// ID of the schedule to run
@@ -402,6 +615,35 @@ enum RecipeCommand {
         )]
         verbose: bool,
     },
+
+    /// Run a recipe's test cases against recorded provider and extension responses
+    #[command(
+        about = "Run a recipe's test cases",
+        long_about = "Run the test cases declared in <recipe>.test.yaml next to the recipe.\n\
+                      Cases replay the provider and extension responses recorded in\n\
+                      cassettes/<recipe>/<case>.json; use --record to run them for real and\n\
+                      rewrite the cassettes."
+    )]
+    Test {
+        /// Recipe name to get recipe file to test
+        #[arg(help = "recipe name or full path to the recipe file")]
+        recipe_name: String,
+
+        /// Only run the test case with this name
+        #[arg(
+            long = "case",
+            value_name = "NAME",
+            help = "Only run the named test case"
+        )]
+        case: Option<String>,
+
+        /// Run against the configured provider and extensions and re-record the cassettes
+        #[arg(
+            long,
+            help = "Run against the configured provider and extensions and re-record the cassettes"
+        )]
+        record: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -481,6 +723,9 @@ enum Command {
         )]
         max_turns: Option<u32>,
 
+        #[command(flatten)]
+        budget: BudgetArgs,
+
         /// Add stdio extensions with environment variables and commands
         #[arg(
             long = "with-extension",
@@ -637,6 +882,9 @@ enum Command {
         )]
         max_turns: Option<u32>,
 
+        #[command(flatten)]
+        budget: BudgetArgs,
+
         /// Identifier for this run session
         #[command(flatten)]
         identifier: Option<Identifier>,
@@ -727,13 +975,14 @@ enum Command {
         )]
         additional_sub_recipes: Vec<String>,
 
-        /// Output format (text, json)
+        /// Output format (text, json, stream-json)
         #[arg(
             long = "output-format",
             value_name = "FORMAT",
-            help = "Output format (text, json)",
+            help = "Output format (text, json, stream-json)",
+            long_help = "Output format:\n  - text: render the conversation for a terminal\n  - json: print the messages and token usage once the run finishes\n  - stream-json: print each event as a line of JSON as it happens, using the same events as goosed's /reply endpoint",
             default_value = "text",
-            value_parser = clap::builder::PossibleValuesParser::new(["text", "json"])
+            value_parser = clap::builder::PossibleValuesParser::new(["text", "json", "stream-json"])
         )]
         output_format: String,
 
@@ -898,6 +1147,7 @@ pub async fn cli() -> anyhow::Result<()> {
             debug,
             max_tool_repetitions,
             max_turns,
+            budget,
             extensions,
             remote_extensions,
             streamable_http_extensions,
@@ -961,6 +1211,29 @@ pub async fn cli() -> anyhow::Result<()> {
                     crate::commands::session::handle_diagnostics(&session_id, output).await?;
                     Ok(())
                 }
+                Some(SessionCommand::Search {
+                    query,
+                    format,
+                    working_dir,
+                    after,
+                    before,
+                    limit,
+                }) => {
+                    crate::commands::session::handle_session_search(
+                        query,
+                        format,
+                        working_dir,
+                        after,
+                        before,
+                        limit,
+                    )
+                    .await?;
+                    Ok(())
+                }
+                Some(SessionCommand::IndexEmbeddings) => {
+                    crate::commands::session::handle_session_index_embeddings().await?;
+                    Ok(())
+                }
                 None => {
                     let session_start = std::time::Instant::now();
                     let session_type = if resume { "resumed" } else { "new" };
@@ -1002,6 +1275,7 @@ pub async fn cli() -> anyhow::Result<()> {
                         debug,
                         max_tool_repetitions,
                         max_turns,
+                        budget: budget.budget(),
                         scheduled_job_id: None,
                         interactive: true,
                         quiet: false,
@@ -1079,6 +1353,7 @@ pub async fn cli() -> anyhow::Result<()> {
             debug,
             max_tool_repetitions,
             max_turns,
+            budget,
             extensions,
             remote_extensions,
             streamable_http_extensions,
@@ -1211,6 +1486,7 @@ pub async fn cli() -> anyhow::Result<()> {
                 debug,
                 max_tool_repetitions,
                 max_turns,
+                budget: budget.budget(),
                 scheduled_job_id,
                 interactive, // Use the interactive flag from the Run command
                 quiet,
@@ -1291,8 +1567,20 @@ pub async fn cli() -> anyhow::Result<()> {
                     schedule_id,
                     cron,
                     recipe_source,
+                    budget,
+                    triggers,
+                    run_policy,
                 } => {
-                    handle_schedule_add(schedule_id, cron, recipe_source).await?;
+                    handle_schedule_add(
+                        schedule_id,
+                        cron,
+                        recipe_source,
+                        budget.budget(),
+                        triggers.triggers(),
+                        triggers.debounce,
+                        run_policy.run_policy(),
+                    )
+                    .await?;
                 }
                 SchedulerCommand::List {} => {
                     handle_schedule_list().await?;
@@ -1304,6 +1592,9 @@ pub async fn cli() -> anyhow::Result<()> {
                     // New arm
                     handle_schedule_sessions(schedule_id, limit).await?;
                 }
+                SchedulerCommand::History { schedule_id, limit } => {
+                    handle_schedule_history(schedule_id, limit).await?;
+                }
                 SchedulerCommand::RunNow { schedule_id } => {
                     // New arm
                     handle_schedule_run_now(schedule_id).await?;
@@ -1361,6 +1652,13 @@ pub async fn cli() -> anyhow::Result<()> {
                 RecipeCommand::List { format, verbose } => {
                     handle_list(&format, verbose)?;
                 }
+                RecipeCommand::Test {
+                    recipe_name,
+                    case,
+                    record,
+                } => {
+                    handle_test(&recipe_name, case.as_deref(), record).await?;
+                }
             }
             return Ok(());
         }
@@ -1397,6 +1695,7 @@ pub async fn cli() -> anyhow::Result<()> {
                     debug: false,
                     max_tool_repetitions: None,
                     max_turns: None,
+                    budget: None,
                     scheduled_job_id: None,
                     interactive: true,
                     quiet: false,
@@ -3,17 +3,20 @@ use agent_client_protocol::{
     ToolCallContent,
 };
 use anyhow::Result;
-use goose::agents::{Agent, SessionConfig};
-use goose::config::{get_all_extensions, Config};
+use goose::agents::{Agent, AgentEvent, SessionConfig, MANUAL_COMPACT_TRIGGER};
+use goose::config::{get_all_extensions, Config, GooseMode};
 use goose::conversation::message::{Message, MessageContent};
 use goose::conversation::Conversation;
 use goose::mcp_utils::ToolResult;
-use goose::providers::create;
+use goose::model::ModelConfig;
+use goose::providers::{create, create_session_provider};
 use goose::session::session_manager::SessionType;
 use goose::session::SessionManager;
-use rmcp::model::{Content, RawContent, ResourceContents};
+use rmcp::model::{Content, RawContent, ResourceContents, Role};
+use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::task::JoinSet;
@@ -35,6 +38,112 @@ struct GooseAcpAgent {
     agent: Agent, // Shared agent instance
 }
 
+/// Goose modes exposed as ACP session modes: (mode, id, name, description)
+const SESSION_MODES: [(GooseMode, &str, &str, &str); 4] = [
+    (
+        GooseMode::Auto,
+        "auto",
+        "Auto",
+        "Run tools without asking for approval",
+    ),
+    (
+        GooseMode::Approve,
+        "approve",
+        "Approve",
+        "Ask for approval before every tool call",
+    ),
+    (
+        GooseMode::SmartApprove,
+        "smart_approve",
+        "Smart Approve",
+        "Ask for approval only for tool calls that may modify state",
+    ),
+    (
+        GooseMode::Chat,
+        "chat",
+        "Chat",
+        "Chat only, without calling any tools",
+    ),
+];
+
+const AUTH_METHOD_ID: &str = "goose-configure";
+
+/// Slash commands advertised to ACP clients: (name, description, input hint)
+const SLASH_COMMANDS: [(&str, &str, Option<&str>); 3] = [
+    (
+        "compact",
+        "Summarize the conversation to free up context",
+        None,
+    ),
+    ("prompts", "List prompts provided by extensions", None),
+    (
+        "model",
+        "Switch the model used by goose",
+        Some("[provider] <model>"),
+    ),
+];
+
+fn session_mode_state(current: GooseMode) -> acp::SessionModeState {
+    let current_mode_id = SESSION_MODES
+        .iter()
+        .find(|(mode, ..)| *mode == current)
+        .map(|(_, id, ..)| *id)
+        .unwrap_or("auto");
+
+    acp::SessionModeState {
+        current_mode_id: acp::SessionModeId(current_mode_id.into()),
+        available_modes: SESSION_MODES
+            .iter()
+            .map(|(_, id, name, description)| acp::SessionMode {
+                id: acp::SessionModeId((*id).into()),
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                meta: None,
+            })
+            .collect(),
+        meta: None,
+    }
+}
+
+fn available_commands() -> Vec<acp::AvailableCommand> {
+    SLASH_COMMANDS
+        .iter()
+        .map(|(name, description, hint)| acp::AvailableCommand {
+            name: name.to_string(),
+            description: description.to_string(),
+            input: hint.map(|hint| acp::AvailableCommandInput::Unstructured {
+                hint: hint.to_string(),
+            }),
+            meta: None,
+        })
+        .collect()
+}
+
+/// Parse a prompt consisting of a single `/command args` text block
+fn parse_slash_command(prompt: &[acp::ContentBlock]) -> Option<(String, String)> {
+    let [acp::ContentBlock::Text(text)] = prompt else {
+        return None;
+    };
+    let command = text.text.trim().strip_prefix('/')?;
+    let (name, args) = command
+        .split_once(char::is_whitespace)
+        .unwrap_or((command, ""));
+    SLASH_COMMANDS
+        .iter()
+        .any(|(known, ..)| *known == name)
+        .then(|| (name.to_string(), args.trim().to_string()))
+}
+
+/// Parse `[provider] <model>` into an optional provider and a model name
+fn parse_model_args(args: &str) -> Option<(Option<String>, String)> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    match parts.as_slice() {
+        [model] => Some((None, model.to_string())),
+        [provider, model] => Some((Some(provider.to_string()), model.to_string())),
+        _ => None,
+    }
+}
+
 /// Create a ToolCallLocation with common defaults
 fn create_tool_location(path: &str, line: Option<u32>) -> acp::ToolCallLocation {
     acp::ToolCallLocation {
@@ -228,7 +337,7 @@ impl GooseAcpAgent {
             toolshim_model: None,
             fast_model: None,
         };
-        let provider = create(&provider_name, model_config).await?;
+        let provider = create_session_provider(&provider_name, model_config).await?;
 
         // Create a shared agent instance
         let agent = Agent::new();
@@ -324,6 +433,217 @@ impl GooseAcpAgent {
         user_message
     }
 
+    async fn send_session_update(
+        &self,
+        session_id: &acp::SessionId,
+        update: acp::SessionUpdate,
+    ) -> Result<(), acp::Error> {
+        let (tx, rx) = oneshot::channel();
+        self.session_update_tx
+            .send((
+                SessionNotification {
+                    session_id: session_id.clone(),
+                    update,
+                    meta: None,
+                },
+                tx,
+            ))
+            .map_err(|_| acp::Error::internal_error())?;
+        rx.await.map_err(|_| acp::Error::internal_error())
+    }
+
+    async fn send_agent_text(
+        &self,
+        session_id: &acp::SessionId,
+        text: impl Into<String>,
+    ) -> Result<(), acp::Error> {
+        self.send_session_update(
+            session_id,
+            acp::SessionUpdate::AgentMessageChunk {
+                content: text.into().into(),
+            },
+        )
+        .await
+    }
+
+    /// Replay a stored message to the client when a session is loaded
+    async fn replay_message(
+        &self,
+        message: &Message,
+        session_id: &acp::SessionId,
+        session: &mut GooseAcpSession,
+    ) -> Result<(), acp::Error> {
+        match message.role {
+            Role::Assistant => {
+                for content_item in &message.content {
+                    self.handle_message_content(content_item, session_id, session)
+                        .await?;
+                }
+            }
+            Role::User => {
+                for content_item in &message.content {
+                    match content_item {
+                        MessageContent::Text(text) => {
+                            self.send_session_update(
+                                session_id,
+                                acp::SessionUpdate::UserMessageChunk {
+                                    content: text.text.clone().into(),
+                                },
+                            )
+                            .await?;
+                        }
+                        MessageContent::Image(image) => {
+                            self.send_session_update(
+                                session_id,
+                                acp::SessionUpdate::UserMessageChunk {
+                                    content: acp::ContentBlock::Image(ImageContent {
+                                        annotations: None,
+                                        data: image.data.clone(),
+                                        mime_type: image.mime_type.clone(),
+                                        uri: None,
+                                        meta: None,
+                                    }),
+                                },
+                            )
+                            .await?;
+                        }
+                        MessageContent::ToolResponse(tool_response) => {
+                            self.handle_tool_response(tool_response, session_id, session)
+                                .await?;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn ensure_session(&self, session_id: &str) -> Result<(), acp::Error> {
+        if self.sessions.lock().await.contains_key(session_id) {
+            Ok(())
+        } else {
+            Err(acp::Error::invalid_params().with_data(format!("Unknown session: {}", session_id)))
+        }
+    }
+
+    /// Summarize the session's conversation, replacing its history
+    async fn compact_session(&self, session_id: &str) -> Result<(), acp::Error> {
+        self.ensure_session(session_id).await?;
+
+        let session_config = SessionConfig {
+            id: session_id.to_string(),
+            schedule_id: None,
+            max_turns: None,
+            retry_config: None,
+        };
+        let mut stream = self
+            .agent
+            .reply(
+                Message::user().with_text(MANUAL_COMPACT_TRIGGER),
+                session_config,
+                None,
+            )
+            .await
+            .map_err(|e| {
+                error!("Error compacting session: {}", e);
+                acp::Error::internal_error()
+            })?;
+
+        use futures::StreamExt;
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(AgentEvent::HistoryReplaced(conversation)) => {
+                    if let Some(session) = self.sessions.lock().await.get_mut(session_id) {
+                        session.messages = conversation;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Error compacting session: {}", e);
+                    return Err(acp::Error::internal_error());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_prompts(&self) -> Value {
+        let prompts = self.agent.list_extension_prompts().await;
+        json!({ "prompts": prompts })
+    }
+
+    /// Switch the agent to another model, optionally with another provider
+    async fn switch_model(
+        &self,
+        provider_name: Option<String>,
+        model_name: String,
+    ) -> Result<Value, acp::Error> {
+        let provider_name = match provider_name {
+            Some(provider_name) => provider_name,
+            None => Config::global().get_goose_provider().map_err(|e| {
+                acp::Error::invalid_params().with_data(format!("No provider configured: {}", e))
+            })?,
+        };
+        let model_config = ModelConfig::new(&model_name)
+            .map_err(|e| acp::Error::invalid_params().with_data(e.to_string()))?;
+        let provider = create(&provider_name, model_config)
+            .await
+            .map_err(|e| acp::Error::invalid_params().with_data(e.to_string()))?;
+        self.agent.update_provider(provider).await.map_err(|e| {
+            error!("Failed to switch model: {}", e);
+            acp::Error::internal_error()
+        })?;
+
+        info!("Switched to {} model {}", provider_name, model_name);
+        Ok(json!({ "provider": provider_name, "model": model_name }))
+    }
+
+    /// Run a slash command typed into the prompt, reporting the result as agent text
+    async fn run_slash_command(
+        &self,
+        name: &str,
+        args: &str,
+        session_id: &acp::SessionId,
+    ) -> Result<(), acp::Error> {
+        let text = match name {
+            "compact" => {
+                self.compact_session(&session_id.0).await?;
+                "Conversation compacted.".to_string()
+            }
+            "prompts" => {
+                let prompts = self.agent.list_extension_prompts().await;
+                if prompts.is_empty() {
+                    "No prompts available.".to_string()
+                } else {
+                    let mut extensions: Vec<_> = prompts.into_iter().collect();
+                    extensions.sort_by(|a, b| a.0.cmp(&b.0));
+                    extensions
+                        .into_iter()
+                        .map(|(extension, prompts)| {
+                            let names: Vec<_> = prompts.into_iter().map(|p| p.name).collect();
+                            format!("{}: {}", extension, names.join(", "))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            "model" => match parse_model_args(args) {
+                Some((provider, model)) => match self.switch_model(provider, model).await {
+                    Ok(result) => format!(
+                        "Switched to {} model {}.",
+                        result["provider"].as_str().unwrap_or_default(),
+                        result["model"].as_str().unwrap_or_default()
+                    ),
+                    Err(e) => format!("Failed to switch model: {}", e.data.unwrap_or_default()),
+                },
+                None => "Usage: /model [provider] <model>".to_string(),
+            },
+            _ => return Err(acp::Error::method_not_found()),
+        };
+        self.send_agent_text(session_id, text).await
+    }
+
     async fn handle_message_content(
         &self,
         content_item: &MessageContent,
@@ -568,7 +888,7 @@ impl acp::Agent for GooseAcpAgent {
 
         // Advertise Goose's capabilities
         let agent_capabilities = acp::AgentCapabilities {
-            load_session: false, // TODO: Implement session persistence
+            load_session: true,
             prompt_capabilities: acp::PromptCapabilities {
                 image: true,            // Goose supports image inputs via providers
                 audio: false,           // TODO: Add audio support when providers support it
@@ -586,7 +906,15 @@ impl acp::Agent for GooseAcpAgent {
         Ok(acp::InitializeResponse {
             protocol_version: acp::V1,
             agent_capabilities,
-            auth_methods: Vec::new(),
+            auth_methods: vec![acp::AuthMethod {
+                id: acp::AuthMethodId(AUTH_METHOD_ID.into()),
+                name: "Configure goose".to_string(),
+                description: Some(
+                    "Run `goose configure` in a terminal to set up a provider and model"
+                        .to_string(),
+                ),
+                meta: None,
+            }],
             meta: None,
         })
     }
@@ -596,6 +924,18 @@ impl acp::Agent for GooseAcpAgent {
         args: acp::AuthenticateRequest,
     ) -> Result<acp::AuthenticateResponse, acp::Error> {
         info!("ACP: Received authenticate request {:?}", args);
+
+        if args.method_id.0.as_ref() != AUTH_METHOD_ID {
+            return Err(acp::Error::invalid_params());
+        }
+
+        // Credentials are managed by `goose configure`, so all we can do here is
+        // check that a provider and model are set up.
+        let config = Config::global();
+        if config.get_goose_provider().is_err() || config.get_goose_model().is_err() {
+            return Err(acp::Error::auth_required());
+        }
+
         Ok(acp::AuthenticateResponse { meta: None })
     }
 
@@ -619,14 +959,27 @@ impl acp::Agent for GooseAcpAgent {
             cancel_token: None,
         };
 
-        let mut sessions = self.sessions.lock().await;
-        sessions.insert(goose_session.id.clone(), session);
+        self.sessions
+            .lock()
+            .await
+            .insert(goose_session.id.clone(), session);
 
         info!("Created new ACP/goose session {}", goose_session.id);
 
+        let session_id = acp::SessionId(goose_session.id.into());
+        self.send_session_update(
+            &session_id,
+            acp::SessionUpdate::AvailableCommandsUpdate {
+                available_commands: available_commands(),
+            },
+        )
+        .await?;
+
         Ok(acp::NewSessionResponse {
-            session_id: acp::SessionId(goose_session.id.into()),
-            modes: None,
+            session_id,
+            modes: Some(session_mode_state(
+                Config::global().get_goose_mode().unwrap_or(GooseMode::Auto),
+            )),
             meta: None,
         })
     }
@@ -636,19 +989,53 @@ impl acp::Agent for GooseAcpAgent {
         args: acp::LoadSessionRequest,
     ) -> Result<acp::LoadSessionResponse, acp::Error> {
         info!("ACP: Received load session request {:?}", args);
-        // For now, will start a new session. We could use goose session storage as an enhancement
-        // we would need to map ACP session IDs to goose session ids (which by default are auto generated)
-        // normal goose session restore in CLI doesn't load conversation visually.
-        //
-        // Example flow:
-        // - Load session file by session_id (might need to map ACP session IDs to Goose session paths)
-        // - For each message in history:
-        //   - If user message: send user_message_chunk notification
-        //   - If assistant message: send agent_message_chunk notification
-        //   - If tool calls/responses: send appropriate notifications
 
-        // For now, we don't support loading previous sessions
-        Err(acp::Error::method_not_found())
+        let session_id = args.session_id.0.to_string();
+        let goose_session = SessionManager::get_session(&session_id, true)
+            .await
+            .map_err(|e| {
+                warn!("Failed to load session {}: {}", session_id, e);
+                acp::Error::invalid_params().with_data(format!("Unknown session: {}", session_id))
+            })?;
+        let conversation = goose_session
+            .conversation
+            .unwrap_or_else(|| Conversation::new_unvalidated(Vec::new()));
+
+        let mut session = GooseAcpSession {
+            messages: conversation.clone(),
+            tool_call_ids: HashMap::new(),
+            tool_requests: HashMap::new(),
+            cancel_token: None,
+        };
+
+        // Replay the history so the client can render the previous conversation
+        for message in conversation.messages() {
+            if message.is_user_visible() {
+                self.replay_message(message, &args.session_id, &mut session)
+                    .await?;
+            }
+        }
+
+        self.sessions
+            .lock()
+            .await
+            .insert(session_id.clone(), session);
+        info!("Loaded ACP/goose session {}", session_id);
+
+        self.send_session_update(
+            &args.session_id,
+            acp::SessionUpdate::AvailableCommandsUpdate {
+                available_commands: available_commands(),
+            },
+        )
+        .await?;
+
+        Ok(acp::LoadSessionResponse {
+            modes: Some(session_mode_state(
+                Config::global().get_goose_mode().unwrap_or(GooseMode::Auto),
+            )),
+            meta: None,
+        })
     }
 
     async fn prompt(&self, args: acp::PromptRequest) -> Result<acp::PromptResponse, acp::Error> {
@@ -663,6 +1050,20 @@ impl acp::Agent for GooseAcpAgent {
             session.cancel_token = Some(cancel_token.clone());
         }
 
+        if let Some((name, command_args)) = parse_slash_command(&args.prompt) {
+            let result = self
+                .run_slash_command(&name, &command_args, &args.session_id)
+                .await;
+            if let Some(session) = self.sessions.lock().await.get_mut(&session_id) {
+                session.cancel_token = None;
+            }
+            result?;
+            return Ok(acp::PromptResponse {
+                stop_reason: acp::StopReason::EndTurn,
+                meta: None,
+            });
+        }
+
         let user_message = self.convert_acp_prompt_to_message(args.prompt);
 
         let session_config = SessionConfig {
@@ -748,18 +1149,69 @@ impl acp::Agent for GooseAcpAgent {
 
     async fn set_session_mode(
         &self,
-        _args: acp::SetSessionModeRequest,
+        args: acp::SetSessionModeRequest,
     ) -> Result<acp::SetSessionModeResponse, acp::Error> {
-        // TODO: Implement session modes if needed
-        Err(acp::Error::method_not_found())
+        info!("ACP: Received set session mode request {:?}", args);
+
+        self.ensure_session(&args.session_id.0).await?;
+        let mode = GooseMode::from_str(&args.mode_id.0)
+            .map_err(|e| acp::Error::invalid_params().with_data(e))?;
+
+        // The mode is global, just like `/mode` in the CLI
+        Config::global().set_goose_mode(mode).map_err(|e| {
+            error!("Failed to set goose mode: {}", e);
+            acp::Error::internal_error()
+        })?;
+
+        self.send_session_update(
+            &args.session_id,
+            acp::SessionUpdate::CurrentModeUpdate {
+                current_mode_id: args.mode_id,
+            },
+        )
+        .await?;
+
+        Ok(acp::SetSessionModeResponse { meta: None })
     }
 
+    /// Extension methods, called by clients as `_goose/<name>`:
+    /// - `goose/compact` `{ "sessionId" }` summarizes the session's conversation
+    /// - `goose/list_prompts` returns prompts by extension
+    /// - `goose/switch_model` `{ "provider"?, "model" }` switches the agent's model
     async fn ext_method(
         &self,
-        _args: acp::ExtRequest,
+        args: acp::ExtRequest,
     ) -> Result<std::sync::Arc<acp::RawValue>, acp::Error> {
-        // TODO: Implement extension methods if needed
-        Err(acp::Error::method_not_found())
+        info!("ACP: Received extension method {}", args.method);
+
+        let params: Value = serde_json::from_str(args.params.get())
+            .map_err(|e| acp::Error::invalid_params().with_data(e.to_string()))?;
+        let string_param = |name: &str| params.get(name).and_then(Value::as_str);
+
+        let result = match args.method.as_ref() {
+            "goose/compact" => {
+                let session_id = string_param("sessionId").ok_or_else(|| {
+                    acp::Error::invalid_params().with_data("sessionId is required")
+                })?;
+                self.compact_session(session_id).await?;
+                json!({})
+            }
+            "goose/list_prompts" => self.list_prompts().await,
+            "goose/switch_model" => {
+                let model = string_param("model")
+                    .ok_or_else(|| acp::Error::invalid_params().with_data("model is required"))?;
+                self.switch_model(
+                    string_param("provider").map(str::to_string),
+                    model.to_string(),
+                )
+                .await?
+            }
+            _ => return Err(acp::Error::method_not_found()),
+        };
+
+        serde_json::value::to_raw_value(&result)
+            .map(Arc::from)
+            .map_err(|_| acp::Error::internal_error())
     }
 
     async fn ext_notification(&self, _args: acp::ExtNotification) -> Result<(), acp::Error> {
@@ -815,11 +1267,24 @@ pub async fn run_acp_agent() -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use agent_client_protocol::ResourceLink;
+    use agent_client_protocol::{self as acp, Agent as _, ResourceLink};
+    use rmcp::model::{CallToolRequestParam, Content};
+    use rmcp::object;
+    use std::collections::HashMap;
     use std::io::Write;
+    use std::sync::Arc;
     use tempfile::NamedTempFile;
-
-    use crate::commands::acp::{format_tool_name, read_resource_link};
+    use tokio::sync::{mpsc, oneshot, Mutex};
+
+    use crate::commands::acp::{
+        format_tool_name, parse_model_args, parse_slash_command, read_resource_link,
+        session_mode_state, GooseAcpAgent,
+    };
+    use goose::agents::Agent;
+    use goose::config::GooseMode;
+    use goose::conversation::message::Message;
+    use goose::session::session_manager::SessionType;
+    use goose::session::SessionManager;
 
     fn new_resource_link(content: &str) -> anyhow::Result<(ResourceLink, NamedTempFile)> {
         let mut file = NamedTempFile::new()?;
@@ -888,4 +1353,140 @@ print(\"hello, world\")
         assert_eq!(format_tool_name("extension__"), "Extension: ");
         assert_eq!(format_tool_name("__tool"), ": Tool");
     }
+
+    #[test]
+    fn test_session_mode_state() {
+        let state = session_mode_state(GooseMode::SmartApprove);
+        assert_eq!(state.current_mode_id.0.as_ref(), "smart_approve");
+
+        let ids: Vec<_> = state
+            .available_modes
+            .iter()
+            .map(|mode| mode.id.0.to_string())
+            .collect();
+        assert_eq!(ids, vec!["auto", "approve", "smart_approve", "chat"]);
+        for id in ids {
+            assert!(id.parse::<GooseMode>().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_parse_slash_command() {
+        let text = |text: &str| vec![text.to_string().into()];
+
+        assert_eq!(
+            parse_slash_command(&text("/compact")),
+            Some(("compact".to_string(), String::new()))
+        );
+        assert_eq!(
+            parse_slash_command(&text(" /model  openai gpt-4o ")),
+            Some(("model".to_string(), "openai gpt-4o".to_string()))
+        );
+        assert_eq!(parse_slash_command(&text("/unknown")), None);
+        assert_eq!(parse_slash_command(&text("compact")), None);
+
+        let mut two_blocks = text("/compact");
+        two_blocks.push("more".to_string().into());
+        assert_eq!(parse_slash_command(&two_blocks), None);
+    }
+
+    #[test]
+    fn test_parse_model_args() {
+        assert_eq!(
+            parse_model_args("gpt-4o"),
+            Some((None, "gpt-4o".to_string()))
+        );
+        assert_eq!(
+            parse_model_args("openai gpt-4o"),
+            Some((Some("openai".to_string()), "gpt-4o".to_string()))
+        );
+        assert_eq!(parse_model_args(""), None);
+        assert_eq!(parse_model_args("a b c"), None);
+    }
+
+    #[tokio::test]
+    async fn test_load_session_replays_history() {
+        let session = SessionManager::create_session(
+            std::env::temp_dir(),
+            "ACP load test".to_string(),
+            SessionType::Hidden,
+        )
+        .await
+        .unwrap();
+        let tool_call = CallToolRequestParam {
+            name: "developer__shell".into(),
+            arguments: Some(object!({"command": "ls"})),
+        };
+        for message in [
+            Message::user().with_text("List the files"),
+            Message::assistant()
+                .with_text("Listing them")
+                .with_tool_request("call-1", Ok(tool_call)),
+            Message::user().with_tool_response("call-1", Ok(vec![Content::text("notes.md")])),
+            Message::assistant().with_text("There is one file"),
+        ] {
+            SessionManager::add_message(&session.id, &message)
+                .await
+                .unwrap();
+        }
+
+        let (tx, mut rx) =
+            mpsc::unbounded_channel::<(acp::SessionNotification, oneshot::Sender<()>)>();
+        let updates = tokio::spawn(async move {
+            let mut updates = Vec::new();
+            while let Some((notification, ack)) = rx.recv().await {
+                updates.push(notification);
+                let _ = ack.send(());
+            }
+            updates
+        });
+        let agent = GooseAcpAgent {
+            session_update_tx: tx,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            agent: Agent::new(),
+        };
+
+        let session_id = acp::SessionId(session.id.clone().into());
+        agent
+            .load_session(acp::LoadSessionRequest {
+                mcp_servers: vec![],
+                cwd: std::env::temp_dir(),
+                session_id: session_id.clone(),
+                meta: None,
+            })
+            .await
+            .unwrap();
+        let loaded = agent.sessions.lock().await[&session.id].messages.len();
+        drop(agent);
+        let updates = updates.await.unwrap();
+        let _ = SessionManager::delete_session(&session.id).await;
+
+        assert_eq!(loaded, 4);
+        assert!(updates.iter().all(|n| n.session_id == session_id));
+        let kinds: Vec<&str> = updates
+            .iter()
+            .map(|n| match &n.update {
+                acp::SessionUpdate::UserMessageChunk { .. } => "user",
+                acp::SessionUpdate::AgentMessageChunk { .. } => "agent",
+                acp::SessionUpdate::ToolCall(_) => "tool call",
+                acp::SessionUpdate::ToolCallUpdate(update) => {
+                    assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Completed));
+                    "tool result"
+                }
+                acp::SessionUpdate::AvailableCommandsUpdate { .. } => "commands",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "user",
+                "agent",
+                "tool call",
+                "tool result",
+                "agent",
+                "commands"
+            ]
+        );
+    }
 }
@@ -3,6 +3,7 @@ use console::style;
 use goose::recipe::validate_recipe::validate_recipe_template_from_file;
 
 use crate::recipes::github_recipe::RecipeSource;
+use crate::recipes::recipe_test::run_recipe_tests;
 use crate::recipes::search_recipe::{list_available_recipes, load_recipe_file};
 use goose::recipe_deeplink;
 
@@ -20,6 +21,14 @@ pub fn handle_validate(recipe_name: &str) -> Result<()> {
     Ok(())
 }
 
+pub async fn handle_test(recipe_name: &str, case: Option<&str>, record: bool) -> Result<()> {
+    if run_recipe_tests(recipe_name, case, record).await? {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Some recipe test cases failed"))
+    }
+}
+
 pub fn handle_deeplink(recipe_name: &str) -> Result<String> {
     match generate_deeplink(recipe_name) {
         Ok((deeplink_url, recipe)) => {
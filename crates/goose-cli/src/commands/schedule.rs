@@ -1,8 +1,11 @@
 use anyhow::{bail, Context, Result};
 use goose::scheduler::{
-    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, ScheduledJob, Scheduler,
-    SchedulerError,
+    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, RunPolicy, ScheduledJob,
+    Scheduler, SchedulerError,
 };
+use goose::scheduler_history::DEFAULT_HISTORY_MAX_RUNS;
+use goose::scheduler_triggers::JobTrigger;
+use goose::session::Budget;
 use std::path::Path;
 
 fn validate_cron_expression(cron: &str) -> Result<()> {
@@ -65,15 +68,33 @@ fn validate_cron_expression(cron: &str) -> Result<()> {
 
 pub async fn handle_schedule_add(
     schedule_id: String,
-    cron: String,
+    cron: Option<String>,
     recipe_source_arg: String, // This is expected to be a file path by the Scheduler
+    budget: Option<Budget>,
+    triggers: Vec<JobTrigger>,
+    debounce_seconds: Option<u64>,
+    run_policy: RunPolicy,
 ) -> Result<()> {
     println!(
         "[CLI Debug] Scheduling job ID: {}, Cron: {}, Recipe Source Path: {}",
-        schedule_id, cron, recipe_source_arg
+        schedule_id,
+        cron.as_deref().unwrap_or("none"),
+        recipe_source_arg
     );
 
-    validate_cron_expression(&cron)?;
+    let cron = match cron {
+        Some(cron) => {
+            validate_cron_expression(&cron)?;
+            cron
+        }
+        None if triggers.is_empty() => {
+            bail!("A job needs --cron or at least one trigger (--watch, --webhook, --on-success, --on-failure)");
+        }
+        None => String::new(),
+    };
+    for trigger in &triggers {
+        println!("✅ Runs when {}", trigger);
+    }
 
     // The Scheduler's add_scheduled_job will handle copying the recipe from recipe_source_arg
     // to its internal storage and validating the path.
@@ -86,6 +107,10 @@ pub async fn handle_schedule_add(
         paused: false,
         current_session_id: None,
         process_start_time: None,
+        budget,
+        triggers,
+        debounce_seconds,
+        run_policy,
     };
 
     let scheduler_storage_path =
@@ -126,6 +151,9 @@ pub async fn handle_schedule_add(
                         recipe_source_arg
                     );
                 }
+                SchedulerError::TriggerError(msg) => {
+                    bail!("Error: Invalid trigger: {}", msg);
+                }
                 _ => Err(anyhow::Error::new(e))
                     .context(format!("Failed to add job '{}' to scheduler", schedule_id)),
             }
@@ -154,15 +182,43 @@ pub async fn handle_schedule_list() -> Result<()> {
                 "⏹️  IDLE"
             };
 
+            let cron = if job.cron.is_empty() {
+                "none"
+            } else {
+                job.cron.as_str()
+            };
             println!(
                 "- ID: {}\n  Status: {}\n  Cron: {}\n  Recipe Source (in store): {}\n  Last Run: {}",
                 job.id,
                 status,
-                job.cron,
+                cron,
                 job.source, // This source is now the path within scheduled_recipes_dir
                 job.last_run
                     .map_or_else(|| "Never".to_string(), |dt| dt.to_rfc3339())
             );
+            if !job.triggers.is_empty() {
+                let triggers: Vec<String> = job
+                    .triggers
+                    .iter()
+                    .map(|trigger| match trigger {
+                        JobTrigger::Webhook => {
+                            format!("webhook (POST /schedule/{}/webhook)", job.id)
+                        }
+                        other => other.to_string(),
+                    })
+                    .collect();
+                println!("  Triggers: {}", triggers.join("; "));
+            }
+            if job.run_policy != RunPolicy::default() {
+                let policy = &job.run_policy;
+                let max_runtime = policy
+                    .max_runtime_seconds
+                    .map_or_else(|| "none".to_string(), |seconds| format!("{}s", seconds));
+                println!(
+                    "  Max Runtime: {}, Retries: {}, Overlap: {}",
+                    max_runtime, policy.retries, policy.overlap
+                );
+            }
         }
     }
     Ok(())
@@ -231,6 +287,66 @@ pub async fn handle_schedule_sessions(schedule_id: String, limit: Option<usize>)
     Ok(())
 }
 
+pub async fn handle_schedule_history(schedule_id: String, limit: Option<usize>) -> Result<()> {
+    let scheduler_storage_path =
+        get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
+    let scheduler = Scheduler::new(scheduler_storage_path)
+        .await
+        .context("Failed to initialize scheduler")?;
+
+    let runs = match scheduler
+        .runs(&schedule_id, limit.unwrap_or(DEFAULT_HISTORY_MAX_RUNS))
+        .await
+    {
+        Ok(runs) => runs,
+        Err(SchedulerError::JobNotFound(job_id)) => {
+            bail!("Error: Job with ID '{}' not found.", job_id);
+        }
+        Err(e) => bail!(
+            "Failed to get run history for schedule '{}': {:?}",
+            schedule_id,
+            e
+        ),
+    };
+
+    if runs.is_empty() {
+        println!("No runs recorded for schedule ID '{}'.", schedule_id);
+        return Ok(());
+    }
+
+    println!("Runs of schedule ID '{}':", schedule_id);
+    for run in runs {
+        let duration = run
+            .duration()
+            .map(|duration| format!("{}s", duration.num_seconds()))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  - {} [{}] {} (trigger: {:?}, duration: {})",
+            run.started_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            run.status,
+            run.id,
+            run.trigger,
+            duration
+        );
+        if let Some(session_id) = &run.session_id {
+            println!("      Session: {}", session_id);
+        }
+        if let Some(total_tokens) = run.total_tokens {
+            let cost = run
+                .cost_usd
+                .map(|cost| format!(", cost: ${:.4}", cost))
+                .unwrap_or_default();
+            println!("      Tokens: {}{}", total_tokens, cost);
+        }
+        if let Some(error) = &run.error {
+            println!("      Error: {}", error);
+        }
+    }
+    Ok(())
+}
+
 pub async fn handle_schedule_run_now(schedule_id: String) -> Result<()> {
     let scheduler_storage_path =
         get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
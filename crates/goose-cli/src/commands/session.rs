@@ -1,8 +1,10 @@
 use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
 
+use chrono::{DateTime, NaiveDate, Utc};
 use cliclack::{confirm, multiselect, select};
-use goose::session::{generate_diagnostics, Session, SessionManager};
+use console::style;
+use goose::session::{generate_diagnostics, Budget, Session, SessionManager, SessionSearchQuery};
 use goose::utils::safe_truncate;
 use regex::Regex;
 use std::fs;
@@ -168,7 +170,11 @@ pub async fn handle_session_list(
 
             println!("Available sessions:");
             for session in sessions {
-                let output = format!("{} - {} - {}", session.id, session.name, session.updated_at);
+                let mut output =
+                    format!("{} - {} - {}", session.id, session.name, session.updated_at);
+                if let Some(spend) = format_spend(&session) {
+                    output.push_str(&format!(" - {}", spend));
+                }
                 println!("{}", output);
             }
         }
@@ -176,6 +182,111 @@ pub async fn handle_session_list(
     Ok(())
 }
 
+/// Estimated cost of a session and the budget that applies to it, if either is known
+fn format_spend(session: &Session) -> Option<String> {
+    let budget = Budget::for_session(session);
+    match (session.accumulated_cost, budget.is_empty()) {
+        (None, true) => None,
+        (cost, true) => cost.map(|cost| format!("${:.4}", cost)),
+        (cost, false) => Some(format!("${:.4} (budget: {})", cost.unwrap_or(0.0), budget)),
+    }
+}
+
+// Control characters marking matched words in snippets, replaced by styling when printed
+const HIGHLIGHT_START: &str = "\u{1}";
+const HIGHLIGHT_END: &str = "\u{2}";
+
+/// Parse an RFC 3339 timestamp or a `YYYY-MM-DD` date, taking the start or the end of the day
+fn parse_search_date(value: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+        format!(
+            "Invalid date '{}': expected YYYY-MM-DD or an RFC 3339 timestamp",
+            value
+        )
+    })?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.expect("valid time of day").and_utc())
+}
+
+fn highlight_snippet(snippet: &str) -> String {
+    let mut output = String::new();
+    for (i, part) in snippet.split(HIGHLIGHT_START).enumerate() {
+        match part.split_once(HIGHLIGHT_END) {
+            Some((matched, rest)) if i > 0 => {
+                output.push_str(&style(matched).yellow().bold().to_string());
+                output.push_str(rest);
+            }
+            _ => output.push_str(part),
+        }
+    }
+    output.replace('\n', " ")
+}
+
+pub async fn handle_session_search(
+    query: String,
+    format: String,
+    working_dir: Option<PathBuf>,
+    after: Option<String>,
+    before: Option<String>,
+    limit: Option<usize>,
+) -> Result<()> {
+    let is_json = format == "json";
+    let search = SessionSearchQuery {
+        query,
+        limit,
+        after_date: after.map(|d| parse_search_date(&d, false)).transpose()?,
+        before_date: before.map(|d| parse_search_date(&d, true)).transpose()?,
+        working_dir: working_dir.map(|dir| dir.to_string_lossy().to_string()),
+        highlight: (!is_json).then(|| (HIGHLIGHT_START.to_string(), HIGHLIGHT_END.to_string())),
+        owner: None,
+    };
+
+    let results = SessionManager::search_sessions(&search).await?;
+
+    if is_json {
+        println!("{}", serde_json::to_string(&results)?);
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        println!("No sessions found matching '{}'", search.query);
+        return Ok(());
+    }
+
+    for result in results {
+        let session = result.session;
+        println!(
+            "{} - {} - {} ({})",
+            style(&session.id).bold(),
+            session.name,
+            session.updated_at.format("%Y-%m-%d %H:%M"),
+            session.working_dir.display()
+        );
+        for search_match in result.matches {
+            println!(
+                "    {} {}",
+                style(format!("{}:", search_match.role)).dim(),
+                highlight_snippet(&search_match.snippet)
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_session_index_embeddings() -> Result<()> {
+    println!("Indexing session messages for semantic chat recall...");
+    let indexed = SessionManager::index_embeddings().await?;
+    println!("Indexed {} message(s).", indexed);
+    Ok(())
+}
+
 pub async fn handle_session_export(
     session_id: String,
     output_path: Option<PathBuf>,
@@ -368,3 +479,36 @@ pub async fn prompt_interactive_session_selection() -> Result<String> {
         Err(anyhow::anyhow!("Invalid selection"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_date() {
+        assert_eq!(
+            parse_search_date("2025-03-04", false).unwrap().to_rfc3339(),
+            "2025-03-04T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_search_date("2025-03-04", true).unwrap().to_rfc3339(),
+            "2025-03-04T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_search_date("2025-03-04T10:00:00+02:00", false)
+                .unwrap()
+                .to_rfc3339(),
+            "2025-03-04T08:00:00+00:00"
+        );
+        assert!(parse_search_date("yesterday", false).is_err());
+    }
+
+    #[test]
+    fn test_highlight_snippet_without_colors() {
+        console::set_colors_enabled(false);
+        assert_eq!(
+            highlight_snippet("a \u{1}match\u{2} on\nline"),
+            "a match on line"
+        );
+    }
+}
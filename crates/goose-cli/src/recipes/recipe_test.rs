@@ -0,0 +1,842 @@
+use anyhow::{anyhow, Context, Result};
+use console::style;
+use futures::StreamExt;
+use goose::agents::extension_manager::ExtensionManager;
+use goose::agents::mcp_cassette::{
+    snapshot_dir, McpCassette, MissedToolCalls, RecordingClient, ReplayingClient, SharedMcpCassette,
+};
+use goose::agents::{Agent, AgentEvent, SessionConfig};
+use goose::config::Config;
+use goose::conversation::message::{Message, MessageContent};
+use goose::model::ModelConfig;
+use goose::providers::base::Provider;
+use goose::providers::create;
+use goose::providers::testprovider::TestProvider;
+use goose::recipe::build_recipe::{build_recipe_from_template, RecipeError};
+use goose::recipe::read_recipe_file_content::RecipeFile;
+use goose::recipe::Recipe;
+use goose::session::session_manager::SessionType;
+use goose::session::SessionManager;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::recipes::print_recipe::missing_parameters_command_line;
+use crate::recipes::search_recipe::load_recipe_file;
+
+/// Suffix of the sidecar file declaring the test cases of a recipe, e.g. `release.test.yaml`
+pub const TEST_FILE_SUFFIX: &str = ".test.yaml";
+const CASSETTES_DIR: &str = "cassettes";
+/// Stands in for the temporary working directory of a case in its cassette
+const WORKING_DIR_PLACEHOLDER: &str = "${GOOSE_TEST_WORKING_DIR}";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecipeTestFile {
+    pub cases: Vec<TestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestCase {
+    pub name: String,
+    /// Values for the recipe's parameters
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// Directory copied into the working directory before the run, relative to the test file
+    #[serde(default)]
+    pub fixtures: Option<PathBuf>,
+    #[serde(default)]
+    pub expect: Expectations,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectations {
+    /// Substrings the final output must contain
+    #[serde(default)]
+    pub output_contains: Vec<String>,
+    /// Regular expression the final output must match
+    #[serde(default)]
+    pub output_matches: Option<String>,
+    /// Tools that must have been called, by full name or without the extension prefix
+    #[serde(default)]
+    pub tool_calls: Vec<String>,
+    /// Exact set of files added, modified or removed, relative to the working directory
+    #[serde(default)]
+    pub files_changed: Option<Vec<String>>,
+}
+
+/// Provider and extension traffic of one test case, replayed instead of calling them
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cassette {
+    provider: Value,
+    mcp: McpCassette,
+}
+
+/// A replay asked for provider responses or tool calls that its cassette has no recording of
+#[derive(Debug)]
+pub struct CassetteMismatch {
+    pub missing_responses: usize,
+    pub missing_tool_calls: Vec<String>,
+}
+
+impl fmt::Display for CassetteMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.missing_responses > 0 {
+            writeln!(
+                f,
+                "No recorded provider response for {} request(s)",
+                self.missing_responses
+            )?;
+        }
+        if !self.missing_tool_calls.is_empty() {
+            writeln!(
+                f,
+                "No recorded call for tool(s) {}",
+                format_list(&self.missing_tool_calls)
+            )?;
+        }
+        write!(
+            f,
+            "The recipe or test case changed since its cassette was recorded; re-record it with --record"
+        )
+    }
+}
+
+impl std::error::Error for CassetteMismatch {}
+
+/// What a recipe run produced, checked against the expectations of its test case
+#[derive(Debug, Default)]
+pub struct RunOutcome {
+    pub output: String,
+    pub tool_calls: Vec<String>,
+    pub files_changed: Vec<String>,
+}
+
+pub fn test_file_path(recipe_file: &RecipeFile) -> PathBuf {
+    recipe_file
+        .parent_dir
+        .join(format!("{}{}", recipe_stem(recipe_file), TEST_FILE_SUFFIX))
+}
+
+fn recipe_stem(recipe_file: &RecipeFile) -> String {
+    recipe_file
+        .file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "recipe".to_string())
+}
+
+fn cassette_path(recipe_file: &RecipeFile, case: &TestCase) -> PathBuf {
+    recipe_file
+        .parent_dir
+        .join(CASSETTES_DIR)
+        .join(recipe_stem(recipe_file))
+        .join(format!("{}.json", case.name))
+}
+
+pub fn parse_test_file(content: &str) -> Result<RecipeTestFile> {
+    let test_file: RecipeTestFile = serde_yaml::from_str(content)?;
+    let mut names = BTreeSet::new();
+    for case in &test_file.cases {
+        if !names.insert(case.name.as_str()) {
+            return Err(anyhow!("Duplicate test case name: {}", case.name));
+        }
+    }
+    Ok(test_file)
+}
+
+/// Check a run against the expectations of its case and the recipe's response schema,
+/// returning a reason for each expectation that was not met
+pub fn check_outcome(
+    expect: &Expectations,
+    json_schema: Option<&Value>,
+    outcome: &RunOutcome,
+) -> Result<Vec<String>> {
+    let mut failures = Vec::new();
+
+    for expected in &expect.output_contains {
+        if !outcome.output.contains(expected) {
+            failures.push(format!("output does not contain {:?}", expected));
+        }
+    }
+
+    if let Some(pattern) = &expect.output_matches {
+        let regex = Regex::new(pattern)
+            .with_context(|| format!("Invalid output_matches pattern {:?}", pattern))?;
+        if !regex.is_match(&outcome.output) {
+            failures.push(format!("output does not match /{}/", pattern));
+        }
+    }
+
+    if let Some(schema) = json_schema {
+        match serde_json::from_str::<Value>(&outcome.output) {
+            Ok(output) => {
+                let validator = jsonschema::validator_for(schema)
+                    .map_err(|e| anyhow!("Invalid response.json_schema: {}", e))?;
+                failures.extend(validator.iter_errors(&output).map(|error| {
+                    format!(
+                        "output does not match response.json_schema at '{}': {}",
+                        error.instance_path, error
+                    )
+                }));
+            }
+            Err(e) => failures.push(format!("output is not valid JSON: {}", e)),
+        }
+    }
+
+    for expected in &expect.tool_calls {
+        let called = outcome.tool_calls.iter().any(|name| {
+            name == expected
+                || name
+                    .rsplit_once("__")
+                    .is_some_and(|(_, tool)| tool == expected)
+        });
+        if !called {
+            failures.push(format!(
+                "tool {} was not called (called: {})",
+                expected,
+                format_list(&outcome.tool_calls)
+            ));
+        }
+    }
+
+    if let Some(expected) = &expect.files_changed {
+        let expected: BTreeSet<&String> = expected.iter().collect();
+        let actual: BTreeSet<&String> = outcome.files_changed.iter().collect();
+        if expected != actual {
+            failures.push(format!(
+                "files changed were {}, expected {}",
+                format_list(actual),
+                format_list(expected)
+            ));
+        }
+    }
+
+    Ok(failures)
+}
+
+fn format_list<I, S>(items: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let items: Vec<String> = items
+        .into_iter()
+        .map(|item| item.as_ref().to_string())
+        .collect();
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+/// Run the test cases declared next to a recipe. With `record`, the cases run against the
+/// configured provider and extensions and their cassettes are rewritten; otherwise they
+/// replay the cassettes. Returns whether every case passed.
+pub async fn run_recipe_tests(
+    recipe_name: &str,
+    case_filter: Option<&str>,
+    record: bool,
+) -> Result<bool> {
+    let recipe_file = load_recipe_file(recipe_name)?;
+    let test_path = test_file_path(&recipe_file);
+    let content = fs::read_to_string(&test_path)
+        .with_context(|| format!("No test cases found at {}", test_path.display()))?;
+    let test_file = parse_test_file(&content)
+        .with_context(|| format!("Invalid test file {}", test_path.display()))?;
+
+    let cases: Vec<&TestCase> = test_file
+        .cases
+        .iter()
+        .filter(|case| case_filter.is_none_or(|name| case.name == name))
+        .collect();
+    if cases.is_empty() {
+        return Err(anyhow!(
+            "No test case named {} in {}",
+            case_filter.unwrap_or_default(),
+            test_path.display()
+        ));
+    }
+
+    let original_dir = std::env::current_dir()?;
+    let mut passed = 0;
+    for case in &cases {
+        let result = run_case(&recipe_file, &test_path, case, record).await;
+        std::env::set_current_dir(&original_dir)?;
+
+        match result {
+            Ok(failures) if failures.is_empty() => {
+                passed += 1;
+                println!("{} {}", style("✓").green().bold(), case.name);
+            }
+            Ok(failures) => {
+                println!("{} {}", style("✗").red().bold(), case.name);
+                for failure in failures {
+                    println!("    {}", failure);
+                }
+            }
+            Err(e) => {
+                println!("{} {}", style("✗").red().bold(), case.name);
+                println!("    {}", e);
+            }
+        }
+    }
+
+    println!("\n{} of {} test cases passed", passed, cases.len());
+    Ok(passed == cases.len())
+}
+
+async fn run_case(
+    recipe_file: &RecipeFile,
+    test_path: &Path,
+    case: &TestCase,
+    record: bool,
+) -> Result<Vec<String>> {
+    let working_dir = tempfile::tempdir()?;
+    if let Some(fixtures) = &case.fixtures {
+        let fixtures = test_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(fixtures);
+        copy_dir(&fixtures, working_dir.path())
+            .with_context(|| format!("Failed to copy fixtures from {}", fixtures.display()))?;
+    }
+    std::env::set_current_dir(working_dir.path())?;
+
+    let recipe = build_case_recipe(recipe_file, case)?;
+    let recorder = if record {
+        Some(configured_provider(&recipe).await?)
+    } else {
+        None
+    };
+    run_case_in(recipe_file, case, &recipe, working_dir.path(), recorder).await
+}
+
+/// Run a case in `working_dir`, recording it against `recorder` when given one and replaying
+/// its cassette otherwise. A recording is only saved when it meets the case's expectations,
+/// so a failed run never replaces a good cassette.
+async fn run_case_in(
+    recipe_file: &RecipeFile,
+    case: &TestCase,
+    recipe: &Recipe,
+    working_dir: &Path,
+    recorder: Option<Arc<dyn Provider>>,
+) -> Result<Vec<String>> {
+    let cassette_path = cassette_path(recipe_file, case);
+    let json_schema = recipe
+        .response
+        .as_ref()
+        .and_then(|response| response.json_schema.as_ref());
+
+    match recorder {
+        Some(inner) => {
+            let (outcome, cassette) = record_case(recipe, working_dir, inner).await?;
+            let mut failures = check_outcome(&case.expect, json_schema, &outcome)?;
+            if failures.is_empty() {
+                save_cassette(&cassette_path, &cassette, working_dir)?;
+            } else {
+                failures.push(format!(
+                    "Cassette not saved to {} because the run did not meet its expectations",
+                    cassette_path.display()
+                ));
+            }
+            Ok(failures)
+        }
+        None => {
+            let cassette = load_cassette(&cassette_path, working_dir)?;
+            let outcome = replay_case(recipe, working_dir, cassette).await?;
+            check_outcome(&case.expect, json_schema, &outcome)
+        }
+    }
+}
+
+fn build_case_recipe(recipe_file: &RecipeFile, case: &TestCase) -> Result<Recipe> {
+    let params = case
+        .params
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    build_recipe_from_template(
+        recipe_file.content.clone(),
+        &recipe_file.parent_dir,
+        params,
+        None::<fn(&str, &str) -> Result<String>>,
+    )
+    .map_err(|e| match e {
+        RecipeError::MissingParams { parameters } => anyhow!(
+            "Please provide the following params in the test case: {}",
+            missing_parameters_command_line(parameters)
+        ),
+        e => anyhow!(e.to_string()),
+    })
+}
+
+/// The provider and model the recipe would run with outside of tests
+async fn configured_provider(recipe: &Recipe) -> Result<Arc<dyn Provider>> {
+    let config = Config::global();
+    let settings = recipe.settings.as_ref();
+    let provider_name = settings
+        .and_then(|s| s.goose_provider.clone())
+        .map_or_else(|| config.get_goose_provider(), Ok)?;
+    let model_name = settings
+        .and_then(|s| s.goose_model.clone())
+        .map_or_else(|| config.get_goose_model(), Ok)?;
+    let model_config =
+        ModelConfig::new(&model_name)?.with_temperature(settings.and_then(|s| s.temperature));
+    create(&provider_name, model_config).await
+}
+
+async fn record_case(
+    recipe: &Recipe,
+    working_dir: &Path,
+    inner: Arc<dyn Provider>,
+) -> Result<(RunOutcome, Cassette)> {
+    let provider = Arc::new(TestProvider::new_recording(inner, ""));
+
+    let agent = Agent::new();
+    for extension in recipe.extensions.iter().flatten() {
+        agent
+            .add_extension(extension.clone())
+            .await
+            .map_err(|e| anyhow!("Failed to start extension {}: {}", extension.name(), e))?;
+    }
+    let mcp = SharedMcpCassette::default();
+    agent
+        .extension_manager
+        .wrap_clients(|name, client, info| {
+            Arc::new(Mutex::new(Box::new(
+                RecordingClient::new(name, client, info, mcp.clone())
+                    .with_working_dir(working_dir.to_path_buf()),
+            )))
+        })
+        .await;
+
+    let before = snapshot_dir(working_dir)?;
+    let mut outcome = run_agent(&agent, provider.clone(), recipe, working_dir).await?;
+    outcome.files_changed = changed_files(&before, &snapshot_dir(working_dir)?);
+
+    let cassette = Cassette {
+        provider: serde_json::from_str(&provider.records_json()?)?,
+        mcp: mcp.lock().unwrap().clone(),
+    };
+    Ok((outcome, cassette))
+}
+
+async fn replay_case(
+    recipe: &Recipe,
+    working_dir: &Path,
+    cassette: Cassette,
+) -> Result<RunOutcome> {
+    let provider = Arc::new(TestProvider::new_replaying_from_str(
+        &serde_json::to_string(&cassette.provider)?,
+    )?);
+
+    let missed_calls = MissedToolCalls::default();
+    let agent = Agent::new();
+    for extension in recipe.extensions.iter().flatten() {
+        let name = ExtensionManager::extension_name(extension);
+        let recorded = cassette
+            .mcp
+            .extensions
+            .get(&name)
+            .cloned()
+            .unwrap_or_default();
+        let info = recorded.info.clone();
+        let client = ReplayingClient::new(recorded)
+            .with_working_dir(working_dir.to_path_buf())
+            .with_missed_calls(missed_calls.clone());
+        agent
+            .extension_manager
+            .add_client(
+                name,
+                extension.clone(),
+                Arc::new(Mutex::new(Box::new(client))),
+                info,
+                None,
+            )
+            .await;
+    }
+
+    let before = snapshot_dir(working_dir)?;
+    let result = run_agent(&agent, provider.clone(), recipe, working_dir).await;
+
+    let missing_responses = provider.missing_records().len();
+    let missing_tool_calls = missed_calls.lock().unwrap().clone();
+    if missing_responses > 0 || !missing_tool_calls.is_empty() {
+        return Err(CassetteMismatch {
+            missing_responses,
+            missing_tool_calls,
+        }
+        .into());
+    }
+
+    let mut outcome = result?;
+    outcome.files_changed = changed_files(&before, &snapshot_dir(working_dir)?);
+    Ok(outcome)
+}
+
+async fn run_agent(
+    agent: &Agent,
+    provider: Arc<dyn Provider>,
+    recipe: &Recipe,
+    working_dir: &Path,
+) -> Result<RunOutcome> {
+    agent.update_provider(provider).await?;
+    agent
+        .apply_recipe_components(recipe.sub_recipes.clone(), recipe.response.clone(), true)
+        .await;
+
+    // Recipes without a prompt are run with their instructions as the request, like `goose run`
+    let prompt = match recipe.prompt.as_ref().filter(|p| !p.trim().is_empty()) {
+        Some(prompt) => {
+            if let Some(instructions) = &recipe.instructions {
+                agent.extend_system_prompt(instructions.clone()).await;
+            }
+            prompt.clone()
+        }
+        None => recipe
+            .instructions
+            .clone()
+            .ok_or_else(|| anyhow!("Recipe has neither a prompt nor instructions to test"))?,
+    };
+
+    let session = SessionManager::create_session(
+        working_dir.to_path_buf(),
+        format!("recipe test: {}", recipe.title),
+        SessionType::Hidden,
+    )
+    .await?;
+    // A user-set name stops the agent from asking the provider for one in the background,
+    // which would make the recorded requests depend on timing
+    SessionManager::update_session(&session.id)
+        .user_provided_name(session.name.clone())
+        .apply()
+        .await?;
+    let session_config = SessionConfig {
+        id: session.id.clone(),
+        schedule_id: None,
+        max_turns: None,
+        retry_config: recipe.retry.clone(),
+    };
+
+    let result = collect_outcome(agent, Message::user().with_text(prompt), session_config).await;
+    let _ = SessionManager::delete_session(&session.id).await;
+    result
+}
+
+async fn collect_outcome(
+    agent: &Agent,
+    message: Message,
+    session_config: SessionConfig,
+) -> Result<RunOutcome> {
+    let mut outcome = RunOutcome::default();
+    let mut stream = agent.reply(message, session_config, None).await?;
+    while let Some(event) = stream.next().await {
+        let AgentEvent::Message(message) = event? else {
+            continue;
+        };
+        for content in &message.content {
+            if let MessageContent::ToolRequest(request) = content {
+                if let Ok(call) = &request.tool_call {
+                    outcome.tool_calls.push(call.name.to_string());
+                }
+            }
+        }
+        let text = message.as_concat_text();
+        if message.role == rmcp::model::Role::Assistant && !text.trim().is_empty() {
+            outcome.output = text;
+        }
+    }
+    Ok(outcome)
+}
+
+fn load_cassette(path: &Path, working_dir: &Path) -> Result<Cassette> {
+    let content = fs::read_to_string(path).map_err(|_| {
+        anyhow!(
+            "No cassette at {}. Record one with --record",
+            path.display()
+        )
+    })?;
+    let content = content.replace(WORKING_DIR_PLACEHOLDER, &json_escaped_path(working_dir));
+    serde_json::from_str(&content).with_context(|| format!("Invalid cassette {}", path.display()))
+}
+
+fn save_cassette(path: &Path, cassette: &Cassette, working_dir: &Path) -> Result<()> {
+    let mut content = serde_json::to_string_pretty(cassette)?;
+    let canonical = working_dir.canonicalize()?;
+    // Replace the longer form first in case one path is a prefix of the other
+    let mut dirs = [
+        json_escaped_path(working_dir),
+        json_escaped_path(&canonical),
+    ];
+    dirs.sort_by_key(|dir| std::cmp::Reverse(dir.len()));
+    for dir in dirs {
+        content = content.replace(&dir, WORKING_DIR_PLACEHOLDER);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn json_escaped_path(path: &Path) -> String {
+    let quoted = serde_json::to_string(&path.to_string_lossy()).unwrap_or_default();
+    quoted.trim_matches('"').to_string()
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn changed_files(
+    before: &BTreeMap<String, Vec<u8>>,
+    after: &BTreeMap<String, Vec<u8>>,
+) -> Vec<String> {
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| before.get(*path) != after.get(*path))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use goose::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+    use rmcp::model::Tool;
+    use serde_json::json;
+
+    /// Answers every request with the same text
+    struct FixedProvider(String);
+
+    #[async_trait]
+    impl Provider for FixedProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::new("fixed", "Fixed", "", "fixed-model", vec![], "", vec![])
+        }
+
+        fn get_name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text(&self.0),
+                ProviderUsage::new("fixed-model".to_string(), Usage::default()),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("fixed-model")
+        }
+    }
+
+    #[test]
+    fn test_parse_test_file() {
+        let test_file = parse_test_file(
+            r#"
+cases:
+  - name: summary
+    params:
+      topic: rust
+    fixtures: fixtures/basic
+    expect:
+      output_contains: ["Summary"]
+      tool_calls: [shell]
+      files_changed: [notes.md]
+  - name: empty
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(test_file.cases.len(), 2);
+        let case = &test_file.cases[0];
+        assert_eq!(case.params["topic"], "rust");
+        assert_eq!(case.fixtures, Some(PathBuf::from("fixtures/basic")));
+        assert_eq!(case.expect.tool_calls, vec!["shell"]);
+        assert!(test_file.cases[1].expect.files_changed.is_none());
+
+        assert!(parse_test_file("cases:\n  - name: a\n    expected: {}\n").is_err());
+        assert!(parse_test_file("cases:\n  - name: a\n  - name: a\n").is_err());
+    }
+
+    #[test]
+    fn test_check_outcome() {
+        let expect = Expectations {
+            output_contains: vec!["\"status\"".to_string()],
+            output_matches: Some(r#""count":\s*\d+"#.to_string()),
+            tool_calls: vec!["shell".to_string(), "developer__text_editor".to_string()],
+            files_changed: Some(vec!["b.txt".to_string(), "a.txt".to_string()]),
+        };
+        let schema = json!({
+            "type": "object",
+            "properties": {"status": {"type": "string"}, "count": {"type": "integer"}},
+            "required": ["status", "count"]
+        });
+        let outcome = RunOutcome {
+            output: r#"{"status":"ok","count":3}"#.to_string(),
+            tool_calls: vec![
+                "developer__shell".to_string(),
+                "developer__text_editor".to_string(),
+            ],
+            files_changed: vec!["a.txt".to_string(), "b.txt".to_string()],
+        };
+        assert!(check_outcome(&expect, Some(&schema), &outcome)
+            .unwrap()
+            .is_empty());
+
+        let outcome = RunOutcome {
+            output: r#"{"status":"ok"}"#.to_string(),
+            tool_calls: vec!["developer__text_editor".to_string()],
+            files_changed: vec!["a.txt".to_string()],
+        };
+        let failures = check_outcome(&expect, Some(&schema), &outcome).unwrap();
+        assert_eq!(failures.len(), 4, "{:?}", failures);
+        assert!(failures[0].starts_with("output does not match /"));
+        assert!(failures[1].contains("response.json_schema"));
+        assert!(failures[2].starts_with("tool shell was not called"));
+        assert_eq!(
+            failures[3],
+            "files changed were a.txt, expected a.txt, b.txt"
+        );
+    }
+
+    #[test]
+    fn test_cassette_paths_are_portable() {
+        let recorded_dir = tempfile::tempdir().unwrap();
+        let replay_dir = tempfile::tempdir().unwrap();
+        let path = recorded_dir.path().join("cassette.json");
+        let cassette = Cassette {
+            provider: json!({
+                "input": format!("Edit {}/notes.md", recorded_dir.path().display())
+            }),
+            ..Default::default()
+        };
+
+        save_cassette(&path, &cassette, recorded_dir.path()).unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains(WORKING_DIR_PLACEHOLDER));
+
+        let replayed = load_cassette(&path, replay_dir.path()).unwrap();
+        assert_eq!(
+            replayed.provider["input"],
+            format!("Edit {}/notes.md", replay_dir.path().display())
+        );
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("kept.txt"), "same").unwrap();
+        fs::write(dir.path().join("edited.txt"), "before").unwrap();
+        fs::write(dir.path().join("removed.txt"), "gone").unwrap();
+        let before = snapshot_dir(dir.path()).unwrap();
+
+        fs::write(dir.path().join("edited.txt"), "after").unwrap();
+        fs::remove_file(dir.path().join("removed.txt")).unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/added.md"), "new").unwrap();
+
+        assert_eq!(
+            changed_files(&before, &snapshot_dir(dir.path()).unwrap()),
+            vec!["docs/added.md", "edited.txt", "removed.txt"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_case() {
+        let recipe_dir = tempfile::tempdir().unwrap();
+        let recipe_file = RecipeFile {
+            content: "version: 1.0.0\ntitle: Greeter\ndescription: Greets\nprompt: Say hello\n"
+                .to_string(),
+            parent_dir: recipe_dir.path().to_path_buf(),
+            file_path: recipe_dir.path().join("greeter.yaml"),
+        };
+        let test_file = parse_test_file(
+            "cases:\n  - name: hello\n    expect:\n      output_contains: [Hello]\n      files_changed: []\n",
+        )
+        .unwrap();
+        let case = &test_file.cases[0];
+        let recipe = build_case_recipe(&recipe_file, case).unwrap();
+        let cassette_path = cassette_path(&recipe_file, case);
+
+        let failing: Arc<dyn Provider> = Arc::new(FixedProvider("Goodbye".to_string()));
+        let working_dir = tempfile::tempdir().unwrap();
+        let failures = run_case_in(
+            &recipe_file,
+            case,
+            &recipe,
+            working_dir.path(),
+            Some(failing),
+        )
+        .await
+        .unwrap();
+        assert_eq!(failures.len(), 2, "{:?}", failures);
+        assert!(failures[1].starts_with("Cassette not saved"));
+        assert!(!cassette_path.exists());
+
+        let provider: Arc<dyn Provider> = Arc::new(FixedProvider("Hello there".to_string()));
+        let working_dir = tempfile::tempdir().unwrap();
+        let failures = run_case_in(
+            &recipe_file,
+            case,
+            &recipe,
+            working_dir.path(),
+            Some(provider),
+        )
+        .await
+        .unwrap();
+        assert!(failures.is_empty(), "{:?}", failures);
+        assert!(cassette_path.exists());
+
+        let working_dir = tempfile::tempdir().unwrap();
+        let failures = run_case_in(&recipe_file, case, &recipe, working_dir.path(), None)
+            .await
+            .unwrap();
+        assert!(failures.is_empty(), "{:?}", failures);
+
+        let changed = RecipeFile {
+            content: recipe_file.content.replace("Say hello", "Say goodbye"),
+            ..recipe_file
+        };
+        let recipe = build_case_recipe(&changed, case).unwrap();
+        let error = run_case_in(&changed, case, &recipe, working_dir.path(), None)
+            .await
+            .unwrap_err();
+        let mismatch = error.downcast_ref::<CassetteMismatch>().unwrap();
+        assert!(mismatch.missing_responses > 0);
+    }
+}
@@ -7,12 +7,13 @@ use goose::config::{
     extensions::{get_extension_by_name, set_extension, ExtensionEntry},
     get_all_extensions, get_enabled_extensions, Config, ExtensionConfig,
 };
-use goose::providers::create;
+use goose::context_mgmt::ContextStrategyKind;
+use goose::providers::create_session_provider;
 use goose::recipe::{Response, SubRecipe};
 
 use goose::agents::extension::PlatformExtensionContext;
 use goose::session::session_manager::SessionType;
-use goose::session::SessionManager;
+use goose::session::{Budget, SessionManager};
 use goose::session::{EnabledExtensionsState, ExtensionState};
 use rustyline::EditMode;
 use std::collections::HashSet;
@@ -56,6 +57,8 @@ pub struct SessionBuilderConfig {
     pub max_tool_repetitions: Option<u32>,
     /// Maximum number of turns (iterations) allowed without user input
     pub max_turns: Option<u32>,
+    /// Spending limits for this session
+    pub budget: Option<Budget>,
     /// ID of the scheduled job that triggered this session (if any)
     pub scheduled_job_id: Option<String>,
     /// Whether this session will be used interactively (affects debugging prompts)
@@ -92,6 +95,7 @@ impl Default for SessionBuilderConfig {
             debug: false,
             max_tool_repetitions: None,
             max_turns: None,
+            budget: None,
             scheduled_job_id: None,
             interactive: false,
             quiet: false,
@@ -243,6 +247,8 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub context_strategy: Option<ContextStrategyKind>,
+    pub budget: Option<Budget>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
@@ -290,8 +296,16 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
             true,
         )
         .await;
+    agent
+        .set_context_strategy(
+            session_config
+                .settings
+                .as_ref()
+                .and_then(|s| s.context_strategy),
+        )
+        .await;
 
-    let new_provider = match create(&provider_name, model_config).await {
+    let new_provider = match create_session_provider(&provider_name, model_config).await {
         Ok(provider) => provider,
         Err(e) => {
             output::render_error(&format!(
@@ -371,6 +385,23 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
         })
         .await;
 
+    let recipe_budget = session_config.settings.as_ref().and_then(|s| s.budget);
+    if let Some(budget) = session_config
+        .budget
+        .into_iter()
+        .chain(recipe_budget)
+        .reduce(Budget::merge)
+    {
+        if let Err(e) = SessionManager::update_session(&session_id)
+            .budget(Some(budget))
+            .apply()
+            .await
+        {
+            output::render_error(&format!("Failed to set session budget: {}", e));
+            process::exit(1);
+        }
+    }
+
     if session_config.resume {
         let session = SessionManager::get_session(&session_id, false)
             .await
@@ -655,6 +686,15 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> CliSession {
             Some(&provider_for_display),
         );
     }
+
+    if session_config.resume {
+        if let Err(e) = session
+            .check_interrupted_turn(session_config.interactive)
+            .await
+        {
+            output::render_error(&format!("Failed to recover interrupted turn: {}", e));
+        }
+    }
     session
 }
 
@@ -680,6 +720,7 @@ mod tests {
             debug: true,
             max_tool_repetitions: Some(5),
             max_turns: None,
+            budget: None,
             scheduled_job_id: None,
             interactive: true,
             quiet: false,
@@ -21,6 +21,7 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Compact,
+    Rewind(Option<usize>),
 }
 
 #[derive(Debug)]
@@ -122,6 +123,7 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_COMPACT: &str = "/compact";
+    const CMD_REWIND: &str = "/rewind";
     const CMD_SUMMARIZE_DEPRECATED: &str = "/summarize";
 
     match input {
@@ -185,6 +187,21 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_COMPACT => Some(InputResult::Compact),
+        s if s == CMD_REWIND => Some(InputResult::Rewind(None)),
+        s if s.starts_with("/rewind ") => {
+            match s
+                .get(CMD_REWIND.len()..)
+                .unwrap_or("")
+                .trim()
+                .parse::<usize>()
+            {
+                Ok(steps) if steps > 0 => Some(InputResult::Rewind(Some(steps))),
+                _ => {
+                    println!("Usage: /rewind [number of prompts to go back]");
+                    Some(InputResult::Retry)
+                }
+            }
+        }
         s if s == CMD_SUMMARIZE_DEPRECATED => {
             println!("{}", console::style("⚠️  Note: /summarize has been renamed to /compact and will be removed in a future release.").yellow());
             Some(InputResult::Compact)
@@ -314,6 +331,8 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /compact - Compact the current conversation to reduce context length while preserving key information.
+/rewind [n] - Go back to before one of your earlier prompts (or n prompts back) and continue from there in a new session.
+              The original session is kept, and text editor changes made since then can optionally be reverted.
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
@@ -374,6 +393,26 @@ mod tests {
         assert!(handle_slash_command("/unknown").is_none());
     }
 
+    #[test]
+    fn test_rewind_command() {
+        assert!(matches!(
+            handle_slash_command("/rewind"),
+            Some(InputResult::Rewind(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind 2"),
+            Some(InputResult::Rewind(Some(2)))
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind 0"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind back"),
+            Some(InputResult::Retry)
+        ));
+    }
+
     #[test]
     fn test_prompts_command() {
         // Test basic prompts command
@@ -33,14 +33,14 @@ use goose::agents::types::RetryConfig;
 use goose::agents::{Agent, SessionConfig, MANUAL_COMPACT_TRIGGER};
 use goose::config::{Config, GooseMode};
 use goose::providers::pricing::initialize_pricing_cache;
-use goose::session::SessionManager;
+use goose::session::{SessionManager, TurnPhase, TurnRecovery, INTERRUPTED_TOOL_CALL_REASON};
 use input::InputResult;
 use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
 use rmcp::model::{ErrorCode, ErrorData};
 
 use goose::config::paths::Paths;
-use goose::conversation::message::{Message, MessageContent};
+use goose::conversation::message::{Message, MessageContent, MessageEvent};
 use rand::{distributions::Alphanumeric, Rng};
 use rustyline::EditMode;
 use serde::{Deserialize, Serialize};
@@ -82,6 +82,8 @@ pub struct CliSession {
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
     output_format: String,
+    /// Rerun the interrupted last turn before handling the next message
+    rerun_interrupted_turn: bool,
 }
 
 // Cache structure for completion data
@@ -190,6 +192,7 @@ impl CliSession {
             edit_mode,
             retry_config,
             output_format,
+            rerun_interrupted_turn: false,
         }
     }
 
@@ -402,8 +405,95 @@ impl CliSession {
         Ok(())
     }
 
+    /// Check whether the last turn of a resumed session was interrupted, for example because
+    /// goose was killed while a tool was running, and let the user choose how to continue.
+    /// Without a terminal to ask on, the interrupted tool calls are marked as failed.
+    pub async fn check_interrupted_turn(&mut self, interactive: bool) -> Result<()> {
+        let Some(journal) = SessionManager::get_turn_journal(&self.session_id).await? else {
+            return Ok(());
+        };
+
+        let interrupted = journal.interrupted_tool_requests();
+        let activity = match journal.phase {
+            TurnPhase::AwaitingResponse => "while waiting for the model to respond".to_string(),
+            TurnPhase::RunningTools => {
+                let tool_names: Vec<String> = interrupted
+                    .iter()
+                    .filter_map(|request| request.tool_call.as_ref().ok())
+                    .map(|tool_call| tool_call.name.to_string())
+                    .collect();
+                format!("while running {}", tool_names.join(", "))
+            }
+        };
+        eprintln!(
+            "{}",
+            console::style(format!(
+                "The last turn of this session was interrupted {}.",
+                activity
+            ))
+            .yellow()
+        );
+
+        let recovery = if interactive {
+            let (rerun_label, keep_label) = if interrupted.is_empty() {
+                ("Continue", "Keep the conversation as it is")
+            } else {
+                ("Rerun the interrupted tool calls", "Mark them as failed")
+            };
+            cliclack::select("How would you like to continue?")
+                .item(
+                    TurnRecovery::Rerun,
+                    rerun_label,
+                    "and let goose finish the turn",
+                )
+                .item(
+                    TurnRecovery::MarkFailed,
+                    keep_label,
+                    "keep the work done so far",
+                )
+                .item(
+                    TurnRecovery::Rollback,
+                    "Roll back the turn",
+                    "remove your last message and everything after it",
+                )
+                .interact()
+                .unwrap_or(TurnRecovery::MarkFailed)
+        } else {
+            TurnRecovery::MarkFailed
+        };
+
+        if recovery == TurnRecovery::Rerun {
+            self.rerun_interrupted_turn = true;
+            return Ok(());
+        }
+
+        let rolled_back = SessionManager::resolve_interrupted_turn(
+            &self.session_id,
+            recovery,
+            INTERRUPTED_TOOL_CALL_REASON,
+        )
+        .await?;
+        self.messages = SessionManager::get_session(&self.session_id, true)
+            .await?
+            .conversation
+            .unwrap_or_default();
+        if let Some(message) = rolled_back {
+            eprintln!(
+                "{} {}",
+                console::style("Rolled back your last message:").dim(),
+                message.as_concat_text()
+            );
+        }
+        Ok(())
+    }
+
     /// Start an interactive session, optionally with an initial message
     pub async fn interactive(&mut self, prompt: Option<String>) -> Result<()> {
+        if self.rerun_interrupted_turn {
+            self.process_agent_response(true, CancellationToken::default())
+                .await?;
+        }
+
         // Process initial message if provided
         if let Some(prompt) = prompt {
             let msg = Message::user().with_text(&prompt);
@@ -701,6 +791,11 @@ impl CliSession {
                     }
                     continue;
                 }
+                InputResult::Rewind(steps) => {
+                    save_history(&mut editor);
+                    self.handle_rewind(steps).await?;
+                    continue;
+                }
             }
         }
 
@@ -789,6 +884,11 @@ impl CliSession {
 
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
+        if self.rerun_interrupted_turn {
+            self.process_agent_response(false, CancellationToken::default())
+                .await?;
+        }
+
         let message = Message::user().with_text(&prompt);
         self.process_message(message, CancellationToken::default())
             .await?;
@@ -802,6 +902,9 @@ impl CliSession {
     ) -> Result<()> {
         // Cache the output format check to avoid repeated string comparisons in the hot loop
         let is_json_mode = self.output_format == "json";
+        let is_stream_json = self.output_format == "stream-json";
+        let is_structured_output = is_json_mode || is_stream_json;
+        let mut finish_reason = "stop";
 
         let session_config = SessionConfig {
             id: self.session_id.clone(),
@@ -809,11 +912,6 @@ impl CliSession {
             max_turns: self.max_turns,
             retry_config: self.retry_config.clone(),
         };
-        let user_message = self
-            .messages
-            .last()
-            .ok_or_else(|| anyhow::anyhow!("No user message"))?;
-
         let cancel_token_interrupt = cancel_token.clone();
         let handle = tokio::spawn(async move {
             if ctrl_c().await.is_ok() {
@@ -822,14 +920,23 @@ impl CliSession {
         });
         let _drop_handle = AbortOnDropHandle::new(handle);
 
-        let mut stream = self
-            .agent
-            .reply(
-                user_message.clone(),
-                session_config.clone(),
-                Some(cancel_token.clone()),
-            )
-            .await?;
+        let mut stream = if std::mem::take(&mut self.rerun_interrupted_turn) {
+            self.agent
+                .rerun_interrupted_turn(session_config.clone(), Some(cancel_token.clone()))
+                .await?
+        } else {
+            let user_message = self
+                .messages
+                .last()
+                .ok_or_else(|| anyhow::anyhow!("No user message"))?;
+            self.agent
+                .reply(
+                    user_message.clone(),
+                    session_config.clone(),
+                    Some(cancel_token.clone()),
+                )
+                .await?
+        };
 
         let mut progress_bars = output::McpSpinners::new();
         let cancel_token_clone = cancel_token.clone();
@@ -840,6 +947,13 @@ impl CliSession {
                 result = stream.next() => {
                     match result {
                         Some(Ok(AgentEvent::Message(message))) => {
+                            if is_stream_json {
+                                self.emit_stream_event(MessageEvent::Message {
+                                    message: message.clone(),
+                                    token_state: SessionManager::get_token_state(&self.session_id).await,
+                                });
+                            }
+
                             // If it's a confirmation request, get approval but otherwise do not render/persist
                             if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
@@ -951,12 +1065,18 @@ impl CliSession {
                                 let _ = progress_bars.hide();
 
                                 // Don't render in JSON mode
-                                if !is_json_mode {
+                                if !is_structured_output {
                                     output::render_message(&message, self.debug);
                                 }
                             }
                         }
-                        Some(Ok(AgentEvent::McpNotification((_id, message)))) => {
+                        Some(Ok(AgentEvent::McpNotification((request_id, message)))) => {
+                            if is_stream_json {
+                                self.emit_stream_event(MessageEvent::Notification {
+                                    request_id,
+                                    message: message.clone(),
+                                });
+                            }
                             match &message {
                                 ServerNotification::LoggingMessageNotification(notification) => {
                                     let data = &notification.params.data;
@@ -1025,21 +1145,21 @@ impl CliSession {
                                         // TODO: proper display for subagent notifications
                                         if interactive {
                                             let _ = progress_bars.hide();
-                                            if !is_json_mode {
+                                            if !is_structured_output {
                                                 println!("{}", console::style(&formatted_message).green().dim());
                                             }
-                                        } else if !is_json_mode {
+                                        } else if !is_structured_output {
                                             progress_bars.log(&formatted_message);
                                         }
                                     } else if let Some(ref notification_type) = message_notification_type {
                                         if notification_type == TASK_EXECUTION_NOTIFICATION_TYPE {
                                             if interactive {
                                                 let _ = progress_bars.hide();
-                                                if !is_json_mode {
+                                                if !is_structured_output {
                                                     print!("{}", formatted_message);
                                                     std::io::stdout().flush().unwrap();
                                                 }
-                                            } else if !is_json_mode {
+                                            } else if !is_structured_output {
                                                 print!("{}", formatted_message);
                                                 std::io::stdout().flush().unwrap();
                                             }
@@ -1067,9 +1187,20 @@ impl CliSession {
                             }
                         }
                         Some(Ok(AgentEvent::HistoryReplaced(updated_conversation))) => {
+                            if is_stream_json {
+                                self.emit_stream_event(MessageEvent::UpdateConversation {
+                                    conversation: updated_conversation.clone(),
+                                });
+                            }
                             self.messages = updated_conversation;
                         }
                         Some(Ok(AgentEvent::ModelChange { model, mode })) => {
+                            if is_stream_json {
+                                self.emit_stream_event(MessageEvent::ModelChange {
+                                    model: model.clone(),
+                                    mode: mode.clone(),
+                                });
+                            }
                             // Log model change if in debug mode
                             if self.debug {
                                 eprintln!("Model changed to {} in {} mode", model, mode);
@@ -1077,6 +1208,12 @@ impl CliSession {
                         }
 
                         Some(Err(e)) => {
+                            finish_reason = "error";
+                            if is_stream_json {
+                                self.emit_stream_event(MessageEvent::Error {
+                                    error: e.to_string(),
+                                });
+                            }
                             // TODO(Douwe): Delete this
                             // Check if it's a ProviderError::ContextLengthExceeded
                             if e.downcast_ref::<goose::providers::errors::ProviderError>()
@@ -1109,6 +1246,7 @@ impl CliSession {
                     }
                 }
                 _ = cancel_token_clone.cancelled() => {
+                    finish_reason = "interrupted";
                     drop(stream);
                     if let Err(e) = self.handle_interrupted_messages(true).await {
                         eprintln!("Error handling interruption: {}", e);
@@ -1137,6 +1275,11 @@ impl CliSession {
             };
 
             println!("{}", serde_json::to_string_pretty(&json_output)?);
+        } else if is_stream_json {
+            self.emit_stream_event(MessageEvent::Finish {
+                reason: finish_reason.to_string(),
+                token_state: SessionManager::get_token_state(&self.session_id).await,
+            });
         } else {
             println!();
         }
@@ -1144,6 +1287,17 @@ impl CliSession {
         Ok(())
     }
 
+    /// Print one line of `--output-format stream-json` output
+    fn emit_stream_event(&self, event: MessageEvent) {
+        match serde_json::to_string(&event) {
+            Ok(json) => {
+                println!("{}", json);
+                let _ = std::io::stdout().flush();
+            }
+            Err(e) => warn!("Failed to serialize stream event: {}", e),
+        }
+    }
+
     async fn handle_interrupted_messages(&mut self, interrupt: bool) -> Result<()> {
         // First, get any tool requests from the last message if it exists
         let tool_requests = self
@@ -1192,7 +1346,8 @@ impl CliSession {
                     }),
                 ));
             }
-            // TODO(Douwe): update also db
+            self.resolve_interrupted_turn(TurnRecovery::MarkFailed, &notification)
+                .await;
             self.push_message(response_message);
             let prompt = format!(
                 "The existing call to {} was interrupted. How would you like to proceed?",
@@ -1207,6 +1362,11 @@ impl CliSession {
                     match last_msg.content.first() {
                         Some(MessageContent::ToolResponse(_)) => {
                             // Interruption occurred after a tool had completed but not assistant reply
+                            self.resolve_interrupted_turn(
+                                TurnRecovery::MarkFailed,
+                                INTERRUPTED_TOOL_CALL_REASON,
+                            )
+                            .await;
                             let prompt = "The tool calling loop was interrupted. How would you like to proceed?";
                             self.push_message(Message::assistant().with_text(prompt));
                             output::render_message(
@@ -1216,6 +1376,11 @@ impl CliSession {
                         }
                         Some(_) => {
                             // A real users message
+                            self.resolve_interrupted_turn(
+                                TurnRecovery::Rollback,
+                                INTERRUPTED_TOOL_CALL_REASON,
+                            )
+                            .await;
                             self.messages.pop();
                             let prompt = "Interrupted before the model replied and removed the last message.";
                             output::render_message(
@@ -1231,6 +1396,23 @@ impl CliSession {
         Ok(())
     }
 
+    /// Apply the same recovery to the stored session as to the messages shown here, so the
+    /// interrupted turn is not offered again when the session is resumed
+    async fn resolve_interrupted_turn(&self, recovery: TurnRecovery, reason: &str) {
+        match SessionManager::get_turn_journal(&self.session_id).await {
+            Ok(Some(_)) => {
+                if let Err(e) =
+                    SessionManager::resolve_interrupted_turn(&self.session_id, recovery, reason)
+                        .await
+                {
+                    warn!("Failed to resolve interrupted turn: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read turn journal: {}", e),
+        }
+    }
+
     /// Update the completion cache with fresh data
     /// This should be called before the interactive session starts
     pub async fn update_completion_cache(&mut self) -> Result<()> {
@@ -1473,6 +1655,107 @@ impl CliSession {
     fn push_message(&mut self, message: Message) {
         self.messages.push(message);
     }
+
+    /// Continue in a fork of this session that ends right before an earlier user prompt,
+    /// optionally reverting the file edits made since then.
+    async fn handle_rewind(&mut self, steps: Option<usize>) -> Result<()> {
+        let prompts: Vec<(usize, String)> = self
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.role == rmcp::model::Role::User && m.is_user_visible())
+            .filter_map(|(i, m)| {
+                let text = m.as_concat_text();
+                (!text.trim().is_empty()).then_some((i, text))
+            })
+            .collect();
+
+        if prompts.is_empty() {
+            println!("{}", console::style("Nothing to rewind.").yellow());
+            return Ok(());
+        }
+
+        let (index, prompt) = match steps {
+            Some(steps) if steps > prompts.len() => {
+                println!(
+                    "{}",
+                    console::style(format!(
+                        "Can only rewind up to {} prompt(s).",
+                        prompts.len()
+                    ))
+                    .yellow()
+                );
+                return Ok(());
+            }
+            Some(steps) => prompts[prompts.len() - steps].clone(),
+            None => {
+                let mut selector = cliclack::select("Rewind to before which prompt?");
+                for (i, text) in prompts.iter().rev() {
+                    let label = safe_truncate(&text.replace('\n', " "), 80);
+                    selector = selector.item(*i, label, "");
+                }
+                let selected = match selector.interact() {
+                    Ok(selected) => selected,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                        println!("{}", console::style("Rewind cancelled.").yellow());
+                        return Ok(());
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                prompts
+                    .into_iter()
+                    .find(|(i, _)| *i == selected)
+                    .expect("selected prompt exists")
+            }
+        };
+
+        let rewound = self.messages.messages()[index..].to_vec();
+        let edits = goose::agents::collect_file_edits(&rewound);
+        let revert = if edits.is_empty() {
+            false
+        } else {
+            let files: Vec<&str> = edits.iter().map(|f| f.path.as_str()).collect();
+            let question = format!(
+                "Also revert the changes made since then to {} file(s)? ({})",
+                files.len(),
+                files.join(", ")
+            );
+            match cliclack::confirm(question).initial_value(false).interact() {
+                Ok(choice) => choice,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => false,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        let fork = SessionManager::fork_session(&self.session_id, index).await?;
+
+        if revert {
+            for file in self.agent.revert_file_edits(&rewound).await {
+                match file.error {
+                    None => println!("Reverted {}", file.path),
+                    Some(e) => output::render_error(&format!(
+                        "Could not fully revert {} ({} edit(s) undone): {}",
+                        file.path, file.reverted, e
+                    )),
+                }
+            }
+        }
+
+        let original_id = std::mem::replace(&mut self.session_id, fork.id.clone());
+        self.messages = fork.conversation.unwrap_or_default();
+
+        println!(
+            "{}",
+            console::style(format!(
+                "Rewound to before: {}\nContinuing in session {} (the original is kept as {}).",
+                safe_truncate(&prompt, 200),
+                fork.id,
+                original_id
+            ))
+            .green()
+        );
+        Ok(())
+    }
 }
 
 async fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
@@ -0,0 +1,725 @@
+use anyhow::{bail, Context, Result};
+use reqwest::{header, redirect, Client, Response};
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+use url::Url;
+
+use super::readable;
+
+/// The product token matched against robots.txt user-agent lines
+pub const ROBOTS_USER_AGENT: &str = "goose";
+
+/// Upper bound on how long a robots.txt crawl-delay makes us wait between pages
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(5);
+
+/// How many redirects are followed for one request
+const MAX_REDIRECTS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    /// How many links away from the start page to follow
+    pub max_depth: usize,
+    pub max_pages: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrawledPage {
+    pub url: Url,
+    pub depth: usize,
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrawlReport {
+    pub start: Url,
+    pub pages: Vec<CrawledPage>,
+    /// Pages that were found but not included, with the reason
+    pub skipped: Vec<(Url, String)>,
+}
+
+impl CrawlReport {
+    /// All pages as one markdown document, starting with a source map that
+    /// numbers each page and links it to the section holding its content
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!(
+            "# Crawl of {}\n\n{} pages\n\n## Source map\n\n",
+            self.start,
+            self.pages.len()
+        );
+        for (index, page) in self.pages.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. {} - {} (depth {})\n",
+                index + 1,
+                page.title.as_deref().unwrap_or("Untitled"),
+                page.url,
+                page.depth
+            ));
+        }
+        if !self.skipped.is_empty() {
+            output.push_str("\nSkipped:\n");
+            for (url, reason) in &self.skipped {
+                output.push_str(&format!("- {} ({})\n", url, reason));
+            }
+        }
+
+        for (index, page) in self.pages.iter().enumerate() {
+            output.push_str(&format!(
+                "\n---\n\n## [{}] {}\n\nSource: {}\n\n{}\n",
+                index + 1,
+                page.title.as_deref().unwrap_or("Untitled"),
+                page.url,
+                page.markdown
+            ));
+        }
+        output
+    }
+}
+
+/// The rules of a robots.txt file that apply to goose
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    /// (allow, path pattern) pairs
+    rules: Vec<(bool, String)>,
+    pub crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![(false, "/".to_string())],
+            crawl_delay: None,
+        }
+    }
+
+    /// Parse robots.txt (RFC 9309), keeping the groups for `user_agent`, or the
+    /// `*` groups when none name it
+    pub fn parse(content: &str, user_agent: &str) -> Self {
+        struct Group {
+            agents: Vec<String>,
+            rules: Vec<(bool, String)>,
+            crawl_delay: Option<Duration>,
+        }
+
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_agent_lines = false;
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            if key == "user-agent" {
+                if !in_agent_lines {
+                    groups.push(Group {
+                        agents: Vec::new(),
+                        rules: Vec::new(),
+                        crawl_delay: None,
+                    });
+                }
+                in_agent_lines = true;
+                if let Some(group) = groups.last_mut() {
+                    group.agents.push(value.to_ascii_lowercase());
+                }
+                continue;
+            }
+
+            in_agent_lines = false;
+            let Some(group) = groups.last_mut() else {
+                continue;
+            };
+            match key.as_str() {
+                // An empty disallow allows everything, which is the default anyway
+                "disallow" if !value.is_empty() => group.rules.push((false, value.to_string())),
+                "allow" if !value.is_empty() => group.rules.push((true, value.to_string())),
+                // Negative, NaN and huge delays are ignored, long ones are capped
+                "crawl-delay" => {
+                    group.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                        .map(|delay| delay.min(MAX_CRAWL_DELAY))
+                }
+                _ => {}
+            }
+        }
+
+        let user_agent = user_agent.to_ascii_lowercase();
+        let named: Vec<&Group> = groups
+            .iter()
+            .filter(|group| group.agents.contains(&user_agent))
+            .collect();
+        let applicable = if named.is_empty() {
+            groups
+                .iter()
+                .filter(|group| group.agents.iter().any(|agent| agent == "*"))
+                .collect()
+        } else {
+            named
+        };
+
+        Self {
+            rules: applicable
+                .iter()
+                .flat_map(|group| group.rules.iter().cloned())
+                .collect(),
+            crawl_delay: applicable.iter().find_map(|group| group.crawl_delay),
+        }
+    }
+
+    /// Whether the path (with its query) may be fetched. The longest matching
+    /// rule decides, and allow wins a tie.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+
+    pub fn is_url_allowed(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.is_allowed(&path)
+    }
+}
+
+/// Match a robots.txt path pattern, where `*` matches any characters and a
+/// trailing `$` anchors the pattern at the end of the path
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+
+    let Some(mut rest) = path.strip_prefix(parts[0]) else {
+        return false;
+    };
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        let is_last = index == parts.len() - 1;
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = rest.get(position + part.len()..).unwrap_or_default(),
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.origin() == b.origin()
+}
+
+/// A client that leaves redirects to the crawler, so each target can be checked
+/// before it is fetched
+fn client(user_agent: &str) -> Result<Client> {
+    Client::builder()
+        .user_agent(user_agent)
+        .redirect(redirect::Policy::none())
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// The target of a redirect response, if it is one
+fn redirect_target(response: &Response) -> Option<Url> {
+    if !response.status().is_redirection() {
+        return None;
+    }
+    let location = response.headers().get(header::LOCATION)?.to_str().ok()?;
+    response.url().join(location).ok()
+}
+
+/// Fetch robots.txt for the URL's origin. A missing file allows everything,
+/// while an unreachable one allows nothing, as RFC 9309 asks.
+pub async fn fetch_robots(client: &Client, url: &Url) -> RobotsRules {
+    let Ok(mut robots_url) = url.join("/robots.txt") else {
+        return RobotsRules::allow_all();
+    };
+    for _ in 0..=MAX_REDIRECTS {
+        let response = match client.get(robots_url.clone()).send().await {
+            Ok(response) => response,
+            Err(_) => return RobotsRules::disallow_all(),
+        };
+        if let Some(target) = redirect_target(&response) {
+            robots_url = target;
+            continue;
+        }
+        return if response.status().is_success() {
+            match response.text().await {
+                Ok(content) => RobotsRules::parse(&content, ROBOTS_USER_AGENT),
+                Err(_) => RobotsRules::disallow_all(),
+            }
+        } else if response.status().is_client_error() {
+            RobotsRules::allow_all()
+        } else {
+            RobotsRules::disallow_all()
+        };
+    }
+    RobotsRules::disallow_all()
+}
+
+/// Fetch a page, following only redirects that stay on the site and that robots.txt
+/// allows. Returns why the page was skipped otherwise.
+async fn fetch_page(
+    client: &Client,
+    url: &Url,
+    start: &Url,
+    robots: &RobotsRules,
+) -> Result<Response, String> {
+    let mut url = url.clone();
+    for _ in 0..=MAX_REDIRECTS {
+        let response = client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {}", e))?;
+        let Some(target) = redirect_target(&response) else {
+            return Ok(response);
+        };
+        if !same_origin(&target, start) {
+            return Err(format!("redirected off site to {}", target));
+        }
+        if !robots.is_url_allowed(&target) {
+            return Err(format!(
+                "redirected to {}, which robots.txt disallows",
+                target
+            ));
+        }
+        url = target;
+    }
+    Err("too many redirects".to_string())
+}
+
+/// Fetch the start page and the same-origin pages it links to, breadth first,
+/// within the depth and page limits and the site's robots.txt
+pub async fn crawl(user_agent: &str, start: Url, options: &CrawlOptions) -> Result<CrawlReport> {
+    let client = client(user_agent)?;
+    let robots = fetch_robots(&client, &start).await;
+    if !robots.is_url_allowed(&start) {
+        bail!(
+            "robots.txt of {} disallows fetching {}",
+            start.origin().ascii_serialization(),
+            start
+        );
+    }
+
+    let mut report = CrawlReport {
+        start: start.clone(),
+        pages: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+
+    while let Some((url, depth)) = queue.pop_front() {
+        if report.pages.len() >= options.max_pages {
+            break;
+        }
+        if let (Some(delay), false) = (robots.crawl_delay, report.pages.is_empty()) {
+            tokio::time::sleep(delay).await;
+        }
+
+        let response = match fetch_page(&client, &url, &start, &robots).await {
+            Ok(response) => response,
+            Err(reason) if url == start => bail!("Failed to fetch {}: {}", url, reason),
+            Err(reason) => {
+                report.skipped.push((url, reason));
+                continue;
+            }
+        };
+        if !response.status().is_success() {
+            if url == start {
+                bail!("HTTP request failed with status: {}", response.status());
+            }
+            report
+                .skipped
+                .push((url, format!("HTTP {}", response.status())));
+            continue;
+        }
+        let final_url = response.url().clone();
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_none_or(|content_type| content_type.contains("html"));
+        if !is_html {
+            report.skipped.push((url, "not an HTML page".to_string()));
+            continue;
+        }
+
+        let html = match response.text().await {
+            Ok(html) => html,
+            Err(e) if url == start => {
+                return Err(e).with_context(|| format!("Failed to read {}", url))
+            }
+            Err(e) => {
+                report.skipped.push((url, format!("failed to read: {}", e)));
+                continue;
+            }
+        };
+        let document = readable::extract(&html, &final_url);
+
+        if depth < options.max_depth {
+            for link in &document.links {
+                if !same_origin(link, &start) || !seen.insert(link.to_string()) {
+                    continue;
+                }
+                if robots.is_url_allowed(link) {
+                    queue.push_back((link.clone(), depth + 1));
+                } else {
+                    report
+                        .skipped
+                        .push((link.clone(), "disallowed by robots.txt".to_string()));
+                }
+            }
+        }
+
+        report.pages.push(CrawledPage {
+            url: final_url,
+            depth,
+            title: document.title,
+            markdown: document.markdown,
+        });
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Fixture content type for a redirect to the URL given as the body
+    const REDIRECT: &str = "redirect";
+    /// Fixture content type for an HTML page whose body is cut short
+    const TRUNCATED: &str = "truncated";
+
+    /// A minimal HTTP server with fixed responses per path, recording the
+    /// paths that were requested
+    struct FixtureServer {
+        base: Url,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FixtureServer {
+        async fn start(pages: &[(&str, &str, &str)]) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+            let pages: Arc<HashMap<String, (String, String)>> = Arc::new(
+                pages
+                    .iter()
+                    .map(|(path, content_type, body)| {
+                        (
+                            path.to_string(),
+                            (content_type.to_string(), body.to_string()),
+                        )
+                    })
+                    .collect(),
+            );
+            let requests = Arc::new(Mutex::new(Vec::new()));
+
+            let recorded = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let pages = pages.clone();
+                    let recorded = recorded.clone();
+                    tokio::spawn(async move {
+                        let mut request = Vec::new();
+                        let mut buffer = [0u8; 1024];
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            match stream.read(&mut buffer).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => request.extend_from_slice(&buffer[..n]),
+                            }
+                        }
+                        let request = String::from_utf8_lossy(&request);
+                        let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                        recorded.lock().unwrap().push(path.clone());
+
+                        let response = match pages.get(&path) {
+                            Some((content_type, target)) if content_type == REDIRECT => format!(
+                                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                                target
+                            ),
+                            // Promise more body than is sent, so reading it fails
+                            Some((content_type, body)) if content_type == TRUNCATED => format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                body.len() + 100,
+                                body
+                            ),
+                            Some((content_type, body)) => format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                content_type,
+                                body.len(),
+                                body
+                            ),
+                            None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                                .to_string(),
+                        };
+                        let _ = stream.write_all(response.as_bytes()).await;
+                    });
+                }
+            });
+
+            Self { base, requests }
+        }
+
+        fn requested(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn page(title: &str, body: &str) -> String {
+        format!(
+            "<html><head><title>{}</title></head><body><nav><a href=\"/\">Home</a></nav><main>{}</main></body></html>",
+            title, body
+        )
+    }
+
+    #[test]
+    fn test_robots_rules() {
+        let robots = RobotsRules::parse(
+            "# comment\n\
+             User-agent: *\n\
+             Disallow: /private/\n\
+             Allow: /private/open.html\n\
+             Disallow: /*.pdf$\n\
+             \n\
+             User-agent: otherbot\n\
+             Disallow: /\n",
+            ROBOTS_USER_AGENT,
+        );
+        assert!(robots.is_allowed("/"));
+        assert!(robots.is_allowed("/docs/guide.html"));
+        assert!(!robots.is_allowed("/private/secret.html"));
+        assert!(robots.is_allowed("/private/open.html"));
+        assert!(!robots.is_allowed("/files/report.pdf"));
+        assert!(robots.is_allowed("/files/report.pdf?download=1"));
+
+        // A group naming goose replaces the wildcard group
+        let robots = RobotsRules::parse(
+            "User-agent: *\nDisallow: /\n\nUser-agent: Goose\nUser-agent: otherbot\nDisallow: /admin\nCrawl-delay: 2\n",
+            ROBOTS_USER_AGENT,
+        );
+        assert!(robots.is_allowed("/docs"));
+        assert!(!robots.is_allowed("/admin/users"));
+        assert_eq!(robots.crawl_delay, Some(Duration::from_secs(2)));
+
+        assert!(
+            RobotsRules::parse("User-agent: *\nDisallow:\n", ROBOTS_USER_AGENT).is_allowed("/any")
+        );
+        for (delay, expected) in [
+            ("-1", None),
+            ("NaN", None),
+            ("inf", None),
+            ("1e300", None),
+            ("0.5", Some(Duration::from_millis(500))),
+            ("3600", Some(MAX_CRAWL_DELAY)),
+        ] {
+            let robots = RobotsRules::parse(
+                &format!("User-agent: *\nCrawl-delay: {}\n", delay),
+                ROBOTS_USER_AGENT,
+            );
+            assert_eq!(robots.crawl_delay, expected, "{}", delay);
+        }
+
+        assert!(!RobotsRules::disallow_all().is_allowed("/any"));
+        assert!(RobotsRules::disallow_all().is_allowed("/robots.txt"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/a", "/a/b"));
+        assert!(pattern_matches("/a*c", "/abbbc/d"));
+        assert!(pattern_matches("/a*c$", "/abbbc"));
+        assert!(!pattern_matches("/a*c$", "/abbbcd"));
+        assert!(pattern_matches("/index.html$", "/index.html"));
+        assert!(!pattern_matches("/index.html$", "/index.html?x"));
+        assert!(!pattern_matches("/b", "/a/b"));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_follows_same_origin_links_within_limits() {
+        let index = page(
+            "Home",
+            r#"<h1>Welcome</h1><p>Start here, the docs are linked below.</p>
+               <a href="/docs.html">Docs</a> <a href="/private/secret.html">Secret</a>
+               <a href="https://elsewhere.example/">Elsewhere</a> <a href="/file.zip">File</a>"#,
+        );
+        let docs = page(
+            "Docs",
+            r#"<p>All about the docs.</p><a href="/deep.html">Deeper</a>"#,
+        );
+        let deep = page("Deep", "<p>Too deep to reach.</p>");
+        let server = FixtureServer::start(&[
+            ("/", "text/html", &index),
+            ("/docs.html", "text/html; charset=utf-8", &docs),
+            ("/deep.html", "text/html", &deep),
+            ("/private/secret.html", "text/html", "<p>secret</p>"),
+            ("/file.zip", "application/zip", "PK"),
+            (
+                "/robots.txt",
+                "text/plain",
+                "User-agent: *\nDisallow: /private/\n",
+            ),
+        ])
+        .await;
+
+        let options = CrawlOptions {
+            max_depth: 1,
+            max_pages: 10,
+        };
+        let report = crawl("goose-test", server.base.clone(), &options)
+            .await
+            .unwrap();
+
+        let urls: Vec<String> = report
+            .pages
+            .iter()
+            .map(|p| p.url.path().to_string())
+            .collect();
+        assert_eq!(urls, vec!["/", "/docs.html"]);
+        assert_eq!(report.pages[1].depth, 1);
+        assert_eq!(report.pages[1].title.as_deref(), Some("Docs"));
+
+        let requested = server.requested();
+        assert!(requested.contains(&"/robots.txt".to_string()));
+        assert!(!requested.contains(&"/private/secret.html".to_string()));
+        assert!(!requested.contains(&"/deep.html".to_string()));
+
+        let skipped: Vec<(String, &str)> = report
+            .skipped
+            .iter()
+            .map(|(url, reason)| (url.path().to_string(), reason.as_str()))
+            .collect();
+        assert!(skipped.contains(&(
+            "/private/secret.html".to_string(),
+            "disallowed by robots.txt"
+        )));
+        assert!(skipped.contains(&("/file.zip".to_string(), "not an HTML page")));
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## Source map"), "{markdown}");
+        assert!(
+            markdown.contains(&format!("1. Home - {} (depth 0)", server.base)),
+            "{markdown}"
+        );
+        assert!(markdown.contains("## [2] Docs"), "{markdown}");
+        assert!(markdown.contains("All about the docs."), "{markdown}");
+        assert!(!markdown.contains("Too deep"), "{markdown}");
+    }
+
+    #[tokio::test]
+    async fn test_crawl_page_limit_and_robots_on_start() {
+        let index = page(
+            "Home",
+            r#"<a href="/a.html">A</a> <a href="/b.html">B</a> <a href="/c.html">C</a>"#,
+        );
+        let other = page("Other", "<p>Another page.</p>");
+        let server = FixtureServer::start(&[
+            ("/", "text/html", &index),
+            ("/a.html", "text/html", &other),
+            ("/b.html", "text/html", &other),
+            ("/c.html", "text/html", &other),
+        ])
+        .await;
+
+        // No robots.txt means everything is allowed
+        let options = CrawlOptions {
+            max_depth: 3,
+            max_pages: 2,
+        };
+        let report = crawl("goose-test", server.base.clone(), &options)
+            .await
+            .unwrap();
+        assert_eq!(report.pages.len(), 2);
+        assert!(!server.requested().contains(&"/b.html".to_string()));
+
+        let blocked = FixtureServer::start(&[
+            ("/", "text/html", &index),
+            (
+                "/robots.txt",
+                "text/plain",
+                "User-agent: goose\nDisallow: /\n",
+            ),
+        ])
+        .await;
+        let error = crawl("goose-test", blocked.base.clone(), &options)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("disallows"), "{error}");
+        assert_eq!(blocked.requested(), vec!["/robots.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_checks_redirects_and_skips_unreadable_pages() {
+        let index = page(
+            "Home",
+            r#"<a href="/old.html">Old</a> <a href="/moved.html">Moved</a>
+               <a href="/away.html">Away</a> <a href="/broken.html">Broken</a>"#,
+        );
+        let new_page = page("New", "<p>The new page.</p>");
+        let server = FixtureServer::start(&[
+            ("/", "text/html", &index),
+            ("/old.html", REDIRECT, "/private/old.html"),
+            ("/moved.html", REDIRECT, "/new.html"),
+            ("/new.html", "text/html", &new_page),
+            ("/away.html", REDIRECT, "https://elsewhere.example/"),
+            ("/broken.html", TRUNCATED, "<p>Cut"),
+            (
+                "/robots.txt",
+                "text/plain",
+                "User-agent: *\nDisallow: /private/\n",
+            ),
+        ])
+        .await;
+
+        let options = CrawlOptions {
+            max_depth: 1,
+            max_pages: 10,
+        };
+        let report = crawl("goose-test", server.base.clone(), &options)
+            .await
+            .unwrap();
+
+        let urls: Vec<String> = report
+            .pages
+            .iter()
+            .map(|p| p.url.path().to_string())
+            .collect();
+        assert_eq!(urls, vec!["/", "/new.html"]);
+        assert!(!server
+            .requested()
+            .contains(&"/private/old.html".to_string()));
+
+        let reason = |path: &str| {
+            report
+                .skipped
+                .iter()
+                .find(|(url, _)| url.path() == path)
+                .map(|(_, reason)| reason.clone())
+                .unwrap_or_default()
+        };
+        assert!(reason("/old.html").contains("robots.txt disallows"));
+        assert!(reason("/away.html").contains("redirected off site"));
+        assert!(reason("/broken.html").starts_with("failed to read"));
+    }
+}
@@ -13,14 +13,22 @@ use rmcp::{
     tool, tool_handler, tool_router, RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::PathBuf, sync::Arc, sync::Mutex};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    sync::Mutex,
+};
 use tokio::process::Command;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
+mod crawl;
 mod docx_tool;
 mod pdf_tool;
+mod readable;
 mod xlsx_tool;
 
 mod platform;
@@ -33,6 +41,8 @@ pub enum SaveAsFormat {
     /// Save as text (for HTML pages)
     #[default]
     Text,
+    /// Save the main readable content of an HTML page as markdown
+    Markdown,
     /// Save as JSON (for API responses)
     Json,
     /// Save as binary (for images and other files)
@@ -47,8 +57,20 @@ pub struct WebScrapeParams {
     /// How to interpret and save the content
     #[serde(default)]
     pub save_as: SaveAsFormat,
+    /// Follow links to other pages on the same site and combine them into one markdown document
+    #[serde(default)]
+    pub crawl: bool,
+    /// How many links away from the URL to follow when crawling (default 1)
+    pub max_depth: Option<usize>,
+    /// Maximum number of pages to fetch when crawling (default 10, at most 50)
+    pub max_pages: Option<usize>,
 }
 
+const USER_AGENT: &str = "goose/1.0";
+const DEFAULT_CRAWL_DEPTH: usize = 1;
+const DEFAULT_CRAWL_PAGES: usize = 10;
+const MAX_CRAWL_PAGES: usize = 50;
+
 /// Enum for language parameter in automation_script tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -250,6 +272,24 @@ pub enum XlsxOperation {
     GetCell,
     /// Save changes back to the file
     Save,
+    /// Create a new workbook at the path
+    CreateWorkbook,
+    /// Add an empty worksheet
+    AddWorksheet,
+    /// Rename a worksheet
+    RenameWorksheet,
+    /// Delete a worksheet
+    DeleteWorksheet,
+    /// Write a 2D array of values starting at a cell
+    WriteRange,
+    /// Set a formula in a specific cell
+    SetFormula,
+    /// Apply a number format to a cell range
+    SetNumberFormat,
+    /// Export a worksheet to a CSV file
+    ExportCsv,
+    /// Import a CSV file as a new worksheet
+    ImportCsv,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -260,7 +300,8 @@ pub struct XlsxToolParams {
     pub operation: XlsxOperation,
     /// Worksheet name (if not provided, uses first worksheet)
     pub worksheet: Option<String>,
-    /// Cell range in A1 notation (e.g., 'A1:C10') for get_range operation
+    /// Cell range in A1 notation (e.g., 'A1:C10') for get_range and set_number_format,
+    /// or the top-left cell (e.g., 'B2') for write_range
     pub range: Option<String>,
     /// Text to search for in find_text operation
     pub search_text: Option<String>,
@@ -273,6 +314,17 @@ pub struct XlsxToolParams {
     pub col: Option<u64>,
     /// New value for update_cell operation
     pub value: Option<String>,
+    /// New worksheet name for rename_worksheet operation
+    pub new_name: Option<String>,
+    /// Rows of values for write_range operation, e.g. [["Name", "Total"], ["East", 42]].
+    /// Strings starting with '=' are written as formulas and nulls are skipped.
+    pub values: Option<Vec<Vec<serde_json::Value>>>,
+    /// Formula for set_formula operation (e.g., '=SUM(A1:A10)')
+    pub formula: Option<String>,
+    /// Number format code for set_number_format operation (e.g., '0.00', '0%', 'yyyy-mm-dd')
+    pub number_format: Option<String>,
+    /// CSV file to write for export_csv, or to read for import_csv
+    pub csv_path: Option<String>,
 }
 
 /// ComputerController MCP Server using official RMCP SDK
@@ -398,7 +450,9 @@ impl ComputerControllerServer {
 
             web_scrape
               - Fetch content from html websites and APIs
-              - Save as text, JSON, or binary files
+              - Save as text, markdown, JSON, or binary files
+              - Prefer markdown for web pages: it keeps the readable content and drops navigation and scripts
+              - Crawl a site to combine several of its pages into one markdown document
               - Content is cached locally for later use
               - This is not optimised for complex websites, so don't use this as the first tool.
             cache
@@ -417,7 +471,7 @@ impl ComputerControllerServer {
             tool_router: Self::tool_router(),
             cache_dir,
             active_resources: Arc::new(Mutex::new(HashMap::new())),
-            http_client: Client::builder().user_agent("goose/1.0").build().unwrap(),
+            http_client: Client::builder().user_agent(USER_AGENT).build().unwrap(),
             instructions,
             system_automation,
         }
@@ -477,8 +531,12 @@ impl ComputerControllerServer {
         description = "
             Fetch and save content from a web page. The content can be saved as:
             - text (for HTML pages)
+            - markdown (the main readable content of an HTML page, without navigation, scripts and boilerplate)
             - json (for API responses)
             - binary (for images and other files)
+            Set crawl to also follow links to pages on the same site, up to max_depth links
+            away and max_pages pages, skipping pages robots.txt disallows. Crawling saves one
+            markdown document with a source map listing every page and its URL.
             The content is cached locally and can be accessed later using the cache_path
             returned in the response.
         "
@@ -491,6 +549,12 @@ impl ComputerControllerServer {
         let url = &params.url;
         let save_as = params.save_as;
 
+        if params.crawl {
+            return self
+                .crawl_site(url, &save_as, params.max_depth, params.max_pages)
+                .await;
+        }
+
         // Fetch the content
         let response = self.http_client.get(url).send().await.map_err(|e| {
             ErrorData::new(
@@ -521,6 +585,28 @@ impl ComputerControllerServer {
                 })?;
                 (text.into_bytes(), "txt", "text/plain")
             }
+            SaveAsFormat::Markdown => {
+                let page_url = response.url().clone();
+                let is_html = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_none_or(|content_type| content_type.contains("html"));
+                let text = response.text().await.map_err(|e| {
+                    ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to get text: {}", e),
+                        None,
+                    )
+                })?;
+                // Plain text and markdown responses are already readable
+                let markdown = if is_html {
+                    readable::extract(&text, &page_url).to_markdown(&page_url)
+                } else {
+                    text
+                };
+                (markdown.into_bytes(), "md", "text/markdown")
+            }
             SaveAsFormat::Json => {
                 let text = response.text().await.map_err(|e| {
                     ErrorData::new(
@@ -563,6 +649,51 @@ impl ComputerControllerServer {
         ))]))
     }
 
+    async fn crawl_site(
+        &self,
+        url: &str,
+        save_as: &SaveAsFormat,
+        max_depth: Option<usize>,
+        max_pages: Option<usize>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if !matches!(save_as, SaveAsFormat::Text | SaveAsFormat::Markdown) {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Crawling saves markdown; save_as must be 'markdown' or 'text'".to_string(),
+                None,
+            ));
+        }
+        let start = Url::parse(url).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Invalid URL '{}': {}", url, e),
+                None,
+            )
+        })?;
+        let options = crawl::CrawlOptions {
+            max_depth: max_depth.unwrap_or(DEFAULT_CRAWL_DEPTH),
+            max_pages: max_pages
+                .unwrap_or(DEFAULT_CRAWL_PAGES)
+                .clamp(1, MAX_CRAWL_PAGES),
+        };
+
+        let report = crawl::crawl(USER_AGENT, start, &options)
+            .await
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, format!("{:#}", e), None))?;
+
+        let cache_path = self
+            .save_to_cache(report.to_markdown().as_bytes(), "crawl", "md")
+            .await?;
+        self.register_as_resource(&cache_path, "text/markdown")?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Crawled {} pages ({} skipped). Content saved to: {}",
+            report.pages.len(),
+            report.skipped.len(),
+            cache_path.display()
+        ))]))
+    }
+
     /// Create and run small scripts for automation tasks
     #[cfg(target_os = "windows")]
     #[tool(
@@ -895,7 +1026,17 @@ impl ComputerControllerServer {
             - update_cell: Update a single cell's value (returns confirmation message)
             - get_cell: Get value and formula from a specific cell (returns both value and formula if present)
             - save: Save changes back to the file (returns confirmation message)
-
+            - create_workbook: Create a new workbook at path, optionally naming its first worksheet (fails if the file exists)
+            - add_worksheet: Add an empty worksheet named by worksheet
+            - rename_worksheet: Rename worksheet to new_name
+            - delete_worksheet: Delete worksheet (a workbook keeps at least one worksheet)
+            - write_range: Write a 2D array of values ([row][column]) starting at the cell in range (e.g., 'B2'); strings starting with '=' become formulas
+            - set_formula: Set a formula in the cell at row and col
+            - set_number_format: Apply a number format code (e.g., '0.00%', 'yyyy-mm-dd') to range
+            - export_csv: Write a worksheet's values to csv_path
+            - import_csv: Import csv_path as a new worksheet (named by worksheet, or the CSV file name); creates the workbook if it doesn't exist
+
+            Operations that modify the workbook save it immediately.
             Use this when working with Excel spreadsheets to analyze or modify data.
         "
     )]
@@ -1078,6 +1219,268 @@ impl ComputerControllerServer {
                     cell_value
                 ))]))
             }
+            XlsxOperation::CreateWorkbook => {
+                if Path::new(path).exists() {
+                    return Err(ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        format!("File '{}' already exists", path),
+                        None,
+                    ));
+                }
+
+                let xlsx = xlsx_tool::XlsxTool::create(params.worksheet.as_deref())
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Created workbook '{}'",
+                    path
+                ))]))
+            }
+            XlsxOperation::AddWorksheet => {
+                let worksheet_name = params.worksheet.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'worksheet' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.add_worksheet(worksheet_name)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Added worksheet '{}'",
+                    worksheet_name
+                ))]))
+            }
+            XlsxOperation::RenameWorksheet => {
+                let worksheet_name = params.worksheet.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'worksheet' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let new_name = params.new_name.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'new_name' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.rename_worksheet(worksheet_name, new_name)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Renamed worksheet '{}' to '{}'",
+                    worksheet_name, new_name
+                ))]))
+            }
+            XlsxOperation::DeleteWorksheet => {
+                let worksheet_name = params.worksheet.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'worksheet' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.delete_worksheet(worksheet_name)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Deleted worksheet '{}'",
+                    worksheet_name
+                ))]))
+            }
+            XlsxOperation::WriteRange => {
+                let range = params.range.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'range' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let values = params.values.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'values' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                let written = xlsx
+                    .write_range(params.worksheet.as_deref(), range, values)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Wrote {} rows to {}",
+                    values.len(),
+                    written
+                ))]))
+            }
+            XlsxOperation::SetFormula => {
+                let row = params.row.ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'row' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let col = params.col.ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'col' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let formula = params.formula.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'formula' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.set_formula(params.worksheet.as_deref(), row as u32, col as u32, formula)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Set formula of cell ({}, {}) to '{}'. The value is calculated when the file is opened in a spreadsheet application.",
+                    row, col, formula
+                ))]))
+            }
+            XlsxOperation::SetNumberFormat => {
+                let range = params.range.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'range' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let number_format = params.number_format.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'number_format' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let mut xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                let count = xlsx
+                    .set_number_format(params.worksheet.as_deref(), range, number_format)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Applied number format '{}' to {} cells in {}",
+                    number_format, count, range
+                ))]))
+            }
+            XlsxOperation::ExportCsv => {
+                let csv_path = params.csv_path.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'csv_path' parameter".to_string(),
+                        None,
+                    )
+                })?;
+
+                let xlsx = xlsx_tool::XlsxTool::new(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                let worksheet = if let Some(name) = &params.worksheet {
+                    xlsx.get_worksheet_by_name(name).map_err(|e| {
+                        ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None)
+                    })?
+                } else {
+                    xlsx.get_worksheet_by_index(0).map_err(|e| {
+                        ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None)
+                    })?
+                };
+                let csv = xlsx.to_csv(worksheet);
+                fs::write(csv_path, &csv).map_err(|e| {
+                    ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to write CSV file: {}", e),
+                        None,
+                    )
+                })?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Exported {} rows from worksheet '{}' to {}",
+                    worksheet.get_highest_row(),
+                    worksheet.get_name(),
+                    csv_path
+                ))]))
+            }
+            XlsxOperation::ImportCsv => {
+                let csv_path = params.csv_path.as_ref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "Missing 'csv_path' parameter".to_string(),
+                        None,
+                    )
+                })?;
+                let content = fs::read_to_string(csv_path).map_err(|e| {
+                    ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to read CSV file: {}", e),
+                        None,
+                    )
+                })?;
+                let worksheet_name = params.worksheet.clone().unwrap_or_else(|| {
+                    Path::new(csv_path)
+                        .file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .unwrap_or("Sheet1")
+                        .to_string()
+                });
+
+                // Import into a fresh workbook when there isn't one yet, replacing its default worksheet
+                let (mut xlsx, placeholder) = if Path::new(path).exists() {
+                    let xlsx = xlsx_tool::XlsxTool::new(path).map_err(|e| {
+                        ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None)
+                    })?;
+                    (xlsx, None)
+                } else {
+                    let placeholder = format!("{}_", worksheet_name);
+                    let xlsx = xlsx_tool::XlsxTool::create(Some(&placeholder)).map_err(|e| {
+                        ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None)
+                    })?;
+                    (xlsx, Some(placeholder))
+                };
+                let rows = xlsx
+                    .import_csv(&worksheet_name, &content)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                if let Some(placeholder) = placeholder {
+                    xlsx.delete_worksheet(&placeholder).map_err(|e| {
+                        ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None)
+                    })?;
+                }
+                xlsx.save(path)
+                    .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Imported {} rows from {} into worksheet '{}'",
+                    rows, csv_path, worksheet_name
+                ))]))
+            }
         }
     }
 
@@ -0,0 +1,1093 @@
+use std::collections::{HashMap, HashSet};
+use url::Url;
+
+/// Elements that never hold readable content
+const DROPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object", "embed",
+    "form", "button", "input", "select", "textarea", "nav", "aside", "dialog", "head",
+];
+
+/// Elements whose content is not markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements that end an open paragraph when they start
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+const BOILERPLATE_ROLES: &[&str] = &[
+    "navigation",
+    "banner",
+    "contentinfo",
+    "complementary",
+    "search",
+    "dialog",
+    "menu",
+    "menubar",
+];
+
+/// Class and id words that mark page furniture when they appear anywhere in the name
+const BOILERPLATE_NAME_PARTS: &[&str] = &[
+    "advert",
+    "breadcrumb",
+    "comment",
+    "cookie",
+    "footer",
+    "masthead",
+    "menu",
+    "navbar",
+    "newsletter",
+    "popup",
+    "modal",
+    "share",
+    "sidebar",
+    "social",
+    "subscribe",
+];
+
+/// Class and id names that mark page furniture only when they are the whole name
+const BOILERPLATE_NAMES: &[&str] = &["ad", "ads", "banner", "header", "nav", "promo", "related"];
+
+/// Paragraphs shorter than this don't count towards picking the main content
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// Opening an element this deep closes the deepest open element first, so hostile pages
+/// cannot overflow the stack of the recursive passes over the tree
+const MAX_NESTING_DEPTH: usize = 256;
+
+/// A page reduced to its main content
+#[derive(Debug, Clone)]
+pub struct ReadableDocument {
+    pub title: Option<String>,
+    pub markdown: String,
+    /// Every http(s) link on the page, resolved and without fragments, in page order
+    pub links: Vec<Url>,
+}
+
+impl ReadableDocument {
+    /// The markdown with the title and source URL as a header
+    pub fn to_markdown(&self, url: &Url) -> String {
+        let mut output = String::new();
+        if let Some(title) = &self.title {
+            if !self.markdown.starts_with(&format!("# {}\n", title)) {
+                output.push_str(&format!("# {}\n\n", title));
+            }
+        }
+        output.push_str(&format!("Source: {}\n\n", url));
+        output.push_str(&self.markdown);
+        output.push('\n');
+        output
+    }
+}
+
+/// Extract the readable content of an HTML page as markdown, dropping navigation,
+/// scripts and other boilerplate
+pub fn extract(html: &str, url: &Url) -> ReadableDocument {
+    let document = parse(html);
+    let base = find_first(&document, &|e| e.name == "base")
+        .and_then(|e| e.attr("href"))
+        .and_then(|href| url.join(href).ok())
+        .unwrap_or_else(|| url.clone());
+
+    let title = find_first(&document, &|e| e.name == "title")
+        .map(|e| collapse_whitespace(&e.text()))
+        .or_else(|| {
+            find_first(&document, &|e| e.name == "h1").map(|e| collapse_whitespace(&e.text()))
+        })
+        .filter(|title| !title.is_empty());
+
+    let mut links = Vec::new();
+    collect_links(&document, &base, &mut links, &mut HashSet::new());
+
+    let cleaned = clean(&document, false);
+    let content = main_content(&cleaned);
+    let mut renderer = MarkdownRenderer {
+        base: &base,
+        out: String::new(),
+    };
+    renderer.children(content);
+
+    ReadableDocument {
+        title,
+        markdown: normalize(&renderer.out),
+        links,
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn new(name: &str, attrs: Vec<(String, String)>) -> Self {
+        Self {
+            name: name.to_string(),
+            attrs,
+            children: Vec::new(),
+        }
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn text(&self) -> String {
+        let mut text = String::new();
+        self.append_text(&mut text);
+        text
+    }
+
+    fn append_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Text(t) => text.push_str(t),
+                Node::Element(element) => element.append_text(text),
+            }
+        }
+    }
+
+    fn link_text_len(&self) -> usize {
+        if self.name == "a" {
+            return collapse_whitespace(&self.text()).len();
+        }
+        self.elements().map(Element::link_text_len).sum()
+    }
+}
+
+enum Token {
+    Open {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    Close(String),
+    Text(String),
+}
+
+/// Split HTML into tags and text. Malformed markup is tolerated rather than rejected,
+/// since real pages are rarely valid.
+fn tokenize(html: &str) -> Vec<Token> {
+    let chars: Vec<char> = html.chars().collect();
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut pos = 0;
+
+    let starts_with = |pos: usize, pattern: &str| {
+        pattern.chars().enumerate().all(|(i, p)| {
+            chars
+                .get(pos + i)
+                .is_some_and(|c| c.eq_ignore_ascii_case(&p))
+        })
+    };
+    let find = |from: usize, pattern: &str| (from..chars.len()).find(|&i| starts_with(i, pattern));
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).copied().unwrap_or(' ');
+        if c != '<' || !(next.is_ascii_alphabetic() || matches!(next, '/' | '!' | '?')) {
+            text.push(c);
+            pos += 1;
+            continue;
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(decode_entities(&std::mem::take(&mut text))));
+        }
+
+        if starts_with(pos, "<!--") {
+            pos = find(pos + 4, "-->").map_or(chars.len(), |end| end + 3);
+            continue;
+        }
+        if next == '!' || next == '?' {
+            pos = find(pos, ">").map_or(chars.len(), |end| end + 1);
+            continue;
+        }
+
+        let closing = next == '/';
+        let mut i = pos + if closing { 2 } else { 1 };
+        let mut name = String::new();
+        while let Some(&c) = chars.get(i) {
+            if c.is_whitespace() || c == '>' || c == '/' {
+                break;
+            }
+            name.push(c.to_ascii_lowercase());
+            i += 1;
+        }
+
+        if closing {
+            pos = find(i, ">").map_or(chars.len(), |end| end + 1);
+            tokens.push(Token::Close(name));
+            continue;
+        }
+
+        let mut attrs = Vec::new();
+        let mut self_closing = false;
+        loop {
+            while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                i += 1;
+            }
+            match chars.get(i) {
+                None => break,
+                Some('>') => {
+                    i += 1;
+                    break;
+                }
+                Some('/') => {
+                    self_closing = chars.get(i + 1) == Some(&'>');
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let mut key = String::new();
+            while let Some(&c) = chars.get(i) {
+                if c.is_whitespace() || matches!(c, '=' | '>' | '/') {
+                    break;
+                }
+                key.push(c.to_ascii_lowercase());
+                i += 1;
+            }
+            if key.is_empty() {
+                i += 1;
+                continue;
+            }
+
+            while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                i += 1;
+            }
+            let mut value = String::new();
+            if chars.get(i) == Some(&'=') {
+                i += 1;
+                while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                    i += 1;
+                }
+                match chars.get(i) {
+                    Some(&quote) if quote == '"' || quote == '\'' => {
+                        i += 1;
+                        while let Some(&c) = chars.get(i) {
+                            i += 1;
+                            if c == quote {
+                                break;
+                            }
+                            value.push(c);
+                        }
+                    }
+                    _ => {
+                        while let Some(&c) = chars.get(i) {
+                            if c.is_whitespace() || c == '>' {
+                                break;
+                            }
+                            value.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            attrs.push((key, decode_entities(&value)));
+        }
+        pos = i;
+
+        if RAW_TEXT_ELEMENTS.contains(&name.as_str()) && !self_closing {
+            let end = find(pos, &format!("</{}", name)).unwrap_or(chars.len());
+            let raw: String = chars[pos..end].iter().collect();
+            pos = find(end, ">").map_or(chars.len(), |close| close + 1);
+            tokens.push(Token::Open {
+                name: name.clone(),
+                attrs,
+                self_closing: false,
+            });
+            tokens.push(Token::Text(decode_entities(&raw)));
+            tokens.push(Token::Close(name));
+            continue;
+        }
+
+        tokens.push(Token::Open {
+            name,
+            attrs,
+            self_closing,
+        });
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(decode_entities(&text)));
+    }
+    tokens
+}
+
+/// Build an element tree, closing elements the way browsers do when the end
+/// tag is missing
+fn parse(html: &str) -> Element {
+    let mut stack = vec![Element::new("#document", Vec::new())];
+
+    fn pop(stack: &mut Vec<Element>) {
+        if stack.len() > 1 {
+            let element = stack.pop().expect("stack is not empty");
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(Node::Element(element));
+            }
+        }
+    }
+
+    // Close an open element named in `names`, unless one named in `boundaries` is open above it
+    fn close_implied(stack: &mut Vec<Element>, names: &[&str], boundaries: &[&str]) {
+        let position = stack.iter().rposition(|e| {
+            names.contains(&e.name.as_str()) || boundaries.contains(&e.name.as_str())
+        });
+        if let Some(position) = position {
+            if names.contains(&stack[position].name.as_str()) {
+                while stack.len() > position {
+                    pop(stack);
+                }
+            }
+        }
+    }
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => {
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Text(text));
+                }
+            }
+            Token::Open {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                match name.as_str() {
+                    "li" => close_implied(&mut stack, &["li"], &["ul", "ol"]),
+                    "dt" | "dd" => close_implied(&mut stack, &["dt", "dd"], &["dl"]),
+                    "tr" => {
+                        close_implied(&mut stack, &["td", "th"], &["tr", "table"]);
+                        close_implied(&mut stack, &["tr"], &["table"]);
+                    }
+                    "td" | "th" => close_implied(&mut stack, &["td", "th"], &["tr", "table"]),
+                    "option" => close_implied(&mut stack, &["option"], &["select"]),
+                    _ => {}
+                }
+                if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                    close_implied(&mut stack, &["p"], &["div", "td", "th", "li", "blockquote"]);
+                }
+
+                let element = Element::new(&name, attrs);
+                if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(Node::Element(element));
+                    }
+                } else {
+                    if stack.len() > MAX_NESTING_DEPTH {
+                        pop(&mut stack);
+                    }
+                    stack.push(element);
+                }
+            }
+            Token::Close(name) => {
+                if let Some(position) = stack.iter().rposition(|e| e.name == name) {
+                    if position > 0 {
+                        while stack.len() > position {
+                            pop(&mut stack);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        pop(&mut stack);
+    }
+    stack.pop().expect("document root")
+}
+
+fn find_first<'a>(
+    element: &'a Element,
+    predicate: &dyn Fn(&Element) -> bool,
+) -> Option<&'a Element> {
+    if predicate(element) {
+        return Some(element);
+    }
+    element
+        .elements()
+        .find_map(|child| find_first(child, predicate))
+}
+
+fn collect_links(element: &Element, base: &Url, links: &mut Vec<Url>, seen: &mut HashSet<Url>) {
+    if element.name == "a" {
+        if let Some(url) = element.attr("href").and_then(|href| resolve(base, href)) {
+            if seen.insert(url.clone()) {
+                links.push(url);
+            }
+        }
+    }
+    for child in element.elements() {
+        collect_links(child, base, links, seen);
+    }
+}
+
+/// Resolve an http(s) link against the page, without its fragment
+fn resolve(base: &Url, href: &str) -> Option<Url> {
+    let mut url = base.join(href.trim()).ok()?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url)
+}
+
+fn is_boilerplate(element: &Element, in_content: bool) -> bool {
+    let name = element.name.as_str();
+    if DROPPED_ELEMENTS.contains(&name) {
+        return true;
+    }
+    // Page headers and footers are furniture, but an article's own header holds its title
+    if (name == "header" || name == "footer") && !in_content {
+        return true;
+    }
+    if element.attr("hidden").is_some() || element.attr("aria-hidden") == Some("true") {
+        return true;
+    }
+    if element
+        .attr("style")
+        .is_some_and(|style| style.replace(' ', "").contains("display:none"))
+    {
+        return true;
+    }
+    if element
+        .attr("role")
+        .is_some_and(|role| BOILERPLATE_ROLES.contains(&role.to_ascii_lowercase().as_str()))
+    {
+        return true;
+    }
+    if matches!(name, "html" | "body" | "main" | "article") {
+        return false;
+    }
+
+    let class = element.attr("class").unwrap_or_default();
+    let id = element.attr("id").unwrap_or_default();
+    class.split_whitespace().chain([id]).any(|value| {
+        let value = value.to_ascii_lowercase();
+        BOILERPLATE_NAMES.contains(&value.as_str())
+            || BOILERPLATE_NAME_PARTS
+                .iter()
+                .any(|part| value.contains(part))
+    })
+}
+
+/// A copy of the tree without boilerplate elements
+fn clean(element: &Element, in_content: bool) -> Element {
+    let in_content = in_content || matches!(element.name.as_str(), "main" | "article");
+    let children = element
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            Node::Element(e) if is_boilerplate(e, in_content) => None,
+            Node::Element(e) => Some(Node::Element(clean(e, in_content))),
+            Node::Text(text) => Some(Node::Text(text.clone())),
+        })
+        .collect();
+    Element {
+        name: element.name.clone(),
+        attrs: element.attrs.clone(),
+        children,
+    }
+}
+
+/// The element holding the page's main content: a marked-up main or article
+/// element when there is one, otherwise the element with the most paragraph text
+fn main_content(document: &Element) -> &Element {
+    let mut marked = Vec::new();
+    collect_marked_content(document, &mut marked);
+    if let Some(best) = marked
+        .into_iter()
+        .max_by_key(|element| collapse_whitespace(&element.text()).len())
+    {
+        return best;
+    }
+
+    let mut scores = HashMap::new();
+    score_paragraphs(document, &mut Vec::new(), &mut scores);
+    let best = scores
+        .into_iter()
+        .filter_map(|(path, score)| {
+            let element = element_at(document, &path)?;
+            let text_len = collapse_whitespace(&element.text()).len().max(1);
+            let link_density = element.link_text_len() as f64 / text_len as f64;
+            Some((element, score * (1.0 - link_density.min(1.0))))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    match best {
+        Some((element, score)) if score > 0.0 => element,
+        _ => find_first(document, &|e| e.name == "body").unwrap_or(document),
+    }
+}
+
+fn collect_marked_content<'a>(element: &'a Element, marked: &mut Vec<&'a Element>) {
+    if element.name == "main" || element.name == "article" || element.attr("role") == Some("main") {
+        marked.push(element);
+        return;
+    }
+    for child in element.elements() {
+        collect_marked_content(child, marked);
+    }
+}
+
+/// Credit each paragraph's parent, and half as much its grandparent, so the
+/// container of most of the prose scores highest
+fn score_paragraphs(
+    element: &Element,
+    path: &mut Vec<usize>,
+    scores: &mut HashMap<Vec<usize>, f64>,
+) {
+    if matches!(element.name.as_str(), "p" | "pre" | "td" | "blockquote") && !path.is_empty() {
+        let text = collapse_whitespace(&element.text());
+        if text.len() >= MIN_PARAGRAPH_LENGTH {
+            let score =
+                1.0 + text.matches(',').count() as f64 + (text.len() as f64 / 100.0).min(3.0);
+            let parent = path[..path.len() - 1].to_vec();
+            *scores.entry(parent.clone()).or_default() += score;
+            if let Some((_, grandparent)) = parent.split_last() {
+                *scores.entry(grandparent.to_vec()).or_default() += score / 2.0;
+            }
+        }
+    }
+    for (index, child) in element.children.iter().enumerate() {
+        if let Node::Element(child) = child {
+            path.push(index);
+            score_paragraphs(child, path, scores);
+            path.pop();
+        }
+    }
+}
+
+fn element_at<'a>(root: &'a Element, path: &[usize]) -> Option<&'a Element> {
+    path.iter()
+        .try_fold(root, |element, &index| match element.children.get(index) {
+            Some(Node::Element(child)) => Some(child),
+            _ => None,
+        })
+}
+
+struct MarkdownRenderer<'a> {
+    base: &'a Url,
+    out: String,
+}
+
+impl MarkdownRenderer<'_> {
+    fn children(&mut self, element: &Element) {
+        for child in &element.children {
+            match child {
+                Node::Text(text) => self.text(text),
+                Node::Element(e) => self.element(e),
+            }
+        }
+    }
+
+    /// Render the element's children on their own, for content that gets wrapped or prefixed
+    fn render_inner(&self, element: &Element) -> String {
+        let mut renderer = MarkdownRenderer {
+            base: self.base,
+            out: String::new(),
+        };
+        renderer.children(element);
+        normalize(&renderer.out)
+    }
+
+    fn text(&mut self, text: &str) {
+        let mut collapsed = collapse_runs(text);
+        if self.out.is_empty() || self.out.ends_with(['\n', ' ']) {
+            collapsed = collapsed.trim_start().to_string();
+        }
+        self.out.push_str(&collapsed);
+    }
+
+    fn block(&mut self, content: &str) {
+        if content.is_empty() {
+            return;
+        }
+        self.out.push_str("\n\n");
+        self.out.push_str(content);
+        self.out.push_str("\n\n");
+    }
+
+    fn inline(&mut self, content: &str) {
+        if self.out.ends_with(|c: char| c.is_alphanumeric())
+            && content.starts_with(['[', '!', '*', '`'])
+        {
+            self.out.push(' ');
+        }
+        self.out.push_str(content);
+    }
+
+    fn element(&mut self, element: &Element) {
+        match element.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = element
+                    .name
+                    .chars()
+                    .nth(1)
+                    .and_then(|c| c.to_digit(10))
+                    .unwrap_or(1) as usize;
+                let text = self.render_inner(element).replace('\n', " ");
+                if !text.is_empty() {
+                    self.block(&format!("{} {}", "#".repeat(level), text));
+                }
+            }
+            "br" => self.out.push('\n'),
+            "hr" => self.block("---"),
+            "a" => {
+                let text = self.render_inner(element).replace('\n', " ");
+                let href = element
+                    .attr("href")
+                    .filter(|href| !href.starts_with('#'))
+                    .and_then(|href| resolve(self.base, href));
+                match href {
+                    _ if text.is_empty() => {}
+                    Some(url) => self.inline(&format!("[{}]({})", text, url)),
+                    None => self.inline(&text),
+                }
+            }
+            "img" => {
+                let alt = collapse_whitespace(element.attr("alt").unwrap_or_default());
+                let src = element.attr("src").and_then(|src| resolve(self.base, src));
+                if let (false, Some(src)) = (alt.is_empty(), src) {
+                    self.inline(&format!("![{}]({})", alt, src));
+                }
+            }
+            "strong" | "b" => self.wrap(element, "**"),
+            "em" | "i" => self.wrap(element, "*"),
+            "code" | "kbd" | "samp" => self.wrap(element, "`"),
+            "pre" => {
+                let code = element.text();
+                let code = code.trim_matches('\n');
+                if !code.trim().is_empty() {
+                    self.block(&format!("```\n{}\n```", code));
+                }
+            }
+            "ul" | "ol" => {
+                let ordered = element.name == "ol";
+                let items: Vec<String> = element
+                    .elements()
+                    .filter(|child| child.name == "li")
+                    .map(|item| self.render_inner(item))
+                    .filter(|item| !item.is_empty())
+                    .enumerate()
+                    .map(|(index, item)| {
+                        let marker = if ordered {
+                            format!("{}. ", index + 1)
+                        } else {
+                            "- ".to_string()
+                        };
+                        let indent = " ".repeat(marker.len());
+                        item.lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .enumerate()
+                            .map(|(i, line)| {
+                                if i == 0 {
+                                    format!("{}{}", marker, line)
+                                } else {
+                                    format!("{}{}", indent, line)
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .collect();
+                self.block(&items.join("\n"));
+            }
+            "blockquote" => {
+                let quoted = self
+                    .render_inner(element)
+                    .lines()
+                    .map(|line| format!("> {}", line).trim_end().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.block(&quoted);
+            }
+            "table" => {
+                let table = self.table(element);
+                self.block(&table);
+            }
+            "p" | "div" | "section" | "article" | "main" | "header" | "footer" | "figure"
+            | "figcaption" | "dl" | "dt" | "dd" | "address" | "details" | "summary" | "body"
+            | "html" | "li" | "tr" => {
+                self.out.push_str("\n\n");
+                self.children(element);
+                self.out.push_str("\n\n");
+            }
+            _ => self.children(element),
+        }
+    }
+
+    fn wrap(&mut self, element: &Element, marker: &str) {
+        let text = self.render_inner(element).replace('\n', " ");
+        if !text.is_empty() {
+            self.inline(&format!("{}{}{}", marker, text, marker));
+        }
+    }
+
+    fn table(&self, table: &Element) -> String {
+        // Rows of this table, not of tables nested in its cells
+        fn rows<'a>(element: &'a Element, found: &mut Vec<&'a Element>) {
+            for child in element.elements() {
+                match child.name.as_str() {
+                    "tr" => found.push(child),
+                    "table" => {}
+                    _ => rows(child, found),
+                }
+            }
+        }
+        let mut found = Vec::new();
+        rows(table, &mut found);
+
+        let cells: Vec<Vec<String>> = found
+            .iter()
+            .map(|row| {
+                row.elements()
+                    .filter(|cell| cell.name == "td" || cell.name == "th")
+                    .map(|cell| {
+                        self.render_inner(cell)
+                            .replace('\n', " ")
+                            .replace('|', "\\|")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| !row.is_empty())
+            .collect();
+        let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return String::new();
+        }
+
+        let line = |row: &[String]| {
+            let mut row = row.to_vec();
+            row.resize(columns, String::new());
+            format!("| {} |", row.join(" | "))
+        };
+        let mut lines = vec![line(&cells[0]), line(&vec!["---".to_string(); columns])];
+        lines.extend(cells[1..].iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+}
+
+fn collapse_runs(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(c);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    collapse_runs(text).trim().to_string()
+}
+
+/// Trim trailing spaces and leave at most one blank line between blocks,
+/// except inside code fences
+fn normalize(markdown: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let line = if in_fence {
+            line.to_string()
+        } else {
+            line.trim_end().to_string()
+        };
+        if !in_fence && line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        let (before, after) = rest.split_at(start);
+        decoded.push_str(before);
+        let entity_end = after
+            .char_indices()
+            .take(12)
+            .find(|(_, c)| *c == ';')
+            .map(|(i, _)| i);
+        let replacement = entity_end.and_then(|end| {
+            let name = after.get(1..end)?;
+            let c = match name {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                "mdash" => '—',
+                "ndash" => '–',
+                "hellip" => '…',
+                "lsquo" => '‘',
+                "rsquo" => '’',
+                "ldquo" => '“',
+                "rdquo" => '”',
+                "copy" => '©',
+                "reg" => '®',
+                "trade" => '™',
+                _ => {
+                    let code = name.strip_prefix('#')?;
+                    let value = match code.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => code.parse().ok()?,
+                    };
+                    char::from_u32(value)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match replacement {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = after.get(len..).unwrap_or_default();
+            }
+            None => {
+                decoded.push('&');
+                rest = after.get(1..).unwrap_or_default();
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Tide Pools &amp; Their Residents</title>
+  <style>body { color: red; }</style>
+  <script>var tracking = "<p>not content</p>";</script>
+</head>
+<body>
+  <header class="site-header"><a href="/">Home</a> <a href="/about">About</a></header>
+  <nav><ul><li><a href="/a">A</a><li><a href="/b">B</a></ul></nav>
+  <div id="cookie-banner">We use cookies, please accept them.</div>
+  <article>
+    <header><h1>Tide Pools</h1></header>
+    <p>Tide pools form on rocky shores, where the ocean leaves water behind at low tide.
+    Many creatures live there, including <strong>anemones</strong>, crabs, and <a href="creatures/snails.html#top">snails</a>.</p>
+    <h2>Visiting</h2>
+    <ul>
+      <li>Go at low tide
+      <li>Wear shoes with grip
+        <ol><li>Rubber soles</ol>
+    </ul>
+    <pre><code>low_tide = 0.3m
+high_tide = 2.1m</code></pre>
+    <table>
+      <tr><th>Creature<th>Count
+      <tr><td>Crab<td>4
+    </table>
+    <blockquote>Look, but don't touch.</blockquote>
+    <img src="/img/pool.jpg" alt="A tide pool">
+  </article>
+  <aside>Related posts you might like</aside>
+  <footer>Copyright 2024</footer>
+</body>
+</html>"#;
+
+    fn url() -> Url {
+        Url::parse("https://example.com/guides/tide-pools.html").unwrap()
+    }
+
+    #[test]
+    fn test_extracts_article_as_markdown() {
+        let document = extract(ARTICLE_PAGE, &url());
+        let markdown = &document.markdown;
+
+        assert_eq!(
+            document.title.as_deref(),
+            Some("Tide Pools & Their Residents")
+        );
+        assert!(markdown.starts_with("# Tide Pools\n\n"), "{markdown}");
+        assert!(markdown.contains("## Visiting"), "{markdown}");
+        assert!(
+            markdown.contains("including **anemones**, crabs, and [snails](https://example.com/guides/creatures/snails.html)."),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("- Go at low tide\n- Wear shoes with grip\n  1. Rubber soles"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("```\nlow_tide = 0.3m\nhigh_tide = 2.1m\n```"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("| Creature | Count |\n| --- | --- |\n| Crab | 4 |"),
+            "{markdown}"
+        );
+        assert!(markdown.contains("> Look, but don't touch."), "{markdown}");
+        assert!(
+            markdown.contains("![A tide pool](https://example.com/img/pool.jpg)"),
+            "{markdown}"
+        );
+    }
+
+    #[test]
+    fn test_drops_boilerplate() {
+        let markdown = extract(ARTICLE_PAGE, &url()).markdown;
+        for boilerplate in [
+            "color: red",
+            "tracking",
+            "About",
+            "cookies",
+            "Related posts",
+            "Copyright",
+        ] {
+            assert!(
+                !markdown.contains(boilerplate),
+                "{boilerplate} in {markdown}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_picks_densest_content_without_article() {
+        let html = r#"<body>
+            <div class="links"><a href="/1">One</a> <a href="/2">Two</a> <a href="/3">Three</a></div>
+            <div class="content">
+              <p>The first paragraph of the story, which goes on for a while, with commas.</p>
+              <p>The second paragraph carries on, adding more detail to the story.</p>
+            </div>
+            <div><p>Short</p></div>
+        </body>"#;
+        let markdown = extract(html, &url()).markdown;
+        assert!(markdown.starts_with("The first paragraph"), "{markdown}");
+        assert!(markdown.contains("\n\nThe second paragraph"), "{markdown}");
+        assert!(!markdown.contains("One"), "{markdown}");
+        assert!(!markdown.contains("Short"), "{markdown}");
+    }
+
+    #[test]
+    fn test_collects_links() {
+        let document = extract(ARTICLE_PAGE, &url());
+        let links: Vec<&str> = document.links.iter().map(Url::as_str).collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/",
+                "https://example.com/about",
+                "https://example.com/a",
+                "https://example.com/b",
+                "https://example.com/guides/creatures/snails.html",
+            ]
+        );
+
+        let html = r#"<base href="https://docs.example.com/v2/"><a href="intro">Intro</a>
+            <a href="mailto:me@example.com">Mail</a><a href="javascript:void(0)">JS</a>"#;
+        let links = extract(html, &url()).links;
+        assert_eq!(
+            links,
+            vec![Url::parse("https://docs.example.com/v2/intro").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("a &amp; b &lt;c&gt;"), "a & b <c>");
+        assert_eq!(decode_entities("&#65;&#x42;&nbsp;&rsquo;"), "AB ’");
+        assert_eq!(
+            decode_entities("AT&T &unknown; & done"),
+            "AT&T &unknown; & done"
+        );
+    }
+
+    #[test]
+    fn test_deeply_nested_page_is_parsed() {
+        let depth = 100_000;
+        let html = format!(
+            "<body>{}<p>Deep text, nested far below the surface.</p><a href=\"/deep\">Deep</a>{}</body>",
+            "<div>".repeat(depth),
+            "</div>".repeat(depth)
+        );
+        let document = extract(&html, &url());
+        assert!(
+            document
+                .markdown
+                .contains("Deep text, nested far below the surface."),
+            "{}",
+            document.markdown
+        );
+        assert_eq!(
+            document.links,
+            vec![Url::parse("https://example.com/deep").unwrap()]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::path::Path;
 use umya_spreadsheet::{Spreadsheet, Worksheet};
 
@@ -38,6 +39,17 @@ impl XlsxTool {
         Ok(Self { workbook })
     }
 
+    /// A new workbook with a single empty worksheet
+    pub fn create(worksheet_name: Option<&str>) -> Result<Self> {
+        let mut workbook = umya_spreadsheet::new_file();
+        if let Some(name) = worksheet_name {
+            workbook
+                .set_sheet_name(0, name)
+                .map_err(|e| anyhow::anyhow!("Failed to name worksheet '{}': {}", name, e))?;
+        }
+        Ok(Self { workbook })
+    }
+
     pub fn list_worksheets(&self) -> Result<Vec<WorksheetInfo>> {
         let mut worksheets = Vec::new();
         for (index, worksheet) in self.workbook.get_sheet_collection().iter().enumerate() {
@@ -151,6 +163,197 @@ impl XlsxTool {
         Ok(())
     }
 
+    pub fn add_worksheet(&mut self, name: &str) -> Result<()> {
+        self.workbook
+            .new_sheet(name)
+            .map_err(|e| anyhow::anyhow!("Failed to add worksheet '{}': {}", name, e))?;
+        Ok(())
+    }
+
+    pub fn rename_worksheet(&mut self, name: &str, new_name: &str) -> Result<()> {
+        let index = self.worksheet_index(name)?;
+        self.workbook
+            .set_sheet_name(index, new_name)
+            .map_err(|e| anyhow::anyhow!("Failed to rename worksheet to '{}': {}", new_name, e))
+    }
+
+    pub fn delete_worksheet(&mut self, name: &str) -> Result<()> {
+        let index = self.worksheet_index(name)?;
+        if self.workbook.get_sheet_count() == 1 {
+            anyhow::bail!("Cannot delete the only worksheet in the workbook");
+        }
+        self.workbook
+            .remove_sheet(index)
+            .map_err(|e| anyhow::anyhow!("Failed to delete worksheet '{}': {}", name, e))?;
+        // The active tab may have pointed at the removed worksheet
+        self.workbook.set_active_sheet(0);
+        Ok(())
+    }
+
+    /// Write rows of values starting at the top-left cell of `start` (e.g. 'B2' or 'B2:D4').
+    /// Strings starting with '=' are written as formulas and nulls leave the cell unchanged.
+    /// Returns the range that was written in A1 notation.
+    pub fn write_range(
+        &mut self,
+        worksheet_name: Option<&str>,
+        start: &str,
+        values: &[Vec<Value>],
+    ) -> Result<String> {
+        let start_ref = start.split(':').next().unwrap_or_default();
+        let (start_row, start_col) = parse_cell_reference(start_ref)?;
+        let worksheet = self.worksheet_mut(worksheet_name)?;
+
+        let mut end_col = start_col;
+        for (row_offset, row_values) in values.iter().enumerate() {
+            let row = start_row + row_offset as u32;
+            for (col_offset, value) in row_values.iter().enumerate() {
+                let col = start_col + col_offset as u32;
+                end_col = end_col.max(col);
+                let cell = worksheet.get_cell_mut((col, row));
+                match value {
+                    Value::Null => {}
+                    Value::Bool(value) => {
+                        cell.set_value_bool(*value);
+                    }
+                    Value::Number(number) => {
+                        let number = number
+                            .as_f64()
+                            .with_context(|| format!("Invalid number {}", number))?;
+                        cell.set_value_number(number);
+                    }
+                    Value::String(text) => match text.strip_prefix('=') {
+                        Some(formula) => {
+                            cell.set_formula(formula);
+                        }
+                        None => {
+                            cell.set_value(text.as_str());
+                        }
+                    },
+                    Value::Array(_) | Value::Object(_) => {
+                        cell.set_value(value.to_string());
+                    }
+                }
+            }
+        }
+
+        let end_row = start_row + values.len().saturating_sub(1) as u32;
+        Ok(format!(
+            "{}{}:{}{}",
+            column_number_to_letter(start_col),
+            start_row,
+            column_number_to_letter(end_col),
+            end_row
+        ))
+    }
+
+    pub fn set_formula(
+        &mut self,
+        worksheet_name: Option<&str>,
+        row: u32,
+        col: u32,
+        formula: &str,
+    ) -> Result<()> {
+        let formula = formula.trim();
+        let formula = formula.strip_prefix('=').unwrap_or(formula);
+        if formula.is_empty() {
+            anyhow::bail!("Formula is empty");
+        }
+        self.worksheet_mut(worksheet_name)?
+            .get_cell_mut((col, row))
+            .set_formula(formula);
+        Ok(())
+    }
+
+    /// Apply a number format code (e.g. '0.00%' or 'yyyy-mm-dd') to a cell or range,
+    /// returning how many cells were formatted
+    pub fn set_number_format(
+        &mut self,
+        worksheet_name: Option<&str>,
+        range: &str,
+        format_code: &str,
+    ) -> Result<usize> {
+        let (start_row, start_col, end_row, end_col) = if range.contains(':') {
+            parse_range(range)?
+        } else {
+            let (row, col) = parse_cell_reference(range)?;
+            (row, col, row, col)
+        };
+        let worksheet = self.worksheet_mut(worksheet_name)?;
+
+        let mut count = 0;
+        for row in start_row..=end_row {
+            for col in start_col..=end_col {
+                worksheet
+                    .get_style_mut((col, row))
+                    .get_number_format_mut()
+                    .set_format_code(format_code);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// The worksheet's values as CSV, one line per row up to the last used row and column
+    pub fn to_csv(&self, worksheet: &Worksheet) -> String {
+        let mut csv = String::new();
+        for row_num in 1..=worksheet.get_highest_row() {
+            let fields: Vec<String> = (1..=worksheet.get_highest_column())
+                .map(|col_num| {
+                    worksheet
+                        .get_cell((col_num, row_num))
+                        .map(|cell| csv_field(&cell.get_value()))
+                        .unwrap_or_default()
+                })
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+
+    /// Write CSV content into a new worksheet, returning the number of rows imported.
+    /// Numbers and booleans are stored as such rather than as text.
+    pub fn import_csv(&mut self, worksheet_name: &str, content: &str) -> Result<usize> {
+        let rows = parse_csv(content)?;
+        let worksheet = self
+            .workbook
+            .new_sheet(worksheet_name)
+            .map_err(|e| anyhow::anyhow!("Failed to add worksheet '{}': {}", worksheet_name, e))?;
+
+        for (row_idx, fields) in rows.iter().enumerate() {
+            for (col_idx, field) in fields.iter().enumerate() {
+                if !field.is_empty() {
+                    worksheet
+                        .get_cell_mut((col_idx as u32 + 1, row_idx as u32 + 1))
+                        .set_value(field.as_str());
+                }
+            }
+        }
+        Ok(rows.len())
+    }
+
+    fn worksheet_index(&self, name: &str) -> Result<usize> {
+        self.workbook
+            .get_sheet_collection()
+            .iter()
+            .position(|worksheet| worksheet.get_name() == name)
+            .with_context(|| format!("Worksheet '{}' not found", name))
+    }
+
+    fn worksheet_mut(&mut self, name: Option<&str>) -> Result<&mut Worksheet> {
+        match name {
+            Some(name) => self
+                .workbook
+                .get_sheet_by_name_mut(name)
+                .with_context(|| format!("Worksheet '{}' not found", name)),
+            None => self
+                .workbook
+                .get_sheet_collection_mut()
+                .first_mut()
+                .context("Workbook has no worksheets"),
+        }
+    }
+
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         umya_spreadsheet::writer::xlsx::write(&self.workbook, path)
             .context("Failed to save Excel file")?;
@@ -256,6 +459,68 @@ fn column_letter_to_number(column: &str) -> Result<u32> {
     Ok(result)
 }
 
+fn column_number_to_letter(mut column: u32) -> String {
+    let mut letters = Vec::new();
+    while column > 0 {
+        let remainder = (column - 1) % 26;
+        letters.push((b'A' + remainder as u8) as char);
+        column = (column - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse CSV as described in RFC 4180: fields separated by commas, optionally
+/// quoted with doubled quotes inside, and quoted fields may span lines
+fn parse_csv(content: &str) -> Result<Vec<Vec<String>>> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        anyhow::bail!("Invalid CSV: unterminated quoted field");
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +535,12 @@ mod tests {
             .join("FinancialSample.xlsx")
     }
 
+    fn copy_test_file(dir: &tempfile::TempDir) -> PathBuf {
+        let path = dir.path().join("FinancialSample.xlsx");
+        std::fs::copy(get_test_file(), &path).unwrap();
+        path
+    }
+
     #[test]
     fn test_open_xlsx() -> Result<()> {
         let xlsx = XlsxTool::new(get_test_file())?;
@@ -413,4 +684,123 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_create_and_manage_worksheets() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("new.xlsx");
+
+        let mut xlsx = XlsxTool::create(Some("Summary"))?;
+        xlsx.add_worksheet("Data")?;
+        xlsx.add_worksheet("Scratch")?;
+        assert!(xlsx.add_worksheet("Data").is_err());
+        xlsx.rename_worksheet("Data", "Sales")?;
+        xlsx.delete_worksheet("Scratch")?;
+        xlsx.save(&path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let names: Vec<String> = xlsx
+            .list_worksheets()?
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(names, vec!["Summary", "Sales"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cannot_delete_last_worksheet() -> Result<()> {
+        let mut xlsx = XlsxTool::create(None)?;
+        assert!(xlsx.delete_worksheet("Sheet1").is_err());
+        assert!(xlsx.rename_worksheet("Missing", "Other").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_range_formula_and_number_format() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = copy_test_file(&dir);
+
+        let mut xlsx = XlsxTool::new(&path)?;
+        xlsx.add_worksheet("Totals")?;
+        let written = xlsx.write_range(
+            Some("Totals"),
+            "B2",
+            &[
+                vec![serde_json::json!("Item"), serde_json::json!("Amount")],
+                vec![serde_json::json!("Paseo"), serde_json::json!(0.125)],
+                vec![serde_json::json!("Velo"), serde_json::json!(42)],
+                vec![serde_json::json!("Total"), serde_json::json!("=SUM(C3:C4)")],
+            ],
+        )?;
+        assert_eq!(written, "B2:C5");
+        xlsx.set_formula(Some("Totals"), 6, 3, "=C5*2")?;
+        assert_eq!(xlsx.set_number_format(Some("Totals"), "C3:C4", "0.00%")?, 2);
+        xlsx.save(&path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let worksheet = xlsx.get_worksheet_by_name("Totals")?;
+        let range = xlsx.get_range(worksheet, "B2:C6")?;
+        assert_eq!(range.values[0][0].value, "Item");
+        assert_eq!(range.values[1][1].value, "0.125");
+        assert_eq!(range.values[2][1].value, "42");
+        assert_eq!(range.values[3][1].formula.as_deref(), Some("SUM(C3:C4)"));
+        assert_eq!(range.values[4][1].formula.as_deref(), Some("C5*2"));
+        let format = worksheet
+            .get_style((3, 3))
+            .get_number_format()
+            .map(|format| format.get_format_code().to_string());
+        assert_eq!(format.as_deref(), Some("0.00%"));
+
+        // The original data is untouched
+        let original = xlsx.get_worksheet_by_index(0)?;
+        assert_eq!(xlsx.get_cell_value(original, 2, 1)?.value, "Government");
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = copy_test_file(&dir);
+
+        let mut xlsx = XlsxTool::new(&path)?;
+        let worksheet = xlsx.get_worksheet_by_index(0)?;
+        let csv = xlsx.to_csv(worksheet);
+        assert!(csv.starts_with("Segment,Country,"));
+        let (columns, rows) = xlsx.get_worksheet_dimensions(worksheet)?;
+
+        assert_eq!(xlsx.import_csv("Imported", &csv)?, rows);
+        xlsx.save(&path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let original = xlsx.get_worksheet_by_index(0)?;
+        let imported = xlsx.get_worksheet_by_name("Imported")?;
+        assert_eq!(xlsx.get_worksheet_dimensions(imported)?, (columns, rows));
+        for (row, col) in [(1, 1), (2, 2), (2, 5), (rows as u32, columns as u32)] {
+            assert_eq!(
+                xlsx.get_cell_value(imported, row, col)?.value,
+                xlsx.get_cell_value(original, row, col)?.value
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_csv() -> Result<()> {
+        let rows = parse_csv("name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\n,last")?;
+        assert_eq!(
+            rows,
+            vec![
+                vec!["name", "note"],
+                vec!["Smith, J", "said \"hi\"\nthen left"],
+                vec!["", "last"],
+            ]
+        );
+        assert!(parse_csv("\"unterminated").is_err());
+
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
+use crate::developer::analyze::lookup;
 use crate::developer::analyze::types::{
-    AnalysisMode, AnalysisResult, CallChain, EntryType, FocusedAnalysisData,
+    AnalysisMode, AnalysisResult, CallChain, EntryType, FocusedAnalysisData, SymbolLocation,
 };
 use crate::developer::lang;
 use goose::utils::safe_truncate;
@@ -30,9 +31,9 @@ impl Formatter {
         match mode {
             AnalysisMode::Structure => Self::format_structure_overview(path, result),
             AnalysisMode::Semantic => Self::format_semantic_result(path, result),
-            AnalysisMode::Focused => {
-                // Focused mode is handled separately
-                tracing::warn!("format_analysis_result called with Focused mode");
+            AnalysisMode::Focused | AnalysisMode::Definition | AnalysisMode::References => {
+                // Symbol modes are handled separately
+                tracing::warn!("format_analysis_result called with {:?} mode", mode);
                 String::new()
             }
         }
@@ -705,6 +706,155 @@ impl Formatter {
         }
     }
 
+    /// Format definition lookup output, with the code around each definition
+    pub fn format_definitions(
+        symbol: &str,
+        root: &Path,
+        definitions: &[SymbolLocation],
+        context_lines: usize,
+    ) -> String {
+        if definitions.is_empty() {
+            return format!(
+                "Symbol '{}' has no definition in any analyzed files.\n",
+                symbol
+            );
+        }
+
+        let name = lookup::SymbolQuery::parse(symbol).name;
+        let mut output = format!("DEFINITION: {}\n", symbol);
+        Self::append_ambiguity_note(&mut output, symbol, definitions);
+
+        for definition in definitions {
+            output.push_str(&format!(
+                "\n{}:{} {}\n",
+                Self::relative_display(&definition.file, root),
+                definition.line,
+                Self::describe_definition(definition, &name)
+            ));
+
+            let Ok(content) = std::fs::read_to_string(&definition.file) else {
+                continue;
+            };
+            let lines: Vec<&str> = content.lines().collect();
+            let start = definition.line.saturating_sub(context_lines).max(1);
+            let end = (definition.line + context_lines).min(lines.len());
+            let width = end.to_string().len();
+            for (index, line) in lines
+                .iter()
+                .enumerate()
+                .take(end)
+                .skip(start.saturating_sub(1))
+            {
+                let number = index + 1;
+                let marker = if number == definition.line { '>' } else { ' ' };
+                output.push_str(&format!(
+                    "{}{:>width$} | {}\n",
+                    marker,
+                    number,
+                    line,
+                    width = width
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Format references lookup output, one file:line per reference
+    pub fn format_references(
+        symbol: &str,
+        root: &Path,
+        definitions: &[SymbolLocation],
+        references: &[SymbolLocation],
+    ) -> String {
+        if definitions.is_empty() && references.is_empty() {
+            return format!("Symbol '{}' not found in any analyzed files.\n", symbol);
+        }
+
+        let name = lookup::SymbolQuery::parse(symbol).name;
+        let files: HashSet<_> = references.iter().map(|r| &r.file).collect();
+        let mut output = format!(
+            "REFERENCES: {} ({} in {} files)\n",
+            symbol,
+            references.len(),
+            files.len()
+        );
+        Self::append_ambiguity_note(&mut output, symbol, definitions);
+
+        if !definitions.is_empty() {
+            output.push_str("\nDEFINED AT:\n");
+            for definition in definitions {
+                output.push_str(&format!(
+                    "{}:{} {}\n",
+                    Self::relative_display(&definition.file, root),
+                    definition.line,
+                    Self::describe_definition(definition, &name)
+                ));
+            }
+        }
+
+        if !references.is_empty() {
+            output.push_str("\nREFERENCES:\n");
+            for reference in references {
+                let location = format!(
+                    "{}:{}",
+                    Self::relative_display(&reference.file, root),
+                    reference.line
+                );
+                let kind = match &reference.container {
+                    Some(caller) => format!("{} in {}", reference.kind, caller),
+                    None => reference.kind.to_string(),
+                };
+                output.push_str(&format!(
+                    "{} [{}] {}\n",
+                    location,
+                    kind,
+                    safe_truncate(&reference.context, 120)
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Point out same-named definitions, so the model can qualify the symbol
+    fn append_ambiguity_note(output: &mut String, symbol: &str, definitions: &[SymbolLocation]) {
+        let query = lookup::SymbolQuery::parse(symbol);
+        if !query.qualifier.is_empty() {
+            return;
+        }
+        let mut names: Vec<String> = definitions
+            .iter()
+            .map(|d| lookup::qualified_name(d, &query.name))
+            .collect();
+        names.sort();
+        names.dedup();
+        if names.len() > 1 {
+            output.push_str(&format!(
+                "NOTE: {} definitions share this name ({}). Qualify the symbol, e.g. focus=\"{}\", to narrow the results.\n",
+                definitions.len(),
+                names.join(", "),
+                names[0]
+            ));
+        }
+    }
+
+    fn describe_definition(definition: &SymbolLocation, name: &str) -> String {
+        match &definition.container {
+            Some(container) => format!("({} {}::{})", definition.kind, container, name),
+            None => format!("({})", definition.kind),
+        }
+    }
+
+    fn relative_display(file: &Path, root: &Path) -> String {
+        file.strip_prefix(root)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(file)
+            .display()
+            .to_string()
+    }
+
     /// Filter output by focus symbol
     pub fn filter_by_focus(output: &str, focus: &str) -> String {
         let mut filtered = String::new();
@@ -0,0 +1,344 @@
+use etcetera::{choose_app_strategy, AppStrategy};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+
+use super::lock_or_recover;
+use crate::developer::analyze::types::AnalysisResult;
+
+/// Bumped when the index layout changes; results from other goose versions are also discarded
+/// because their queries may have extracted different symbols
+const INDEX_VERSION: u32 = 3;
+
+/// How long to wait after a change before writing the index, so a burst of edits
+/// or analyses results in a single write
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    goose_version: String,
+    files: HashMap<PathBuf, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    modified: SystemTime,
+    hash: String,
+    result: AnalysisResult,
+}
+
+#[derive(Default)]
+struct IndexState {
+    loaded: bool,
+    entries: HashMap<PathBuf, IndexEntry>,
+    /// Entries changed since the last save, or removed if no longer in `entries`
+    dirty: HashSet<PathBuf>,
+    /// Whether a delayed save has been scheduled and not run yet
+    save_pending: bool,
+}
+
+/// Semantic analysis results for the files of one project, persisted on disk so
+/// they survive across sessions. Every session in the same working directory
+/// shares the index, and entries are reused while a file's modification time or
+/// content hash is unchanged. A save that is still pending when the last handle to
+/// the index is dropped is written right away.
+#[derive(Clone)]
+pub struct AnalysisIndex {
+    root: PathBuf,
+    index_path: PathBuf,
+    save_delay: Duration,
+    state: Arc<Mutex<IndexState>>,
+}
+
+impl AnalysisIndex {
+    pub fn new(root: PathBuf, index_path: PathBuf) -> Self {
+        Self {
+            root,
+            index_path,
+            save_delay: SAVE_DELAY,
+            state: Arc::new(Mutex::new(IndexState::default())),
+        }
+    }
+
+    /// Set how long `schedule_save` waits before writing; a zero delay saves immediately
+    pub fn with_save_delay(mut self, save_delay: Duration) -> Self {
+        self.save_delay = save_delay;
+        self
+    }
+
+    /// The index for a project, stored in goose's data directory
+    /// - macOS/Linux: ~/.local/share/goose/analyze_index/
+    /// - Windows:     ~\AppData\Roaming\Block\goose\data\analyze_index\
+    pub fn for_root(root: &Path) -> Option<Self> {
+        let index_dir = choose_app_strategy(crate::APP_STRATEGY.clone())
+            .ok()?
+            .in_data_dir("analyze_index");
+        // Name the index after the canonical path so every way of reaching the project shares it
+        let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let name: String = hash_bytes(canonical.to_string_lossy().as_bytes())
+            .chars()
+            .take(16)
+            .collect();
+        let index_path = index_dir.join(format!("{}.json", name));
+        Some(Self::new(root.to_path_buf(), index_path))
+    }
+
+    /// Whether `path` is inside the indexed project
+    pub fn covers(&self, path: &Path) -> bool {
+        self.relative(path).is_some()
+    }
+
+    /// The indexed result for `path` if the file has not been modified since it was indexed
+    pub fn get_fresh(&self, path: &Path, modified: SystemTime) -> Option<AnalysisResult> {
+        let key = self.relative(path)?;
+        let state = self.loaded_state();
+        state
+            .entries
+            .get(&key)
+            .filter(|entry| entry.modified == modified)
+            .map(|entry| entry.result.clone())
+    }
+
+    /// The indexed result for `path` if its content is unchanged, even though its
+    /// modification time is not, e.g. after a checkout or a touch
+    pub fn get_unchanged(
+        &self,
+        path: &Path,
+        modified: SystemTime,
+        content: &str,
+    ) -> Option<AnalysisResult> {
+        let key = self.relative(path)?;
+        let hash = hash_bytes(content.as_bytes());
+        let mut state = self.loaded_state();
+        let entry = state
+            .entries
+            .get_mut(&key)
+            .filter(|entry| entry.hash == hash)?;
+        entry.modified = modified;
+        let result = entry.result.clone();
+        state.dirty.insert(key);
+        Some(result)
+    }
+
+    pub fn put(&self, path: &Path, modified: SystemTime, content: &str, result: AnalysisResult) {
+        let Some(key) = self.relative(path) else {
+            return;
+        };
+        let entry = IndexEntry {
+            modified,
+            hash: hash_bytes(content.as_bytes()),
+            result,
+        };
+        let mut state = self.loaded_state();
+        state.entries.insert(key.clone(), entry);
+        state.dirty.insert(key);
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.relative(path)
+            .is_some_and(|key| self.loaded_state().entries.contains_key(&key))
+    }
+
+    pub fn remove(&self, path: &Path) {
+        let Some(key) = self.relative(path) else {
+            return;
+        };
+        let mut state = self.loaded_state();
+        if state.entries.remove(&key).is_some() {
+            state.dirty.insert(key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.loaded_state().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Save changed entries once the save delay has passed. Further changes made
+    /// in the meantime are written by the same save.
+    pub fn schedule_save(&self) {
+        if self.save_delay.is_zero() {
+            if let Err(e) = self.save() {
+                tracing::warn!("Failed to save analysis index: {}", e);
+            }
+            return;
+        }
+
+        {
+            let mut state = lock_or_recover(&self.state, |s| *s = IndexState::default());
+            if state.dirty.is_empty() || state.save_pending {
+                return;
+            }
+            state.save_pending = true;
+        }
+
+        // The thread does not keep the index alive, so dropping the last handle
+        // flushes the save instead of leaving it to a thread the process may not wait for
+        let state = Arc::downgrade(&self.state);
+        let root = self.root.clone();
+        let index_path = self.index_path.clone();
+        let save_delay = self.save_delay;
+        std::thread::spawn(move || {
+            std::thread::sleep(save_delay);
+            let Some(state) = Weak::upgrade(&state) else {
+                return;
+            };
+            if let Err(e) = save_state(&root, &index_path, &state) {
+                tracing::warn!("Failed to save analysis index: {}", e);
+            }
+        });
+    }
+
+    /// Write changed entries to disk. Entries saved by other sessions since this
+    /// index was loaded are kept, so sessions sharing a project don't undo each
+    /// other's work, and entries for files that no longer exist are dropped.
+    pub fn save(&self) -> std::io::Result<()> {
+        save_state(&self.root, &self.index_path, &self.state)
+    }
+
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        path.strip_prefix(&self.root).ok().map(Path::to_path_buf)
+    }
+
+    fn loaded_state(&self) -> std::sync::MutexGuard<'_, IndexState> {
+        let mut state = lock_or_recover(&self.state, |s| *s = IndexState::default());
+        if !state.loaded {
+            state.entries = read_index_file(&self.index_path).unwrap_or_default();
+            state.loaded = true;
+            tracing::debug!(
+                "Loaded {} entries from analysis index {:?}",
+                state.entries.len(),
+                self.index_path
+            );
+        }
+        state
+    }
+}
+
+impl Drop for AnalysisIndex {
+    fn drop(&mut self) {
+        if Arc::strong_count(&self.state) > 1 {
+            return;
+        }
+        let save_pending =
+            lock_or_recover(&self.state, |s| *s = IndexState::default()).save_pending;
+        if save_pending {
+            if let Err(e) = self.save() {
+                tracing::warn!("Failed to save analysis index: {}", e);
+            }
+        }
+    }
+}
+
+/// Save the changes in `state`, holding its lock only to take and return them so
+/// analyses can carry on while the index is written
+fn save_state(root: &Path, index_path: &Path, state: &Mutex<IndexState>) -> std::io::Result<()> {
+    let (changes, keys) = {
+        let mut state = lock_or_recover(state, |s| *s = IndexState::default());
+        state.save_pending = false;
+        if state.dirty.is_empty() {
+            return Ok(());
+        }
+        let dirty = std::mem::take(&mut state.dirty);
+        let changes: Vec<(PathBuf, Option<IndexEntry>)> = dirty
+            .into_iter()
+            .map(|key| {
+                let entry = state.entries.get(&key).cloned();
+                (key, entry)
+            })
+            .collect();
+        let keys: Vec<PathBuf> = state.entries.keys().cloned().collect();
+        (changes, keys)
+    };
+
+    if let Err(e) = write_changes(root, index_path, &changes) {
+        // Keep the changes so a later save retries them
+        let mut state = lock_or_recover(state, |s| *s = IndexState::default());
+        state.dirty.extend(changes.into_iter().map(|(key, _)| key));
+        return Err(e);
+    }
+
+    let removed: Vec<PathBuf> = keys
+        .into_iter()
+        .filter(|key| !root.join(key).exists())
+        .collect();
+    let mut state = lock_or_recover(state, |s| *s = IndexState::default());
+    for key in removed {
+        // Entries changed while saving are left for the next save
+        if !state.dirty.contains(&key) {
+            state.entries.remove(&key);
+        }
+    }
+    Ok(())
+}
+
+fn write_changes(
+    root: &Path,
+    index_path: &Path,
+    changes: &[(PathBuf, Option<IndexEntry>)],
+) -> std::io::Result<()> {
+    let parent = index_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)?;
+
+    // Hold the lock from reading the current index until the merged one replaces
+    // it, so a concurrent save from another process cannot be lost. It is released
+    // when the lock file is closed.
+    let lock_file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(index_path.with_extension("lock"))?;
+    lock_file.lock_exclusive()?;
+
+    let mut files = read_index_file(index_path).unwrap_or_default();
+    for (key, entry) in changes {
+        match entry {
+            Some(entry) => files.insert(key.clone(), entry.clone()),
+            None => files.remove(key),
+        };
+    }
+    files.retain(|key, _| root.join(key).exists());
+
+    let index = IndexFile {
+        version: INDEX_VERSION,
+        goose_version: env!("CARGO_PKG_VERSION").to_string(),
+        files,
+    };
+    let json = serde_json::to_vec(&index).map_err(std::io::Error::other)?;
+
+    // Write to a temporary file first so concurrent readers never see a partial index
+    let mut tmp_file = tempfile::NamedTempFile::new_in(parent)?;
+    std::io::Write::write_all(&mut tmp_file, &json)?;
+    tmp_file.persist(index_path).map_err(|e| e.error)?;
+
+    tracing::debug!(
+        "Saved {} changed entries to analysis index {:?}",
+        changes.len(),
+        index_path
+    );
+    Ok(())
+}
+
+fn read_index_file(path: &Path) -> Option<HashMap<PathBuf, IndexEntry>> {
+    let data = std::fs::read(path).ok()?;
+    let index: IndexFile = serde_json::from_slice(&data)
+        .map_err(|e| tracing::warn!("Ignoring unreadable analysis index {:?}: {}", path, e))
+        .ok()?;
+    (index.version == INDEX_VERSION && index.goose_version == env!("CARGO_PKG_VERSION"))
+        .then_some(index.files)
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
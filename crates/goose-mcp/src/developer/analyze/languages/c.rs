@@ -0,0 +1,96 @@
+/// Tree-sitter query for extracting C code elements
+pub const ELEMENT_QUERY: &str = r#"
+    ; Function definitions, including those returning pointers
+    (function_definition
+      declarator: (function_declarator
+        declarator: (identifier) @func))
+    (function_definition
+      declarator: (pointer_declarator
+        declarator: (function_declarator
+          declarator: (identifier) @func)))
+
+    ; Struct definitions and typedefs
+    (struct_specifier
+      name: (type_identifier) @struct
+      body: (field_declaration_list))
+    (type_definition
+      declarator: (type_identifier) @struct)
+
+    (preproc_include path: (_) @import)
+"#;
+
+/// Tree-sitter query for extracting C function calls
+pub const CALL_QUERY: &str = r#"
+    ; Function calls
+    (call_expression
+      function: (identifier) @function.call)
+
+    ; Calls through struct fields (function pointers)
+    (call_expression
+      function: (field_expression
+        field: (field_identifier) @method.call))
+"#;
+
+/// Tree-sitter query for extracting C type references and usage patterns
+pub const REFERENCE_QUERY: &str = r#"
+    ; Compound literals - (Point){1, 2}
+    (compound_literal_expression
+      type: (type_descriptor
+        type: (type_identifier) @struct.literal))
+
+    ; Field types in structs
+    (field_declaration
+      type: (type_identifier) @field.type)
+    (field_declaration
+      type: (struct_specifier
+        name: (type_identifier) @field.type))
+
+    ; Parameter types
+    (parameter_declaration
+      type: (type_identifier) @param.type)
+    (parameter_declaration
+      type: (struct_specifier
+        name: (type_identifier) @param.type))
+
+    ; Local variable types
+    (compound_statement
+      (declaration
+        type: (type_identifier) @var.type))
+    (compound_statement
+      (declaration
+        type: (struct_specifier
+          name: (type_identifier) @var.type)))
+"#;
+
+/// Extract function name for C and C++ function definitions
+///
+/// The name of a C function is nested in its declarator, possibly behind
+/// pointer or reference declarators. For C++ methods defined outside their
+/// class (`Type::method`), only the method name is returned.
+pub fn extract_function_name_for_kind(
+    node: &tree_sitter::Node,
+    source: &str,
+    kind: &str,
+) -> Option<String> {
+    if kind != "function_definition" {
+        return None;
+    }
+
+    let mut declarator = node.child_by_field_name("declarator")?;
+    loop {
+        match declarator.kind() {
+            "function_declarator" | "pointer_declarator" | "reference_declarator" => {
+                declarator = declarator
+                    .child_by_field_name("declarator")
+                    .or_else(|| declarator.named_child(0))?;
+            }
+            "qualified_identifier" => {
+                declarator = declarator.child_by_field_name("name")?;
+            }
+            "identifier" | "field_identifier" | "destructor_name" | "operator_name" => {
+                return source.get(declarator.byte_range()).map(|s| s.to_string());
+            }
+            _ => return None,
+        }
+    }
+}
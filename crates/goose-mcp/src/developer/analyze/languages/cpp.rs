@@ -0,0 +1,140 @@
+/// Tree-sitter query for extracting C++ code elements
+pub const ELEMENT_QUERY: &str = r#"
+    ; Free functions
+    (function_definition
+      declarator: (function_declarator
+        declarator: (identifier) @func))
+    (function_definition
+      declarator: (pointer_declarator
+        declarator: (function_declarator
+          declarator: (identifier) @func)))
+    (function_definition
+      declarator: (reference_declarator
+        (function_declarator
+          declarator: (identifier) @func)))
+
+    ; Methods defined inside a class body
+    (function_definition
+      declarator: (function_declarator
+        declarator: (field_identifier) @func))
+
+    ; Methods defined outside their class - Type::method
+    (function_definition
+      declarator: (function_declarator
+        declarator: (qualified_identifier
+          name: (identifier) @func)))
+
+    ; Classes and structs
+    (class_specifier
+      name: (type_identifier) @class
+      body: (field_declaration_list))
+    (struct_specifier
+      name: (type_identifier) @struct
+      body: (field_declaration_list))
+
+    (preproc_include path: (_) @import)
+    (using_declaration) @import
+"#;
+
+/// Tree-sitter query for extracting C++ function calls
+pub const CALL_QUERY: &str = r#"
+    ; Function calls
+    (call_expression
+      function: (identifier) @function.call)
+
+    ; Method calls - obj.method() and ptr->method()
+    (call_expression
+      function: (field_expression
+        field: (field_identifier) @method.call))
+
+    ; Qualified calls - captures the full ns::function or Type::method
+    (call_expression
+      function: (qualified_identifier) @scoped.call)
+
+    ; Constructor calls with new
+    (new_expression
+      type: (type_identifier) @constructor.call)
+    (new_expression
+      type: (qualified_identifier
+        name: (type_identifier) @constructor.call))
+"#;
+
+/// Tree-sitter query for extracting C++ type references and usage patterns
+pub const REFERENCE_QUERY: &str = r#"
+    ; Methods defined inside a class body
+    (field_declaration_list
+      (function_definition
+        declarator: (function_declarator
+          declarator: (field_identifier) @method.receiver)))
+
+    ; Methods defined outside their class - Type::method
+    (function_definition
+      declarator: (function_declarator
+        declarator: (qualified_identifier
+          name: (identifier) @method.receiver)))
+
+    ; Instantiation with new
+    (new_expression
+      type: (type_identifier) @struct.literal)
+    (new_expression
+      type: (qualified_identifier
+        name: (type_identifier) @struct.literal))
+
+    ; Field types in classes and structs
+    (field_declaration
+      type: (type_identifier) @field.type)
+
+    ; Parameter types
+    (parameter_declaration
+      type: (type_identifier) @param.type)
+
+    ; Local variable types
+    (compound_statement
+      (declaration
+        type: (type_identifier) @var.type))
+    (compound_statement
+      (declaration
+        type: (qualified_identifier
+          name: (type_identifier) @var.type)))
+"#;
+
+/// Find the method name for a method receiver node in C++
+///
+/// The receiver node is the method's name, either inside a class body or
+/// as the last part of a qualified `Type::method` definition.
+pub fn find_method_for_receiver(
+    receiver_node: &tree_sitter::Node,
+    source: &str,
+    _ast_recursion_limit: Option<usize>,
+) -> Option<String> {
+    source
+        .get(receiver_node.byte_range())
+        .map(|s| s.to_string())
+}
+
+/// Find the class that a method belongs to in C++
+///
+/// For `Type::method` definitions this is the qualifier; for methods defined
+/// inside a class body it is the enclosing class or struct.
+pub fn find_receiver_type(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    if let Some(parent) = node.parent() {
+        if parent.kind() == "qualified_identifier" {
+            return parent
+                .child_by_field_name("scope")
+                .and_then(|scope| source.get(scope.byte_range()))
+                .map(|s| s.to_string());
+        }
+    }
+
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        if matches!(parent.kind(), "class_specifier" | "struct_specifier") {
+            return parent
+                .child_by_field_name("name")
+                .and_then(|name| source.get(name.byte_range()))
+                .map(|s| s.to_string());
+        }
+        current = parent;
+    }
+    None
+}
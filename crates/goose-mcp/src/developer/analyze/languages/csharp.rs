@@ -0,0 +1,93 @@
+/// Tree-sitter query for extracting C# code elements
+pub const ELEMENT_QUERY: &str = r#"
+    ; Methods, constructors and local functions
+    (method_declaration name: (identifier) @func)
+    (constructor_declaration name: (identifier) @func)
+    (local_function_statement name: (identifier) @func)
+
+    ; Types
+    (class_declaration name: (identifier) @class)
+    (interface_declaration name: (identifier) @class)
+    (record_declaration name: (identifier) @class)
+    (enum_declaration name: (identifier) @class)
+    (struct_declaration name: (identifier) @struct)
+
+    (using_directive) @import
+"#;
+
+/// Tree-sitter query for extracting C# function calls
+pub const CALL_QUERY: &str = r#"
+    ; Method calls on the current instance or static imports
+    (invocation_expression
+      function: (identifier) @function.call)
+
+    ; Method calls - obj.Method() and Type.Method()
+    (invocation_expression
+      function: (member_access_expression
+        name: (identifier) @method.call))
+
+    ; Constructor calls
+    (object_creation_expression
+      type: (identifier) @constructor.call)
+"#;
+
+/// Tree-sitter query for extracting C# type references and usage patterns
+pub const REFERENCE_QUERY: &str = r#"
+    ; Methods declared in a type
+    (method_declaration
+      name: (identifier) @method.receiver)
+
+    ; Object creation
+    (object_creation_expression
+      type: (identifier) @struct.literal)
+
+    ; Field and property types
+    (field_declaration
+      (variable_declaration
+        type: (identifier) @field.type))
+    (property_declaration
+      type: (identifier) @field.type)
+
+    ; Parameter types
+    (parameter
+      type: (identifier) @param.type)
+
+    ; Local variable types
+    (local_declaration_statement
+      (variable_declaration
+        type: (identifier) @var.type))
+"#;
+
+/// Find the method name for a method receiver node in C#
+///
+/// The receiver node is the name of a method declaration.
+pub fn find_method_for_receiver(
+    receiver_node: &tree_sitter::Node,
+    source: &str,
+    _ast_recursion_limit: Option<usize>,
+) -> Option<String> {
+    source
+        .get(receiver_node.byte_range())
+        .map(|s| s.to_string())
+}
+
+/// Find the type that declares a method in C#
+pub fn find_receiver_type(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        if matches!(
+            parent.kind(),
+            "class_declaration"
+                | "struct_declaration"
+                | "record_declaration"
+                | "interface_declaration"
+        ) {
+            return parent
+                .child_by_field_name("name")
+                .and_then(|name| source.get(name.byte_range()))
+                .map(|s| s.to_string());
+        }
+        current = parent;
+    }
+    None
+}
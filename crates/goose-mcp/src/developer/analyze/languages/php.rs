@@ -0,0 +1,97 @@
+/// Tree-sitter query for extracting PHP code elements
+pub const ELEMENT_QUERY: &str = r#"
+    ; Functions and methods
+    (function_definition name: (name) @func)
+    (method_declaration name: (name) @func)
+
+    ; Types
+    (class_declaration name: (name) @class)
+    (interface_declaration name: (name) @class)
+    (trait_declaration name: (name) @class)
+    (enum_declaration name: (name) @class)
+
+    ; Imports
+    (namespace_use_declaration) @import
+    (include_expression) @import
+    (include_once_expression) @import
+    (require_expression) @import
+    (require_once_expression) @import
+"#;
+
+/// Tree-sitter query for extracting PHP function calls
+pub const CALL_QUERY: &str = r#"
+    ; Function calls - foo() and \Vendor\foo()
+    (function_call_expression
+      function: (name) @function.call)
+    (function_call_expression
+      function: (qualified_name (name) @function.call .))
+
+    ; Method calls - $obj->method() and $obj?->method()
+    (member_call_expression
+      name: (name) @method.call)
+    (nullsafe_member_call_expression
+      name: (name) @method.call)
+
+    ; Static calls - Type::method()
+    (scoped_call_expression
+      name: (name) @scoped.call)
+
+    ; Constructor calls
+    (object_creation_expression
+      (name) @constructor.call)
+"#;
+
+/// Tree-sitter query for extracting PHP type references and usage patterns
+pub const REFERENCE_QUERY: &str = r#"
+    ; Methods declared in a type
+    (method_declaration
+      name: (name) @method.receiver)
+
+    ; Object creation
+    (object_creation_expression
+      (name) @struct.literal)
+
+    ; Typed properties
+    (property_declaration
+      type: (named_type (name) @field.type))
+    (property_promotion_parameter
+      type: (named_type (name) @field.type))
+
+    ; Parameter types
+    (simple_parameter
+      type: (named_type (name) @param.type))
+"#;
+
+/// Find the method name for a method receiver node in PHP
+///
+/// The receiver node is the name of a method declaration.
+pub fn find_method_for_receiver(
+    receiver_node: &tree_sitter::Node,
+    source: &str,
+    _ast_recursion_limit: Option<usize>,
+) -> Option<String> {
+    source
+        .get(receiver_node.byte_range())
+        .map(|s| s.to_string())
+}
+
+/// Find the class, interface, trait or enum that declares a method in PHP
+pub fn find_receiver_type(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        if matches!(
+            parent.kind(),
+            "class_declaration"
+                | "interface_declaration"
+                | "trait_declaration"
+                | "enum_declaration"
+        ) {
+            return parent
+                .child_by_field_name("name")
+                .and_then(|name| source.get(name.byte_range()))
+                .map(|s| s.to_string());
+        }
+        current = parent;
+    }
+    None
+}
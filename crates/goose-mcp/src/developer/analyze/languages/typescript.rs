@@ -0,0 +1,110 @@
+/// Tree-sitter query for extracting TypeScript and TSX code elements
+pub const ELEMENT_QUERY: &str = r#"
+    ; Functions, methods and functions assigned to variables
+    (function_declaration name: (identifier) @func)
+    (method_definition name: (property_identifier) @func)
+    (variable_declarator
+      name: (identifier) @func
+      value: [(arrow_function) (function_expression)])
+
+    ; Classes and other named types
+    (class_declaration name: (type_identifier) @class)
+    (abstract_class_declaration name: (type_identifier) @class)
+    (interface_declaration name: (type_identifier) @class)
+    (enum_declaration name: (identifier) @class)
+
+    (import_statement) @import
+"#;
+
+/// Tree-sitter query for extracting TypeScript function calls
+pub const CALL_QUERY: &str = r#"
+    ; Function calls
+    (call_expression
+      function: (identifier) @function.call)
+
+    ; Method calls
+    (call_expression
+      function: (member_expression
+        property: (property_identifier) @method.call))
+
+    ; Constructor calls
+    (new_expression
+      constructor: (identifier) @constructor.call)
+"#;
+
+/// Tree-sitter query for extracting TSX function calls, including component usage
+pub const TSX_CALL_QUERY: &str = r#"
+    ; Function calls
+    (call_expression
+      function: (identifier) @function.call)
+
+    ; Method calls
+    (call_expression
+      function: (member_expression
+        property: (property_identifier) @method.call))
+
+    ; Constructor calls
+    (new_expression
+      constructor: (identifier) @constructor.call)
+
+    ; Components rendered in JSX
+    (jsx_opening_element name: (identifier) @function.call)
+    (jsx_self_closing_element name: (identifier) @function.call)
+"#;
+
+/// Tree-sitter query for extracting TypeScript type references and usage patterns
+pub const REFERENCE_QUERY: &str = r#"
+    ; Methods declared in a class body
+    (class_body
+      (method_definition
+        name: (property_identifier) @method.receiver))
+
+    ; Class instantiation
+    (new_expression
+      constructor: (identifier) @struct.literal)
+
+    ; Class field types
+    (public_field_definition
+      type: (type_annotation (type_identifier) @field.type))
+
+    ; Parameter types
+    (required_parameter
+      type: (type_annotation (type_identifier) @param.type))
+    (optional_parameter
+      type: (type_annotation (type_identifier) @param.type))
+
+    ; Variable type annotations
+    (variable_declarator
+      type: (type_annotation (type_identifier) @var.type))
+"#;
+
+/// Find the method name for a method receiver node in TypeScript
+///
+/// The receiver node is the name of a method declared in a class body.
+pub fn find_method_for_receiver(
+    receiver_node: &tree_sitter::Node,
+    source: &str,
+    _ast_recursion_limit: Option<usize>,
+) -> Option<String> {
+    source
+        .get(receiver_node.byte_range())
+        .map(|s| s.to_string())
+}
+
+/// Find the class that declares a method in TypeScript
+pub fn find_receiver_type(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        if matches!(
+            parent.kind(),
+            "class_declaration" | "abstract_class_declaration" | "class"
+        ) {
+            return parent
+                .child_by_field_name("name")
+                .and_then(|name| source.get(name.byte_range()))
+                .map(|s| s.to_string());
+        }
+        current = parent;
+    }
+    None
+}
@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+
+use crate::developer::analyze::types::{AnalysisResult, ReferenceType, SymbolLocation};
+
+/// File stems that name their directory's module rather than a module of their own
+const MODULE_INDEX_STEMS: &[&str] = &["mod", "lib", "main", "index", "__init__"];
+
+/// Path prefixes that only say where a module path starts
+const MODULE_PATH_PREFIXES: &[&str] = &["crate", "self", "super"];
+
+/// A symbol to look up, optionally qualified by the type or module it belongs to,
+/// e.g. `bar`, `Foo::bar`, `Foo.bar` or `utils::parse`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolQuery {
+    pub name: String,
+    pub qualifier: Vec<String>,
+}
+
+impl SymbolQuery {
+    pub fn parse(symbol: &str) -> Self {
+        let mut parts: Vec<String> = symbol
+            .split("::")
+            .flat_map(|part| part.split('.'))
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .skip_while(|part| MODULE_PATH_PREFIXES.contains(part))
+            .map(String::from)
+            .collect();
+        let name = parts.pop().unwrap_or_default();
+        Self {
+            name,
+            qualifier: parts,
+        }
+    }
+}
+
+/// Find where the symbol is defined. A qualified symbol only matches definitions
+/// inside that type, or in a file whose module path ends with the qualifier.
+pub fn find_definitions(
+    results: &[(PathBuf, AnalysisResult)],
+    query: &SymbolQuery,
+) -> Vec<SymbolLocation> {
+    let mut definitions = Vec::new();
+
+    for (file, result) in results {
+        let mut found: Vec<SymbolLocation> = Vec::new();
+        let mut add = |line: usize, kind: &'static str, container: Option<String>| {
+            // Languages report some definitions twice, e.g. a Rust method is both a
+            // function and a method of its impl type; keep the most specific one
+            if found.iter().any(|location| location.line == line) {
+                return;
+            }
+            found.push(SymbolLocation {
+                file: file.clone(),
+                line,
+                kind,
+                container,
+                context: String::new(),
+            });
+        };
+
+        for reference in result.references.iter().filter(|r| r.symbol == query.name) {
+            match reference.ref_type {
+                ReferenceType::MethodDefinition => {
+                    add(reference.line, "method", reference.associated_type.clone())
+                }
+                ReferenceType::Definition => add(reference.line, "type", None),
+                _ => {}
+            }
+        }
+        for class in &result.classes {
+            if class.name == query.name {
+                add(class.line, "type", None);
+            }
+            for method in class.methods.iter().filter(|m| m.name == query.name) {
+                add(method.line, "method", Some(class.name.clone()));
+            }
+        }
+        for function in result.functions.iter().filter(|f| f.name == query.name) {
+            add(function.line, "function", None);
+        }
+
+        definitions.extend(found.into_iter().filter(|location| {
+            matches_qualifier(
+                file,
+                result,
+                location.line,
+                location.container.as_deref(),
+                &query.qualifier,
+            )
+        }));
+    }
+
+    definitions.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    definitions
+}
+
+/// Find every call and type usage of the symbol. For a qualified symbol, unqualified
+/// uses only count in files that belong to the qualifying module or type, or import it.
+pub fn find_references(
+    results: &[(PathBuf, AnalysisResult)],
+    query: &SymbolQuery,
+) -> Vec<SymbolLocation> {
+    let mut references = Vec::new();
+    let qualifier_name = query.qualifier.last();
+
+    for (file, result) in results {
+        let file_matches = match qualifier_name {
+            None => true,
+            Some(qualifier_name) => {
+                module_matches(file, &query.qualifier)
+                    || result.classes.iter().any(|c| &c.name == qualifier_name)
+                    || result
+                        .imports
+                        .iter()
+                        .any(|import| contains_identifier(import, qualifier_name))
+            }
+        };
+
+        for call in &result.calls {
+            let callee = SymbolQuery::parse(&call.callee_name);
+            if callee.name != query.name {
+                continue;
+            }
+            let matches = match (qualifier_name, callee.qualifier.last()) {
+                (None, _) => true,
+                (Some(expected), Some(actual)) => expected == actual,
+                (Some(expected), None) => {
+                    file_matches
+                        || [".", "::"].iter().any(|separator| {
+                            call.context
+                                .contains(&format!("{}{}{}", expected, separator, query.name))
+                        })
+                }
+            };
+            if matches {
+                references.push(SymbolLocation {
+                    file: file.clone(),
+                    line: call.line,
+                    kind: "call",
+                    container: call.caller_name.clone(),
+                    context: call.context.clone(),
+                });
+            }
+        }
+
+        if !file_matches {
+            continue;
+        }
+        for reference in result.references.iter().filter(|r| r.symbol == query.name) {
+            let kind = match reference.ref_type {
+                ReferenceType::Call => "call",
+                ReferenceType::TypeInstantiation => "instantiation",
+                ReferenceType::FieldType => "field type",
+                ReferenceType::VariableType => "variable type",
+                ReferenceType::ParameterType => "parameter type",
+                ReferenceType::Import => "import",
+                ReferenceType::Definition | ReferenceType::MethodDefinition => continue,
+            };
+            references.push(SymbolLocation {
+                file: file.clone(),
+                line: reference.line,
+                kind,
+                container: None,
+                context: reference.context.clone(),
+            });
+        }
+    }
+
+    references.sort_by(|a, b| (&a.file, a.line, a.kind).cmp(&(&b.file, b.line, b.kind)));
+    references.dedup_by(|a, b| a.file == b.file && a.line == b.line && a.kind == b.kind);
+    references
+}
+
+/// A name for the definition that tells it apart from same-named ones elsewhere
+pub fn qualified_name(location: &SymbolLocation, name: &str) -> String {
+    match &location.container {
+        Some(container) => format!("{}::{}", container, name),
+        None => match module_path(&location.file).last() {
+            Some(module) => format!("{}::{}", module, name),
+            None => name.to_string(),
+        },
+    }
+}
+
+fn matches_qualifier(
+    file: &Path,
+    result: &AnalysisResult,
+    line: usize,
+    container: Option<&str>,
+    qualifier: &[String],
+) -> bool {
+    let Some((type_name, module)) = qualifier.split_last() else {
+        return true;
+    };
+    // When the language doesn't report the container, the innermost type whose
+    // definition spans the line stands in for it
+    let enclosing_type = container.or_else(|| {
+        result
+            .classes
+            .iter()
+            .filter(|class| class.line <= line && line <= class.end_line)
+            .max_by_key(|class| class.line)
+            .map(|class| class.name.as_str())
+    });
+
+    let type_matches = enclosing_type == Some(type_name.as_str())
+        && (module.is_empty() || module_matches(file, module));
+    type_matches || module_matches(file, qualifier)
+}
+
+/// Whether the file's module path ends with the given segments
+fn module_matches(file: &Path, segments: &[String]) -> bool {
+    let path = module_path(file);
+    !segments.is_empty() && path.ends_with(segments)
+}
+
+fn module_path(file: &Path) -> Vec<String> {
+    let mut path: Vec<String> = file
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .filter_map(|c| c.as_os_str().to_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(stem) = file.file_stem().and_then(|s| s.to_str()) {
+        if !MODULE_INDEX_STEMS.contains(&stem) {
+            path.push(stem.to_string());
+        }
+    }
+    path
+}
+
+fn contains_identifier(text: &str, identifier: &str) -> bool {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|word| word == identifier)
+}
@@ -1,7 +1,9 @@
 pub mod cache;
 pub mod formatter;
 pub mod graph;
+pub mod index;
 pub mod languages;
+pub mod lookup;
 pub mod parser;
 pub mod traversal;
 pub mod types;
@@ -18,9 +20,11 @@ use crate::developer::lang;
 use self::cache::AnalysisCache;
 use self::formatter::Formatter;
 use self::graph::CallGraph;
+use self::index::AnalysisIndex;
+use self::lookup::SymbolQuery;
 use self::parser::{ElementExtractor, ParserManager};
 use self::traversal::FileTraverser;
-use self::types::{AnalysisMode, AnalysisResult, AnalyzeParams, FocusedAnalysisData};
+use self::types::{AnalysisMode, AnalysisResult, AnalyzeParams, FocusedAnalysisData, LookupMode};
 
 /// Helper to safely lock a mutex with poison recovery
 /// The recovery function is called on the mutex contents if the lock was poisoned
@@ -44,6 +48,7 @@ where
 pub struct CodeAnalyzer {
     parser_manager: ParserManager,
     cache: AnalysisCache,
+    index: Option<AnalysisIndex>,
 }
 
 impl Default for CodeAnalyzer {
@@ -58,6 +63,52 @@ impl CodeAnalyzer {
         Self {
             parser_manager: ParserManager::new(),
             cache: AnalysisCache::new(100),
+            index: None,
+        }
+    }
+
+    /// A code analyzer that also keeps semantic results in a persistent index,
+    /// so they can be reused by later sessions in the same project
+    pub fn with_index(index: AnalysisIndex) -> Self {
+        Self {
+            index: Some(index),
+            ..Self::new()
+        }
+    }
+
+    /// Bring the index up to date after `path` was changed, e.g. by the text editor.
+    /// Files that were never indexed are left to be analyzed when they are first needed.
+    pub fn refresh_file(&self, path: &Path) {
+        let Some(index) = &self.index else {
+            return;
+        };
+        if !index.contains(path) {
+            return;
+        }
+
+        // Drop the old entry first; with a coarse mtime an edit may not change it
+        index.remove(path);
+        if path.is_file() {
+            let params = AnalyzeParams {
+                path: path.display().to_string(),
+                focus: None,
+                follow_depth: 0,
+                max_depth: 0,
+                ast_recursion_limit: None,
+                force: false,
+                mode: None,
+                context_lines: 0,
+            };
+            if let Err(e) = self.analyze_file(path, &AnalysisMode::Semantic, &params) {
+                tracing::warn!("Failed to refresh analysis index for {:?}: {}", path, e);
+            }
+        }
+        self.save_index();
+    }
+
+    fn save_index(&self) {
+        if let Some(index) = &self.index {
+            index.schedule_save();
         }
     }
 
@@ -79,6 +130,9 @@ impl CodeAnalyzer {
 
         let mut output = match mode {
             AnalysisMode::Focused => self.analyze_focused(&path, &params, &traverser)?,
+            AnalysisMode::Definition | AnalysisMode::References => {
+                self.analyze_lookup(&path, &params, &traverser, mode)?
+            }
             AnalysisMode::Semantic => {
                 if path.is_file() {
                     let result = self.analyze_file(&path, &mode, &params)?;
@@ -96,10 +150,11 @@ impl CodeAnalyzer {
                 }
             }
         };
+        self.save_index();
 
         // If focus is specified with non-focused mode, filter results
         if let Some(focus) = &params.focus {
-            if mode != AnalysisMode::Focused {
+            if mode == AnalysisMode::Semantic || mode == AnalysisMode::Structure {
                 output = Formatter::filter_by_focus(&output, focus);
             }
         }
@@ -139,6 +194,12 @@ impl CodeAnalyzer {
     }
 
     fn determine_mode(&self, params: &AnalyzeParams, path: &Path) -> AnalysisMode {
+        match params.mode {
+            Some(LookupMode::Definition) => return AnalysisMode::Definition,
+            Some(LookupMode::References) => return AnalysisMode::References,
+            None => {}
+        }
+
         if params.focus.is_some() {
             return AnalysisMode::Focused;
         }
@@ -184,6 +245,19 @@ impl CodeAnalyzer {
             return Ok(cached);
         }
 
+        // Only full semantic results are indexed; a recursion limit changes what is extracted
+        let index = self
+            .index
+            .as_ref()
+            .filter(|_| *mode == AnalysisMode::Semantic && params.ast_recursion_limit.is_none());
+
+        if let Some(indexed) = index.and_then(|index| index.get_fresh(path, modified)) {
+            tracing::trace!("Using indexed result for {:?}", path);
+            self.cache
+                .put(path.to_path_buf(), modified, mode, indexed.clone());
+            return Ok(indexed);
+        }
+
         let content = match std::fs::read_to_string(path) {
             Ok(content) => content,
             Err(e) => {
@@ -192,6 +266,14 @@ impl CodeAnalyzer {
             }
         };
 
+        if let Some(indexed) = index.and_then(|index| index.get_unchanged(path, modified, &content))
+        {
+            tracing::trace!("Using indexed result for unchanged {:?}", path);
+            self.cache
+                .put(path.to_path_buf(), modified, mode, indexed.clone());
+            return Ok(indexed);
+        }
+
         let line_count = content.lines().count();
 
         let language = lang::get_language_identifier(path);
@@ -226,6 +308,9 @@ impl CodeAnalyzer {
 
         self.cache
             .put(path.to_path_buf(), modified, mode, result.clone());
+        if let Some(index) = index {
+            index.put(path, modified, &content, result.clone());
+        }
 
         Ok(result)
     }
@@ -269,26 +354,9 @@ impl CodeAnalyzer {
 
         tracing::info!("Running focused analysis for symbol '{}'", focus_symbol);
 
-        let files_to_analyze = if path.is_file() {
-            vec![path.to_path_buf()]
-        } else {
-            traverser.collect_files_for_focused(path, params.max_depth)?
-        };
-
-        tracing::debug!(
-            "Analyzing {} files for focused analysis",
-            files_to_analyze.len()
-        );
-
-        use rayon::prelude::*;
-        let all_results: Result<Vec<_>, _> = files_to_analyze
-            .par_iter()
-            .map(|file_path| {
-                self.analyze_file(file_path, &AnalysisMode::Semantic, params)
-                    .map(|result| (file_path.clone(), result))
-            })
-            .collect();
-        let all_results = all_results?;
+        let all_results = self.analyze_symbol_files(path, params, traverser)?;
+        let files_to_analyze: Vec<PathBuf> =
+            all_results.iter().map(|(file, _)| file.clone()).collect();
 
         let graph = CallGraph::build_from_results(&all_results);
 
@@ -329,4 +397,69 @@ impl CodeAnalyzer {
 
         Ok(output)
     }
+
+    fn analyze_lookup(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+        mode: AnalysisMode,
+    ) -> Result<String, ErrorData> {
+        let symbol = params.focus.as_ref().ok_or_else(|| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "The {} mode requires 'focus' to name the symbol to look up",
+                    mode.as_str()
+                ),
+                None,
+            )
+        })?;
+
+        tracing::info!("Looking up {} of symbol '{}'", mode.as_str(), symbol);
+
+        let all_results = self.analyze_symbol_files(path, params, traverser)?;
+        let query = SymbolQuery::parse(symbol);
+        let definitions = lookup::find_definitions(&all_results, &query);
+        let root = if path.is_file() {
+            path.parent().unwrap_or(path)
+        } else {
+            path
+        };
+
+        Ok(if mode == AnalysisMode::Definition {
+            Formatter::format_definitions(symbol, root, &definitions, params.context_lines)
+        } else {
+            let references = lookup::find_references(&all_results, &query);
+            Formatter::format_references(symbol, root, &definitions, &references)
+        })
+    }
+
+    /// Semantic results for every file a symbol search covers
+    fn analyze_symbol_files(
+        &self,
+        path: &Path,
+        params: &AnalyzeParams,
+        traverser: &FileTraverser<'_>,
+    ) -> Result<Vec<(PathBuf, AnalysisResult)>, ErrorData> {
+        let files_to_analyze = if path.is_file() {
+            vec![path.to_path_buf()]
+        } else {
+            traverser.collect_files_for_focused(path, params.max_depth)?
+        };
+
+        tracing::debug!(
+            "Analyzing {} files for symbol search",
+            files_to_analyze.len()
+        );
+
+        use rayon::prelude::*;
+        files_to_analyze
+            .par_iter()
+            .map(|file_path| {
+                self.analyze_file(file_path, &AnalysisMode::Semantic, params)
+                    .map(|result| (file_path.clone(), result))
+            })
+            .collect()
+    }
 }
@@ -1,6 +1,7 @@
 use rmcp::model::{ErrorCode, ErrorData};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use streaming_iterator::StreamingIterator;
 use tree_sitter::{Language, Parser, Tree};
 
 use super::lock_or_recover;
@@ -33,14 +34,20 @@ impl ParserManager {
         tracing::debug!("Creating new parser for {}", language);
         let mut parser = Parser::new();
         let language_config: Language = match language {
-            "python" => tree_sitter_python::language(),
-            "rust" => tree_sitter_rust::language(),
-            "javascript" | "typescript" => tree_sitter_javascript::language(),
-            "go" => tree_sitter_go::language(),
-            "java" => tree_sitter_java::language(),
-            "kotlin" => tree_sitter_kotlin::language(),
-            "swift" => devgen_tree_sitter_swift::language(),
-            "ruby" => tree_sitter_ruby::language(),
+            "python" => tree_sitter_python::LANGUAGE.into(),
+            "rust" => tree_sitter_rust::LANGUAGE.into(),
+            "javascript" => tree_sitter_javascript::LANGUAGE.into(),
+            "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            "go" => tree_sitter_go::LANGUAGE.into(),
+            "java" => tree_sitter_java::LANGUAGE.into(),
+            "kotlin" => tree_sitter_kotlin_ng::LANGUAGE.into(),
+            "swift" => tree_sitter_swift::LANGUAGE.into(),
+            "ruby" => tree_sitter_ruby::LANGUAGE.into(),
+            "php" => tree_sitter_php::LANGUAGE_PHP.into(),
+            "c" => tree_sitter_c::LANGUAGE.into(),
+            "cpp" => tree_sitter_cpp::LANGUAGE.into(),
+            "csharp" => tree_sitter_c_sharp::LANGUAGE.into(),
             _ => {
                 tracing::warn!("Unsupported language: {}", language);
                 return Err(ErrorData::new(
@@ -214,16 +221,13 @@ impl ElementExtractor {
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
 
-        for match_ in matches.by_ref() {
+        while let Some(match_) = matches.next() {
             for capture in match_.captures {
                 let node = capture.node;
                 let Some(text) = source.get(node.byte_range()) else {
                     continue;
                 };
-                let line = source
-                    .get(..node.start_byte())
-                    .map(|s| s.lines().count() + 1)
-                    .unwrap_or(1);
+                let line = node.start_position().row + 1;
 
                 match query.capture_names()[capture.index as usize] {
                     "func" | "const" => {
@@ -234,9 +238,14 @@ impl ElementExtractor {
                         });
                     }
                     "class" | "struct" => {
+                        // The capture is the name; its parent is the whole definition
+                        let end_line = node
+                            .parent()
+                            .map_or(line, |definition| definition.end_position().row + 1);
                         classes.push(ClassInfo {
                             name: text.to_string(),
                             line,
+                            end_line,
                             methods: vec![], // Simplified for now
                         });
                     }
@@ -287,7 +296,7 @@ impl ElementExtractor {
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
 
-        for match_ in matches.by_ref() {
+        while let Some(match_) = matches.next() {
             for capture in match_.captures {
                 let node = capture.node;
                 let Some(text) = source.get(node.byte_range()) else {
@@ -366,7 +375,7 @@ impl ElementExtractor {
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
 
-        for match_ in matches.by_ref() {
+        while let Some(match_) = matches.next() {
             for capture in match_.captures {
                 let node = capture.node;
                 let Some(text) = source.get(node.byte_range()) else {
@@ -0,0 +1,74 @@
+use crate::developer::analyze::parser::{ElementExtractor, ParserManager};
+use crate::developer::analyze::types::{AnalysisResult, ReferenceType};
+use std::collections::HashSet;
+
+fn parse_and_extract(code: &str) -> AnalysisResult {
+    let manager = ParserManager::new();
+    let tree = manager.parse(code, "c").unwrap();
+    ElementExtractor::extract_with_depth(&tree, code, "c", "semantic", None).unwrap()
+}
+
+#[test]
+fn test_c_functions_structs_and_calls() {
+    let code = r#"
+#include <stdio.h>
+#include "point.h"
+
+typedef struct Point {
+    int x;
+    int y;
+} Point;
+
+struct Line {
+    struct Point start;
+    Point end;
+};
+
+static int add(int a, int b) {
+    return a + b;
+}
+
+char *name(void) {
+    return "line";
+}
+
+int main(int argc, char **argv) {
+    Point p = (Point){1, 2};
+    struct Line line;
+    printf("%d\n", add(p.x, p.y));
+    return 0;
+}
+"#;
+
+    let result = parse_and_extract(code);
+
+    let function_names: HashSet<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(function_names, HashSet::from(["add", "name", "main"]));
+    assert!(result.main_line.is_some());
+
+    let struct_names: HashSet<_> = result.classes.iter().map(|c| c.name.as_str()).collect();
+    assert!(struct_names.contains("Point"));
+    assert!(struct_names.contains("Line"));
+
+    assert_eq!(result.imports, vec!["<stdio.h>", "\"point.h\""]);
+
+    let add_call = result
+        .calls
+        .iter()
+        .find(|c| c.callee_name == "add")
+        .expect("Expected a call to add");
+    assert_eq!(add_call.caller_name.as_deref(), Some("main"));
+
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Point" && r.ref_type == ReferenceType::TypeInstantiation));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Point" && r.ref_type == ReferenceType::FieldType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Line" && r.ref_type == ReferenceType::VariableType));
+}
@@ -0,0 +1,107 @@
+use crate::developer::analyze::graph::CallGraph;
+use crate::developer::analyze::parser::{ElementExtractor, ParserManager};
+use crate::developer::analyze::types::{AnalysisResult, ReferenceType};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+fn parse_and_extract(code: &str) -> AnalysisResult {
+    let manager = ParserManager::new();
+    let tree = manager.parse(code, "cpp").unwrap();
+    ElementExtractor::extract_with_depth(&tree, code, "cpp", "semantic", None).unwrap()
+}
+
+fn build_test_graph(files: Vec<(&str, &str)>) -> CallGraph {
+    let manager = ParserManager::new();
+    let results: Vec<_> = files
+        .iter()
+        .map(|(path, code)| {
+            let tree = manager.parse(code, "cpp").unwrap();
+            let result =
+                ElementExtractor::extract_with_depth(&tree, code, "cpp", "semantic", None).unwrap();
+            (PathBuf::from(*path), result)
+        })
+        .collect();
+    CallGraph::build_from_results(&results)
+}
+
+#[test]
+fn test_cpp_class_and_method_tracking() {
+    let code = r#"
+#include <vector>
+#include "layout.h"
+
+using std::vector;
+
+class Widget {
+public:
+    Widget(int w);
+    void render() const { draw(); }
+    int width() const;
+
+private:
+    Layout layout;
+};
+
+struct Point {
+    int x;
+};
+
+Widget::Widget(int w) : width_(w) {}
+
+int Widget::width() const {
+    return layout.measure();
+}
+
+int main() {
+    Widget *w = new Widget(3);
+    Widget v(2);
+    w->render();
+    std::sort(items.begin(), items.end());
+    return v.width();
+}
+"#;
+
+    let result = parse_and_extract(code);
+    let graph = build_test_graph(vec![("widget.cpp", code)]);
+
+    let class_names: HashSet<_> = result.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(class_names, HashSet::from(["Widget", "Point"]));
+
+    let function_names: HashSet<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        function_names,
+        HashSet::from(["render", "Widget", "width", "main"])
+    );
+    assert_eq!(result.import_count, 3);
+
+    let widget_methods: HashSet<_> = result
+        .references
+        .iter()
+        .filter(|r| {
+            r.ref_type == ReferenceType::MethodDefinition
+                && r.associated_type.as_deref() == Some("Widget")
+        })
+        .map(|r| r.symbol.as_str())
+        .collect();
+    assert_eq!(widget_methods, HashSet::from(["render", "Widget", "width"]));
+
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Widget" && r.ref_type == ReferenceType::TypeInstantiation));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Layout" && r.ref_type == ReferenceType::FieldType));
+
+    let measure_call = result
+        .calls
+        .iter()
+        .find(|c| c.callee_name == "measure")
+        .expect("Expected a call to measure");
+    assert_eq!(measure_call.caller_name.as_deref(), Some("width"));
+    assert!(result.calls.iter().any(|c| c.callee_name == "std::sort"));
+
+    let outgoing = graph.find_outgoing_chains("Widget", 1);
+    assert!(!outgoing.is_empty(), "Expected to find methods on Widget");
+}
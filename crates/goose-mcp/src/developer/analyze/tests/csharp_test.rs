@@ -0,0 +1,109 @@
+use crate::developer::analyze::graph::CallGraph;
+use crate::developer::analyze::parser::{ElementExtractor, ParserManager};
+use crate::developer::analyze::types::{AnalysisResult, ReferenceType};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+fn parse_and_extract(code: &str) -> AnalysisResult {
+    let manager = ParserManager::new();
+    let tree = manager.parse(code, "csharp").unwrap();
+    ElementExtractor::extract_with_depth(&tree, code, "csharp", "semantic", None).unwrap()
+}
+
+fn build_test_graph(files: Vec<(&str, &str)>) -> CallGraph {
+    let manager = ParserManager::new();
+    let results: Vec<_> = files
+        .iter()
+        .map(|(path, code)| {
+            let tree = manager.parse(code, "csharp").unwrap();
+            let result =
+                ElementExtractor::extract_with_depth(&tree, code, "csharp", "semantic", None)
+                    .unwrap();
+            (PathBuf::from(*path), result)
+        })
+        .collect();
+    CallGraph::build_from_results(&results)
+}
+
+#[test]
+fn test_csharp_class_and_method_tracking() {
+    let code = r#"
+using System;
+using System.Collections.Generic;
+
+namespace Shapes
+{
+    public interface IShape { double Area(); }
+
+    public struct Point { public int X; }
+
+    public class Circle : IShape
+    {
+        private Logger logger;
+        public Point Center { get; set; }
+
+        public Circle(double r) { logger = new Logger(); }
+
+        public double Area() { return Math.PI * Radius(); }
+
+        private double Radius(Helper helper) { return helper.Compute(); }
+
+        public static void Main(string[] args)
+        {
+            Circle circle = new Circle(1);
+            Console.WriteLine(circle.Area());
+        }
+    }
+}
+"#;
+
+    let result = parse_and_extract(code);
+    let graph = build_test_graph(vec![("Circle.cs", code)]);
+
+    let class_names: HashSet<_> = result.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(class_names, HashSet::from(["IShape", "Point", "Circle"]));
+
+    let function_names: HashSet<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+    assert!(function_names.contains("Circle"));
+    assert!(function_names.contains("Radius"));
+    assert!(function_names.contains("Main"));
+    assert_eq!(result.import_count, 2);
+
+    let circle_methods: HashSet<_> = result
+        .references
+        .iter()
+        .filter(|r| {
+            r.ref_type == ReferenceType::MethodDefinition
+                && r.associated_type.as_deref() == Some("Circle")
+        })
+        .map(|r| r.symbol.as_str())
+        .collect();
+    assert_eq!(circle_methods, HashSet::from(["Area", "Radius", "Main"]));
+
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Logger" && r.ref_type == ReferenceType::TypeInstantiation));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Point" && r.ref_type == ReferenceType::FieldType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Helper" && r.ref_type == ReferenceType::ParameterType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Circle" && r.ref_type == ReferenceType::VariableType));
+
+    let radius_call = result
+        .calls
+        .iter()
+        .find(|c| c.callee_name == "Radius")
+        .expect("Expected a call to Radius");
+    assert_eq!(radius_call.caller_name.as_deref(), Some("Area"));
+
+    let outgoing = graph.find_outgoing_chains("Circle", 1);
+    assert!(!outgoing.is_empty(), "Expected to find methods on Circle");
+}
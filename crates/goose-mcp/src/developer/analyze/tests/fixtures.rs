@@ -21,6 +21,7 @@ pub fn create_test_result() -> AnalysisResult {
         classes: vec![ClassInfo {
             name: "TestClass".to_string(),
             line: 5,
+            end_line: 8,
             methods: vec![],
         }],
         imports: vec!["use std::fs".to_string()],
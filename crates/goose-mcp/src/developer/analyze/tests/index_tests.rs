@@ -0,0 +1,358 @@
+// Tests for the persistent analysis index
+
+use crate::developer::analyze::index::AnalysisIndex;
+use crate::developer::analyze::tests::fixtures::create_test_gitignore;
+use crate::developer::analyze::types::{AnalysisResult, AnalyzeParams, FunctionInfo};
+use crate::developer::analyze::CodeAnalyzer;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+fn result_with_function(name: &str) -> AnalysisResult {
+    let mut result = AnalysisResult::empty(1);
+    result.functions.push(FunctionInfo {
+        name: name.to_string(),
+        line: 1,
+        params: vec![],
+    });
+    result.function_count = 1;
+    result
+}
+
+fn focus_params(path: &Path, focus: &str) -> AnalyzeParams {
+    AnalyzeParams {
+        path: path.to_string_lossy().to_string(),
+        focus: Some(focus.to_string()),
+        follow_depth: 1,
+        max_depth: 3,
+        ast_recursion_limit: None,
+        force: false,
+        mode: None,
+        context_lines: 10,
+    }
+}
+
+fn output_text(result: &rmcp::model::CallToolResult) -> String {
+    result.content[0]
+        .as_text()
+        .map(|text| text.text.clone())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_index_persists_across_instances() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index/project.json");
+    let file = project.path().join("main.rs");
+    fs::write(&file, "fn main() {}").unwrap();
+    let modified = SystemTime::now();
+
+    let index = AnalysisIndex::new(project.path().to_path_buf(), index_path.clone());
+    index.put(
+        &file,
+        modified,
+        "fn main() {}",
+        result_with_function("main"),
+    );
+    index.save().unwrap();
+
+    let reopened = AnalysisIndex::new(project.path().to_path_buf(), index_path);
+    let indexed = reopened.get_fresh(&file, modified).unwrap();
+    assert_eq!(indexed.functions[0].name, "main");
+}
+
+#[test]
+fn test_index_invalidation() {
+    let project = TempDir::new().unwrap();
+    let file = project.path().join("main.rs");
+    let modified = SystemTime::now();
+    let later = modified + Duration::from_secs(5);
+
+    let index = AnalysisIndex::new(
+        project.path().to_path_buf(),
+        project.path().join("index.json"),
+    );
+    index.put(
+        &file,
+        modified,
+        "fn main() {}",
+        result_with_function("main"),
+    );
+
+    // A new modification time misses, unless the content is still the same
+    assert!(index.get_fresh(&file, later).is_none());
+    assert!(index.get_unchanged(&file, later, "fn other() {}").is_none());
+    assert!(index.get_unchanged(&file, later, "fn main() {}").is_some());
+
+    // The matching hash refreshed the stored modification time
+    assert!(index.get_fresh(&file, later).is_some());
+
+    // Files outside the project are never indexed
+    let outside = TempDir::new().unwrap().path().join("main.rs");
+    index.put(
+        &outside,
+        modified,
+        "fn main() {}",
+        result_with_function("main"),
+    );
+    assert!(!index.covers(&outside));
+    assert!(index.get_fresh(&outside, modified).is_none());
+}
+
+#[test]
+fn test_index_save_merges_other_sessions() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let root = project.path().to_path_buf();
+    let modified = SystemTime::now();
+    fs::write(root.join("a.rs"), "fn a() {}").unwrap();
+    fs::write(root.join("b.rs"), "fn b() {}").unwrap();
+
+    let first = AnalysisIndex::new(root.clone(), index_path.clone());
+    let second = AnalysisIndex::new(root.clone(), index_path.clone());
+    // Load both before either saves, like two sessions running side by side
+    assert!(first.is_empty());
+    assert!(second.is_empty());
+
+    first.put(
+        &root.join("a.rs"),
+        modified,
+        "fn a() {}",
+        result_with_function("a"),
+    );
+    first.save().unwrap();
+    second.put(
+        &root.join("b.rs"),
+        modified,
+        "fn b() {}",
+        result_with_function("b"),
+    );
+    second.save().unwrap();
+
+    let merged = AnalysisIndex::new(root.clone(), index_path);
+    assert_eq!(merged.len(), 2);
+    assert!(merged.contains(&root.join("a.rs")));
+    assert!(merged.contains(&root.join("b.rs")));
+}
+
+#[test]
+fn test_focused_analysis_reuses_index() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join(".index/project.json");
+    let file = project.path().join("lib.py");
+    fs::write(
+        &file,
+        "def main():\n    helper()\n\ndef helper():\n    pass",
+    )
+    .unwrap();
+    let ignore = create_test_gitignore();
+
+    let analyzer = CodeAnalyzer::with_index(
+        AnalysisIndex::new(project.path().to_path_buf(), index_path.clone())
+            .with_save_delay(Duration::ZERO),
+    );
+    analyzer
+        .analyze(
+            focus_params(project.path(), "helper"),
+            project.path().to_path_buf(),
+            &ignore,
+        )
+        .unwrap();
+    assert!(index_path.exists());
+
+    // A new session reads the results from the index instead of parsing the file again
+    let index = AnalysisIndex::new(project.path().to_path_buf(), index_path);
+    assert!(index.contains(&file));
+    let modified = fs::metadata(&file).unwrap().modified().unwrap();
+    index.put(&file, modified, "", result_with_function("indexed_only"));
+    let analyzer = CodeAnalyzer::with_index(index);
+    let result = analyzer
+        .analyze(
+            focus_params(project.path(), "indexed_only"),
+            project.path().to_path_buf(),
+            &ignore,
+        )
+        .unwrap();
+    assert!(output_text(&result).contains("lib.py:1"));
+}
+
+#[test]
+fn test_refresh_file_after_edit() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let file = project.path().join("lib.py");
+    fs::write(&file, "def before():\n    pass").unwrap();
+    let ignore = create_test_gitignore();
+
+    let analyzer = CodeAnalyzer::with_index(
+        AnalysisIndex::new(project.path().to_path_buf(), index_path.clone())
+            .with_save_delay(Duration::ZERO),
+    );
+    analyzer
+        .analyze(
+            focus_params(project.path(), "before"),
+            project.path().to_path_buf(),
+            &ignore,
+        )
+        .unwrap();
+
+    fs::write(&file, "def after():\n    pass").unwrap();
+    analyzer.refresh_file(&file);
+
+    let reopened = AnalysisIndex::new(project.path().to_path_buf(), index_path.clone());
+    let modified = fs::metadata(&file).unwrap().modified().unwrap();
+    let indexed = reopened.get_fresh(&file, modified).unwrap();
+    assert_eq!(indexed.functions[0].name, "after");
+
+    // Deleted files are dropped from the index
+    fs::remove_file(&file).unwrap();
+    analyzer.refresh_file(&file);
+    let reopened = AnalysisIndex::new(project.path().to_path_buf(), index_path);
+    assert!(!reopened.contains(&file));
+}
+
+#[test]
+fn test_index_save_prunes_missing_files() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let root = project.path().to_path_buf();
+    let modified = SystemTime::now();
+    fs::write(root.join("kept.rs"), "fn kept() {}").unwrap();
+
+    let index = AnalysisIndex::new(root.clone(), index_path.clone());
+    index.put(
+        &root.join("kept.rs"),
+        modified,
+        "fn kept() {}",
+        result_with_function("kept"),
+    );
+    index.put(
+        &root.join("deleted.rs"),
+        modified,
+        "fn deleted() {}",
+        result_with_function("deleted"),
+    );
+    index.save().unwrap();
+
+    assert!(!index.contains(&root.join("deleted.rs")));
+    let reopened = AnalysisIndex::new(root.clone(), index_path);
+    assert_eq!(reopened.len(), 1);
+    assert!(reopened.contains(&root.join("kept.rs")));
+}
+
+#[test]
+fn test_scheduled_saves_are_debounced() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let root = project.path().to_path_buf();
+    let modified = SystemTime::now();
+    fs::write(root.join("a.rs"), "fn a() {}").unwrap();
+    fs::write(root.join("b.rs"), "fn b() {}").unwrap();
+
+    let index = AnalysisIndex::new(root.clone(), index_path.clone())
+        .with_save_delay(Duration::from_millis(200));
+    index.put(
+        &root.join("a.rs"),
+        modified,
+        "fn a() {}",
+        result_with_function("a"),
+    );
+    index.schedule_save();
+    index.put(
+        &root.join("b.rs"),
+        modified,
+        "fn b() {}",
+        result_with_function("b"),
+    );
+    index.schedule_save();
+    assert!(!index_path.exists());
+
+    // Both changes land in the single delayed save
+    std::thread::sleep(Duration::from_secs(1));
+    let reopened = AnalysisIndex::new(root.clone(), index_path);
+    assert_eq!(reopened.len(), 2);
+}
+
+#[test]
+fn test_index_from_older_layout_is_discarded() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let root = project.path().to_path_buf();
+    fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+
+    let index = AnalysisIndex::new(root.clone(), index_path.clone());
+    index.put(
+        &root.join("main.rs"),
+        SystemTime::now(),
+        "fn main() {}",
+        result_with_function("main"),
+    );
+    index.save().unwrap();
+
+    let mut stored: serde_json::Value =
+        serde_json::from_slice(&fs::read(&index_path).unwrap()).unwrap();
+    stored["version"] = serde_json::json!(1);
+    fs::write(&index_path, serde_json::to_vec(&stored).unwrap()).unwrap();
+
+    let reopened = AnalysisIndex::new(root, index_path);
+    assert!(reopened.is_empty());
+}
+
+#[test]
+fn test_pending_save_is_flushed_on_drop() {
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let root = project.path().to_path_buf();
+    fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+
+    let index = AnalysisIndex::new(root.clone(), index_path.clone())
+        .with_save_delay(Duration::from_secs(60));
+    let analyzer_handle = index.clone();
+    index.put(
+        &root.join("main.rs"),
+        SystemTime::now(),
+        "fn main() {}",
+        result_with_function("main"),
+    );
+    index.schedule_save();
+
+    drop(index);
+    assert!(!index_path.exists());
+    drop(analyzer_handle);
+    let reopened = AnalysisIndex::new(root, index_path);
+    assert_eq!(reopened.len(), 1);
+}
+
+#[test]
+fn test_index_is_usable_while_a_save_waits_for_the_file_lock() {
+    use fs2::FileExt;
+
+    let project = TempDir::new().unwrap();
+    let index_path = project.path().join("index.json");
+    let root = project.path().to_path_buf();
+    fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+
+    // Another process is writing the index
+    let lock_file = fs::File::create(index_path.with_extension("lock")).unwrap();
+    lock_file.lock_exclusive().unwrap();
+
+    let index = AnalysisIndex::new(root.clone(), index_path.clone());
+    index.put(
+        &root.join("main.rs"),
+        SystemTime::now(),
+        "fn main() {}",
+        result_with_function("main"),
+    );
+    let saving = index.clone();
+    let save = std::thread::spawn(move || saving.save());
+    std::thread::sleep(Duration::from_millis(200));
+
+    assert!(index.contains(&root.join("main.rs")));
+    assert!(!save.is_finished());
+
+    FileExt::unlock(&lock_file).unwrap();
+    save.join().unwrap().unwrap();
+    let reopened = AnalysisIndex::new(root, index_path);
+    assert_eq!(reopened.len(), 1);
+}
@@ -19,6 +19,8 @@ fn test_analyze_python_file() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -48,6 +50,8 @@ fn test_analyze_directory() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -85,6 +89,8 @@ fn test_focused_analysis() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -114,6 +120,8 @@ fn test_analyze_with_cache() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -146,6 +154,8 @@ fn test_analyze_unsupported_file() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -165,6 +175,8 @@ fn test_analyze_nonexistent_path() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -190,6 +202,8 @@ fn test_focused_without_symbol() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -228,6 +242,8 @@ fn test_nested_directory_analysis() {
         max_depth: 3, // Increase max_depth to ensure we reach nested files
         ast_recursion_limit: None,
         force: false,
+        mode: None,
+        context_lines: 10,
     };
 
     let ignore = create_test_gitignore();
@@ -36,6 +36,8 @@ fn test_large_output_warning() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false, // Should trigger warning
+        mode: None,
+        context_lines: 10,
     };
 
     let result = analyzer
@@ -85,6 +87,8 @@ fn test_force_flag_bypasses_warning() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: true, // Should bypass warning
+        mode: None,
+        context_lines: 10,
     };
 
     let result = analyzer
@@ -123,6 +127,8 @@ fn test_small_output_no_warning() {
         max_depth: 3,
         ast_recursion_limit: None,
         force: false, // Shouldn't matter for small output
+        mode: None,
+        context_lines: 10,
     };
 
     let result = analyzer
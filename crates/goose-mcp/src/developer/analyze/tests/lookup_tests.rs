@@ -0,0 +1,189 @@
+// Tests for definition and references lookups
+
+use crate::developer::analyze::lookup::SymbolQuery;
+use crate::developer::analyze::tests::fixtures::create_test_gitignore;
+use crate::developer::analyze::types::{AnalyzeParams, LookupMode};
+use crate::developer::analyze::CodeAnalyzer;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn lookup(dir: &Path, symbol: &str, mode: LookupMode) -> String {
+    let params = AnalyzeParams {
+        path: dir.to_string_lossy().to_string(),
+        focus: Some(symbol.to_string()),
+        follow_depth: 2,
+        max_depth: 3,
+        ast_recursion_limit: None,
+        force: false,
+        mode: Some(mode),
+        context_lines: 2,
+    };
+    let result = CodeAnalyzer::new()
+        .analyze(params, dir.to_path_buf(), &create_test_gitignore())
+        .unwrap();
+    result.content[0].as_text().unwrap().text.clone()
+}
+
+fn write_rust_project(dir: &Path) {
+    fs::create_dir(dir.join("shapes")).unwrap();
+    fs::write(
+        dir.join("shapes/circle.rs"),
+        r#"pub struct Circle {
+    radius: f64,
+}
+
+impl Circle {
+    pub fn area(&self) -> f64 {
+        3.14 * self.radius * self.radius
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("shapes/square.rs"),
+        r#"pub struct Square {
+    side: f64,
+}
+
+impl Square {
+    pub fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("main.rs"),
+        r#"use shapes::circle::Circle;
+
+fn describe(circle: &Circle) -> f64 {
+    circle.area()
+}
+
+fn main() {
+    let circle = Circle { radius: 1.0 };
+    describe(&circle);
+}
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_symbol_query_parse() {
+    let query = SymbolQuery::parse("crate::shapes::Circle::area");
+    assert_eq!(query.name, "area");
+    assert_eq!(query.qualifier, vec!["shapes", "Circle"]);
+
+    let query = SymbolQuery::parse("Circle.area");
+    assert_eq!(query.name, "area");
+    assert_eq!(query.qualifier, vec!["Circle"]);
+
+    let query = SymbolQuery::parse("area");
+    assert_eq!(query.name, "area");
+    assert!(query.qualifier.is_empty());
+}
+
+#[test]
+fn test_definition_with_snippet() {
+    let temp_dir = TempDir::new().unwrap();
+    write_rust_project(temp_dir.path());
+
+    let output = lookup(temp_dir.path(), "Circle::area", LookupMode::Definition);
+    assert!(output.starts_with("DEFINITION: Circle::area"), "{output}");
+    assert!(
+        output.contains("circle.rs:6 (method Circle::area)"),
+        "{output}"
+    );
+    assert!(output.contains(">6 | "), "{output}");
+    assert!(output.contains("3.14 * self.radius"), "{output}");
+    assert!(!output.contains("square.rs"), "{output}");
+}
+
+#[test]
+fn test_definition_name_collision() {
+    let temp_dir = TempDir::new().unwrap();
+    write_rust_project(temp_dir.path());
+
+    // An unqualified name lists every definition and suggests how to narrow it
+    let output = lookup(temp_dir.path(), "area", LookupMode::Definition);
+    assert!(output.contains("circle.rs:6"), "{output}");
+    assert!(output.contains("square.rs:6"), "{output}");
+    assert!(
+        output.contains("NOTE: 2 definitions share this name"),
+        "{output}"
+    );
+    assert!(output.contains("Circle::area, Square::area"), "{output}");
+
+    // A module qualifier picks the definitions in that file
+    let output = lookup(temp_dir.path(), "square::area", LookupMode::Definition);
+    assert!(output.contains("square.rs:6"), "{output}");
+    assert!(!output.contains("circle.rs"), "{output}");
+
+    let output = lookup(temp_dir.path(), "Triangle::area", LookupMode::Definition);
+    assert!(output.contains("has no definition"), "{output}");
+}
+
+#[test]
+fn test_definition_qualifier_ends_with_enclosing_type() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("geometry.py"),
+        r#"class Shape:
+    def area(self):
+        return 0
+
+
+def area(shapes):
+    return sum(shape.area() for shape in shapes)
+"#,
+    )
+    .unwrap();
+
+    // The free function follows the class but is not inside it
+    let output = lookup(temp_dir.path(), "Shape::area", LookupMode::Definition);
+    assert!(output.contains("geometry.py:2"), "{output}");
+    assert!(!output.contains("geometry.py:6"), "{output}");
+}
+
+#[test]
+fn test_references() {
+    let temp_dir = TempDir::new().unwrap();
+    write_rust_project(temp_dir.path());
+
+    let output = lookup(temp_dir.path(), "Circle", LookupMode::References);
+    assert!(output.starts_with("REFERENCES: Circle"), "{output}");
+    assert!(output.contains("DEFINED AT:"), "{output}");
+    assert!(output.contains("main.rs:3 [parameter type]"), "{output}");
+    assert!(output.contains("main.rs:8 [instantiation]"), "{output}");
+
+    // Unqualified method calls count for the type only where it is in scope
+    let output = lookup(temp_dir.path(), "Circle::area", LookupMode::References);
+    assert!(output.contains("main.rs:4 [call in describe]"), "{output}");
+    let output = lookup(temp_dir.path(), "Square::area", LookupMode::References);
+    assert!(!output.contains("main.rs:4"), "{output}");
+}
+
+#[test]
+fn test_lookup_requires_focus() {
+    let temp_dir = TempDir::new().unwrap();
+    let params = AnalyzeParams {
+        path: temp_dir.path().to_string_lossy().to_string(),
+        focus: None,
+        follow_depth: 2,
+        max_depth: 3,
+        ast_recursion_limit: None,
+        force: false,
+        mode: Some(LookupMode::References),
+        context_lines: 10,
+    };
+    let result = CodeAnalyzer::new().analyze(
+        params,
+        temp_dir.path().to_path_buf(),
+        &create_test_gitignore(),
+    );
+    assert!(result.is_err());
+}
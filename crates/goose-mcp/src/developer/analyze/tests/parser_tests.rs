@@ -225,6 +225,14 @@ fun helper() {
     assert_eq!(result.class_count, 1); // MyClass
     assert!(result.import_count > 0); // import statements
     assert!(result.main_line.is_some());
+
+    let result =
+        ElementExtractor::extract_with_depth(&tree, content, "kotlin", "semantic", None).unwrap();
+    assert!(result.calls.iter().any(|c| c.callee_name == "println"));
+    assert!(result
+        .calls
+        .iter()
+        .any(|c| c.callee_name == "main" && c.caller_name.as_deref() == Some("helper")));
 }
 
 #[test]
@@ -241,6 +249,11 @@ fn test_language_registry() {
         "kotlin",
         "swift",
         "ruby",
+        "tsx",
+        "c",
+        "cpp",
+        "csharp",
+        "php",
     ];
 
     for lang in supported {
@@ -266,11 +279,15 @@ fn test_language_registry() {
         );
     }
 
-    let js = languages::get_language_info("javascript").unwrap();
     let ts = languages::get_language_info("typescript").unwrap();
+    let tsx = languages::get_language_info("tsx").unwrap();
     assert_eq!(
-        js.element_query, ts.element_query,
-        "JS/TS should share config"
+        ts.element_query, tsx.element_query,
+        "TS/TSX should share config"
+    );
+    assert!(
+        !ts.reference_query.is_empty(),
+        "TypeScript should have reference tracking"
     );
 
     let go = languages::get_language_info("go").unwrap();
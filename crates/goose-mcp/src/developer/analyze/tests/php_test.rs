@@ -0,0 +1,135 @@
+use crate::developer::analyze::graph::CallGraph;
+use crate::developer::analyze::parser::{ElementExtractor, ParserManager};
+use crate::developer::analyze::types::{AnalysisResult, ReferenceType};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+fn parse_and_extract(code: &str) -> AnalysisResult {
+    let manager = ParserManager::new();
+    let tree = manager.parse(code, "php").unwrap();
+    ElementExtractor::extract_with_depth(&tree, code, "php", "semantic", None).unwrap()
+}
+
+fn build_test_graph(files: Vec<(&str, &str)>) -> CallGraph {
+    let manager = ParserManager::new();
+    let results: Vec<_> = files
+        .iter()
+        .map(|(path, code)| {
+            let tree = manager.parse(code, "php").unwrap();
+            let result =
+                ElementExtractor::extract_with_depth(&tree, code, "php", "semantic", None).unwrap();
+            (PathBuf::from(*path), result)
+        })
+        .collect();
+    CallGraph::build_from_results(&results)
+}
+
+#[test]
+fn test_php_class_and_method_tracking() {
+    let code = r#"<?php
+namespace App\Shapes;
+
+use App\Support\Logger;
+require_once 'helpers.php';
+
+interface Shape { public function area(): float; }
+
+trait Describes { public function describe() { return static::class; } }
+
+class Circle implements Shape
+{
+    use Describes;
+
+    private Logger $logger;
+
+    public function __construct(private Point $center, float $radius)
+    {
+        $this->logger = new Logger();
+    }
+
+    public function area(): float
+    {
+        return M_PI * $this->radius(new Helper());
+    }
+
+    private function radius(Helper $helper): float
+    {
+        return $helper->compute();
+    }
+
+    public static function make(): Circle
+    {
+        return new Circle(new Point(), 1.0);
+    }
+}
+
+function main()
+{
+    $circle = Circle::make();
+    echo format_area($circle->area());
+}
+"#;
+
+    let result = parse_and_extract(code);
+    let graph = build_test_graph(vec![("Circle.php", code)]);
+
+    let class_names: HashSet<_> = result.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(class_names, HashSet::from(["Shape", "Describes", "Circle"]));
+
+    let function_names: HashSet<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+    assert!(function_names.contains("__construct"));
+    assert!(function_names.contains("radius"));
+    assert!(function_names.contains("make"));
+    assert!(function_names.contains("main"));
+    assert_eq!(result.import_count, 2);
+    assert!(result.main_line.is_some());
+
+    let circle_methods: HashSet<_> = result
+        .references
+        .iter()
+        .filter(|r| {
+            r.ref_type == ReferenceType::MethodDefinition
+                && r.associated_type.as_deref() == Some("Circle")
+        })
+        .map(|r| r.symbol.as_str())
+        .collect();
+    assert_eq!(
+        circle_methods,
+        HashSet::from(["__construct", "area", "radius", "make"])
+    );
+
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Logger" && r.ref_type == ReferenceType::TypeInstantiation));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Logger" && r.ref_type == ReferenceType::FieldType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Point" && r.ref_type == ReferenceType::FieldType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Helper" && r.ref_type == ReferenceType::ParameterType));
+
+    let radius_call = result
+        .calls
+        .iter()
+        .find(|c| c.callee_name == "radius")
+        .expect("Expected a call to radius");
+    assert_eq!(radius_call.caller_name.as_deref(), Some("area"));
+
+    let callees: HashSet<_> = result
+        .calls
+        .iter()
+        .filter(|c| c.caller_name.as_deref() == Some("main"))
+        .map(|c| c.callee_name.as_str())
+        .collect();
+    assert_eq!(callees, HashSet::from(["make", "format_area", "area"]));
+
+    let outgoing = graph.find_outgoing_chains("Circle", 1);
+    assert!(!outgoing.is_empty(), "Expected to find methods on Circle");
+}
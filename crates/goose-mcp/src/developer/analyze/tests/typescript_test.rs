@@ -0,0 +1,137 @@
+use crate::developer::analyze::graph::CallGraph;
+use crate::developer::analyze::parser::{ElementExtractor, ParserManager};
+use crate::developer::analyze::types::{AnalysisResult, ReferenceType};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+fn parse_and_extract(code: &str, language: &str) -> AnalysisResult {
+    let manager = ParserManager::new();
+    let tree = manager.parse(code, language).unwrap();
+    ElementExtractor::extract_with_depth(&tree, code, language, "semantic", None).unwrap()
+}
+
+fn build_test_graph(files: Vec<(&str, &str)>) -> CallGraph {
+    let manager = ParserManager::new();
+    let results: Vec<_> = files
+        .iter()
+        .map(|(path, code)| {
+            let tree = manager.parse(code, "typescript").unwrap();
+            let result =
+                ElementExtractor::extract_with_depth(&tree, code, "typescript", "semantic", None)
+                    .unwrap();
+            (PathBuf::from(*path), result)
+        })
+        .collect();
+    CallGraph::build_from_results(&results)
+}
+
+#[test]
+fn test_typescript_class_and_type_tracking() {
+    let code = r#"
+import { Logger } from "./logger";
+
+interface Shape {
+  area(): number;
+}
+
+enum Color { Red, Green }
+
+export class Circle implements Shape {
+  private logger: Logger;
+
+  constructor(private radius: number, logger?: Logger) {
+    this.logger = new Logger();
+  }
+
+  area(): number {
+    return Math.PI * this.radius ** 2;
+  }
+}
+
+export function makeCircle(r: number): Circle {
+  const circle: Circle = new Circle(r);
+  circle.area();
+  return circle;
+}
+
+const double = (x: number): number => x * 2;
+"#;
+
+    let result = parse_and_extract(code, "typescript");
+    let graph = build_test_graph(vec![("shapes.ts", code)]);
+
+    let class_names: HashSet<_> = result.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(class_names, HashSet::from(["Shape", "Color", "Circle"]));
+
+    let function_names: HashSet<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+    assert!(function_names.contains("makeCircle"));
+    assert!(function_names.contains("area"));
+    assert!(function_names.contains("constructor"));
+    assert!(function_names.contains("double"));
+    assert_eq!(result.import_count, 1);
+
+    let circle_methods: Vec<_> = result
+        .references
+        .iter()
+        .filter(|r| {
+            r.ref_type == ReferenceType::MethodDefinition
+                && r.associated_type.as_deref() == Some("Circle")
+        })
+        .collect();
+    assert_eq!(circle_methods.len(), 2);
+
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Circle" && r.ref_type == ReferenceType::TypeInstantiation));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Logger" && r.ref_type == ReferenceType::FieldType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Logger" && r.ref_type == ReferenceType::ParameterType));
+    assert!(result
+        .references
+        .iter()
+        .any(|r| r.symbol == "Circle" && r.ref_type == ReferenceType::VariableType));
+
+    let callers: Vec<_> = result
+        .calls
+        .iter()
+        .filter(|c| c.callee_name == "area")
+        .map(|c| c.caller_name.as_deref())
+        .collect();
+    assert_eq!(callers, vec![Some("makeCircle")]);
+
+    let outgoing = graph.find_outgoing_chains("Circle", 1);
+    assert!(!outgoing.is_empty(), "Expected to find methods on Circle");
+}
+
+#[test]
+fn test_tsx_components() {
+    let code = r#"
+import React from "react";
+
+export function Button({ label }: { label: string }) {
+  return <button onClick={() => track(label)}>{label}</button>;
+}
+
+const App = () => <Button label="hi" />;
+"#;
+
+    let result = parse_and_extract(code, "tsx");
+
+    let function_names: HashSet<_> = result.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(function_names, HashSet::from(["Button", "App"]));
+    assert_eq!(result.import_count, 1);
+
+    let callees: HashSet<_> = result
+        .calls
+        .iter()
+        .map(|c| c.callee_name.as_str())
+        .collect();
+    assert!(callees.contains("track"));
+    assert!(callees.contains("Button"));
+}
@@ -23,6 +23,25 @@ pub struct AnalyzeParams {
     /// Allow large outputs without warning (default: false)
     #[serde(default)]
     pub force: bool,
+
+    /// Look up the `focus` symbol instead of tracing its call graph. `focus` may be
+    /// qualified by type or module to tell same-named symbols apart, e.g. Type::method
+    #[serde(default)]
+    pub mode: Option<LookupMode>,
+
+    /// Lines of code shown before and after each definition in definition mode
+    #[serde(default = "default_context_lines")]
+    pub context_lines: usize,
+}
+
+/// Symbol lookups available through `AnalyzeParams::mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupMode {
+    /// Where the symbol is defined, with the surrounding code
+    Definition,
+    /// Every place the symbol is called or its type is used, as file:line
+    References,
 }
 
 fn default_follow_depth() -> u32 {
@@ -33,6 +52,10 @@ fn default_max_depth() -> u32 {
     3
 }
 
+fn default_context_lines() -> usize {
+    10
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
     pub functions: Vec<FunctionInfo>,
@@ -60,6 +83,8 @@ pub struct FunctionInfo {
 pub struct ClassInfo {
     pub name: String,
     pub line: usize,
+    /// Last line of the definition the name belongs to
+    pub end_line: usize,
     pub methods: Vec<FunctionInfo>,
 }
 
@@ -130,12 +155,26 @@ pub struct FocusedAnalysisData<'a> {
     pub outgoing_chains: &'a [CallChain],
 }
 
+/// Where a symbol is defined or referenced, found by a definition or references lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolLocation {
+    pub file: PathBuf,
+    pub line: usize,
+    /// What kind of definition or reference this is, e.g. "method" or "call"
+    pub kind: &'static str,
+    /// The type a method belongs to, when known
+    pub container: Option<String>,
+    pub context: String,
+}
+
 /// Analysis modes
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AnalysisMode {
-    Structure, // Directory overview
-    Semantic,  // File details
-    Focused,   // Symbol tracking
+    Structure,  // Directory overview
+    Semantic,   // File details
+    Focused,    // Symbol tracking
+    Definition, // Symbol definition lookup
+    References, // Symbol reference lookup
 }
 
 impl AnalysisMode {
@@ -144,6 +183,8 @@ impl AnalysisMode {
             AnalysisMode::Structure => "structure",
             AnalysisMode::Semantic => "semantic",
             AnalysisMode::Focused => "focused",
+            AnalysisMode::Definition => "definition",
+            AnalysisMode::References => "references",
         }
     }
 
@@ -152,6 +193,8 @@ impl AnalysisMode {
             "structure" => AnalysisMode::Structure,
             "semantic" => AnalysisMode::Semantic,
             "focused" => AnalysisMode::Focused,
+            "definition" => AnalysisMode::Definition,
+            "references" => AnalysisMode::References,
             _ => AnalysisMode::Structure,
         }
     }
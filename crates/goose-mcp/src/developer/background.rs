@@ -0,0 +1,424 @@
+use std::{
+    collections::HashMap,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    process::{Child, ChildStdin},
+    sync::RwLock,
+};
+
+use super::shell::kill_process_group;
+
+/// Maximum amount of unread output kept per background process.
+/// Older output is discarded once this is exceeded.
+const MAX_BUFFERED_OUTPUT: usize = 400_000;
+
+/// Status of a background process at the time it was queried.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessStatus {
+    Running,
+    Exited(Option<i32>),
+}
+
+impl std::fmt::Display for ProcessStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessStatus::Running => write!(f, "running"),
+            ProcessStatus::Exited(Some(code)) => write!(f, "exited with code {}", code),
+            ProcessStatus::Exited(None) => write!(f, "exited (terminated by signal)"),
+        }
+    }
+}
+
+/// Output captured from a background process since the previous read.
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    pub output: String,
+    pub discarded_bytes: usize,
+    pub status: ProcessStatus,
+}
+
+/// Summary of a background process for listing.
+#[derive(Debug, Clone)]
+pub struct ProcessSummary {
+    pub handle: String,
+    pub command: String,
+    pub pid: Option<u32>,
+    pub elapsed_secs: u64,
+    pub status: ProcessStatus,
+}
+
+#[derive(Default)]
+struct OutputBuffer {
+    unread: String,
+    discarded_bytes: usize,
+}
+
+impl OutputBuffer {
+    fn push(&mut self, chunk: &str) {
+        self.unread.push_str(chunk);
+        if self.unread.len() > MAX_BUFFERED_OUTPUT {
+            let mut excess = self.unread.len() - MAX_BUFFERED_OUTPUT;
+            while !self.unread.is_char_boundary(excess) {
+                excess += 1;
+            }
+            self.unread.drain(..excess);
+            self.discarded_bytes += excess;
+        }
+    }
+
+    fn take(&mut self) -> (String, usize) {
+        let discarded = std::mem::take(&mut self.discarded_bytes);
+        (std::mem::take(&mut self.unread), discarded)
+    }
+}
+
+struct BackgroundProcess {
+    command: String,
+    pid: Option<u32>,
+    started_at: Instant,
+    child: tokio::sync::Mutex<Child>,
+    stdin: tokio::sync::Mutex<Option<ChildStdin>>,
+    output: Arc<Mutex<OutputBuffer>>,
+}
+
+impl BackgroundProcess {
+    async fn status(&self) -> ProcessStatus {
+        match self.child.lock().await.try_wait() {
+            Ok(Some(exit_status)) => ProcessStatus::Exited(exit_status.code()),
+            _ => ProcessStatus::Running,
+        }
+    }
+
+    async fn read(&self) -> ProcessOutput {
+        // Check the status first so output written right before exit is not missed
+        let status = self.status().await;
+        let (output, discarded_bytes) = self.output.lock().unwrap().take();
+        ProcessOutput {
+            output,
+            discarded_bytes,
+            status,
+        }
+    }
+}
+
+impl BackgroundProcess {
+    /// Kill whatever is left of the process group. The shell may have exited while
+    /// commands it started are still running in its group.
+    fn kill_group(&self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.pid {
+            unsafe {
+                libc::kill(-(pid as i32), libc::SIGKILL);
+            }
+        }
+
+        #[cfg(windows)]
+        if let Some(pid) = self.pid {
+            let _ = std::process::Command::new("taskkill")
+                .args(["/F", "/T", "/PID", &pid.to_string()])
+                .output();
+        }
+    }
+}
+
+impl Drop for BackgroundProcess {
+    /// Make sure a background process never outlives the server that started it.
+    fn drop(&mut self) {
+        self.kill_group();
+        let child = self.child.get_mut();
+        if matches!(child.try_wait(), Ok(None)) {
+            let _ = child.start_kill();
+        }
+    }
+}
+
+/// Tracks shell commands started with `background: true`.
+///
+/// Processes are addressed by a handle returned from [`BackgroundProcesses::spawn`]
+/// and are killed when they are removed or when the registry is dropped at the end
+/// of the session.
+#[derive(Default)]
+pub struct BackgroundProcesses {
+    next_id: AtomicU64,
+    processes: RwLock<HashMap<String, Arc<BackgroundProcess>>>,
+}
+
+impl BackgroundProcesses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn a configured shell command in the background and return its handle.
+    pub async fn spawn(
+        &self,
+        mut command: tokio::process::Command,
+        display_command: &str,
+    ) -> std::io::Result<String> {
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = command.spawn()?;
+
+        let output = Arc::new(Mutex::new(OutputBuffer::default()));
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(capture_output(stdout, output.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(capture_output(stderr, output.clone()));
+        }
+
+        let handle = format!("bg-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let process = BackgroundProcess {
+            command: display_command.to_string(),
+            pid: child.id(),
+            started_at: Instant::now(),
+            stdin: tokio::sync::Mutex::new(child.stdin.take()),
+            child: tokio::sync::Mutex::new(child),
+            output,
+        };
+
+        self.processes
+            .write()
+            .await
+            .insert(handle.clone(), Arc::new(process));
+        Ok(handle)
+    }
+
+    /// Return the output produced since the last read, along with the current status.
+    pub async fn read_output(&self, handle: &str) -> Option<ProcessOutput> {
+        let process = self.get(handle).await?;
+        Some(process.read().await)
+    }
+
+    /// Write `input` to the process's stdin.
+    pub async fn send_input(&self, handle: &str, input: &str) -> Option<std::io::Result<()>> {
+        let process = self.get(handle).await?;
+        let mut stdin = process.stdin.lock().await;
+        let Some(stdin) = stdin.as_mut() else {
+            return Some(Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "stdin is closed",
+            )));
+        };
+        let result = async {
+            stdin.write_all(input.as_bytes()).await?;
+            stdin.flush().await
+        }
+        .await;
+        Some(result)
+    }
+
+    /// Kill the process and its children, returning any output that was not read yet.
+    pub async fn kill(&self, handle: &str) -> Option<ProcessOutput> {
+        let process = self.processes.write().await.remove(handle)?;
+        if process.status().await == ProcessStatus::Running {
+            let mut child = process.child.lock().await;
+            if let Err(e) = kill_process_group(&mut child, process.pid).await {
+                tracing::warn!("Failed to kill background process {}: {}", handle, e);
+            }
+            let _ = child.wait().await;
+        } else {
+            process.kill_group();
+        }
+        Some(process.read().await)
+    }
+
+    /// List all tracked background processes.
+    pub async fn list(&self) -> Vec<ProcessSummary> {
+        let processes: Vec<_> = self
+            .processes
+            .read()
+            .await
+            .iter()
+            .map(|(handle, process)| (handle.clone(), process.clone()))
+            .collect();
+
+        let mut summaries = Vec::with_capacity(processes.len());
+        for (handle, process) in processes {
+            summaries.push(ProcessSummary {
+                handle,
+                command: process.command.clone(),
+                pid: process.pid,
+                elapsed_secs: process.started_at.elapsed().as_secs(),
+                status: process.status().await,
+            });
+        }
+        summaries.sort_by(|a, b| a.handle.cmp(&b.handle));
+        summaries
+    }
+
+    async fn get(&self, handle: &str) -> Option<Arc<BackgroundProcess>> {
+        self.processes.read().await.get(handle).cloned()
+    }
+}
+
+async fn capture_output<R: AsyncRead + Unpin>(mut reader: R, output: Arc<Mutex<OutputBuffer>>) {
+    let mut buf = [0u8; 8192];
+    let mut pending = Vec::new();
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                pending.extend_from_slice(&buf[..n]);
+                let text = take_utf8(&mut pending);
+                output.lock().unwrap().push(&text);
+            }
+        }
+    }
+    if !pending.is_empty() {
+        output
+            .lock()
+            .unwrap()
+            .push(&String::from_utf8_lossy(&pending));
+    }
+}
+
+/// Decode the complete characters in `pending`, leaving a multi-byte character that was
+/// split across reads for the next chunk. Invalid bytes are replaced.
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let complete = pending.len() - incomplete_suffix_len(pending);
+    let text = String::from_utf8_lossy(&pending[..complete]).into_owned();
+    pending.drain(..complete);
+    text
+}
+
+/// The length of a character at the end of `bytes` that is missing continuation bytes
+fn incomplete_suffix_len(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - len];
+        if byte & 0xC0 != 0x80 {
+            let width = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+            return if width > len { len } else { 0 };
+        }
+    }
+    0
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::developer::shell::{configure_shell_command, get_shell_config};
+    use std::time::Duration;
+
+    async fn wait_for_output(
+        processes: &BackgroundProcesses,
+        handle: &str,
+        needle: &str,
+    ) -> String {
+        let mut collected = String::new();
+        for _ in 0..50 {
+            let output = processes.read_output(handle).await.unwrap();
+            collected.push_str(&output.output);
+            if collected.contains(needle) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        collected
+    }
+
+    #[tokio::test]
+    async fn test_background_output_is_incremental() {
+        let processes = BackgroundProcesses::new();
+        let command =
+            configure_shell_command(&get_shell_config(), "echo first; sleep 0.3; echo second");
+        let handle = processes.spawn(command, "echo").await.unwrap();
+
+        let first = wait_for_output(&processes, &handle, "first").await;
+        assert!(first.contains("first"));
+        assert!(!first.contains("second"));
+
+        let second = wait_for_output(&processes, &handle, "second").await;
+        assert!(second.contains("second"));
+        assert!(!second.contains("first"));
+    }
+
+    #[tokio::test]
+    async fn test_background_stdin_and_kill() {
+        let processes = BackgroundProcesses::new();
+        let command = configure_shell_command(&get_shell_config(), "cat");
+        let handle = processes.spawn(command, "cat").await.unwrap();
+
+        processes
+            .send_input(&handle, "hello from stdin\n")
+            .await
+            .unwrap()
+            .unwrap();
+        let echoed = wait_for_output(&processes, &handle, "hello from stdin").await;
+        assert!(echoed.contains("hello from stdin"));
+
+        let listed = processes.list().await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].status, ProcessStatus::Running);
+
+        let killed = processes.kill(&handle).await.unwrap();
+        assert!(matches!(killed.status, ProcessStatus::Exited(_)));
+        assert!(processes.list().await.is_empty());
+        assert!(processes.read_output(&handle).await.is_none());
+    }
+
+    #[test]
+    fn test_output_buffer_discards_oldest() {
+        let mut buffer = OutputBuffer::default();
+        buffer.push(&"a".repeat(MAX_BUFFERED_OUTPUT));
+        buffer.push("tail");
+
+        let (output, discarded) = buffer.take();
+        assert_eq!(output.len(), MAX_BUFFERED_OUTPUT);
+        assert_eq!(discarded, 4);
+        assert!(output.ends_with("tail"));
+    }
+
+    #[test]
+    fn test_take_utf8_keeps_split_characters() {
+        let text = "héllo → wörld 🦀";
+        for split in 0..=text.len() {
+            let mut pending = text.as_bytes()[..split].to_vec();
+            let mut decoded = take_utf8(&mut pending);
+            pending.extend_from_slice(&text.as_bytes()[split..]);
+            decoded.push_str(&take_utf8(&mut pending));
+            assert_eq!(decoded, text, "split at {}", split);
+            assert!(pending.is_empty());
+        }
+
+        let mut invalid = vec![b'a', 0xFF, b'b'];
+        assert_eq!(take_utf8(&mut invalid), "a\u{FFFD}b");
+    }
+
+    #[tokio::test]
+    async fn test_kill_stops_children_after_the_shell_exits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let marker = temp_dir.path().join("marker");
+        let processes = BackgroundProcesses::new();
+        let command = configure_shell_command(
+            &get_shell_config(),
+            &format!("(sleep 1; touch {}) & echo started", marker.display()),
+        );
+        let handle = processes.spawn(command, "sleep").await.unwrap();
+        wait_for_output(&processes, &handle, "started").await;
+        for _ in 0..50 {
+            if processes.read_output(&handle).await.unwrap().status != ProcessStatus::Running {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        processes.kill(&handle).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+    }
+}
@@ -8,7 +8,8 @@ pub fn get_language_identifier(path: &Path) -> &'static str {
         Some("rkt") | Some("scm") => "scheme",
         Some("py") => "python",
         Some("js") => "javascript",
-        Some("ts") => "typescript",
+        Some("ts") | Some("mts") | Some("cts") => "typescript",
+        Some("tsx") => "tsx",
         Some("json") => "json",
         Some("toml") => "toml",
         Some("yaml") | Some("yml") => "yaml",
@@ -22,6 +23,7 @@ pub fn get_language_identifier(path: &Path) -> &'static str {
         Some("css") => "css",
         Some("sql") => "sql",
         Some("java") => "java",
+        Some("cs") => "csharp",
         Some("cpp") | Some("cc") | Some("cxx") => "cpp",
         Some("c") => "c",
         Some("h") | Some("hpp") => "cpp",
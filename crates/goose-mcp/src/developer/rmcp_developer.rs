@@ -31,7 +31,8 @@ use tokio::{
 use tokio_stream::{wrappers::SplitStream, StreamExt as _};
 use tokio_util::sync::CancellationToken;
 
-use super::analyze::{types::AnalyzeParams, CodeAnalyzer};
+use super::analyze::{index::AnalysisIndex, types::AnalyzeParams, CodeAnalyzer};
+use super::background::BackgroundProcesses;
 use super::editor_models::{create_editor_model, EditorModel};
 use super::shell::{
     configure_shell_command, expand_path, get_shell_config, is_absolute_path, kill_process_group,
@@ -85,10 +86,57 @@ pub struct TextEditorParams {
 }
 
 /// Parameters for the shell tool
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ShellParams {
     /// The command string to execute in the shell
     pub command: String,
+
+    /// Optional: kill the command if it has not finished after this many seconds.
+    /// Ignored for background commands.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Optional: directory to run the command in, absolute or relative to the current directory.
+    #[serde(default)]
+    pub cwd: Option<String>,
+
+    /// Optional: extra environment variables to set for the command.
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+
+    /// Optional: start the command in the background and return a handle immediately.
+    /// Use the shell_process tool with the handle to read output, send input, or kill it.
+    #[serde(default)]
+    pub background: bool,
+}
+
+/// Operations on a background shell process
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShellProcessAction {
+    /// List all background processes
+    List,
+    /// Read the output produced since the last read
+    Output,
+    /// Write `input` to the process's stdin
+    Input,
+    /// Kill the process and its children
+    Kill,
+}
+
+/// Parameters for the shell_process tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ShellProcessParams {
+    /// The operation to perform
+    pub action: ShellProcessAction,
+
+    /// The handle returned by the shell tool. Required for all actions except `list`.
+    #[serde(default)]
+    pub handle: Option<String>,
+
+    /// Text to write to stdin. Required for `input`; include a trailing newline to submit a line.
+    #[serde(default)]
+    pub input: Option<String>,
 }
 
 /// Parameters for the image_processor tool
@@ -179,6 +227,7 @@ pub struct DeveloperServer {
     pub running_processes: Arc<RwLock<HashMap<String, CancellationToken>>>,
     #[cfg(not(test))]
     running_processes: Arc<RwLock<HashMap<String, CancellationToken>>>,
+    background_processes: Arc<BackgroundProcesses>,
 }
 
 #[tool_handler(router = self.tool_router)]
@@ -319,7 +368,11 @@ impl ServerHandler for DeveloperServer {
 
             **Important**: Each shell command runs in its own process. Things like directory changes or
             sourcing files do not persist between tool calls. So you may need to repeat them each time by
-            stringing together commands.
+            stringing together commands, or pass `cwd` and `env` to the shell tool.
+
+            Use `timeout_secs` for commands that might hang. For long lived commands such as dev servers or
+            watchers, pass `background: true` and use the shell_process tool with the returned handle to
+            read output, send input, or kill the process.
         "#};
 
         let windows_specific = indoc! {r#"
@@ -344,9 +397,6 @@ impl ServerHandler for DeveloperServer {
         "#};
 
         let unix_specific = indoc! {r#"
-            If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
-            this tool does not run indefinitely.
-
             **Important**: Use ripgrep - `rg` - exclusively when you need to locate a file or a code reference,
             other solutions may produce too large output because of hidden files! For example *do not* use `find` or `ls -r`
               - List files by name: `rg --files | rg <filename>`
@@ -547,8 +597,11 @@ impl DeveloperServer {
             ignore_patterns,
             editor_model,
             prompts: load_prompt_files(),
-            code_analyzer: CodeAnalyzer::new(),
+            code_analyzer: AnalysisIndex::for_root(&cwd)
+                .map(CodeAnalyzer::with_index)
+                .unwrap_or_default(),
             running_processes: Arc::new(RwLock::new(HashMap::new())),
+            background_processes: Arc::new(BackgroundProcesses::new()),
         }
     }
 
@@ -745,6 +798,7 @@ impl DeveloperServer {
                     )
                 })?;
                 let content = text_editor_write(&path, &file_text).await?;
+                self.code_analyzer.refresh_file(&path);
                 Ok(CallToolResult::success(content))
             }
             "str_replace" => {
@@ -760,6 +814,7 @@ impl DeveloperServer {
                         &self.file_history,
                     )
                     .await?;
+                    self.code_analyzer.refresh_file(&path);
                     Ok(CallToolResult::success(content))
                 } else {
                     // Traditional str_replace with old_str and new_str
@@ -786,6 +841,7 @@ impl DeveloperServer {
                         &self.file_history,
                     )
                     .await?;
+                    self.code_analyzer.refresh_file(&path);
                     Ok(CallToolResult::success(content))
                 }
             }
@@ -807,10 +863,12 @@ impl DeveloperServer {
                 let content =
                     text_editor_insert(&path, insert_line as i64, &new_str, &self.file_history)
                         .await?;
+                self.code_analyzer.refresh_file(&path);
                 Ok(CallToolResult::success(content))
             }
             "undo_edit" => {
                 let content = text_editor_undo(&path, &self.file_history).await?;
+                self.code_analyzer.refresh_file(&path);
                 Ok(CallToolResult::success(content))
             }
             _ => Err(ErrorData::new(
@@ -828,11 +886,11 @@ impl DeveloperServer {
     /// of if the command succeeded or failed.
     ///
     /// Avoid commands that produce a large amount of output, and consider piping those outputs to files.
-    /// If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
+    /// If you need to run a long lived command, start it with `background: true` so that
     /// this tool does not run indefinitely.
     #[tool(
         name = "shell",
-        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that this tool does not run indefinitely."
+        description = "Execute a command in the shell.This will return the output and error concatenated into a single string, as you would see from running on the command line. There will also be an indication of if the command succeeded or failed. Avoid commands that produce a large amount of output, and consider piping those outputs to files. Optionally set `cwd`, `env` and `timeout_secs`. If you need to run a long lived command, set `background: true` to get a handle for the shell_process tool so that this tool does not run indefinitely."
     )]
     pub async fn shell(
         &self,
@@ -847,6 +905,22 @@ impl DeveloperServer {
         // Validate the shell command
         self.validate_shell_command(command)?;
 
+        let shell_command = self.build_shell_command(&params)?;
+
+        if params.background {
+            let handle = self
+                .background_processes
+                .spawn(shell_command, command)
+                .await
+                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Started background process with handle `{}`. Use the shell_process tool to read its output, send input, or kill it.",
+                handle
+            ))]));
+        }
+
+        let timeout = params.timeout_secs.map(std::time::Duration::from_secs);
+
         let cancellation_token = CancellationToken::new();
         // Track the process using the request ID
         {
@@ -857,7 +931,7 @@ impl DeveloperServer {
 
         // Execute the command and capture output
         let output_result = self
-            .execute_shell_command(command, &peer, cancellation_token.clone())
+            .execute_shell_command(shell_command, timeout, &peer, cancellation_token.clone())
             .await;
 
         // Clean up the process from tracking
@@ -933,19 +1007,57 @@ impl DeveloperServer {
         Ok(())
     }
 
+    /// Build the platform-specific shell command, applying the optional working directory
+    /// and environment from the tool parameters.
+    fn build_shell_command(
+        &self,
+        params: &ShellParams,
+    ) -> Result<tokio::process::Command, ErrorData> {
+        // Get platform-specific shell configuration
+        let shell_config = get_shell_config();
+        let mut command = configure_shell_command(&shell_config, &params.command);
+
+        if let Some(cwd) = &params.cwd {
+            let cwd = self.resolve_path(cwd)?;
+            if !cwd.is_dir() {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Working directory '{}' does not exist", cwd.display()),
+                    None,
+                ));
+            }
+            if self.is_ignored(&cwd) {
+                return Err(ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!(
+                        "Access to '{}' is restricted by .gooseignore",
+                        cwd.display()
+                    ),
+                    None,
+                ));
+            }
+            command.current_dir(cwd);
+        }
+
+        if let Some(env) = &params.env {
+            command.envs(env);
+        }
+
+        Ok(command)
+    }
+
     /// Execute a shell command and return the combined output.
     ///
     /// Streams output in real-time to the client using logging notifications.
+    /// The command is killed if it is cancelled or runs longer than `timeout`.
     async fn execute_shell_command(
         &self,
-        command: &str,
+        mut command: tokio::process::Command,
+        timeout: Option<std::time::Duration>,
         peer: &rmcp::service::Peer<RoleServer>,
         cancellation_token: CancellationToken,
     ) -> Result<String, ErrorData> {
-        // Get platform-specific shell configuration
-        let shell_config = get_shell_config();
-
-        let mut child = configure_shell_command(&shell_config, command)
+        let mut child = command
             .spawn()
             .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
 
@@ -956,11 +1068,14 @@ impl DeveloperServer {
             tracing::warn!("Shell process spawned but PID not available");
         }
 
-        // Stream the output and wait for completion with cancellation support
+        // Stream the output and wait for completion with cancellation support. Output is
+        // collected as it arrives so it can still be returned if the command times out.
+        let captured = Arc::new(Mutex::new(String::new()));
         let output_task = self.stream_shell_output(
             child.stdout.take().unwrap(),
             child.stderr.take().unwrap(),
             peer.clone(),
+            captured.clone(),
         );
 
         tokio::select! {
@@ -988,24 +1103,156 @@ impl DeveloperServer {
                     None,
                 ))
             }
+            _ = async {
+                match timeout {
+                    Some(timeout) => tokio::time::sleep(timeout).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                let timeout_secs = timeout.unwrap_or_default().as_secs();
+                tracing::info!("Shell command timed out after {} seconds, killing process", timeout_secs);
+
+                if let Err(e) = kill_process_group(&mut child, pid).await {
+                    tracing::error!("Failed to kill timed out shell process: {}", e);
+                }
+
+                let mut message = format!(
+                    "Shell command timed out after {} seconds and was killed. Consider running it with `background: true`.",
+                    timeout_secs
+                );
+                let output = captured.lock().unwrap().clone();
+                if !output.trim().is_empty() {
+                    let (final_output, _) = self.process_shell_output(&output)?;
+                    message.push_str("\nOutput before it was killed:\n");
+                    message.push_str(&final_output);
+                }
+                Err(ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None))
+            }
         }
     }
 
+    /// Manage shell commands started with `background: true`.
+    #[tool(
+        name = "shell_process",
+        description = "Manage background processes started by the shell tool with `background: true`. Actions: `list` shows all background processes; `output` returns output produced since the last read and whether the process is still running; `input` writes `input` to the process's stdin; `kill` stops the process and its children."
+    )]
+    pub async fn shell_process(
+        &self,
+        params: Parameters<ShellProcessParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        if params.action == ShellProcessAction::List {
+            let processes = self.background_processes.list().await;
+            if processes.is_empty() {
+                return Ok(CallToolResult::success(vec![Content::text(
+                    "No background processes",
+                )]));
+            }
+            let listing = processes
+                .iter()
+                .map(|p| {
+                    format!(
+                        "{} (pid {}, {}s, {}): {}",
+                        p.handle,
+                        p.pid.map_or("?".to_string(), |pid| pid.to_string()),
+                        p.elapsed_secs,
+                        p.status,
+                        p.command
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Ok(CallToolResult::success(vec![Content::text(listing)]));
+        }
+
+        let handle = params.handle.as_deref().ok_or_else(|| {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "The handle parameter is required for this action".to_string(),
+                None,
+            )
+        })?;
+        let unknown_handle = || {
+            ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("No background process with handle '{}'", handle),
+                None,
+            )
+        };
+
+        let output = match params.action {
+            ShellProcessAction::List => unreachable!("handled above"),
+            ShellProcessAction::Output => self
+                .background_processes
+                .read_output(handle)
+                .await
+                .ok_or_else(unknown_handle)?,
+            ShellProcessAction::Input => {
+                let input = params.input.as_deref().ok_or_else(|| {
+                    ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        "The input parameter is required for the input action".to_string(),
+                        None,
+                    )
+                })?;
+                self.background_processes
+                    .send_input(handle, input)
+                    .await
+                    .ok_or_else(unknown_handle)?
+                    .map_err(|e| {
+                        ErrorData::new(
+                            ErrorCode::INTERNAL_ERROR,
+                            format!("Failed to write to stdin: {}", e),
+                            None,
+                        )
+                    })?;
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Sent {} bytes to {}",
+                    input.len(),
+                    handle
+                ))]));
+            }
+            ShellProcessAction::Kill => self
+                .background_processes
+                .kill(handle)
+                .await
+                .ok_or_else(unknown_handle)?,
+        };
+
+        let mut text = format!("Process {} {}.", handle, output.status);
+        if output.discarded_bytes > 0 {
+            text.push_str(&format!(
+                " {} bytes of older output were discarded.",
+                output.discarded_bytes
+            ));
+        }
+        if output.output.is_empty() {
+            text.push_str(" No new output.");
+        } else {
+            let (final_output, _) = self.process_shell_output(&output.output)?;
+            text.push('\n');
+            text.push_str(&final_output);
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
     /// Stream shell output in real-time and return the combined output.
     ///
     /// Merges stdout and stderr streams and sends each line as a logging notification.
+    /// Lines are appended to `captured` as they arrive.
     async fn stream_shell_output(
         &self,
         stdout: tokio::process::ChildStdout,
         stderr: tokio::process::ChildStderr,
         peer: rmcp::service::Peer<RoleServer>,
+        captured: Arc<Mutex<String>>,
     ) -> Result<String, ErrorData> {
         let stdout = BufReader::new(stdout);
         let stderr = BufReader::new(stderr);
 
         let output_task = tokio::spawn(async move {
-            let mut combined_output = String::new();
-
             // Merge stdout and stderr streams
             // ref https://blog.yoshuawuyts.com/futures-concurrency-3
             let stdout = SplitStream::new(stdout.split(b'\n')).map(|v| ("stdout", v));
@@ -1019,7 +1266,7 @@ impl DeveloperServer {
                 // Convert to UTF-8 to avoid corrupted output
                 let line_str = String::from_utf8_lossy(&line);
 
-                combined_output.push_str(&line_str);
+                captured.lock().unwrap().push_str(&line_str);
 
                 // Stream each line back to the client in real-time
                 let trimmed_line = line_str.trim();
@@ -1042,7 +1289,7 @@ impl DeveloperServer {
                     }
                 }
             }
-            Ok::<_, std::io::Error>(combined_output)
+            Ok::<_, std::io::Error>(captured.lock().unwrap().clone())
         });
 
         match output_task.await {
@@ -1084,14 +1331,17 @@ impl DeveloperServer {
     /// - Files: Semantic analysis with call graphs
     /// - Directories: Structure overview with metrics
     /// - With focus parameter: Track symbol across files
+    /// - With focus and mode: Look up a symbol's definition or references
     ///
     /// Examples:
     /// analyze(path="file.py") -> semantic analysis
     /// analyze(path="src/") -> structure overview down to max_depth subdirs
     /// analyze(path="src/", focus="main") -> track main() across files in src/ down to max_depth subdirs
+    /// analyze(path="src/", focus="Foo::bar", mode="definition") -> where Foo's bar is defined, with code around it
+    /// analyze(path="src/", focus="Foo", mode="references") -> every file:line that uses Foo
     #[tool(
         name = "analyze",
-        description = "Analyze code structure in 3 modes: 1) Directory overview - file tree with LOC/function/class counts to max_depth. 2) File details - functions, classes, imports. 3) Symbol focus - call graphs across directory to max_depth (requires directory path, case-sensitive). Typical flow: directory → files → symbols. Functions called >3x show •N."
+        description = "Analyze code structure in 3 modes: 1) Directory overview - file tree with LOC/function/class counts to max_depth. 2) File details - functions, classes, imports. 3) Symbol focus - call graphs across directory to max_depth (requires directory path, case-sensitive). Typical flow: directory → files → symbols. Functions called >3x show •N. Set mode=definition (code around the definition, context_lines each side) or mode=references (every use as file:line) with focus to look up a symbol; qualify focus as Type::name or module::name to tell same-named symbols apart."
     )]
     pub async fn analyze(
         &self,
@@ -1225,7 +1475,6 @@ impl DeveloperServer {
 
     // Helper method to resolve and validate file paths
     fn resolve_path(&self, path_str: &str) -> Result<PathBuf, ErrorData> {
-        let cwd = std::env::current_dir().expect("should have a current working dir");
         let expanded = expand_path(path_str);
         let path = Path::new(&expanded);
 
@@ -1234,6 +1483,13 @@ impl DeveloperServer {
             Ok(path.to_path_buf())
         } else {
             // For relative paths, resolve them relative to the current working directory
+            let cwd = std::env::current_dir().map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Cannot resolve '{}': {}", path_str, e),
+                    None,
+                )
+            })?;
             Ok(cwd.join(path))
         }
     }
@@ -1461,6 +1717,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "".to_string(),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1496,6 +1753,7 @@ mod tests {
             // Test PowerShell command
             let shell_params = Parameters(ShellParams {
                 command: "Get-ChildItem".to_string(),
+                ..Default::default()
             });
 
             let result = server
@@ -1879,6 +2137,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", secret_file_path.to_str().unwrap()),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -1901,6 +2160,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: format!("cat {}", allowed_file_path.to_str().unwrap()),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -2945,6 +3205,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3094,6 +3355,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: command.to_string(),
+                        ..Default::default()
                     }),
                     RequestContext {
                         ct: Default::default(),
@@ -3304,6 +3566,7 @@ mod tests {
                     .shell(
                         Parameters(ShellParams {
                             command: "sleep 30".to_string(),
+                            ..Default::default()
                         }),
                         context,
                     )
@@ -3392,6 +3655,7 @@ mod tests {
                     .shell(
                         Parameters(ShellParams {
                             command: "bash -c 'sleep 60 & wait'".to_string(),
+                            ..Default::default()
                         }),
                         context,
                     )
@@ -3489,6 +3753,7 @@ mod tests {
                 .shell(
                     Parameters(ShellParams {
                         command: "echo 'Hello, World!'".to_string(),
+                        ..Default::default()
                     }),
                     context,
                 )
@@ -3510,4 +3775,144 @@ mod tests {
             cleanup_test_service(running_service, peer);
         });
     }
+
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_shell_cwd_env_and_timeout() {
+        run_shell_test(|| async {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+            let context = |id| RequestContext {
+                ct: Default::default(),
+                id: NumberOrString::Number(id),
+                meta: Default::default(),
+                extensions: Default::default(),
+                peer: peer.clone(),
+            };
+
+            let result = server
+                .shell(
+                    Parameters(ShellParams {
+                        command: "pwd; echo $GOOSE_TEST_VALUE".to_string(),
+                        cwd: Some(temp_dir.path().to_string_lossy().to_string()),
+                        env: Some(HashMap::from([(
+                            "GOOSE_TEST_VALUE".to_string(),
+                            "from-env".to_string(),
+                        )])),
+                        ..Default::default()
+                    }),
+                    context(1),
+                )
+                .await
+                .unwrap();
+            let output = result.content[0].as_text().unwrap().text.clone();
+            let dir_name = temp_dir.path().file_name().unwrap().to_string_lossy();
+            assert!(output.contains(dir_name.as_ref()), "{output}");
+            assert!(output.contains("from-env"), "{output}");
+
+            let start = Instant::now();
+            let result = server
+                .shell(
+                    Parameters(ShellParams {
+                        command: "echo before-timeout; sleep 30".to_string(),
+                        timeout_secs: Some(1),
+                        ..Default::default()
+                    }),
+                    context(2),
+                )
+                .await;
+            let err = result.unwrap_err();
+            assert!(err.message.contains("timed out"), "{}", err.message);
+            assert!(err.message.contains("before-timeout"), "{}", err.message);
+            assert!(start.elapsed() < Duration::from_secs(10));
+
+            let result = server
+                .shell(
+                    Parameters(ShellParams {
+                        command: "ls".to_string(),
+                        cwd: Some(
+                            temp_dir
+                                .path()
+                                .join("missing")
+                                .to_string_lossy()
+                                .to_string(),
+                        ),
+                        ..Default::default()
+                    }),
+                    context(3),
+                )
+                .await;
+            assert_eq!(result.unwrap_err().code, ErrorCode::INVALID_PARAMS);
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_shell_background_process_lifecycle() {
+        run_shell_test(|| async {
+            let server = create_test_server();
+            let running_service = serve_directly(server.clone(), create_test_transport(), None);
+            let peer = running_service.peer().clone();
+
+            let result = server
+                .shell(
+                    Parameters(ShellParams {
+                        command: "while read line; do echo \"got $line\"; done".to_string(),
+                        background: true,
+                        ..Default::default()
+                    }),
+                    RequestContext {
+                        ct: Default::default(),
+                        id: NumberOrString::Number(1),
+                        meta: Default::default(),
+                        extensions: Default::default(),
+                        peer: peer.clone(),
+                    },
+                )
+                .await
+                .unwrap();
+            let started = result.content[0].as_text().unwrap().text.clone();
+            assert!(started.contains("bg-1"), "{started}");
+
+            let process_call = |action, input: Option<&str>| {
+                server.shell_process(Parameters(ShellProcessParams {
+                    action,
+                    handle: Some("bg-1".to_string()),
+                    input: input.map(str::to_string),
+                }))
+            };
+
+            process_call(ShellProcessAction::Input, Some("ping\n"))
+                .await
+                .unwrap();
+
+            let mut output = String::new();
+            for _ in 0..50 {
+                let result = process_call(ShellProcessAction::Output, None)
+                    .await
+                    .unwrap();
+                output.push_str(&result.content[0].as_text().unwrap().text);
+                if output.contains("got ping") {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            assert!(output.contains("got ping"), "{output}");
+            assert!(output.contains("running"), "{output}");
+
+            let killed = process_call(ShellProcessAction::Kill, None).await.unwrap();
+            assert!(killed.content[0].as_text().unwrap().text.contains("exited"));
+
+            let missing = process_call(ShellProcessAction::Output, None).await;
+            assert_eq!(missing.unwrap_err().code, ErrorCode::INVALID_PARAMS);
+
+            cleanup_test_service(running_service, peer);
+        });
+    }
 }
@@ -1,4 +1,9 @@
+mod search;
+mod store;
+
+use chrono::{Duration, Utc};
 use etcetera::{choose_app_strategy, AppStrategy};
+use goose::session_context::SESSION_ID_HEADER;
 use indoc::formatdoc;
 use rmcp::{
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
@@ -7,15 +12,18 @@ use rmcp::{
         ServerInfo,
     },
     schemars::JsonSchema,
-    tool, tool_handler, tool_router, ServerHandler,
+    service::RequestContext,
+    tool, tool_handler, tool_router, RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::HashMap,
-    fs,
-    io::{self, Read, Write},
-    path::PathBuf,
-};
+use std::{collections::HashMap, io, path::PathBuf, sync::Arc};
+use tokio::sync::OnceCell;
+
+use self::search::{Embedder, ProviderEmbedder, SearchCandidate};
+use self::store::{MemoryEntry, MemoryStore, MemoryUpdate};
+
+/// How many memories per scope are loaded into the instructions; the rest are found with search_memories
+const MAX_INSTRUCTION_MEMORIES: usize = 50;
 
 /// Parameters for the remember_memory tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -29,6 +37,9 @@ pub struct RememberMemoryParams {
     pub tags: Vec<String>,
     /// Whether to store globally or locally
     pub is_global: bool,
+    /// Optional number of days after which the memory is forgotten
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
 }
 
 /// Parameters for the retrieve_memories tool
@@ -54,12 +65,56 @@ pub struct RemoveMemoryCategoryParams {
 pub struct RemoveSpecificMemoryParams {
     /// The category containing the memory
     pub category: String,
-    /// The content of the memory to remove
+    /// The id of the memory to remove, or text contained in the memories to remove
     pub memory_content: String,
     /// Whether to remove from global or local storage
     pub is_global: bool,
 }
 
+/// Parameters for the search_memories tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchMemoriesParams {
+    /// Keywords or a question to find memories for; empty lists the most recent memories
+    pub query: String,
+    /// Only return memories that have all of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only search this category
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Only search global (true) or local (false) memories; both when omitted
+    #[serde(default)]
+    pub is_global: Option<bool>,
+    /// Maximum number of memories to return (default: 10)
+    #[serde(default = "default_search_limit")]
+    pub limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    10
+}
+
+/// Parameters for the update_memory tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateMemoryParams {
+    /// The category containing the memory
+    pub category: String,
+    /// The id of the memory to update
+    pub id: String,
+    /// Look for the memory in global (true) or local (false) storage; both when omitted
+    #[serde(default)]
+    pub is_global: Option<bool>,
+    /// New content for the memory
+    #[serde(default)]
+    pub data: Option<String>,
+    /// New tags for the memory, replacing the current ones
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Days from now until the memory is forgotten; 0 keeps it indefinitely
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
+}
+
 /// Memory MCP Server using official RMCP SDK
 #[derive(Clone)]
 pub struct MemoryServer {
@@ -67,6 +122,8 @@ pub struct MemoryServer {
     instructions: String,
     global_memory_dir: PathBuf,
     local_memory_dir: PathBuf,
+    /// Created from the configured embedding provider on first search
+    embedder: Arc<OnceCell<Option<Arc<dyn Embedder>>>>,
 }
 
 impl Default for MemoryServer {
@@ -82,10 +139,11 @@ impl MemoryServer {
              This extension allows storage and retrieval of categorized information with tagging support. It's designed to help
              manage important information across sessions in a systematic and organized manner.
              Capabilities:
-             1. Store information in categories with optional tags for context-based retrieval.
-             2. Search memories by content or specific tags to find relevant information.
-             3. List all available memory categories for easy navigation.
-             4. Remove entire categories of memories when they are no longer needed.
+             1. Store information in categories with optional tags and an optional expiry.
+             2. Search memories by keywords or specific tags to find relevant information.
+             3. Update memories by id when information changes.
+             4. List all available memory categories for easy navigation.
+             5. Remove entire categories of memories when they are no longer needed.
              When to call memory tools:
              - These are examples where the assistant should proactively call the memory tool because the user is providing recurring preferences, project details, or workflow habits that they may expect to be remembered.
              - Preferred Development Tools & Conventions
@@ -110,6 +168,7 @@ impl MemoryServer {
                   - Global storage (~/.config/goose/memory) for user-wide data.
                 - Use the remember_memory tool to store the information.
                   - `remember_memory(category, data, tags, is_global)`
+                  - Set `expires_in_days` for information that is only relevant for a while.
              Keywords that trigger memory tools:
              - "remember"
              - "forget"
@@ -146,6 +205,9 @@ impl MemoryServer {
              Assistant: "I'll store this in the 'github' category. Any specific tags to add? Suggestions: #comments #gh"
              Retrieving Memories:
              To access stored information, utilize the memory retrieval protocols:
+             - **Search by Keywords**:
+               - Finds the most relevant memories across categories without loading them all.
+               - Use: `search_memories(query="code formatting", tags=["formatting"])`
              - **Search by Category**:
                - Provides all memories within the specified context.
                - Use: `retrieve_memories(category="development", is_global=False)`
@@ -154,12 +216,15 @@ impl MemoryServer {
              - **Filter by Tags**:
                - Enables targeted retrieval based on specific tags.
                - Use: Provide tag filters to refine search.
+            To change a memory, use `update_memory(category, id, data, tags, expires_in_days)` with the category and id shown for it.
             To remove a memory, use the following protocol:
             - **Remove by Category**:
               - Removes all memories within the specified category.
               - Use: `remove_memory_category(category="development", is_global=False)`
               - Note: If you want to remove all local memories, use `remove_memory_category(category="*", is_global=False)`
               - Note: If you want to remove all global memories, use `remove_memory_category(category="*", is_global=True)`
+            - **Remove One Memory**:
+              - Use: `remove_specific_memory(category="development", memory_content="<id>", is_global=False)`
             The Protocol is:
              1. Confirm what kind of information the user seeks by category or keyword.
              2. Suggest categories or relevant tags based on the user's request.
@@ -168,7 +233,7 @@ impl MemoryServer {
              Example Interaction for Retrieving Information:
              User: "What configuration do we use for code formatting?"
              Assistant: "Let me check the 'development' category for any related memories. Searching using #formatting tag."
-             Assistant: *Executes retrieval: `retrieve_memories(category="development", is_global=False)`*
+             Assistant: *Executes search: `search_memories(query="code formatting", tags=["formatting"])`*
              Assistant: "We have 'black' configured for code formatting, specific to this project. Would you like further
              details?"
              Memory Overview:
@@ -201,6 +266,7 @@ impl MemoryServer {
             instructions: instructions.clone(),
             global_memory_dir,
             local_memory_dir,
+            embedder: Arc::new(OnceCell::new()),
         };
 
         let retrieved_global_memories = memory_router.retrieve_all(true);
@@ -219,27 +285,17 @@ impl MemoryServer {
         updated_instructions.push_str("\n\n");
         updated_instructions.push_str(&memories_follow_up_instructions);
 
-        if let Ok(global_memories) = retrieved_global_memories {
-            if !global_memories.is_empty() {
-                updated_instructions.push_str("\n\nGlobal Memories:\n");
-                for (category, memories) in global_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
-            }
-        }
-
-        if let Ok(local_memories) = retrieved_local_memories {
-            if !local_memories.is_empty() {
-                updated_instructions.push_str("\n\nLocal Memories:\n");
-                for (category, memories) in local_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
+        for (label, retrieved) in [
+            ("Global Memories", retrieved_global_memories),
+            ("Local Memories", retrieved_local_memories),
+        ] {
+            match retrieved {
+                Ok(memories) if !memories.is_empty() => {
+                    updated_instructions.push_str(&format!("\n\n{}:\n", label));
+                    updated_instructions.push_str(&format_recent_memories(memories));
                 }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to load {}: {}", label.to_lowercase(), e),
             }
         }
 
@@ -257,102 +313,69 @@ impl MemoryServer {
         &self.instructions
     }
 
-    fn get_memory_file(&self, category: &str, is_global: bool) -> PathBuf {
+    /// Rank search results with this embedder instead of the configured embedding provider
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Arc::new(OnceCell::new_with(Some(Some(embedder))));
+        self
+    }
+
+    async fn embedder(&self) -> Option<Arc<dyn Embedder>> {
+        self.embedder
+            .get_or_init(|| async {
+                match ProviderEmbedder::from_config().await {
+                    Ok(embedder) => embedder.map(|e| Arc::new(e) as Arc<dyn Embedder>),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Memory embeddings unavailable, using keyword search: {}",
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .await
+            .clone()
+    }
+
+    fn store(&self, is_global: bool) -> MemoryStore {
         // Defaults to local memory if no is_global flag is provided
         let base_dir = if is_global {
             &self.global_memory_dir
         } else {
             &self.local_memory_dir
         };
-        base_dir.join(format!("{}.txt", category))
+        MemoryStore::new(base_dir.clone())
     }
 
-    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
-        let base_dir = if is_global {
-            &self.global_memory_dir
-        } else {
-            &self.local_memory_dir
-        };
-        let mut memories = HashMap::new();
-        if base_dir.exists() {
-            for entry in fs::read_dir(base_dir)? {
-                let entry = entry?;
-                if entry.file_type()?.is_file() {
-                    let category = entry.file_name().to_string_lossy().replace(".txt", "");
-                    let category_memories = self.retrieve(&category, is_global)?;
-                    memories.insert(
-                        category,
-                        category_memories.into_iter().flat_map(|(_, v)| v).collect(),
-                    );
-                }
-            }
+    /// The scopes to search when the caller didn't pick one
+    fn scopes(is_global: Option<bool>) -> Vec<bool> {
+        match is_global {
+            Some(is_global) => vec![is_global],
+            None => vec![false, true],
         }
-        Ok(memories)
+    }
+
+    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<MemoryEntry>>> {
+        self.store(is_global).load_all()
     }
 
     pub fn remember(
         &self,
-        _context: &str,
         category: &str,
         data: &str,
         tags: &[&str],
         is_global: bool,
-    ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-
-        if let Some(parent) = memory_file_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        let mut file = fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&memory_file_path)?;
-        if !tags.is_empty() {
-            writeln!(file, "# {}", tags.join(" "))?;
-        }
-        writeln!(file, "{}\n", data)?;
-
-        Ok(())
+        expires_in_days: Option<u32>,
+        source_session: Option<String>,
+    ) -> io::Result<MemoryEntry> {
+        let tags = tags.iter().map(|tag| tag.to_string()).collect();
+        let expires_at = expires_in_days.map(|days| Utc::now() + Duration::days(days.into()));
+        self.store(is_global)
+            .add(category, data, tags, expires_at, source_session)
     }
 
-    pub fn retrieve(
-        &self,
-        category: &str,
-        is_global: bool,
-    ) -> io::Result<HashMap<String, Vec<String>>> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(HashMap::new());
-        }
-
-        let mut file = fs::File::open(memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-
-        let mut memories = HashMap::new();
-        for entry in content.split("\n\n") {
-            let mut lines = entry.lines();
-            if let Some(first_line) = lines.next() {
-                if let Some(stripped) = first_line.strip_prefix('#') {
-                    let tags = stripped
-                        .split_whitespace()
-                        .map(String::from)
-                        .collect::<Vec<_>>();
-                    memories.insert(tags.join(" "), lines.map(String::from).collect());
-                } else {
-                    let entry_data: Vec<String> = std::iter::once(first_line.to_string())
-                        .chain(lines.map(String::from))
-                        .collect();
-                    memories
-                        .entry("untagged".to_string())
-                        .or_insert_with(Vec::new)
-                        .extend(entry_data);
-                }
-            }
-        }
-
-        Ok(memories)
+    pub fn retrieve(&self, category: &str, is_global: bool) -> io::Result<Vec<MemoryEntry>> {
+        self.store(is_global).load(category)
     }
 
     pub fn remove_specific_memory_internal(
@@ -360,57 +383,103 @@ impl MemoryServer {
         category: &str,
         memory_content: &str,
         is_global: bool,
-    ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(());
-        }
-
-        let mut file = fs::File::open(&memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-
-        let memories: Vec<&str> = content.split("\n\n").collect();
-        let new_content: Vec<String> = memories
-            .into_iter()
-            .filter(|entry| !entry.contains(memory_content))
-            .map(|s| s.to_string())
-            .collect();
+    ) -> io::Result<usize> {
+        self.store(is_global)
+            .remove_matching(category, memory_content)
+    }
 
-        fs::write(memory_file_path, new_content.join("\n\n"))?;
+    pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
+        self.store(is_global).clear_category(category)
+    }
 
-        Ok(())
+    pub fn clear_all_global_or_local_memories(&self, is_global: bool) -> io::Result<()> {
+        self.store(is_global).clear_all()
     }
 
-    pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if memory_file_path.exists() {
-            fs::remove_file(memory_file_path)?;
+    /// Find the memories most relevant to a query, across categories and scopes
+    pub async fn search(
+        &self,
+        params: &SearchMemoriesParams,
+    ) -> io::Result<Vec<search::SearchHit>> {
+        let mut candidates = Vec::new();
+        for is_global in Self::scopes(params.is_global) {
+            let store = self.store(is_global);
+            let categories = match &params.category {
+                Some(category) => vec![category.clone()],
+                None => store.categories()?,
+            };
+            for category in categories {
+                for entry in store.load(&category)? {
+                    if params.tags.iter().all(|tag| entry.has_tag(tag)) {
+                        candidates.push(SearchCandidate {
+                            category: category.clone(),
+                            is_global,
+                            entry,
+                        });
+                    }
+                }
+            }
         }
 
-        Ok(())
+        let embedder = match self.embedder().await {
+            Some(embedder)
+                if !search::terms(&params.query).is_empty() && !candidates.is_empty() =>
+            {
+                embedder
+            }
+            _ => return Ok(search::rank(candidates, &params.query, None, params.limit)),
+        };
+        let query_embedding =
+            match search::embed(&mut candidates, &params.query, embedder.as_ref()).await {
+                Ok((query_embedding, embedded)) => {
+                    self.cache_embeddings(&candidates, &embedded);
+                    Some(query_embedding)
+                }
+                Err(e) => {
+                    tracing::warn!("Memory embeddings failed, using keyword search: {}", e);
+                    None
+                }
+            };
+        Ok(search::rank(
+            candidates,
+            &params.query,
+            query_embedding.as_deref(),
+            params.limit,
+        ))
     }
 
-    pub fn clear_all_global_or_local_memories(&self, is_global: bool) -> io::Result<()> {
-        let base_dir = if is_global {
-            &self.global_memory_dir
-        } else {
-            &self.local_memory_dir
-        };
-        if base_dir.exists() {
-            fs::remove_dir_all(base_dir)?;
+    /// Store new embeddings with their memories so later searches reuse them
+    fn cache_embeddings(&self, candidates: &[SearchCandidate], embedded: &[usize]) {
+        let mut by_category: HashMap<(bool, &str), Vec<_>> = HashMap::new();
+        for &index in embedded {
+            let candidate = &candidates[index];
+            if let Some(embedding) = &candidate.entry.embedding {
+                by_category
+                    .entry((candidate.is_global, candidate.category.as_str()))
+                    .or_default()
+                    .push((
+                        candidate.entry.id.clone(),
+                        candidate.entry.content.clone(),
+                        embedding.clone(),
+                    ));
+            }
+        }
+        for ((is_global, category), embeddings) in by_category {
+            if let Err(e) = self.store(is_global).set_embeddings(category, &embeddings) {
+                tracing::warn!("Failed to cache memory embeddings for {}: {}", category, e);
+            }
         }
-        Ok(())
     }
 
     /// Stores a memory with optional tags in a specified category
     #[tool(
         name = "remember_memory",
-        description = "Stores a memory with optional tags in a specified category"
+        description = "Stores a memory with optional tags and expiry in a specified category"
     )]
     pub async fn remember_memory(
         &self,
         params: Parameters<RememberMemoryParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let params = params.0;
 
@@ -423,18 +492,20 @@ impl MemoryServer {
         }
 
         let tags: Vec<&str> = params.tags.iter().map(|s| s.as_str()).collect();
-        self.remember(
-            "context",
-            &params.category,
-            &params.data,
-            &tags,
-            params.is_global,
-        )
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        let entry = self
+            .remember(
+                &params.category,
+                &params.data,
+                &tags,
+                params.is_global,
+                params.expires_in_days,
+                session_id(&context),
+            )
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
 
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Stored memory in category: {}",
-            params.category
+            "Stored memory {} in category: {}",
+            entry.id, params.category
         ))]))
     }
 
@@ -453,13 +524,127 @@ impl MemoryServer {
             self.retrieve_all(params.is_global)
         } else {
             self.retrieve(&params.category, params.is_global)
+                .map(|memories| HashMap::from([(params.category.clone(), memories)]))
         }
         .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Retrieved memories: {:?}",
-            memories
-        ))]))
+        let mut categories: Vec<_> = memories.into_iter().collect();
+        categories.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut output = String::new();
+        for (category, memories) in categories {
+            if memories.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("Category: {}\n", category));
+            for memory in &memories {
+                output.push_str(&format_memory(memory));
+            }
+            output.push('\n');
+        }
+        if output.is_empty() {
+            output = "No memories found".to_string();
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    /// Searches memories by keywords and tags, returning the most relevant ones
+    #[tool(
+        name = "search_memories",
+        description = "Searches memories by keywords and tags across categories, returning the most relevant ones with their ids"
+    )]
+    pub async fn search_memories(
+        &self,
+        params: Parameters<SearchMemoriesParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        let hits = self
+            .search(&params)
+            .await
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        if hits.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No memories found for: {}",
+                params.query
+            ))]));
+        }
+
+        let mut output = format!("Found {} memories:\n", hits.len());
+        for hit in &hits {
+            let candidate = &hit.candidate;
+            output.push_str(&format!(
+                "{} [{}] ",
+                candidate.category,
+                if candidate.is_global {
+                    "global"
+                } else {
+                    "local"
+                }
+            ));
+            output.push_str(format_memory(&candidate.entry).trim_start_matches("- "));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    /// Updates the content, tags or expiry of a memory by category and id
+    #[tool(
+        name = "update_memory",
+        description = "Updates the content, tags or expiry of a memory by category and id"
+    )]
+    pub async fn update_memory(
+        &self,
+        params: Parameters<UpdateMemoryParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = params.0;
+
+        if params.data.as_deref().is_some_and(str::is_empty) {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Data must not be empty when updating a memory".to_string(),
+                None,
+            ));
+        }
+        if params.data.is_none() && params.tags.is_none() && params.expires_in_days.is_none() {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Provide data, tags or expires_in_days to update".to_string(),
+                None,
+            ));
+        }
+
+        let update = || MemoryUpdate {
+            content: params.data.clone(),
+            tags: params.tags.clone(),
+            expires_at: params
+                .expires_in_days
+                .map(|days| (days > 0).then(|| Utc::now() + Duration::days(days.into()))),
+        };
+
+        for is_global in Self::scopes(params.is_global) {
+            let updated = self
+                .store(is_global)
+                .update(&params.category, &params.id, update())
+                .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+            if let Some(entry) = updated {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Updated memory in category {}:\n{}",
+                    params.category,
+                    format_memory(&entry)
+                ))]));
+            }
+        }
+
+        Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!(
+                "No memory with id '{}' in category '{}'",
+                params.id, params.category
+            ),
+            None,
+        ))
     }
 
     /// Removes all memories within a specified category
@@ -492,7 +677,7 @@ impl MemoryServer {
     /// Removes a specific memory within a specified category
     #[tool(
         name = "remove_specific_memory",
-        description = "Removes a specific memory within a specified category"
+        description = "Removes a specific memory, by id or content, within a specified category"
     )]
     pub async fn remove_specific_memory(
         &self,
@@ -500,20 +685,84 @@ impl MemoryServer {
     ) -> Result<CallToolResult, ErrorData> {
         let params = params.0;
 
-        self.remove_specific_memory_internal(
-            &params.category,
-            &params.memory_content,
-            params.is_global,
-        )
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        let removed = self
+            .remove_specific_memory_internal(
+                &params.category,
+                &params.memory_content,
+                params.is_global,
+            )
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
 
         Ok(CallToolResult::success(vec![Content::text(format!(
-            "Removed specific memory from category: {}",
-            params.category
+            "Removed {} memories from category: {}",
+            removed, params.category
         ))]))
     }
 }
 
+/// The goose session a request was made from, if the client sent one
+fn session_id(context: &RequestContext<RoleServer>) -> Option<String> {
+    context
+        .meta
+        .0
+        .get(SESSION_ID_HEADER)
+        .and_then(|value| value.as_str())
+        .map(String::from)
+}
+
+/// One memory as a list item, with its id so it can be updated or removed
+fn format_memory(memory: &MemoryEntry) -> String {
+    let mut line = format!("- [{}] {}", memory.id, memory.content.replace('\n', "\n  "));
+    if !memory.tags.is_empty() {
+        let tags: Vec<String> = memory
+            .tags
+            .iter()
+            .map(|tag| format!("#{}", tag.trim_start_matches('#')))
+            .collect();
+        line.push_str(&format!(" {}", tags.join(" ")));
+    }
+    if let Some(expires_at) = memory.expires_at {
+        line.push_str(&format!(" (expires {})", expires_at.format("%Y-%m-%d")));
+    }
+    line.push('\n');
+    line
+}
+
+/// The most recently updated memories of a scope by category, noting how many were left out
+fn format_recent_memories(memories: HashMap<String, Vec<MemoryEntry>>) -> String {
+    let total: usize = memories.values().map(Vec::len).sum();
+    let mut recent: Vec<(&String, &MemoryEntry)> = memories
+        .iter()
+        .flat_map(|(category, entries)| entries.iter().map(move |entry| (category, entry)))
+        .collect();
+    recent.sort_by(|a, b| b.1.updated_at.cmp(&a.1.updated_at));
+    recent.truncate(MAX_INSTRUCTION_MEMORIES);
+
+    let mut by_category: Vec<(&String, Vec<&MemoryEntry>)> = Vec::new();
+    for (category, entry) in recent {
+        match by_category.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, entries)) => entries.push(entry),
+            None => by_category.push((category, vec![entry])),
+        }
+    }
+    by_category.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut output = String::new();
+    for (category, entries) in by_category {
+        output.push_str(&format!("\nCategory: {}\n", category));
+        for entry in entries {
+            output.push_str(&format_memory(entry));
+        }
+    }
+    if total > MAX_INSTRUCTION_MEMORIES {
+        output.push_str(&format!(
+            "\n{} older memories are not shown; use search_memories to find them.\n",
+            total - MAX_INSTRUCTION_MEMORIES
+        ));
+    }
+    output
+}
+
 #[tool_handler(router = self.tool_router)]
 impl ServerHandler for MemoryServer {
     fn get_info(&self) -> ServerInfo {
@@ -537,8 +786,44 @@ impl ServerHandler for MemoryServer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use async_trait::async_trait;
+    use std::fs;
+    use std::sync::Mutex;
     use tempfile::tempdir;
 
+    /// Embeds text by topic, so memories about cars are close whatever words they use
+    #[derive(Default)]
+    struct TopicEmbedder {
+        calls: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Embedder for TopicEmbedder {
+        fn model(&self) -> &str {
+            "topics"
+        }
+
+        async fn embed(&self, texts: Vec<String>) -> anyhow::Result<Vec<Vec<f32>>> {
+            self.calls.lock().unwrap().push(texts.clone());
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let is_about = |words: &[&str]| {
+                        if words.iter().any(|word| text.contains(word)) {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    };
+                    vec![
+                        is_about(&["car", "vehicle", "automobile"]),
+                        is_about(&["pizza", "lunch", "food"]),
+                    ]
+                })
+                .collect())
+        }
+    }
+
     #[test]
     fn test_lazy_directory_creation() {
         let temp_dir = tempdir().unwrap();
@@ -549,19 +834,14 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            embedder: Arc::new(OnceCell::new_with(Some(None))),
         };
 
         assert!(!router.global_memory_dir.exists());
         assert!(!router.local_memory_dir.exists());
 
         router
-            .remember(
-                "test_context",
-                "test_category",
-                "test_data",
-                &["tag1"],
-                false,
-            )
+            .remember("test_category", "test_data", &["tag1"], false, None, None)
             .unwrap();
 
         assert!(router.local_memory_dir.exists());
@@ -569,11 +849,12 @@ mod tests {
 
         router
             .remember(
-                "test_context",
                 "global_category",
                 "global_data",
                 &["global_tag"],
                 true,
+                None,
+                None,
             )
             .unwrap();
 
@@ -590,6 +871,7 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            embedder: Arc::new(OnceCell::new_with(Some(None))),
         };
 
         assert!(router.clear_all_global_or_local_memories(false).is_ok());
@@ -606,26 +888,25 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            embedder: Arc::new(OnceCell::new_with(Some(None))),
         };
 
         router
             .remember(
-                "context",
                 "test_category",
                 "test_data_content",
                 &["test_tag"],
                 false,
+                None,
+                None,
             )
             .unwrap();
 
         let memories = router.retrieve("test_category", false).unwrap();
         assert!(!memories.is_empty());
 
-        let has_content = memories.values().any(|v| {
-            v.iter()
-                .any(|content| content.contains("test_data_content"))
-        });
-        assert!(has_content);
+        assert_eq!(memories[0].content, "test_data_content");
+        assert_eq!(memories[0].tags, vec!["test_tag"]);
 
         router.clear_memory("test_category", false).unwrap();
 
@@ -643,16 +924,17 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            embedder: Arc::new(OnceCell::new_with(Some(None))),
         };
 
         assert!(!router.local_memory_dir.exists());
 
         router
-            .remember("context", "category", "data", &[], false)
+            .remember("category", "data", &[], false, None, None)
             .unwrap();
 
         assert!(router.local_memory_dir.exists());
-        assert!(router.local_memory_dir.join("category.txt").exists());
+        assert!(router.local_memory_dir.join("category.json").exists());
     }
 
     #[test]
@@ -665,31 +947,285 @@ mod tests {
             instructions: String::new(),
             global_memory_dir: memory_base.join("global"),
             local_memory_dir: memory_base.join("local"),
+            embedder: Arc::new(OnceCell::new_with(Some(None))),
         };
 
         router
-            .remember("context", "category", "keep_this", &[], false)
+            .remember("category", "keep_this", &[], false, None, None)
             .unwrap();
         router
-            .remember("context", "category", "remove_this", &[], false)
+            .remember("category", "remove_this", &[], false, None, None)
             .unwrap();
 
         let memories = router.retrieve("category", false).unwrap();
-        assert_eq!(memories.len(), 1);
+        assert_eq!(memories.len(), 2);
 
         router
             .remove_specific_memory_internal("category", "remove_this", false)
             .unwrap();
 
         let memories_after = router.retrieve("category", false).unwrap();
-        let has_removed = memories_after
-            .values()
-            .any(|v| v.iter().any(|content| content.contains("remove_this")));
-        assert!(!has_removed);
-
-        let has_kept = memories_after
-            .values()
-            .any(|v| v.iter().any(|content| content.contains("keep_this")));
-        assert!(has_kept);
+        assert_eq!(memories_after.len(), 1);
+        assert_eq!(memories_after[0].content, "keep_this");
+
+        // Memories can also be removed by id
+        let removed = router
+            .remove_specific_memory_internal("category", &memories_after[0].id, false)
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(router.retrieve("category", false).unwrap().is_empty());
+    }
+
+    fn test_server(base: &std::path::Path) -> MemoryServer {
+        MemoryServer {
+            tool_router: ToolRouter::new(),
+            instructions: String::new(),
+            global_memory_dir: base.join("global"),
+            local_memory_dir: base.join("local"),
+            embedder: Arc::new(OnceCell::new_with(Some(None))),
+        }
+    }
+
+    fn search_params(query: &str, tags: &[&str]) -> SearchMemoriesParams {
+        SearchMemoriesParams {
+            query: query.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            category: None,
+            is_global: None,
+            limit: 10,
+        }
+    }
+
+    #[test]
+    fn test_migrate_legacy_category_file() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_server(temp_dir.path());
+        fs::create_dir_all(&router.local_memory_dir).unwrap();
+        fs::write(
+            router.local_memory_dir.join("development.txt"),
+            "# formatting tools\nwe use black for formatting\n\nrun tests with pytest -q\n\n",
+        )
+        .unwrap();
+
+        let memories = router.retrieve("development", false).unwrap();
+        assert_eq!(memories.len(), 2);
+        assert_eq!(memories[0].content, "we use black for formatting");
+        assert_eq!(memories[0].tags, vec!["formatting", "tools"]);
+        assert_eq!(memories[1].content, "run tests with pytest -q");
+        assert!(memories[1].tags.is_empty());
+        assert_ne!(memories[0].id, memories[1].id);
+
+        assert!(!router.local_memory_dir.join("development.txt").exists());
+        assert!(router.local_memory_dir.join("development.json").exists());
+        assert_eq!(router.retrieve("development", false).unwrap(), memories);
+    }
+
+    #[tokio::test]
+    async fn test_search_memories() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_server(temp_dir.path());
+        router
+            .remember(
+                "development",
+                "we use black for formatting",
+                &["python"],
+                false,
+                None,
+                None,
+            )
+            .unwrap();
+        router
+            .remember(
+                "development",
+                "cargo fmt formats rust code",
+                &["rust"],
+                false,
+                None,
+                None,
+            )
+            .unwrap();
+        router
+            .remember("personal", "my name is Sam", &[], true, None, None)
+            .unwrap();
+
+        let hits = router
+            .search(&search_params("formatting", &[]))
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].candidate.entry.content,
+            "we use black for formatting"
+        );
+
+        let hits = router.search(&search_params("format", &[])).await.unwrap();
+        assert_eq!(hits.len(), 2);
+
+        let hits = router
+            .search(&search_params("format", &["rust"]))
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].candidate.category, "development");
+
+        // An empty query lists the most recent memories of both scopes
+        let hits = router.search(&search_params("", &[])).await.unwrap();
+        assert_eq!(hits.len(), 3);
+        assert!(hits.iter().any(|hit| hit.candidate.is_global));
+
+        let hits = router
+            .search(&search_params("kubernetes", &[]))
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_by_cached_embeddings() {
+        let temp_dir = tempdir().unwrap();
+        let embedder = Arc::new(TopicEmbedder::default());
+        let router = test_server(temp_dir.path()).with_embedder(embedder.clone());
+        let automobile = router
+            .remember("personal", "my automobile is blue", &[], false, None, None)
+            .unwrap();
+        router
+            .remember("personal", "we had pizza", &[], false, None, None)
+            .unwrap();
+
+        // No memory shares a word with the query, but one is about the same thing
+        let hits = router.search(&search_params("vehicle", &[])).await.unwrap();
+        assert_eq!(hits[0].candidate.entry.id, automobile.id);
+        assert_eq!(embedder.calls.lock().unwrap()[0].len(), 3);
+        let stored = router.retrieve("personal", false).unwrap();
+        assert!(stored.iter().all(|memory| memory
+            .embedding
+            .as_ref()
+            .is_some_and(|embedding| embedding.model == "topics")));
+
+        // Later searches only embed the query
+        let hits = router.search(&search_params("food", &[])).await.unwrap();
+        assert_eq!(hits[0].candidate.entry.content, "we had pizza");
+        assert_eq!(embedder.calls.lock().unwrap()[1], vec!["food".to_string()]);
+
+        // Changing a memory drops its embedding, so it is embedded again
+        router
+            .store(false)
+            .update(
+                "personal",
+                &automobile.id,
+                MemoryUpdate {
+                    content: Some("my car is red".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        router.search(&search_params("vehicle", &[])).await.unwrap();
+        assert_eq!(
+            embedder.calls.lock().unwrap()[2],
+            vec!["vehicle".to_string(), "my car is red".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_memory() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_server(temp_dir.path());
+        let entry = router
+            .remember(
+                "development",
+                "we use black",
+                &["python"],
+                false,
+                Some(3),
+                None,
+            )
+            .unwrap();
+        assert!(entry.expires_at.is_some());
+
+        router
+            .update_memory(Parameters(UpdateMemoryParams {
+                category: "development".to_string(),
+                id: entry.id.clone(),
+                is_global: None,
+                data: Some("we use ruff".to_string()),
+                tags: None,
+                expires_in_days: Some(0),
+            }))
+            .await
+            .unwrap();
+
+        let memories = router.retrieve("development", false).unwrap();
+        assert_eq!(memories[0].id, entry.id);
+        assert_eq!(memories[0].content, "we use ruff");
+        assert_eq!(memories[0].tags, vec!["python"]);
+        assert_eq!(memories[0].expires_at, None);
+        assert_eq!(memories[0].created_at, entry.created_at);
+        assert!(memories[0].updated_at >= entry.updated_at);
+
+        let missing = router
+            .update_memory(Parameters(UpdateMemoryParams {
+                category: "development".to_string(),
+                id: "missing".to_string(),
+                is_global: None,
+                data: Some("x".to_string()),
+                tags: None,
+                expires_in_days: None,
+            }))
+            .await;
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_expired_memories_are_dropped() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_server(temp_dir.path());
+        let entry = router
+            .remember(
+                "todo",
+                "release on friday",
+                &[],
+                false,
+                None,
+                Some("session-1".into()),
+            )
+            .unwrap();
+        assert_eq!(entry.source_session.as_deref(), Some("session-1"));
+
+        router
+            .store(false)
+            .update(
+                "todo",
+                &entry.id,
+                MemoryUpdate {
+                    expires_at: Some(Some(Utc::now() - Duration::seconds(1))),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert!(router.retrieve("todo", false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_instructions_show_recent_memories() {
+        let mut memories = HashMap::new();
+        let entries: Vec<MemoryEntry> = (0..MAX_INSTRUCTION_MEMORIES + 5)
+            .map(|i| MemoryEntry {
+                id: format!("{:08x}", i),
+                content: format!("memory {}", i),
+                tags: vec![],
+                created_at: Utc::now(),
+                updated_at: Utc::now() + Duration::seconds(i as i64),
+                expires_at: None,
+                source_session: None,
+                embedding: None,
+            })
+            .collect();
+        memories.insert("notes".to_string(), entries);
+
+        let output = format_recent_memories(memories);
+        assert!(output.contains(&format!("memory {}", MAX_INSTRUCTION_MEMORIES + 4)));
+        assert!(!output.contains("memory 4\n"));
+        assert!(output.contains("5 older memories are not shown"));
     }
 }
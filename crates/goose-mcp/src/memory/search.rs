@@ -0,0 +1,201 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use goose::providers::base::Provider;
+use goose::session::embedding_index::configured_embedding_provider;
+use std::sync::Arc;
+
+use super::store::{MemoryEmbedding, MemoryEntry};
+
+/// Weight of embedding similarity against keyword matches when both are available
+const EMBEDDING_WEIGHT: f32 = 0.6;
+
+/// A memory considered by a search, with where it is stored
+#[derive(Debug, Clone)]
+pub struct SearchCandidate {
+    pub category: String,
+    pub is_global: bool,
+    pub entry: MemoryEntry,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub candidate: SearchCandidate,
+    pub score: f32,
+}
+
+/// Lowercased words of a query, ignoring punctuation
+pub fn terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// How well a memory matches the query terms. Tag and whole-word matches count more
+/// than matches inside a longer word.
+pub fn keyword_score(terms: &[String], entry: &MemoryEntry) -> f32 {
+    let content = entry.content.to_lowercase();
+    let words = self::terms(&content);
+    terms
+        .iter()
+        .map(|term| {
+            let tag_score = if entry.has_tag(term) { 2.0 } else { 0.0 };
+            let word_score = words.iter().filter(|word| *word == term).count() as f32;
+            let partial_score = if word_score == 0.0 && content.contains(term.as_str()) {
+                0.5
+            } else {
+                0.0
+            };
+            tag_score + word_score + partial_score
+        })
+        .sum()
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Embed the query, and the memories that have no embedding from the embedder's
+/// model yet, in one batch. New embeddings are set on the candidates, and their
+/// indices returned along with the query embedding.
+pub async fn embed(
+    candidates: &mut [SearchCandidate],
+    query: &str,
+    embedder: &dyn Embedder,
+) -> Result<(Vec<f32>, Vec<usize>)> {
+    let missing: Vec<usize> = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| {
+            candidate
+                .entry
+                .embedding
+                .as_ref()
+                .is_none_or(|embedding| embedding.model != embedder.model())
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    let texts = std::iter::once(query.to_string())
+        .chain(
+            missing
+                .iter()
+                .map(|&index| candidates[index].entry.content.clone()),
+        )
+        .collect();
+    let mut vectors = embedder.embed(texts).await?;
+    if vectors.len() != missing.len() + 1 {
+        return Err(anyhow!(
+            "Embedding provider returned {} embeddings for {} texts",
+            vectors.len(),
+            missing.len() + 1
+        ));
+    }
+
+    let query_vector = vectors.remove(0);
+    for (&index, vector) in missing.iter().zip(vectors) {
+        candidates[index].entry.embedding = Some(MemoryEmbedding {
+            model: embedder.model().to_string(),
+            vector,
+        });
+    }
+    Ok((query_vector, missing))
+}
+
+/// Rank memories for a query, best first. Without a query embedding only memories
+/// that match a keyword are returned; an empty query returns the most recently updated.
+pub fn rank(
+    candidates: Vec<SearchCandidate>,
+    query: &str,
+    query_embedding: Option<&[f32]>,
+    limit: usize,
+) -> Vec<SearchHit> {
+    let terms = terms(query);
+    let keyword_scores: Vec<f32> = candidates
+        .iter()
+        .map(|candidate| keyword_score(&terms, &candidate.entry))
+        .collect();
+    let max_keyword_score = keyword_scores.iter().copied().fold(0.0, f32::max);
+
+    let mut hits: Vec<SearchHit> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            let keyword = if max_keyword_score > 0.0 {
+                keyword_scores[index] / max_keyword_score
+            } else {
+                0.0
+            };
+            let score = match query_embedding {
+                Some(query_embedding) => {
+                    let similarity = candidate
+                        .entry
+                        .embedding
+                        .as_ref()
+                        .map(|embedding| cosine_similarity(query_embedding, &embedding.vector))
+                        .unwrap_or(0.0);
+                    EMBEDDING_WEIGHT * similarity + (1.0 - EMBEDDING_WEIGHT) * keyword
+                }
+                None if terms.is_empty() => 0.0,
+                None if keyword > 0.0 => keyword,
+                None => return None,
+            };
+            Some(SearchHit { candidate, score })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then_with(|| {
+            b.candidate
+                .entry
+                .updated_at
+                .cmp(&a.candidate.entry.updated_at)
+        })
+    });
+    hits.truncate(limit);
+    hits
+}
+
+/// Source of embeddings, used to rank memories by meaning rather than by shared words
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// The embedding model; memories embedded with another model are embedded again
+    fn model(&self) -> &str;
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embeddings from the provider configured with GOOSE_EMBEDDING_PROVIDER and
+/// GOOSE_EMBEDDING_MODEL, the same one that indexes session history
+pub struct ProviderEmbedder {
+    provider: Arc<dyn Provider>,
+    model: String,
+}
+
+impl ProviderEmbedder {
+    /// Returns `None` if no embedding provider is configured
+    pub async fn from_config() -> Result<Option<Self>> {
+        Ok(configured_embedding_provider()
+            .await?
+            .map(|(provider, model)| Self { provider, model }))
+    }
+}
+
+#[async_trait]
+impl Embedder for ProviderEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(self.provider.create_embeddings(texts).await?)
+    }
+}
@@ -0,0 +1,372 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+const STORE_VERSION: u32 = 1;
+const CATEGORY_EXTENSION: &str = "json";
+const LEGACY_EXTENSION: &str = "txt";
+
+/// A single remembered fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub id: String,
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The goose session the memory was stored from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_session: Option<String>,
+    /// Embedding of the content, kept so searches only embed new or changed memories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<MemoryEmbedding>,
+}
+
+/// An embedding vector and the model that produced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEmbedding {
+    pub model: String,
+    /// Stored as base64 of the little-endian values to keep category files compact
+    #[serde(
+        serialize_with = "serialize_vector",
+        deserialize_with = "deserialize_vector"
+    )]
+    pub vector: Vec<f32>,
+}
+
+fn serialize_vector<S: Serializer>(vector: &[f32], serializer: S) -> Result<S::Ok, S::Error> {
+    let bytes: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+    serializer.serialize_str(&STANDARD.encode(bytes))
+}
+
+fn deserialize_vector<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f32>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    let bytes = STANDARD.decode(encoded).map_err(serde::de::Error::custom)?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+impl MemoryEntry {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim_start_matches('#');
+        self.tags
+            .iter()
+            .any(|t| t.trim_start_matches('#').eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Changes to apply to an existing memory; unset fields are kept
+#[derive(Debug, Default)]
+pub struct MemoryUpdate {
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// `Some(None)` removes the expiry
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CategoryFile {
+    version: u32,
+    memories: Vec<MemoryEntry>,
+}
+
+/// The memories of one scope (global or local), one JSON file per category.
+/// Category files from older versions, which held free-text entries, are
+/// migrated the first time they are read.
+pub struct MemoryStore {
+    dir: PathBuf,
+}
+
+impl MemoryStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn categories(&self) -> io::Result<Vec<String>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut categories = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let extension = path.extension().and_then(|e| e.to_str());
+            if extension != Some(CATEGORY_EXTENSION) && extension != Some(LEGACY_EXTENSION) {
+                continue;
+            }
+            if let Some(category) = path.file_stem().and_then(|s| s.to_str()) {
+                categories.push(category.to_string());
+            }
+        }
+        categories.sort();
+        categories.dedup();
+        Ok(categories)
+    }
+
+    /// All memories of a category that have not expired, oldest first
+    pub fn load(&self, category: &str) -> io::Result<Vec<MemoryEntry>> {
+        self.migrate_legacy(category)?;
+
+        let mut memories = self.read_category(category)?;
+        let now = Utc::now();
+        let count = memories.len();
+        memories.retain(|memory| !memory.is_expired(now));
+        if memories.len() != count {
+            self.write_category(category, &memories)?;
+        }
+        Ok(memories)
+    }
+
+    pub fn load_all(&self) -> io::Result<HashMap<String, Vec<MemoryEntry>>> {
+        let mut all = HashMap::new();
+        for category in self.categories()? {
+            let memories = self.load(&category)?;
+            if !memories.is_empty() {
+                all.insert(category, memories);
+            }
+        }
+        Ok(all)
+    }
+
+    pub fn add(
+        &self,
+        category: &str,
+        content: &str,
+        tags: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+        source_session: Option<String>,
+    ) -> io::Result<MemoryEntry> {
+        let mut memories = self.load(category)?;
+        let now = Utc::now();
+        let entry = MemoryEntry {
+            id: unique_id(&memories, category, content, now),
+            content: content.to_string(),
+            tags,
+            created_at: now,
+            updated_at: now,
+            expires_at,
+            source_session,
+            embedding: None,
+        };
+        memories.push(entry.clone());
+        self.write_category(category, &memories)?;
+        Ok(entry)
+    }
+
+    /// Apply an update to the memory with the given id in a category, returning
+    /// the updated memory, or `None` if the category has no memory with that id
+    pub fn update(
+        &self,
+        category: &str,
+        id: &str,
+        update: MemoryUpdate,
+    ) -> io::Result<Option<MemoryEntry>> {
+        let mut memories = self.load(category)?;
+        let Some(memory) = memories.iter_mut().find(|m| m.id == id) else {
+            return Ok(None);
+        };
+
+        if let Some(content) = update.content {
+            if content != memory.content {
+                memory.embedding = None;
+            }
+            memory.content = content;
+        }
+        if let Some(tags) = update.tags {
+            memory.tags = tags;
+        }
+        if let Some(expires_at) = update.expires_at {
+            memory.expires_at = expires_at;
+        }
+        memory.updated_at = Utc::now();
+        let updated = memory.clone();
+
+        self.write_category(category, &memories)?;
+        Ok(Some(updated))
+    }
+
+    /// Keep embeddings computed for memories of a category, given as
+    /// `(id, embedded content, embedding)`. Memories whose content changed since
+    /// it was embedded are skipped.
+    pub fn set_embeddings(
+        &self,
+        category: &str,
+        embeddings: &[(String, String, MemoryEmbedding)],
+    ) -> io::Result<()> {
+        let mut memories = self.load(category)?;
+        let mut changed = false;
+        for memory in memories.iter_mut() {
+            if let Some((_, _, embedding)) = embeddings
+                .iter()
+                .find(|(id, content, _)| *id == memory.id && *content == memory.content)
+            {
+                memory.embedding = Some(embedding.clone());
+                changed = true;
+            }
+        }
+        if changed {
+            self.write_category(category, &memories)?;
+        }
+        Ok(())
+    }
+
+    /// Remove memories whose id is `selector` or whose content contains it,
+    /// returning how many were removed
+    pub fn remove_matching(&self, category: &str, selector: &str) -> io::Result<usize> {
+        let mut memories = self.load(category)?;
+        let count = memories.len();
+        memories.retain(|memory| memory.id != selector && !memory.content.contains(selector));
+        let removed = count - memories.len();
+        if removed > 0 {
+            self.write_category(category, &memories)?;
+        }
+        Ok(removed)
+    }
+
+    pub fn clear_category(&self, category: &str) -> io::Result<()> {
+        for path in [self.category_path(category), self.legacy_path(category)] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear_all(&self) -> io::Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+
+    fn category_path(&self, category: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", category, CATEGORY_EXTENSION))
+    }
+
+    fn legacy_path(&self, category: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", category, LEGACY_EXTENSION))
+    }
+
+    fn read_category(&self, category: &str) -> io::Result<Vec<MemoryEntry>> {
+        let path = self.category_path(category);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let file: CategoryFile = serde_json::from_slice(&fs::read(&path)?).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid memory file {}: {}", path.display(), e),
+            )
+        })?;
+        Ok(file.memories)
+    }
+
+    fn write_category(&self, category: &str, memories: &[MemoryEntry]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let file = CategoryFile {
+            version: STORE_VERSION,
+            memories: memories.to_vec(),
+        };
+        let json = serde_json::to_vec_pretty(&file).map_err(io::Error::other)?;
+
+        // Write to a temporary file first so a crash never leaves a truncated category behind
+        let path = self.category_path(category);
+        let tmp_path = path.with_extension(format!("{}.tmp", CATEGORY_EXTENSION));
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &path)
+    }
+
+    /// Convert a free-text category file into structured entries. Entries are
+    /// separated by blank lines, and may start with a `# tag1 tag2` line.
+    fn migrate_legacy(&self, category: &str) -> io::Result<()> {
+        let legacy_path = self.legacy_path(category);
+        if !legacy_path.exists() {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(&legacy_path)?;
+        let created_at = fs::metadata(&legacy_path)
+            .and_then(|m| m.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        let mut memories = self.read_category(category)?;
+        for block in content.split("\n\n") {
+            let mut lines = block
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .peekable();
+            let tags = match lines.peek() {
+                Some(first) if first.starts_with('#') => {
+                    let tags = first
+                        .trim_start_matches('#')
+                        .split_whitespace()
+                        .map(String::from)
+                        .collect();
+                    lines.next();
+                    tags
+                }
+                _ => Vec::new(),
+            };
+            let text = lines.collect::<Vec<_>>().join("\n");
+            if text.is_empty() {
+                continue;
+            }
+            memories.push(MemoryEntry {
+                id: unique_id(&memories, category, &text, created_at),
+                content: text,
+                tags,
+                created_at,
+                updated_at: created_at,
+                expires_at: None,
+                source_session: None,
+                embedding: None,
+            });
+        }
+
+        self.write_category(category, &memories)?;
+        fs::remove_file(&legacy_path)?;
+        tracing::info!(
+            "Migrated memory category '{}' in {} to structured entries",
+            category,
+            self.dir.display()
+        );
+        Ok(())
+    }
+}
+
+/// A short id derived from the memory itself, unique within its category
+fn unique_id(existing: &[MemoryEntry], category: &str, content: &str, at: DateTime<Utc>) -> String {
+    (0u32..)
+        .map(|attempt| {
+            let mut hasher = Sha256::new();
+            hasher.update(category.as_bytes());
+            hasher.update(content.as_bytes());
+            hasher.update(at.timestamp_nanos_opt().unwrap_or_default().to_le_bytes());
+            hasher.update(attempt.to_le_bytes());
+            hasher
+                .finalize()
+                .iter()
+                .take(4)
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        })
+        .find(|id| existing.iter().all(|memory| &memory.id != id))
+        .unwrap_or_default()
+}
@@ -0,0 +1,150 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use goose::config::{Config, ConfigError};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Named API keys accepted by the server, stored in the config file with hashed secrets
+pub const API_KEYS_CONFIG_KEY: &str = "GOOSE_SERVER_API_KEYS";
+
+const SECRET_PREFIX: &str = "gsk_";
+const SECRET_LENGTH: usize = 40;
+
+/// What a named API key may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// List and read the key's own sessions
+    ReadSessions,
+    /// Start and run agents, and change the key's own sessions
+    RunAgent,
+    /// Read and change configuration, providers and extensions, except the server's API keys
+    ManageConfig,
+    /// Create, change and run scheduled jobs; listing a job's sessions and runs also needs
+    /// `ReadSessions`
+    ManageSchedules,
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Scope::ReadSessions => "read_sessions",
+            Scope::RunAgent => "run_agent",
+            Scope::ManageConfig => "manage_config",
+            Scope::ManageSchedules => "manage_schedules",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Identifies the key's user; sessions created with the key are owned by this name
+    pub name: String,
+    /// Hex-encoded SHA-256 hash of the secret
+    pub secret_hash: String,
+    pub scopes: Vec<Scope>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+pub fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+pub fn list_api_keys(config: &Config) -> Result<Vec<ApiKey>> {
+    match config.get_param(API_KEYS_CONFIG_KEY) {
+        Ok(keys) => Ok(keys),
+        Err(ConfigError::NotFound(_)) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The key whose secret is `secret`, if any
+pub fn find_api_key(config: &Config, secret: &str) -> Result<Option<ApiKey>> {
+    let hash = hash_secret(secret);
+    Ok(list_api_keys(config)?
+        .into_iter()
+        .find(|key| key.secret_hash == hash))
+}
+
+/// Create a key and return its secret, which is only stored hashed
+pub fn create_api_key(config: &Config, name: &str, scopes: Vec<Scope>) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("API key name must not be empty");
+    }
+    if scopes.is_empty() {
+        bail!("API key needs at least one scope");
+    }
+    let mut keys = list_api_keys(config)?;
+    if keys.iter().any(|key| key.name == name) {
+        bail!("An API key named {} already exists", name);
+    }
+
+    let secret = format!(
+        "{}{}",
+        SECRET_PREFIX,
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(SECRET_LENGTH)
+            .map(char::from)
+            .collect::<String>()
+    );
+    keys.push(ApiKey {
+        name: name.to_string(),
+        secret_hash: hash_secret(&secret),
+        scopes,
+        created_at: Utc::now(),
+    });
+    config.set_param(API_KEYS_CONFIG_KEY, &keys)?;
+    Ok(secret)
+}
+
+/// Remove the key named `name`, returning whether it existed
+pub fn revoke_api_key(config: &Config, name: &str) -> Result<bool> {
+    let mut keys = list_api_keys(config)?;
+    let count = keys.len();
+    keys.retain(|key| key.name != name);
+    if keys.len() == count {
+        return Ok(false);
+    }
+    config.set_param(API_KEYS_CONFIG_KEY, &keys)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_create_find_and_revoke_keys() {
+        let config_file = NamedTempFile::new().unwrap();
+        let config = Config::new(config_file.path(), "goose-test").unwrap();
+
+        let secret = create_api_key(&config, "alice", vec![Scope::ReadSessions]).unwrap();
+        assert!(secret.starts_with(SECRET_PREFIX));
+        assert!(create_api_key(&config, "alice", vec![Scope::RunAgent]).is_err());
+        assert!(create_api_key(&config, "bob", vec![]).is_err());
+
+        let stored = std::fs::read_to_string(config_file.path()).unwrap();
+        assert!(!stored.contains(&secret));
+
+        let key = find_api_key(&config, &secret).unwrap().unwrap();
+        assert_eq!(key.name, "alice");
+        assert!(key.has_scope(Scope::ReadSessions));
+        assert!(!key.has_scope(Scope::RunAgent));
+        assert!(find_api_key(&config, "gsk_wrong").unwrap().is_none());
+
+        assert!(revoke_api_key(&config, "alice").unwrap());
+        assert!(!revoke_api_key(&config, "alice").unwrap());
+        assert!(find_api_key(&config, &secret).unwrap().is_none());
+    }
+}
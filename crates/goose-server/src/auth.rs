@@ -1,25 +1,202 @@
+use crate::api_keys::{self, ApiKey, Scope};
 use axum::{
-    extract::{Request, State},
-    http::StatusCode,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, Method, StatusCode},
     middleware::Next,
     response::Response,
 };
+use goose::config::Config;
+use goose::session::SessionManager;
+use std::convert::Infallible;
+
+/// Who is making a request: the holder of the shared server secret, or a named API key
+#[derive(Debug, Clone)]
+pub enum Caller {
+    Admin,
+    Key(ApiKey),
+}
+
+impl Caller {
+    /// The owner recorded on sessions this caller creates
+    pub fn owner(&self) -> Option<String> {
+        match self {
+            Caller::Admin => None,
+            Caller::Key(key) => Some(key.name.clone()),
+        }
+    }
+
+    /// Check that this caller may use the session. Sessions of other keys are reported
+    /// as missing rather than forbidden, so their ids cannot be probed.
+    pub async fn authorize_session(&self, session_id: &str) -> Result<(), StatusCode> {
+        let Caller::Key(key) = self else {
+            return Ok(());
+        };
+        let session = SessionManager::get_session(session_id, false)
+            .await
+            .map_err(|_| StatusCode::NOT_FOUND)?;
+        if session.owner.as_deref() == Some(key.name.as_str()) {
+            Ok(())
+        } else {
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Caller>()
+            .cloned()
+            .unwrap_or(Caller::Admin))
+    }
+}
+
+/// The scopes a named key needs for a route; it must have all of them. `None` means only the
+/// shared secret may call it.
+pub fn required_scopes(method: &Method, path: &str) -> Option<&'static [Scope]> {
+    let matches = |prefix: &str| {
+        path == prefix
+            || path
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+    // Listing a job's sessions and runs reads session data as well as the schedule
+    let reads_schedule_sessions = path
+        .strip_prefix("/schedule/")
+        .and_then(|rest| rest.split_once('/'))
+        .is_some_and(|(id, rest)| !id.is_empty() && (rest == "sessions" || rest == "runs"));
+
+    if matches("/sessions") || matches("/diagnostics") {
+        if method == Method::GET {
+            Some(&[Scope::ReadSessions])
+        } else {
+            Some(&[Scope::RunAgent])
+        }
+    } else if ["/agent", "/reply", "/confirm", "/v1", "/audio", "/recipes"]
+        .into_iter()
+        .any(matches)
+    {
+        Some(&[Scope::RunAgent])
+    } else if ["/config", "/handle_openrouter", "/handle_tetrate"]
+        .into_iter()
+        .any(matches)
+    {
+        Some(&[Scope::ManageConfig])
+    } else if reads_schedule_sessions {
+        Some(&[Scope::ManageSchedules, Scope::ReadSessions])
+    } else if matches("/schedule") {
+        Some(&[Scope::ManageSchedules])
+    } else {
+        None
+    }
+}
 
 pub async fn check_token(
     State(state): State<String>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
     if request.uri().path() == "/status" {
         return Ok(next.run(request).await);
     }
+    // OpenAI-compatible clients send the key as a bearer token
     let secret_key = request
         .headers()
         .get("X-Secret-Key")
-        .and_then(|value| value.to_str().ok());
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| {
+            request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+        })
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let caller = if secret_key == state {
+        Caller::Admin
+    } else {
+        // Keys are read on every request so a revoked key stops working immediately
+        let key = api_keys::find_api_key(Config::global(), secret_key)
+            .map_err(|e| {
+                tracing::error!("Failed to read API keys: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        match required_scopes(request.method(), request.uri().path()) {
+            Some(scopes) if scopes.iter().all(|scope| key.has_scope(*scope)) => Caller::Key(key),
+            _ => return Err(StatusCode::FORBIDDEN),
+        }
+    };
+
+    request.extensions_mut().insert(caller);
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    match secret_key {
-        Some(key) if key == state => Ok(next.run(request).await),
-        _ => Err(StatusCode::UNAUTHORIZED),
+    #[test]
+    fn test_required_scopes() {
+        let cases = [
+            (Method::GET, "/sessions", Some(&[Scope::ReadSessions][..])),
+            (
+                Method::GET,
+                "/sessions/abc/export",
+                Some(&[Scope::ReadSessions][..]),
+            ),
+            (
+                Method::DELETE,
+                "/sessions/abc",
+                Some(&[Scope::RunAgent][..]),
+            ),
+            (
+                Method::GET,
+                "/diagnostics/abc",
+                Some(&[Scope::ReadSessions][..]),
+            ),
+            (Method::POST, "/reply", Some(&[Scope::RunAgent][..])),
+            (Method::POST, "/agent/start", Some(&[Scope::RunAgent][..])),
+            (
+                Method::POST,
+                "/v1/chat/completions",
+                Some(&[Scope::RunAgent][..]),
+            ),
+            (
+                Method::GET,
+                "/config/providers",
+                Some(&[Scope::ManageConfig][..]),
+            ),
+            (
+                Method::POST,
+                "/handle_openrouter",
+                Some(&[Scope::ManageConfig][..]),
+            ),
+            (
+                Method::GET,
+                "/schedule/list",
+                Some(&[Scope::ManageSchedules][..]),
+            ),
+            (
+                Method::GET,
+                "/schedule/nightly/sessions",
+                Some(&[Scope::ManageSchedules, Scope::ReadSessions][..]),
+            ),
+            (
+                Method::GET,
+                "/schedule/nightly/runs",
+                Some(&[Scope::ManageSchedules, Scope::ReadSessions][..]),
+            ),
+            (Method::GET, "/configuration", None),
+            (Method::POST, "/replying", None),
+            (Method::GET, "/unknown", None),
+        ];
+        for (method, path, scope) in cases {
+            assert_eq!(required_scopes(&method, path), scope, "{} {}", method, path);
+        }
     }
 }
@@ -1,8 +1,8 @@
+use crate::auth::check_token;
 use crate::configuration;
 use crate::state;
 use anyhow::Result;
 use axum::middleware;
-use goose_server::auth::check_token;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
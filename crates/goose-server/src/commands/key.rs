@@ -0,0 +1,38 @@
+use crate::api_keys::{self, Scope};
+use anyhow::{bail, Result};
+use goose::config::Config;
+
+pub fn create(name: &str, scopes: &[Scope]) -> Result<()> {
+    let secret = api_keys::create_api_key(Config::global(), name, scopes.to_vec())?;
+    println!(
+        "Created API key {}. Store this secret now, it is not shown again:",
+        name
+    );
+    println!("{}", secret);
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let keys = api_keys::list_api_keys(Config::global())?;
+    if keys.is_empty() {
+        println!("No API keys");
+    }
+    for key in keys {
+        let scopes: Vec<String> = key.scopes.iter().map(Scope::to_string).collect();
+        println!(
+            "{}  [{}]  created {}",
+            key.name,
+            scopes.join(", "),
+            key.created_at.format("%Y-%m-%d %H:%M")
+        );
+    }
+    Ok(())
+}
+
+pub fn revoke(name: &str) -> Result<()> {
+    if !api_keys::revoke_api_key(Config::global(), name)? {
+        bail!("No API key named {}", name);
+    }
+    println!("Revoked API key {}", name);
+    Ok(())
+}
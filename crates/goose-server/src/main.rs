@@ -1,3 +1,5 @@
+mod api_keys;
+mod auth;
 mod commands;
 mod configuration;
 mod error;
@@ -6,6 +8,7 @@ mod openapi;
 mod routes;
 mod state;
 
+use api_keys::Scope;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -25,6 +28,30 @@ enum Commands {
         /// Name of the MCP server type
         name: String,
     },
+    /// Manage named API keys for the server
+    Key {
+        #[command(subcommand)]
+        command: KeyCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyCommand {
+    /// Create an API key and print its secret
+    Create {
+        /// Name of the key; sessions created with it are owned by this name
+        name: String,
+        /// What the key may do, repeat for several scopes
+        #[arg(long = "scope", value_enum, required = true)]
+        scopes: Vec<Scope>,
+    },
+    /// List API keys
+    List,
+    /// Revoke an API key
+    Revoke {
+        /// Name of the key to revoke
+        name: String,
+    },
 }
 
 #[tokio::main]
@@ -39,6 +66,11 @@ async fn main() -> anyhow::Result<()> {
             logging::setup_logging(Some(&format!("mcp-{name}")))?;
             goose_mcp::mcp_server_runner::run_mcp_server(name).await?;
         }
+        Commands::Key { command } => match command {
+            KeyCommand::Create { name, scopes } => commands::key::create(name, scopes)?,
+            KeyCommand::List => commands::key::list()?,
+            KeyCommand::Revoke { name } => commands::key::revoke(name)?,
+        },
     }
 
     Ok(())
@@ -6,7 +6,7 @@ use goose::config::ExtensionEntry;
 use goose::conversation::Conversation;
 use goose::permission::permission_confirmation::PrincipalType;
 use goose::providers::base::{ConfigKey, ModelInfo, ProviderMetadata, ProviderType};
-use goose::session::{Session, SessionInsights, SessionType};
+use goose::session::{Budget, Session, SessionInsights, SessionType};
 use rmcp::model::{
     Annotations, Content, EmbeddedResource, Icon, ImageContent, JsonObject, RawAudioContent,
     RawEmbeddedResource, RawImageContent, RawResource, RawTextContent, ResourceContents, Role,
@@ -15,15 +15,14 @@ use rmcp::model::{
 use utoipa::{OpenApi, ToSchema};
 
 use goose::config::declarative_providers::{
-    DeclarativeProviderConfig, LoadedProvider, ProviderEngine,
+    DeclarativeProviderConfig, LoadedProvider, OpenAiApi, ProviderEngine,
 };
 use goose::conversation::message::{
-    FrontendToolRequest, Message, MessageContent, MessageMetadata, RedactedThinkingContent,
-    SystemNotificationContent, SystemNotificationType, ThinkingContent, TokenState,
-    ToolConfirmationRequest, ToolRequest, ToolResponse,
+    FrontendToolRequest, Message, MessageContent, MessageEvent, MessageMetadata,
+    RedactedThinkingContent, ServedBy, SystemNotificationContent, SystemNotificationType,
+    ThinkingContent, TokenState, ToolConfirmationRequest, ToolRequest, ToolResponse,
 };
 
-use crate::routes::reply::MessageEvent;
 use utoipa::openapi::schema::{
     AdditionalProperties, AnyOfBuilder, ArrayBuilder, ObjectBuilder, OneOfBuilder, Schema,
     SchemaFormat, SchemaType,
@@ -359,6 +358,8 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::session::list_sessions,
         super::routes::session::get_session,
         super::routes::session::get_session_insights,
+        super::routes::session::search_sessions,
+        super::routes::session::fork_session,
         super::routes::session::update_session_name,
         super::routes::session::delete_session,
         super::routes::session::export_session,
@@ -369,11 +370,13 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::schedule::delete_schedule,
         super::routes::schedule::update_schedule,
         super::routes::schedule::run_now_handler,
+        super::routes::schedule::webhook_handler,
         super::routes::schedule::pause_schedule,
         super::routes::schedule::unpause_schedule,
         super::routes::schedule::kill_running_job,
         super::routes::schedule::inspect_running_job,
         super::routes::schedule::sessions_handler,
+        super::routes::schedule::runs_handler,
         super::routes::recipe::create_recipe,
         super::routes::recipe::encode_recipe,
         super::routes::recipe::decode_recipe,
@@ -402,12 +405,19 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::reply::ChatRequest,
         super::routes::session::ImportSessionRequest,
         super::routes::session::SessionListResponse,
+        super::routes::session::SessionSearchResponse,
+        super::routes::session::ForkSessionRequest,
+        super::routes::session::ForkSessionResponse,
+        goose::agents::FileRevert,
+        goose::session::SessionSearchResult,
+        goose::session::SessionSearchMatch,
         super::routes::session::UpdateSessionNameRequest,
         super::routes::session::UpdateSessionUserRecipeValuesRequest,
         super::routes::session::UpdateSessionUserRecipeValuesResponse,
         Message,
         MessageContent,
         MessageMetadata,
+        ServedBy,
         TokenState,
         ContentSchema,
         EmbeddedResourceSchema,
@@ -435,6 +445,7 @@ derive_utoipa!(Icon as IconSchema);
         ProviderType,
         LoadedProvider,
         ProviderEngine,
+        OpenAiApi,
         DeclarativeProviderConfig,
         ExtensionEntry,
         ExtensionConfig,
@@ -448,6 +459,7 @@ derive_utoipa!(Icon as IconSchema);
         ModelInfo,
         Session,
         SessionInsights,
+        Budget,
         SessionType,
         Conversation,
         IconSchema,
@@ -457,6 +469,13 @@ derive_utoipa!(Icon as IconSchema);
         super::routes::schedule::KillJobResponse,
         super::routes::schedule::InspectJobResponse,
         goose::scheduler::ScheduledJob,
+        goose::scheduler_triggers::JobTrigger,
+        goose::scheduler_triggers::ChainCondition,
+        goose::scheduler::RunPolicy,
+        goose::scheduler::OverlapPolicy,
+        goose::scheduler_history::JobRun,
+        goose::scheduler_history::RunStatus,
+        goose::scheduler_history::RunTrigger,
         super::routes::schedule::RunNowResponse,
         super::routes::schedule::ListSchedulesResponse,
         super::routes::schedule::SessionsQuery,
@@ -481,6 +500,7 @@ derive_utoipa!(Icon as IconSchema);
         goose::recipe::Recipe,
         goose::recipe::Author,
         goose::recipe::Settings,
+        goose::context_mgmt::ContextStrategyKind,
         goose::recipe::RecipeParameter,
         goose::recipe::RecipeParameterInputType,
         goose::recipe::RecipeParameterRequirement,
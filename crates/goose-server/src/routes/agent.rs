@@ -1,3 +1,4 @@
+use crate::auth::Caller;
 use crate::routes::errors::ErrorResponse;
 use crate::routes::recipe_utils::{
     apply_recipe_to_agent, build_recipe_with_parameter_values, load_recipe_by_id, validate_recipe,
@@ -16,7 +17,7 @@ use goose::agents::ExtensionConfig;
 use goose::config::{Config, GooseMode};
 use goose::model::ModelConfig;
 use goose::prompt_template::render_global_file;
-use goose::providers::{create, create_with_named_model};
+use goose::providers::create_session_provider;
 use goose::recipe::Recipe;
 use goose::recipe_deeplink;
 use goose::session::session_manager::SessionType;
@@ -98,6 +99,7 @@ pub struct RemoveExtensionRequest {
 )]
 async fn start_agent(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(payload): Json<StartAgentRequest>,
 ) -> Result<Json<Session>, ErrorResponse> {
     let StartAgentRequest {
@@ -150,6 +152,17 @@ async fn start_agent(
                 }
             })?;
 
+    if let Some(owner) = caller.owner() {
+        SessionManager::update_session(&session.id)
+            .owner(Some(owner.clone()))
+            .apply()
+            .await
+            .map_err(|err| {
+                ErrorResponse::internal(format!("Failed to set session owner: {}", err))
+            })?;
+        session.owner = Some(owner);
+    }
+
     if let Some(recipe) = original_recipe {
         SessionManager::update_session(&session.id)
             .recipe(Some(recipe))
@@ -190,8 +203,13 @@ async fn start_agent(
 )]
 async fn resume_agent(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(payload): Json<ResumeAgentRequest>,
 ) -> Result<Json<Session>, ErrorResponse> {
+    caller
+        .authorize_session(&payload.session_id)
+        .await
+        .map_err(|_| ErrorResponse::session_not_found(&payload.session_id))?;
     let session = SessionManager::get_session(&payload.session_id, true)
         .await
         .map_err(|err| {
@@ -224,7 +242,11 @@ async fn resume_agent(
                 status: StatusCode::INTERNAL_SERVER_ERROR,
             })?;
 
-            let provider = create_with_named_model(&provider_name, &model)
+            let model_config = ModelConfig::new(&model).map_err(|_| ErrorResponse {
+                message: "Could not configure agent: invalid model".into(),
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+            })?;
+            let provider = create_session_provider(&provider_name, model_config)
                 .await
                 .map_err(|_| ErrorResponse {
                     message: "Could not configure agent: missing model".into(),
@@ -282,8 +304,13 @@ async fn resume_agent(
 )]
 async fn update_from_session(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(payload): Json<UpdateFromSessionRequest>,
 ) -> Result<StatusCode, ErrorResponse> {
+    caller
+        .authorize_session(&payload.session_id)
+        .await
+        .map_err(|_| ErrorResponse::session_not_found(&payload.session_id))?;
     let agent = state
         .get_agent_for_route(payload.session_id.clone())
         .await
@@ -345,8 +372,10 @@ async fn update_from_session(
 )]
 async fn get_tools(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(query): Query<GetToolsQuery>,
 ) -> Result<Json<Vec<ToolInfo>>, StatusCode> {
+    caller.authorize_session(&query.session_id).await?;
     let config = Config::global();
     let goose_mode = config.get_goose_mode().unwrap_or(GooseMode::Auto);
     let agent = state.get_agent_for_route(query.session_id).await?;
@@ -399,8 +428,13 @@ async fn get_tools(
 )]
 async fn update_agent_provider(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(payload): Json<UpdateProviderRequest>,
 ) -> Result<(), impl IntoResponse> {
+    caller
+        .authorize_session(&payload.session_id)
+        .await
+        .map_err(|e| (e, "Session not found".to_owned()))?;
     let agent = state
         .get_agent_for_route(payload.session_id.clone())
         .await
@@ -421,12 +455,14 @@ async fn update_agent_provider(
         )
     })?;
 
-    let new_provider = create(&payload.provider, model_config).await.map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            format!("Failed to create {} provider: {}", &payload.provider, e),
-        )
-    })?;
+    let new_provider = create_session_provider(&payload.provider, model_config)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to create {} provider: {}", &payload.provider, e),
+            )
+        })?;
 
     agent.update_provider(new_provider).await.map_err(|e| {
         (
@@ -451,8 +487,10 @@ async fn update_agent_provider(
 )]
 async fn update_router_tool_selector(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(payload): Json<UpdateRouterToolSelectorRequest>,
 ) -> Result<Json<String>, StatusCode> {
+    caller.authorize_session(&payload.session_id).await?;
     let agent = state.get_agent_for_route(payload.session_id).await?;
     agent
         .update_router_tool_selector(None, Some(true))
@@ -480,8 +518,13 @@ async fn update_router_tool_selector(
 )]
 async fn agent_add_extension(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(request): Json<AddExtensionRequest>,
 ) -> Result<StatusCode, ErrorResponse> {
+    caller
+        .authorize_session(&request.session_id)
+        .await
+        .map_err(|_| ErrorResponse::session_not_found(&request.session_id))?;
     if cfg!(target_os = "windows") {
         if let ExtensionConfig::Stdio { cmd, .. } = &request.config {
             if cmd.ends_with("npx.cmd") || cmd.ends_with("npx") {
@@ -543,8 +586,13 @@ async fn agent_add_extension(
 )]
 async fn agent_remove_extension(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(request): Json<RemoveExtensionRequest>,
 ) -> Result<StatusCode, ErrorResponse> {
+    caller
+        .authorize_session(&request.session_id)
+        .await
+        .map_err(|_| ErrorResponse::session_not_found(&request.session_id))?;
     let agent = state.get_agent(request.session_id).await?;
     agent.remove_extension(&request.name).await?;
     Ok(StatusCode::OK)
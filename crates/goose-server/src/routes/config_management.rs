@@ -1,3 +1,5 @@
+use crate::api_keys::API_KEYS_CONFIG_KEY;
+use crate::auth::Caller;
 use crate::routes::utils::check_provider_configured;
 use crate::state::AppState;
 use axum::routing::put;
@@ -113,18 +115,30 @@ pub enum ConfigValueResponse {
     MaskedValue(MaskedSecret),
 }
 
+/// Only the shared secret may read or change the server's API keys; a named key could
+/// otherwise read their hashes or grant itself new keys and scopes
+fn authorize_config_key(caller: &Caller, key: &str) -> Result<(), StatusCode> {
+    match caller {
+        Caller::Key(_) if key == API_KEYS_CONFIG_KEY => Err(StatusCode::FORBIDDEN),
+        _ => Ok(()),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/config/upsert",
     request_body = UpsertConfigQuery,
     responses(
         (status = 200, description = "Configuration value upserted successfully", body = String),
+        (status = 403, description = "The configuration key is reserved for the server secret"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn upsert_config(
+    caller: Caller,
     Json(query): Json<UpsertConfigQuery>,
 ) -> Result<Json<Value>, StatusCode> {
+    authorize_config_key(&caller, &query.key)?;
     let config = Config::global();
     let result = config.set(&query.key, &query.value, query.is_secret);
 
@@ -140,11 +154,16 @@ pub async fn upsert_config(
     request_body = ConfigKeyQuery,
     responses(
         (status = 200, description = "Configuration value removed successfully", body = String),
+        (status = 403, description = "The configuration key is reserved for the server secret"),
         (status = 404, description = "Configuration key not found"),
         (status = 500, description = "Internal server error")
     )
 )]
-pub async fn remove_config(Json(query): Json<ConfigKeyQuery>) -> Result<Json<String>, StatusCode> {
+pub async fn remove_config(
+    caller: Caller,
+    Json(query): Json<ConfigKeyQuery>,
+) -> Result<Json<String>, StatusCode> {
+    authorize_config_key(&caller, &query.key)?;
     let config = Config::global();
 
     let result = if query.is_secret {
@@ -181,12 +200,15 @@ fn mask_secret(secret: Value) -> String {
     request_body = ConfigKeyQuery,
     responses(
         (status = 200, description = "Configuration value retrieved successfully", body = Value),
+        (status = 403, description = "The configuration key is reserved for the server secret"),
         (status = 500, description = "Unable to get the configuration value"),
     )
 )]
 pub async fn read_config(
+    caller: Caller,
     Json(query): Json<ConfigKeyQuery>,
 ) -> Result<Json<ConfigValueResponse>, StatusCode> {
+    authorize_config_key(&caller, &query.key)?;
     if query.key == "model-limits" {
         let limits = ModelConfig::get_all_model_limits();
         return Ok(Json(ConfigValueResponse::Value(
@@ -280,12 +302,15 @@ pub async fn remove_extension(Path(name): Path<String>) -> Result<Json<String>,
         (status = 200, description = "All configuration values retrieved successfully", body = ConfigResponse)
     )
 )]
-pub async fn read_all_config() -> Result<Json<ConfigResponse>, StatusCode> {
+pub async fn read_all_config(caller: Caller) -> Result<Json<ConfigResponse>, StatusCode> {
     let config = Config::global();
 
-    let values = config
+    let mut values = config
         .all_values()
         .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    if authorize_config_key(&caller, API_KEYS_CONFIG_KEY).is_err() {
+        values.remove(API_KEYS_CONFIG_KEY);
+    }
 
     Ok(Json(ConfigResponse { config: values }))
 }
@@ -821,10 +846,13 @@ mod tests {
         let mut headers = HeaderMap::new();
         headers.insert("X-Secret-Key", "test".parse().unwrap());
 
-        let result = read_config(Json(ConfigKeyQuery {
-            key: "model-limits".to_string(),
-            is_secret: false,
-        }))
+        let result = read_config(
+            Caller::Admin,
+            Json(ConfigKeyQuery {
+                key: "model-limits".to_string(),
+                is_secret: false,
+            }),
+        )
         .await;
 
         assert!(result.is_ok());
@@ -840,4 +868,36 @@ mod tests {
         assert!(gpt4_limit.is_some());
         assert_eq!(gpt4_limit.unwrap().context_limit, 128_000);
     }
+
+    #[tokio::test]
+    async fn test_named_keys_cannot_touch_api_keys() {
+        let caller = Caller::Key(crate::api_keys::ApiKey {
+            name: "ci".to_string(),
+            secret_hash: String::new(),
+            scopes: vec![crate::api_keys::Scope::ManageConfig],
+            created_at: chrono::Utc::now(),
+        });
+        let query = || ConfigKeyQuery {
+            key: API_KEYS_CONFIG_KEY.to_string(),
+            is_secret: false,
+        };
+
+        let read = read_config(caller.clone(), Json(query())).await;
+        assert_eq!(read.err(), Some(StatusCode::FORBIDDEN));
+        let removed = remove_config(caller.clone(), Json(query())).await;
+        assert_eq!(removed.err(), Some(StatusCode::FORBIDDEN));
+        let upserted = upsert_config(
+            caller.clone(),
+            Json(UpsertConfigQuery {
+                key: API_KEYS_CONFIG_KEY.to_string(),
+                value: Value::Array(Vec::new()),
+                is_secret: false,
+            }),
+        )
+        .await;
+        assert_eq!(upserted.err(), Some(StatusCode::FORBIDDEN));
+
+        assert!(authorize_config_key(&caller, "GOOSE_MODEL").is_ok());
+        assert!(authorize_config_key(&Caller::Admin, API_KEYS_CONFIG_KEY).is_ok());
+    }
 }
@@ -20,6 +20,13 @@ impl ErrorResponse {
             status: StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    pub(crate) fn session_not_found(session_id: &str) -> Self {
+        Self {
+            message: format!("Session {} not found", session_id),
+            status: StatusCode::NOT_FOUND,
+        }
+    }
 }
 
 impl IntoResponse for ErrorResponse {
@@ -0,0 +1,933 @@
+//! OpenAI-compatible chat completions API, so existing OpenAI clients can talk to goose.
+//!
+//! Each `/v1/chat/completions` request runs a full agent turn in a new hidden session, with
+//! extensions and tool calls handled server-side. Only the assistant's text is returned, and
+//! the session is deleted once the turn ends. There is nobody to approve tool calls, so tools
+//! that need approval are declined; run the server in `auto` mode to let them run.
+//! The `model` field selects what runs the turn:
+//! - `goose` (or empty): the configured provider and model
+//! - the name of a saved recipe: that recipe, with its extensions and settings
+//! - `provider/model`: that provider and model
+//! - anything else: that model on the configured provider
+
+use crate::auth::Caller;
+use crate::routes::recipe_utils::apply_recipe_to_agent;
+use crate::state::AppState;
+use axum::{
+    extract::{DefaultBodyLimit, State},
+    http::{self, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use goose::agents::{Agent, AgentEvent, SessionConfig};
+use goose::config::{get_enabled_extensions, Config};
+use goose::conversation::message::Message;
+use goose::model::ModelConfig;
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::{Permission, PermissionConfirmation};
+use goose::providers::base::Provider;
+use goose::providers::create_session_provider;
+use goose::recipe::build_recipe::{build_recipe_from_template, RecipeError};
+use goose::recipe::local_recipes::list_local_recipes;
+use goose::recipe::read_recipe_file_content::read_recipe_file;
+use goose::recipe::Recipe;
+use goose::session::session_manager::SessionType;
+use goose::session::SessionManager;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+/// Model name that runs the configured provider and model
+const DEFAULT_MODEL: &str = "goose";
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    role: String,
+    #[serde(default)]
+    content: Option<ChatContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ChatContent {
+    Text(String),
+    Parts(Vec<Value>),
+}
+
+impl ChatContent {
+    /// The text of the content; parts other than text are ignored
+    fn text(&self) -> String {
+        match self {
+            ChatContent::Text(text) => text.clone(),
+            ChatContent::Parts(parts) => parts
+                .iter()
+                .filter(|part| part.get("type").and_then(Value::as_str) == Some("text"))
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletion {
+    id: String,
+    object: &'static str,
+    created: i64,
+    model: String,
+    choices: Vec<CompletionChoice>,
+    usage: Usage,
+}
+
+#[derive(Debug, Serialize)]
+struct CompletionChoice {
+    index: u32,
+    message: CompletionMessage,
+    finish_reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct CompletionMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionChunk {
+    id: String,
+    object: &'static str,
+    created: i64,
+    model: String,
+    choices: Vec<ChunkChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChunkChoice {
+    index: u32,
+    delta: ChunkDelta,
+    finish_reason: Option<&'static str>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ChunkDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+struct Usage {
+    prompt_tokens: i32,
+    completion_tokens: i32,
+    total_tokens: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelList {
+    object: &'static str,
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelEntry {
+    id: String,
+    object: &'static str,
+    created: i64,
+    owned_by: String,
+}
+
+/// An error in the shape OpenAI clients expect
+#[derive(Debug)]
+pub struct OpenAiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl OpenAiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: message.into(),
+        }
+    }
+
+    fn body(message: &str, error_type: &str) -> Value {
+        serde_json::json!({
+            "error": {
+                "message": message,
+                "type": error_type,
+                "code": Value::Null,
+            }
+        })
+    }
+}
+
+impl IntoResponse for OpenAiError {
+    fn into_response(self) -> Response {
+        let error_type = if self.status.is_client_error() {
+            "invalid_request_error"
+        } else {
+            "server_error"
+        };
+        (self.status, Json(Self::body(&self.message, error_type))).into_response()
+    }
+}
+
+/// What a `model` name resolves to
+#[derive(Debug, PartialEq)]
+enum ModelTarget {
+    Default,
+    Recipe(PathBuf),
+    Model {
+        provider: Option<String>,
+        model: String,
+    },
+}
+
+fn resolve_model(model: &str, recipes: &[(String, PathBuf)]) -> ModelTarget {
+    let model = model.trim();
+    if model.is_empty() || model == DEFAULT_MODEL {
+        return ModelTarget::Default;
+    }
+    if let Some((_, path)) = recipes.iter().find(|(name, _)| name == model) {
+        return ModelTarget::Recipe(path.clone());
+    }
+    match model.split_once('/') {
+        Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+            ModelTarget::Model {
+                provider: Some(provider.to_string()),
+                model: model.to_string(),
+            }
+        }
+        _ => ModelTarget::Model {
+            provider: None,
+            model: model.to_string(),
+        },
+    }
+}
+
+/// Saved recipes by name, which is their file name without the extension
+fn saved_recipes() -> Vec<(String, PathBuf)> {
+    list_local_recipes()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(path, _)| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some((name, path))
+        })
+        .collect()
+}
+
+fn load_recipe(path: &Path) -> Result<Recipe, OpenAiError> {
+    let recipe_file = read_recipe_file(path)
+        .map_err(|e| OpenAiError::internal(format!("Failed to read recipe: {}", e)))?;
+    build_recipe_from_template(
+        recipe_file.content,
+        &recipe_file.parent_dir,
+        vec![],
+        None::<fn(&str, &str) -> Result<String, anyhow::Error>>,
+    )
+    .map_err(|e| match e {
+        RecipeError::MissingParams { parameters } => OpenAiError::bad_request(format!(
+            "Recipe requires parameters, which can't be passed through this API: {}",
+            parameters.join(", ")
+        )),
+        e => OpenAiError::internal(format!("Failed to build recipe: {}", e)),
+    })
+}
+
+/// A session and agent set up to answer the last message of a request
+struct PreparedTurn {
+    agent: Arc<Agent>,
+    session_id: String,
+    user_message: Message,
+}
+
+async fn prepare_turn(
+    state: &AppState,
+    caller: &Caller,
+    request: &ChatCompletionRequest,
+) -> Result<PreparedTurn, OpenAiError> {
+    let (last, history) = request
+        .messages
+        .split_last()
+        .ok_or_else(|| OpenAiError::bad_request("messages must not be empty"))?;
+    if last.role != "user" {
+        return Err(OpenAiError::bad_request(
+            "The last message must have the user role",
+        ));
+    }
+    let user_message = Message::user().with_text(
+        last.content
+            .as_ref()
+            .map(ChatContent::text)
+            .unwrap_or_default(),
+    );
+
+    let recipe = match resolve_model(&request.model, &saved_recipes()) {
+        ModelTarget::Recipe(path) => Some(load_recipe(&path)?),
+        _ => None,
+    };
+    let (provider_name, model_name) = provider_and_model(&request.model, recipe.as_ref())?;
+    let settings = recipe.as_ref().and_then(|r| r.settings.as_ref());
+    let model_config = ModelConfig::new(&model_name)
+        .map_err(|e| OpenAiError::bad_request(format!("Invalid model: {}", e)))?
+        .with_temperature(settings.and_then(|s| s.temperature));
+    let provider = create_session_provider(&provider_name, model_config)
+        .await
+        .map_err(|e| OpenAiError::bad_request(format!("Failed to create provider: {}", e)))?;
+
+    let working_dir = std::env::current_dir().unwrap_or_default();
+    let session = SessionManager::create_session(
+        working_dir,
+        format!("API: {}", request.model),
+        SessionType::Hidden,
+    )
+    .await
+    .map_err(|e| OpenAiError::internal(format!("Failed to create session: {}", e)))?;
+
+    match set_up_session(state, caller, &session.id, provider, recipe, history).await {
+        Ok(agent) => Ok(PreparedTurn {
+            agent,
+            session_id: session.id,
+            user_message,
+        }),
+        Err(e) => {
+            discard_session(state, &session.id).await;
+            Err(e)
+        }
+    }
+}
+
+async fn set_up_session(
+    state: &AppState,
+    caller: &Caller,
+    session_id: &str,
+    provider: Arc<dyn Provider>,
+    recipe: Option<Recipe>,
+    history: &[ChatMessage],
+) -> Result<Arc<Agent>, OpenAiError> {
+    if let Some(owner) = caller.owner() {
+        SessionManager::update_session(session_id)
+            .owner(Some(owner))
+            .apply()
+            .await
+            .map_err(|e| OpenAiError::internal(format!("Failed to set session owner: {}", e)))?;
+    }
+
+    let agent = state
+        .get_agent(session_id.to_string())
+        .await
+        .map_err(|e| OpenAiError::internal(format!("Failed to create agent: {}", e)))?;
+    agent
+        .update_provider(provider)
+        .await
+        .map_err(|e| OpenAiError::internal(format!("Failed to set provider: {}", e)))?;
+
+    let extensions = match recipe.as_ref().and_then(|r| r.extensions.clone()) {
+        Some(extensions) => extensions,
+        None => get_enabled_extensions(),
+    };
+    futures::future::join_all(extensions.into_iter().map(|config| {
+        let agent = agent.clone();
+        async move {
+            if let Err(e) = agent.add_extension(config.clone()).await {
+                warn!("Failed to load extension {}: {}", config.name(), e);
+            }
+        }
+    }))
+    .await;
+
+    if let Some(recipe) = &recipe {
+        if let Some(prompt) = apply_recipe_to_agent(&agent, recipe, true).await {
+            agent.extend_system_prompt(prompt).await;
+        }
+    }
+
+    for message in history {
+        let text = message
+            .content
+            .as_ref()
+            .map(ChatContent::text)
+            .unwrap_or_default();
+        if text.is_empty() {
+            continue;
+        }
+        let message = match message.role.as_str() {
+            "system" | "developer" => {
+                agent.extend_system_prompt(text).await;
+                continue;
+            }
+            "user" => Message::user().with_text(text),
+            "assistant" => Message::assistant().with_text(text),
+            // Tools run server-side, so results of client-side tools have nowhere to go
+            _ => continue,
+        };
+        SessionManager::add_message(session_id, &message)
+            .await
+            .map_err(|e| OpenAiError::internal(format!("Failed to add message: {}", e)))?;
+    }
+
+    Ok(agent)
+}
+
+/// Drop the agent and the hidden session of a request
+async fn discard_session(state: &AppState, session_id: &str) {
+    let _ = state.agent_manager.remove_session(session_id).await;
+    if let Err(e) = SessionManager::delete_session(session_id).await {
+        warn!("Failed to delete API session {}: {}", session_id, e);
+    }
+}
+
+fn provider_and_model(
+    model: &str,
+    recipe: Option<&Recipe>,
+) -> Result<(String, String), OpenAiError> {
+    let config = Config::global();
+    let settings = recipe.and_then(|r| r.settings.as_ref());
+    let (provider, model) = match resolve_model(model, &[]) {
+        ModelTarget::Model { provider, model } if recipe.is_none() => (provider, Some(model)),
+        _ => (
+            settings.and_then(|s| s.goose_provider.clone()),
+            settings.and_then(|s| s.goose_model.clone()),
+        ),
+    };
+
+    let provider = match provider {
+        Some(provider) => provider,
+        None => config
+            .get_goose_provider()
+            .map_err(|_| OpenAiError::internal("No provider configured"))?,
+    };
+    let model = match model {
+        Some(model) => model,
+        None => config
+            .get_goose_model()
+            .map_err(|_| OpenAiError::internal("No model configured"))?,
+    };
+    Ok((provider, model))
+}
+
+enum TurnEvent {
+    Text(String),
+    Error(String),
+    /// The turn is over; always the last event
+    Done(Usage),
+}
+
+/// Run the agent turn, sending the assistant's text as it streams in
+async fn run_turn(
+    state: Arc<AppState>,
+    turn: PreparedTurn,
+    tx: mpsc::Sender<TurnEvent>,
+    cancel_token: CancellationToken,
+) {
+    let session_config = SessionConfig {
+        id: turn.session_id.clone(),
+        schedule_id: None,
+        max_turns: None,
+        retry_config: None,
+    };
+
+    match turn
+        .agent
+        .reply(
+            turn.user_message,
+            session_config,
+            Some(cancel_token.clone()),
+        )
+        .await
+    {
+        Ok(mut stream) => {
+            let mut last_message_id: Option<String> = None;
+            let mut sent_text = false;
+            while let Some(event) = stream.next().await {
+                if let Ok(AgentEvent::Message(message)) = &event {
+                    decline_confirmations(&turn.agent, message).await;
+                }
+                let text = match event {
+                    Ok(AgentEvent::Message(message))
+                        if message.role == Role::Assistant && message.is_user_visible() =>
+                    {
+                        let text = message.as_concat_text();
+                        if text.is_empty() {
+                            continue;
+                        }
+                        // Streamed chunks share an id; separate distinct messages
+                        let new_message = message.id.is_none() || message.id != last_message_id;
+                        last_message_id = message.id.clone();
+                        if sent_text && new_message {
+                            format!("\n\n{}", text)
+                        } else {
+                            text
+                        }
+                    }
+                    Ok(_) => continue,
+                    Err(e) => {
+                        let _ = tx.send(TurnEvent::Error(e.to_string())).await;
+                        break;
+                    }
+                };
+                sent_text = true;
+                if tx.send(TurnEvent::Text(text)).await.is_err() {
+                    cancel_token.cancel();
+                    break;
+                }
+            }
+        }
+        Err(e) => {
+            let _ = tx.send(TurnEvent::Error(e.to_string())).await;
+        }
+    }
+
+    let usage = session_usage(&turn.session_id).await;
+    discard_session(&state, &turn.session_id).await;
+    let _ = tx.send(TurnEvent::Done(usage)).await;
+}
+
+/// Decline the tool calls in `message` that wait for approval, so the turn can go on
+async fn decline_confirmations(agent: &Agent, message: &Message) {
+    for content in &message.content {
+        if let Some(request) = content.as_tool_confirmation_request() {
+            agent
+                .handle_confirmation(
+                    request.id.clone(),
+                    PermissionConfirmation {
+                        principal_type: PrincipalType::Tool,
+                        permission: Permission::DenyOnce,
+                    },
+                )
+                .await;
+        }
+    }
+}
+
+async fn session_usage(session_id: &str) -> Usage {
+    SessionManager::get_session(session_id, false)
+        .await
+        .map(|session| Usage {
+            prompt_tokens: session.accumulated_input_tokens.unwrap_or(0),
+            completion_tokens: session.accumulated_output_tokens.unwrap_or(0),
+            total_tokens: session.accumulated_total_tokens.unwrap_or(0),
+        })
+        .unwrap_or_default()
+}
+
+fn chunk(
+    id: &str,
+    model: &str,
+    created: i64,
+    delta: ChunkDelta,
+    finish_reason: Option<&'static str>,
+    usage: Option<Usage>,
+) -> Bytes {
+    let chunk = ChatCompletionChunk {
+        id: id.to_string(),
+        object: "chat.completion.chunk",
+        created,
+        model: model.to_string(),
+        choices: vec![ChunkChoice {
+            index: 0,
+            delta,
+            finish_reason,
+        }],
+        usage,
+    };
+    sse_data(&serde_json::to_string(&chunk).unwrap_or_default())
+}
+
+fn sse_data(data: &str) -> Bytes {
+    Bytes::from(format!("data: {}\n\n", data))
+}
+
+async fn chat_completions(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Result<Response, OpenAiError> {
+    let turn = prepare_turn(&state, &caller, &request).await?;
+    respond(state, turn, request.model, request.stream).await
+}
+
+/// Run a prepared turn and answer with a completion, or a stream of chunks
+async fn respond(
+    state: Arc<AppState>,
+    turn: PreparedTurn,
+    model: String,
+    stream: bool,
+) -> Result<Response, OpenAiError> {
+    let id = format!("chatcmpl-{}", turn.session_id);
+    let created = chrono::Utc::now().timestamp();
+
+    let (tx, mut rx) = mpsc::channel(100);
+    let cancel_token = CancellationToken::new();
+    tokio::spawn(run_turn(state, turn, tx, cancel_token));
+
+    if !stream {
+        let mut content = String::new();
+        let mut usage = Usage::default();
+        while let Some(event) = rx.recv().await {
+            match event {
+                TurnEvent::Text(text) => content.push_str(&text),
+                TurnEvent::Error(error) => return Err(OpenAiError::internal(error)),
+                TurnEvent::Done(turn_usage) => usage = turn_usage,
+            }
+        }
+        let completion = ChatCompletion {
+            id,
+            object: "chat.completion",
+            created,
+            model,
+            choices: vec![CompletionChoice {
+                index: 0,
+                message: CompletionMessage {
+                    role: "assistant",
+                    content,
+                },
+                finish_reason: "stop",
+            }],
+            usage,
+        };
+        return Ok(Json(completion).into_response());
+    }
+
+    let first = chunk(
+        &id,
+        &model,
+        created,
+        ChunkDelta {
+            role: Some("assistant"),
+            content: None,
+        },
+        None,
+        None,
+    );
+    let events = ReceiverStream::new(rx).flat_map(move |event| {
+        let chunks = match event {
+            TurnEvent::Text(text) => vec![chunk(
+                &id,
+                &model,
+                created,
+                ChunkDelta {
+                    role: None,
+                    content: Some(text),
+                },
+                None,
+                None,
+            )],
+            TurnEvent::Error(error) => {
+                vec![sse_data(
+                    &OpenAiError::body(&error, "server_error").to_string(),
+                )]
+            }
+            TurnEvent::Done(usage) => vec![
+                chunk(
+                    &id,
+                    &model,
+                    created,
+                    ChunkDelta::default(),
+                    Some("stop"),
+                    Some(usage),
+                ),
+                sse_data("[DONE]"),
+            ],
+        };
+        stream::iter(chunks)
+    });
+
+    let body = stream::once(async move { first })
+        .chain(events)
+        .map(Ok::<_, Infallible>);
+
+    Ok(http::Response::builder()
+        .header("Content-Type", "text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .body(axum::body::Body::from_stream(body))
+        .unwrap())
+}
+
+async fn list_models() -> Json<ModelList> {
+    let config = Config::global();
+    let mut data = vec![ModelEntry {
+        id: DEFAULT_MODEL.to_string(),
+        object: "model",
+        created: 0,
+        owned_by: "goose".to_string(),
+    }];
+    if let (Ok(provider), Ok(model)) = (config.get_goose_provider(), config.get_goose_model()) {
+        data.push(ModelEntry {
+            id: format!("{}/{}", provider, model),
+            object: "model",
+            created: 0,
+            owned_by: provider,
+        });
+    }
+    data.extend(saved_recipes().into_iter().map(|(name, _)| ModelEntry {
+        id: name,
+        object: "model",
+        created: 0,
+        owned_by: "goose".to_string(),
+    }));
+
+    Json(ModelList {
+        object: "list",
+        data,
+    })
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/v1/chat/completions",
+            post(chat_completions).layer(DefaultBodyLimit::max(50 * 1024 * 1024)),
+        )
+        .route("/v1/models", get(list_models))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use goose::conversation::message::MessageContent;
+    use goose::providers::base::{ProviderMetadata, ProviderUsage, Usage as ProviderTokens};
+    use goose::providers::errors::ProviderError;
+    use rmcp::model::{CallToolRequestParam, Tool};
+    use rmcp::object;
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+
+    /// Calls a shell tool, then records what the tool returned
+    #[derive(Default)]
+    struct ToolCallingProvider {
+        tool_results: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ToolCallingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "tool-calling"
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("tool-calling")
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            messages: &[Message],
+            tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let usage = ProviderUsage::new("tool-calling".to_string(), ProviderTokens::default());
+            // Session naming offers no tools
+            if tools.is_empty() {
+                return Ok((Message::assistant().with_text("API test"), usage));
+            }
+
+            let tool_result = messages
+                .last()
+                .and_then(|message| message.content.first())
+                .and_then(|content| match content {
+                    MessageContent::ToolResponse(response) => Some(match &response.tool_result {
+                        Ok(contents) => contents
+                            .iter()
+                            .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+                            .collect::<String>(),
+                        Err(e) => e.to_string(),
+                    }),
+                    _ => None,
+                });
+            let message = match tool_result {
+                Some(result) => {
+                    self.tool_results.lock().unwrap().push(result);
+                    Message::assistant().with_text("Done")
+                }
+                None => Message::assistant().with_tool_request(
+                    "call_1",
+                    Ok(CallToolRequestParam {
+                        name: "developer__shell".into(),
+                        arguments: Some(object!({ "command": "ls" })),
+                    }),
+                ),
+            };
+            Ok((message, usage))
+        }
+    }
+
+    #[test]
+    fn test_resolve_model() {
+        let recipes = vec![(
+            "release-notes".to_string(),
+            PathBuf::from("release-notes.yaml"),
+        )];
+
+        assert_eq!(resolve_model("", &recipes), ModelTarget::Default);
+        assert_eq!(resolve_model("goose", &recipes), ModelTarget::Default);
+        assert_eq!(
+            resolve_model("release-notes", &recipes),
+            ModelTarget::Recipe(PathBuf::from("release-notes.yaml"))
+        );
+        assert_eq!(
+            resolve_model("openrouter/anthropic/claude-sonnet-4", &recipes),
+            ModelTarget::Model {
+                provider: Some("openrouter".to_string()),
+                model: "anthropic/claude-sonnet-4".to_string(),
+            }
+        );
+        assert_eq!(
+            resolve_model("gpt-4o", &recipes),
+            ModelTarget::Model {
+                provider: None,
+                model: "gpt-4o".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_content_text() {
+        let request: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "goose",
+            "messages": [
+                {"role": "system", "content": "Be brief"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Describe"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}},
+                    {"type": "text", "text": "this"}
+                ]},
+                {"role": "assistant", "content": null}
+            ]
+        }))
+        .unwrap();
+
+        assert!(!request.stream);
+        assert_eq!(
+            request.messages[0].content.as_ref().unwrap().text(),
+            "Be brief"
+        );
+        assert_eq!(
+            request.messages[1].content.as_ref().unwrap().text(),
+            "Describe\nthis"
+        );
+        assert!(request.messages[2].content.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chat_completions_rejects_empty_messages() {
+        let state = AppState::new().await.unwrap();
+        let app = routes(state);
+
+        let request = Request::builder()
+            .uri("/v1/chat/completions")
+            .method("POST")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"model": "goose", "messages": []}"#))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["type"], "invalid_request_error");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tool_calls_needing_approval_are_declined() {
+        std::env::set_var("GOOSE_MODE", "approve");
+        let state = AppState::new().await.unwrap();
+        let session = SessionManager::create_session(
+            PathBuf::default(),
+            "API: tool test".to_string(),
+            SessionType::Hidden,
+        )
+        .await
+        .unwrap();
+        let agent = state.get_agent(session.id.clone()).await.unwrap();
+        let provider = Arc::new(ToolCallingProvider::default());
+        agent.update_provider(provider.clone()).await.unwrap();
+        let turn = PreparedTurn {
+            agent,
+            session_id: session.id.clone(),
+            user_message: Message::user().with_text("List the files"),
+        };
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(60),
+            respond(state, turn, DEFAULT_MODEL.to_string(), false),
+        )
+        .await
+        .expect("the turn must not wait for an approval")
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["choices"][0]["message"]["content"], "Done");
+        let tool_results = provider.tool_results.lock().unwrap().clone();
+        assert_eq!(tool_results.len(), 1);
+        assert!(tool_results[0].contains("declined"), "{:?}", tool_results);
+        assert!(SessionManager::get_session(&session.id, false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_models() {
+        let state = AppState::new().await.unwrap();
+        let app = routes(state);
+
+        let request = Request::builder()
+            .uri("/v1/models")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["object"], "list");
+        assert_eq!(body["data"][0]["id"], DEFAULT_MODEL);
+    }
+}
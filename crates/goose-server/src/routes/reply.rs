@@ -1,3 +1,4 @@
+use crate::auth::Caller;
 use crate::state::AppState;
 use axum::{
     extract::{DefaultBodyLimit, State},
@@ -8,7 +9,7 @@ use axum::{
 };
 use bytes::Bytes;
 use futures::{stream::StreamExt, Stream};
-use goose::conversation::message::{Message, MessageContent, TokenState};
+use goose::conversation::message::{Message, MessageContent, MessageEvent};
 use goose::conversation::Conversation;
 use goose::permission::{Permission, PermissionConfirmation};
 use goose::session::SessionManager;
@@ -16,7 +17,6 @@ use goose::{
     agents::{AgentEvent, SessionConfig},
     permission::permission_confirmation::PrincipalType,
 };
-use rmcp::model::ServerNotification;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{
@@ -121,56 +121,6 @@ impl IntoResponse for SseResponse {
     }
 }
 
-#[derive(Debug, Serialize, utoipa::ToSchema)]
-#[serde(tag = "type")]
-pub enum MessageEvent {
-    Message {
-        message: Message,
-        token_state: TokenState,
-    },
-    Error {
-        error: String,
-    },
-    Finish {
-        reason: String,
-        token_state: TokenState,
-    },
-    ModelChange {
-        model: String,
-        mode: String,
-    },
-    Notification {
-        request_id: String,
-        #[schema(value_type = Object)]
-        message: ServerNotification,
-    },
-    UpdateConversation {
-        conversation: Conversation,
-    },
-    Ping,
-}
-
-async fn get_token_state(session_id: &str) -> TokenState {
-    SessionManager::get_session(session_id, false)
-        .await
-        .map(|session| TokenState {
-            input_tokens: session.input_tokens.unwrap_or(0),
-            output_tokens: session.output_tokens.unwrap_or(0),
-            total_tokens: session.total_tokens.unwrap_or(0),
-            accumulated_input_tokens: session.accumulated_input_tokens.unwrap_or(0),
-            accumulated_output_tokens: session.accumulated_output_tokens.unwrap_or(0),
-            accumulated_total_tokens: session.accumulated_total_tokens.unwrap_or(0),
-        })
-        .inspect_err(|e| {
-            tracing::warn!(
-                "Failed to fetch session token state for {}: {}",
-                session_id,
-                e
-            );
-        })
-        .unwrap_or_default()
-}
-
 async fn stream_event(
     event: MessageEvent,
     tx: &mpsc::Sender<String>,
@@ -204,8 +154,10 @@ async fn stream_event(
 )]
 pub async fn reply(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(request): Json<ChatRequest>,
 ) -> Result<SseResponse, StatusCode> {
+    caller.authorize_session(&request.session_id).await?;
     let session_start = std::time::Instant::now();
 
     tracing::info!(
@@ -343,7 +295,7 @@ pub async fn reply(
 
                             all_messages.push(message.clone());
 
-                            let token_state = get_token_state(&session_id).await;
+                            let token_state = SessionManager::get_token_state(&session_id).await;
 
                             stream_event(MessageEvent::Message { message, token_state }, &tx, &cancel_token).await;
                         }
@@ -437,7 +389,7 @@ pub async fn reply(
             );
         }
 
-        let final_token_state = get_token_state(&session_id).await;
+        let final_token_state = SessionManager::get_token_state(&session_id).await;
 
         let _ = stream_event(
             MessageEvent::Finish {
@@ -477,8 +429,10 @@ fn default_principal_type() -> PrincipalType {
 )]
 pub async fn confirm_permission(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(request): Json<PermissionConfirmationRequest>,
 ) -> Result<Json<Value>, StatusCode> {
+    caller.authorize_session(&request.session_id).await?;
     let agent = state.get_agent_for_route(request.session_id).await?;
     let permission = match request.action.as_str() {
         "always_allow" => Permission::AlwaysAllow,
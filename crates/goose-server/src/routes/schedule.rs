@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
     http::StatusCode,
     routing::{delete, get, post, put},
@@ -9,13 +10,30 @@ use axum::{
 use serde::{Deserialize, Serialize};
 
 use crate::state::AppState;
-use goose::scheduler::ScheduledJob;
+use goose::scheduler::{RunPolicy, ScheduledJob};
+use goose::scheduler_history::{JobRun, DEFAULT_HISTORY_MAX_RUNS};
+use goose::scheduler_triggers::JobTrigger;
+use goose::session::Budget;
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
 pub struct CreateScheduleRequest {
     id: String,
     recipe_source: String,
+    /// Cron expression for the job, can be empty when the job has triggers
+    #[serde(default)]
     cron: String,
+    /// Spending limits for each session the job runs
+    #[serde(default)]
+    budget: Option<Budget>,
+    /// Events that run the job in addition to its cron expression
+    #[serde(default)]
+    triggers: Vec<JobTrigger>,
+    /// Seconds a trigger waits for further events before running the job
+    #[serde(default)]
+    debounce_seconds: Option<u64>,
+    /// Max runtime, retries and overlap handling for the job's runs
+    #[serde(default)]
+    run_policy: RunPolicy,
 }
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
@@ -53,6 +71,12 @@ pub struct SessionsQuery {
     limit: usize,
 }
 
+#[derive(Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+pub struct RunsQuery {
+    /// Maximum number of runs to return, newest first
+    limit: Option<usize>,
+}
+
 // Struct for the frontend session list
 #[derive(Serialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -77,7 +101,7 @@ pub struct SessionDisplayInfo {
     request_body = CreateScheduleRequest,
     responses(
         (status = 200, description = "Scheduled job created successfully", body = ScheduledJob),
-        (status = 400, description = "Invalid cron expression or recipe file"),
+        (status = 400, description = "Invalid cron expression, trigger or recipe file"),
         (status = 409, description = "Job ID already exists"),
         (status = 500, description = "Internal server error")
     ),
@@ -106,6 +130,10 @@ async fn create_schedule(
         paused: false,
         current_session_id: None,
         process_start_time: None,
+        budget: req.budget,
+        triggers: req.triggers,
+        debounce_seconds: req.debounce_seconds,
+        run_policy: req.run_policy,
     };
     scheduler
         .add_scheduled_job(job.clone())
@@ -116,6 +144,7 @@ async fn create_schedule(
                 goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
                 goose::scheduler::SchedulerError::CronParseError(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::RecipeLoadError(_) => StatusCode::BAD_REQUEST,
+                goose::scheduler::SchedulerError::TriggerError(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::JobIdExists(_) => StatusCode::CONFLICT,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }
@@ -272,6 +301,51 @@ async fn run_now_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/schedule/{id}/webhook",
+    params(
+        ("id" = String, Path, description = "ID of the schedule to trigger")
+    ),
+    request_body(content = Option<Object>, description = "JSON object whose fields are passed to the recipe as parameters"),
+    responses(
+        (status = 202, description = "Run queued; it starts once the job's debounce period has passed"),
+        (status = 400, description = "The job has no webhook trigger or the payload is not a JSON object"),
+        (status = 404, description = "Scheduled job not found"),
+        (status = 409, description = "The schedule is paused"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+#[axum::debug_handler]
+async fn webhook_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> Result<StatusCode, StatusCode> {
+    let payload = if body.is_empty() {
+        None
+    } else {
+        Some(serde_json::from_slice(&body).map_err(|_| StatusCode::BAD_REQUEST)?)
+    };
+
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    scheduler.fire_webhook(&id, payload).await.map_err(|e| {
+        eprintln!("Error triggering schedule '{}': {:?}", id, e);
+        match e {
+            goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
+            goose::scheduler::SchedulerError::TriggerError(_) => StatusCode::BAD_REQUEST,
+            goose::scheduler::SchedulerError::AnyhowError(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    })?;
+    Ok(StatusCode::ACCEPTED)
+}
+
 #[utoipa::path(
     get,
     path = "/schedule/{id}/sessions",
@@ -330,6 +404,41 @@ async fn sessions_handler(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/schedule/{id}/runs",
+    params(
+        ("id" = String, Path, description = "ID of the schedule"),
+        RunsQuery
+    ),
+    responses(
+        (status = 200, description = "The job's run history, newest first", body = Vec<JobRun>),
+        (status = 404, description = "Scheduled job not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+async fn runs_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<RunsQuery>,
+) -> Result<Json<Vec<JobRun>>, StatusCode> {
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_MAX_RUNS);
+    match scheduler.runs(&id, limit).await {
+        Ok(runs) => Ok(Json(runs)),
+        Err(goose::scheduler::SchedulerError::JobNotFound(_)) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Error fetching runs for schedule '{}': {}", id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/schedule/{id}/pause",
@@ -535,10 +644,12 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/schedule/delete/{id}", delete(delete_schedule)) // Corrected
         .route("/schedule/{id}", put(update_schedule))
         .route("/schedule/{id}/run_now", post(run_now_handler)) // Corrected
+        .route("/schedule/{id}/webhook", post(webhook_handler))
         .route("/schedule/{id}/pause", post(pause_schedule))
         .route("/schedule/{id}/unpause", post(unpause_schedule))
         .route("/schedule/{id}/kill", post(kill_running_job))
         .route("/schedule/{id}/inspect", get(inspect_running_job))
         .route("/schedule/{id}/sessions", get(sessions_handler)) // Corrected
+        .route("/schedule/{id}/runs", get(runs_handler))
         .with_state(state)
 }
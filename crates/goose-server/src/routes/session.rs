@@ -1,7 +1,8 @@
+use crate::auth::Caller;
 use crate::routes::errors::ErrorResponse;
 use crate::routes::recipe_utils::{apply_recipe_to_agent, build_recipe_with_parameter_values};
 use crate::state::AppState;
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::routing::post;
 use axum::{
     extract::Path,
@@ -9,9 +10,10 @@ use axum::{
     routing::{delete, get, put},
     Json, Router,
 };
+use goose::agents::{collect_file_edits, FileRevert};
 use goose::recipe::Recipe;
 use goose::session::session_manager::SessionInsights;
-use goose::session::{Session, SessionManager};
+use goose::session::{Session, SessionManager, SessionSearchQuery, SessionSearchResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -49,6 +51,47 @@ pub struct ImportSessionRequest {
     json: String,
 }
 
+#[derive(Deserialize, ToSchema, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchParams {
+    /// Words that must all appear in a matching message
+    query: String,
+    /// Maximum number of sessions to return (default 20)
+    limit: Option<usize>,
+    /// Only match messages at or after this time (RFC 3339)
+    after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only match messages at or before this time (RFC 3339)
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only sessions whose working directory contains this text
+    working_dir: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchResponse {
+    /// Matching sessions, most relevant first. Matched words in snippets are wrapped in <mark> tags.
+    results: Vec<SessionSearchResult>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkSessionRequest {
+    /// Number of messages to keep; the new session ends right before this message
+    message_index: usize,
+    /// Undo text editor changes made in the messages that are left out
+    #[serde(default)]
+    revert_file_edits: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkSessionResponse {
+    /// The new session, including its messages
+    session: Session,
+    /// Files that were reverted, empty unless requested
+    reverted_files: Vec<FileRevert>,
+}
+
 const MAX_NAME_LENGTH: usize = 200;
 
 #[utoipa::path(
@@ -64,10 +107,13 @@ const MAX_NAME_LENGTH: usize = 200;
     ),
     tag = "Session Management"
 )]
-async fn list_sessions() -> Result<Json<SessionListResponse>, StatusCode> {
-    let sessions = SessionManager::list_sessions()
+async fn list_sessions(caller: Caller) -> Result<Json<SessionListResponse>, StatusCode> {
+    let mut sessions = SessionManager::list_sessions()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(owner) = caller.owner() {
+        sessions.retain(|session| session.owner.as_ref() == Some(&owner));
+    }
 
     Ok(Json(SessionListResponse { sessions }))
 }
@@ -89,7 +135,11 @@ async fn list_sessions() -> Result<Json<SessionListResponse>, StatusCode> {
     ),
     tag = "Session Management"
 )]
-async fn get_session(Path(session_id): Path<String>) -> Result<Json<Session>, StatusCode> {
+async fn get_session(
+    caller: Caller,
+    Path(session_id): Path<String>,
+) -> Result<Json<Session>, StatusCode> {
+    caller.authorize_session(&session_id).await?;
     let session = SessionManager::get_session(&session_id, true)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
@@ -109,13 +159,48 @@ async fn get_session(Path(session_id): Path<String>) -> Result<Json<Session>, St
     ),
     tag = "Session Management"
 )]
-async fn get_session_insights() -> Result<Json<SessionInsights>, StatusCode> {
-    let insights = SessionManager::get_insights()
+async fn get_session_insights(caller: Caller) -> Result<Json<SessionInsights>, StatusCode> {
+    let insights = SessionManager::get_insights(caller.owner().as_deref())
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(Json(insights))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/search",
+    params(SessionSearchParams),
+    responses(
+        (status = 200, description = "Sessions matching the search", body = SessionSearchResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn search_sessions(
+    caller: Caller,
+    Query(params): Query<SessionSearchParams>,
+) -> Result<Json<SessionSearchResponse>, StatusCode> {
+    let query = SessionSearchQuery {
+        query: params.query,
+        limit: params.limit,
+        after_date: params.after,
+        before_date: params.before,
+        working_dir: params.working_dir,
+        owner: caller.owner(),
+        highlight: Some(("<mark>".to_string(), "</mark>".to_string())),
+    };
+
+    let results = SessionManager::search_sessions(&query)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(SessionSearchResponse { results }))
+}
+
 #[utoipa::path(
     put,
     path = "/sessions/{session_id}/name",
@@ -136,9 +221,11 @@ async fn get_session_insights() -> Result<Json<SessionInsights>, StatusCode> {
     tag = "Session Management"
 )]
 async fn update_session_name(
+    caller: Caller,
     Path(session_id): Path<String>,
     Json(request): Json<UpdateSessionNameRequest>,
 ) -> Result<StatusCode, StatusCode> {
+    caller.authorize_session(&session_id).await?;
     let name = request.name.trim();
     if name.is_empty() {
         return Err(StatusCode::BAD_REQUEST);
@@ -177,9 +264,14 @@ async fn update_session_name(
 // Update session user recipe parameter values
 async fn update_session_user_recipe_values(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(session_id): Path<String>,
     Json(request): Json<UpdateSessionUserRecipeValuesRequest>,
 ) -> Result<Json<UpdateSessionUserRecipeValuesResponse>, ErrorResponse> {
+    caller
+        .authorize_session(&session_id)
+        .await
+        .map_err(|_| ErrorResponse::session_not_found(&session_id))?;
     SessionManager::update_session(&session_id)
         .user_recipe_values(Some(request.user_recipe_values))
         .apply()
@@ -243,7 +335,11 @@ async fn update_session_user_recipe_values(
     ),
     tag = "Session Management"
 )]
-async fn delete_session(Path(session_id): Path<String>) -> Result<StatusCode, StatusCode> {
+async fn delete_session(
+    caller: Caller,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    caller.authorize_session(&session_id).await?;
     SessionManager::delete_session(&session_id)
         .await
         .map_err(|e| {
@@ -274,7 +370,11 @@ async fn delete_session(Path(session_id): Path<String>) -> Result<StatusCode, St
     ),
     tag = "Session Management"
 )]
-async fn export_session(Path(session_id): Path<String>) -> Result<Json<String>, StatusCode> {
+async fn export_session(
+    caller: Caller,
+    Path(session_id): Path<String>,
+) -> Result<Json<String>, StatusCode> {
+    caller.authorize_session(&session_id).await?;
     let exported = SessionManager::export_session(&session_id)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
@@ -298,15 +398,104 @@ async fn export_session(Path(session_id): Path<String>) -> Result<Json<String>,
     tag = "Session Management"
 )]
 async fn import_session(
+    caller: Caller,
     Json(request): Json<ImportSessionRequest>,
 ) -> Result<Json<Session>, StatusCode> {
-    let session = SessionManager::import_session(&request.json)
+    let mut session = SessionManager::import_session(&request.json)
         .await
         .map_err(|_| StatusCode::BAD_REQUEST)?;
 
+    if let Some(owner) = caller.owner() {
+        SessionManager::update_session(&session.id)
+            .owner(Some(owner.clone()))
+            .apply()
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        session.owner = Some(owner);
+    }
+
     Ok(Json(session))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/fork",
+    request_body = ForkSessionRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session to fork")
+    ),
+    responses(
+        (status = 200, description = "Session forked successfully", body = ForkSessionResponse),
+        (status = 400, description = "Bad request - Message index out of range", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Branch a session at a message, keeping the original session unchanged
+async fn fork_session(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(session_id): Path<String>,
+    Json(request): Json<ForkSessionRequest>,
+) -> Result<Json<ForkSessionResponse>, ErrorResponse> {
+    caller
+        .authorize_session(&session_id)
+        .await
+        .map_err(|_| ErrorResponse::session_not_found(&session_id))?;
+    let source = SessionManager::get_session(&session_id, true)
+        .await
+        .map_err(|err| ErrorResponse {
+            message: err.to_string(),
+            status: StatusCode::NOT_FOUND,
+        })?;
+    let messages = source
+        .conversation
+        .map(|conversation| conversation.messages().clone())
+        .unwrap_or_default();
+    if request.message_index > messages.len() {
+        return Err(ErrorResponse {
+            message: format!(
+                "Message index {} is out of range, the session has {} messages",
+                request.message_index,
+                messages.len()
+            ),
+            status: StatusCode::BAD_REQUEST,
+        });
+    }
+
+    let session = SessionManager::fork_session(&session_id, request.message_index)
+        .await
+        .map_err(|err| ErrorResponse {
+            message: err.to_string(),
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+
+    let rewound = &messages[request.message_index..];
+    let reverted_files = if request.revert_file_edits && !collect_file_edits(rewound).is_empty() {
+        // The source session's agent holds the editor history for its edits
+        let agent = state
+            .get_agent_for_route(session_id.clone())
+            .await
+            .map_err(|status| ErrorResponse {
+                message: format!("Failed to get agent: {}", status),
+                status,
+            })?;
+        agent.revert_file_edits(rewound).await
+    } else {
+        Vec::new()
+    };
+
+    Ok(Json(ForkSessionResponse {
+        session,
+        reverted_files,
+    }))
+}
+
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/sessions", get(list_sessions))
@@ -315,6 +504,8 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/sessions/{session_id}/export", get(export_session))
         .route("/sessions/import", post(import_session))
         .route("/sessions/insights", get(get_session_insights))
+        .route("/sessions/search", get(search_sessions))
+        .route("/sessions/{session_id}/fork", post(fork_session))
         .route("/sessions/{session_id}/name", put(update_session_name))
         .route(
             "/sessions/{session_id}/user_recipe_values",
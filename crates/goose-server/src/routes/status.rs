@@ -1,3 +1,4 @@
+use crate::auth::Caller;
 use axum::body::Body;
 use axum::http::HeaderValue;
 use axum::response::IntoResponse;
@@ -19,7 +20,8 @@ async fn status() -> String {
         (status = 500, description = "Failed to generate diagnostics"),
     )
 )]
-async fn diagnostics(Path(session_id): Path<String>) -> impl IntoResponse {
+async fn diagnostics(caller: Caller, Path(session_id): Path<String>) -> impl IntoResponse {
+    caller.authorize_session(&session_id).await?;
     match generate_diagnostics(&session_id).await {
         Ok(zip_data) => {
             let filename = format!("attachment; filename=\"diagnostics_{}.zip\"", session_id);
@@ -30,7 +30,7 @@ use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
 use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, SharedProvider, ToolResultReceiver};
 use crate::config::{get_enabled_extensions, Config, GooseMode};
-use crate::context_mgmt::DEFAULT_COMPACTION_THRESHOLD;
+use crate::context_mgmt::{ContextStrategyKind, DEFAULT_COMPACTION_THRESHOLD};
 use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
 use crate::mcp_utils::ToolResult;
 use crate::permission::permission_inspector::PermissionInspector;
@@ -61,10 +61,13 @@ use crate::agents::subagent_task_config::TaskConfig;
 use crate::conversation::message::{Message, MessageContent, SystemNotificationType, ToolRequest};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::session::extension_data::{EnabledExtensionsState, ExtensionState};
-use crate::session::{Session, SessionManager};
+use crate::session::{
+    BudgetStatus, Session, SessionManager, TurnJournal, TurnRecovery, INTERRUPTED_TOOL_CALL_REASON,
+};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
 const COMPACTION_THINKING_TEXT: &str = "goose is compacting the conversation...";
+const BUDGET_EXCEEDED_TEXT: &str = "Stopping here. Raise the budget to continue this session.";
 pub const MANUAL_COMPACT_TRIGGER: &str = "Please compact this conversation";
 
 /// Context needed for the reply function
@@ -104,6 +107,7 @@ pub struct Agent {
     pub(super) retry_manager: RetryManager,
     pub(super) tool_inspection_manager: ToolInspectionManager,
     pub(super) autopilot: Mutex<AutoPilot>,
+    pub(super) context_strategy: Mutex<Option<ContextStrategyKind>>,
 }
 
 #[derive(Clone, Debug)]
@@ -179,6 +183,7 @@ impl Agent {
             retry_manager: RetryManager::new(),
             tool_inspection_manager: Self::create_default_tool_inspection_manager(),
             autopilot: Mutex::new(AutoPilot::new()),
+            context_strategy: Mutex::new(None),
         }
     }
 
@@ -223,6 +228,7 @@ impl Agent {
         messages: &mut Conversation,
         session_config: &SessionConfig,
         initial_messages: &[Message],
+        working_dir: &std::path::Path,
     ) -> Result<bool> {
         let result = self
             .retry_manager
@@ -231,6 +237,8 @@ impl Agent {
                 session_config,
                 initial_messages,
                 &self.final_output_tool,
+                self.provider().await.ok(),
+                working_dir,
             )
             .await?;
 
@@ -260,15 +268,10 @@ impl Agent {
             );
         }
         let initial_messages = conversation.messages().clone();
-        let config = Config::global();
 
         let (tools, toolshim_tools, system_prompt) =
             self.prepare_tools_and_prompt(working_dir).await?;
-        let goose_mode = config.get_goose_mode().unwrap_or(GooseMode::Auto);
-
-        self.tool_inspection_manager
-            .update_permission_inspector_mode(goose_mode)
-            .await;
+        let goose_mode = self.prepare_tool_inspection(working_dir).await;
 
         Ok(ReplyContext {
             conversation,
@@ -280,6 +283,20 @@ impl Agent {
         })
     }
 
+    /// Point the permission inspector at the configured mode and the session's working
+    /// directory, returning the mode
+    async fn prepare_tool_inspection(&self, working_dir: &std::path::Path) -> GooseMode {
+        let goose_mode = Config::global().get_goose_mode().unwrap_or(GooseMode::Auto);
+
+        self.tool_inspection_manager
+            .update_permission_inspector_mode(goose_mode)
+            .await;
+        self.tool_inspection_manager
+            .update_permission_inspector_working_dir(working_dir)
+            .await;
+        goose_mode
+    }
+
     async fn categorize_tools(
         &self,
         response: &Message,
@@ -403,6 +420,16 @@ impl Agent {
         }
     }
 
+    /// Override the context strategy of the sessions this agent runs, e.g. with the one
+    /// chosen by a recipe
+    pub async fn set_context_strategy(&self, strategy: Option<ContextStrategyKind>) {
+        *self.context_strategy.lock().await = strategy;
+    }
+
+    pub async fn context_strategy(&self) -> Option<ContextStrategyKind> {
+        *self.context_strategy.lock().await
+    }
+
     /// Dispatch a single tool call to the appropriate client
     #[instrument(skip(self, tool_call, request_id), fields(input, output))]
     pub async fn dispatch_tool_call(
@@ -785,7 +812,25 @@ impl Agent {
             }
         });
 
+        // A turn that was interrupted and never resolved keeps its work, with its unfinished
+        // tool calls failed, so the new message follows a valid conversation
+        if SessionManager::get_turn_journal(&session_config.id)
+            .await?
+            .is_some()
+        {
+            SessionManager::resolve_interrupted_turn(
+                &session_config.id,
+                TurnRecovery::MarkFailed,
+                INTERRUPTED_TOOL_CALL_REASON,
+            )
+            .await?;
+        }
+
         SessionManager::add_message(&session_config.id, &user_message).await?;
+        let journal = TurnJournal::new(user_message.clone());
+        if !is_manual_compact {
+            Self::save_turn_journal(&session_config.id, &journal).await;
+        }
         let session = SessionManager::get_session(&session_config.id, true).await?;
 
         let conversation = session
@@ -793,16 +838,17 @@ impl Agent {
             .clone()
             .ok_or_else(|| anyhow::anyhow!("Session {} has no conversation", session_config.id))?;
 
-        let needs_auto_compact = !is_manual_compact
-            && crate::context_mgmt::check_if_compaction_needed(self, &conversation, None, &session)
-                .await?;
+        let compaction_strategy = if is_manual_compact {
+            Some(crate::context_mgmt::select_strategy(self, &session).await)
+        } else {
+            crate::context_mgmt::check_if_compaction_needed(self, &conversation, None, &session)
+                .await?
+        };
 
         let conversation_to_compact = conversation.clone();
 
         Ok(Box::pin(async_stream::try_stream! {
-            let final_conversation = if !needs_auto_compact && !is_manual_compact {
-                conversation
-            } else {
+            let final_conversation = if let Some(strategy) = compaction_strategy {
                 if !is_manual_compact {
                     let config = crate::config::Config::global();
                     let threshold = config
@@ -830,10 +876,11 @@ impl Agent {
                     )
                 );
 
-                match crate::context_mgmt::compact_messages(self, &conversation_to_compact, false).await {
+                match crate::context_mgmt::compact_messages(self, strategy.as_ref(), &conversation_to_compact, false).await {
                     Ok((compacted_conversation, summarization_usage)) => {
                         SessionManager::replace_conversation(&session_config.id, &compacted_conversation).await?;
-                        Self::update_session_metrics(&session_config, &summarization_usage, true).await?;
+                        let provider = self.provider().await?;
+                        Self::update_session_metrics(&session_config, provider.get_name(), &summarization_usage, true).await?;
 
                         yield AgentEvent::HistoryReplaced(compacted_conversation.clone());
 
@@ -855,10 +902,12 @@ impl Agent {
                         return;
                     }
                 }
+            } else {
+                conversation
             };
 
             if !is_manual_compact {
-                let mut reply_stream = self.reply_internal(final_conversation, session_config, session, cancel_token).await?;
+                let mut reply_stream = self.reply_internal(final_conversation, session_config, session, cancel_token, journal).await?;
                 while let Some(event) = reply_stream.next().await {
                     yield event?;
                 }
@@ -866,12 +915,152 @@ impl Agent {
         }))
     }
 
+    /// Continue a turn that was interrupted before it finished. The tool calls that had not
+    /// returned are run again after the same permission checks as any other tool call, so
+    /// denied tools stay denied and tools that need approval are confirmed again. The agent
+    /// loop then carries on from their results.
+    pub async fn rerun_interrupted_turn(
+        &self,
+        session_config: SessionConfig,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
+        let mut journal = SessionManager::get_turn_journal(&session_config.id)
+            .await?
+            .ok_or_else(|| anyhow!("Session {} has no interrupted turn", session_config.id))?;
+        let session = SessionManager::get_session(&session_config.id, true).await?;
+        let goose_mode = self.prepare_tool_inspection(&session.working_dir).await;
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let requests = journal.interrupted_tool_requests();
+            let message_tool_response = Arc::new(Mutex::new(Message::user()));
+
+            let (valid_requests, invalid_requests): (Vec<_>, Vec<_>) = requests
+                .into_iter()
+                .partition(|request| request.tool_call.is_ok());
+            for request in invalid_requests {
+                if let Err(e) = request.tool_call {
+                    let mut response = message_tool_response.lock().await;
+                    *response = response.clone().with_tool_response(request.id, Err(e));
+                }
+            }
+
+            if goose_mode == GooseMode::Chat {
+                for request in valid_requests {
+                    let mut response = message_tool_response.lock().await;
+                    *response = response.clone().with_tool_response(
+                        request.id.clone(),
+                        Ok(vec![Content::text(CHAT_MODE_TOOL_SKIPPED_RESPONSE)]),
+                    );
+                }
+            } else if !valid_requests.is_empty() {
+                let messages = session
+                    .conversation
+                    .as_ref()
+                    .map(|conversation| conversation.messages().clone())
+                    .unwrap_or_default();
+                let inspection_results = self.tool_inspection_manager
+                    .inspect_tools(&valid_requests, &messages)
+                    .await?;
+                let permission_check_result = self.tool_inspection_manager
+                    .process_inspection_results_with_permission_inspector(
+                        &valid_requests,
+                        &inspection_results,
+                    )
+                    .unwrap_or_else(|| PermissionCheckResult {
+                        approved: vec![],
+                        needs_approval: valid_requests.clone(),
+                        denied: vec![],
+                    });
+
+                let tool_futures = self.handle_approved_and_denied_tools(
+                    &permission_check_result,
+                    message_tool_response.clone(),
+                    cancel_token.clone(),
+                    &session,
+                ).await?;
+                let tool_futures_arc = Arc::new(Mutex::new(tool_futures));
+
+                let mut tool_approval_stream = self.handle_approval_tool_requests(
+                    &permission_check_result.needs_approval,
+                    tool_futures_arc.clone(),
+                    message_tool_response.clone(),
+                    cancel_token.clone(),
+                    &session,
+                    &inspection_results,
+                );
+                while let Some(msg) = tool_approval_stream.try_next().await? {
+                    yield AgentEvent::Message(msg);
+                }
+
+                let tool_futures = {
+                    let mut futures_lock = tool_futures_arc.lock().await;
+                    futures_lock.drain(..).collect::<Vec<_>>()
+                };
+                let with_id = tool_futures
+                    .into_iter()
+                    .map(|(request_id, stream)| stream.map(move |item| (request_id.clone(), item)))
+                    .collect::<Vec<_>>();
+                let mut combined = stream::select_all(with_id);
+                while let Some((request_id, item)) = combined.next().await {
+                    if is_token_cancelled(&cancel_token) {
+                        break;
+                    }
+                    match item {
+                        ToolStreamItem::Result(output) => {
+                            let mut response = message_tool_response.lock().await;
+                            *response = response.clone().with_tool_response(request_id, output);
+                        }
+                        ToolStreamItem::Message(msg) => {
+                            yield AgentEvent::McpNotification((request_id, msg));
+                        }
+                    }
+                }
+            }
+
+            let results = message_tool_response
+                .lock()
+                .await
+                .content
+                .iter()
+                .filter_map(|content| match content {
+                    MessageContent::ToolResponse(response) => {
+                        Some((response.id.clone(), response.tool_result.clone()))
+                    }
+                    _ => None,
+                })
+                .collect();
+            for message in journal.complete_with(results) {
+                SessionManager::add_message(&session_config.id, &message).await?;
+                yield AgentEvent::Message(message);
+            }
+            journal.begin_request();
+            Self::save_turn_journal(&session_config.id, &journal).await;
+
+            let session = SessionManager::get_session(&session_config.id, true).await?;
+            let conversation = session
+                .conversation
+                .clone()
+                .ok_or_else(|| anyhow!("Session {} has no conversation", session_config.id))?;
+            let mut reply_stream = self.reply_internal(conversation, session_config, session, cancel_token, journal).await?;
+            while let Some(event) = reply_stream.next().await {
+                yield event?;
+            }
+        }))
+    }
+
+    async fn save_turn_journal(session_id: &str, journal: &TurnJournal) {
+        if let Err(e) = SessionManager::save_turn_journal(session_id, journal).await {
+            warn!("Failed to save turn journal: {}", e);
+        }
+    }
+
     async fn reply_internal(
         &self,
         conversation: Conversation,
         session_config: SessionConfig,
         session: Session,
         cancel_token: Option<CancellationToken>,
+        mut journal: TurnJournal,
     ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
         let context = self
             .prepare_reply_context(conversation, &session.working_dir)
@@ -886,6 +1075,7 @@ impl Agent {
         } = context;
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
+        let recovery_strategy = crate::context_mgmt::select_strategy(self, &session).await;
 
         let provider = self.provider().await?;
         let session_id = session_config.id.clone();
@@ -901,6 +1091,38 @@ impl Agent {
             let mut turns_taken = 0u32;
             let max_turns = session_config.max_turns.unwrap_or(DEFAULT_MAX_TURNS);
 
+            let mut budget_warned = false;
+            if let Some(warning) = self.unpriced_budget_warning(&session_config).await? {
+                yield AgentEvent::Message(
+                    Message::assistant().with_system_notification(
+                        SystemNotificationType::InlineMessage,
+                        warning,
+                    )
+                );
+            }
+            match Self::budget_status(&session_config).await? {
+                BudgetStatus::Exceeded(msg) => {
+                    yield AgentEvent::Message(
+                        Message::assistant().with_system_notification(
+                            SystemNotificationType::InlineMessage,
+                            format!("{} {}", msg, BUDGET_EXCEEDED_TEXT),
+                        )
+                    );
+                    SessionManager::clear_turn_journal(&session_config.id).await?;
+                    return;
+                }
+                BudgetStatus::Warning(msg) => {
+                    budget_warned = true;
+                    yield AgentEvent::Message(
+                        Message::assistant().with_system_notification(
+                            SystemNotificationType::InlineMessage,
+                            msg,
+                        )
+                    );
+                }
+                BudgetStatus::Within => {}
+            }
+
             loop {
                 if is_token_cancelled(&cancel_token) {
                     break;
@@ -981,7 +1203,7 @@ impl Agent {
                             }
 
                             if let Some(ref usage) = usage {
-                                Self::update_session_metrics(&session_config, usage, false).await?;
+                                Self::update_session_metrics(&session_config, provider.get_name(), usage, false).await?;
                             }
 
                             if let Some(response) = response {
@@ -1004,6 +1226,9 @@ impl Agent {
                                     continue;
                                 }
 
+                                journal.record_tool_progress(messages_to_add.messages().clone());
+                                Self::save_turn_journal(&session_config.id, &journal).await;
+
                                 let message_tool_response = Arc::new(Mutex::new(Message::user().with_id(
                                     format!("msg_{}", Uuid::new_v4())
                                 )));
@@ -1113,6 +1338,11 @@ impl Agent {
                                                 let mut response = message_tool_response.lock().await;
                                                 *response =
                                                     response.clone().with_tool_response(request_id, output);
+
+                                                let mut pending = messages_to_add.messages().clone();
+                                                pending.push(response.clone());
+                                                journal.record_tool_progress(pending);
+                                                Self::save_turn_journal(&session_config.id, &journal).await;
                                             }
                                             ToolStreamItem::Message(msg) => {
                                                 yield AgentEvent::McpNotification((
@@ -1151,10 +1381,11 @@ impl Agent {
                                 )
                             );
 
-                            match crate::context_mgmt::compact_messages(self, &conversation, true).await {
+                            match crate::context_mgmt::compact_messages(self, recovery_strategy.as_ref(), &conversation, true).await {
                                 Ok((compacted_conversation, usage)) => {
                                     SessionManager::replace_conversation(&session_config.id, &compacted_conversation).await?;
-                                    Self::update_session_metrics(&session_config, &usage, true).await?;
+                                    let provider = self.provider().await?;
+                                    Self::update_session_metrics(&session_config, provider.get_name(), &usage, true).await?;
                                     conversation = compacted_conversation;
                                     did_recovery_compact_this_iteration = true;
                                     yield AgentEvent::HistoryReplaced(conversation.clone());
@@ -1203,7 +1434,7 @@ impl Agent {
                     } else if did_recovery_compact_this_iteration {
                         // Avoid setting exit_chat; continue from last user message in the conversation
                     } else {
-                        match self.handle_retry_logic(&mut conversation, &session_config, &initial_messages).await {
+                        match self.handle_retry_logic(&mut conversation, &session_config, &initial_messages, &working_dir).await {
                             Ok(should_retry) => {
                                 if should_retry {
                                     info!("Retry logic triggered, restarting agent loop");
@@ -1228,12 +1459,39 @@ impl Agent {
                     SessionManager::add_message(&session_config.id, msg).await?;
                 }
                 conversation.extend(messages_to_add);
+                journal.begin_request();
+                Self::save_turn_journal(&session_config.id, &journal).await;
+
+                match Self::budget_status(&session_config).await? {
+                    BudgetStatus::Exceeded(msg) => {
+                        yield AgentEvent::Message(
+                            Message::assistant().with_system_notification(
+                                SystemNotificationType::InlineMessage,
+                                format!("{} {}", msg, BUDGET_EXCEEDED_TEXT),
+                            )
+                        );
+                        break;
+                    }
+                    BudgetStatus::Warning(msg) if !budget_warned => {
+                        budget_warned = true;
+                        yield AgentEvent::Message(
+                            Message::assistant().with_system_notification(
+                                SystemNotificationType::InlineMessage,
+                                msg,
+                            )
+                        );
+                    }
+                    _ => {}
+                }
+
                 if exit_chat {
                     break;
                 }
 
                 tokio::task::yield_now().await;
             }
+
+            SessionManager::clear_turn_journal(&session_config.id).await?;
         }))
     }
 
@@ -1490,6 +1748,8 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            context_strategy: self.context_strategy().await,
+            budget: None,
         };
 
         tracing::debug!(
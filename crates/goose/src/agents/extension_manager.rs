@@ -584,6 +584,26 @@ impl ExtensionManager {
             .insert(name, Extension::new(config, client, info, temp_dir));
     }
 
+    /// Name under which the extension for `config` is added
+    pub fn extension_name(config: &ExtensionConfig) -> String {
+        normalize(config.key())
+    }
+
+    /// Replace the client of every extension with `wrap(name, client, server_info)`,
+    /// e.g. to record the traffic with its server
+    pub async fn wrap_clients<F>(&self, wrap: F)
+    where
+        F: Fn(&str, McpClientBox, Option<&ServerInfo>) -> McpClientBox,
+    {
+        for (name, extension) in self.extensions.lock().await.iter_mut() {
+            extension.client = wrap(
+                name,
+                extension.client.clone(),
+                extension.server_info.as_ref(),
+            );
+        }
+    }
+
     /// Get extensions info
     pub async fn get_extensions_info(&self) -> Vec<ExtensionInfo> {
         self.extensions
@@ -0,0 +1,196 @@
+use crate::agents::Agent;
+use crate::conversation::message::{Message, MessageContent};
+use rmcp::model::CallToolRequestParam;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+/// Suffix of the developer extension's text editor tool, which keeps an undo history per file
+const TEXT_EDITOR_TOOL_SUFFIX: &str = "__text_editor";
+const EDIT_COMMANDS: [&str; 3] = ["write", "str_replace", "insert"];
+const UNDO_COMMAND: &str = "undo_edit";
+
+/// Edits made to one file by a text editor tool, undone one at a time through its `undo_edit` command
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEdits {
+    pub tool_name: String,
+    pub path: String,
+    pub edits: usize,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRevert {
+    pub path: String,
+    /// Number of edits that were undone
+    pub reverted: usize,
+    /// Why the file could not be fully reverted, if it could not
+    pub error: Option<String>,
+}
+
+/// Files edited by successful text editor calls in `messages`, in order of first edit.
+/// Edits applied as diffs and changes made by shell commands are not tracked.
+pub fn collect_file_edits(messages: &[Message]) -> Vec<FileEdits> {
+    let succeeded: HashSet<&str> = messages
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| match content {
+            MessageContent::ToolResponse(response) if response.tool_result.is_ok() => {
+                Some(response.id.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut edits: Vec<FileEdits> = Vec::new();
+    let mut counts: HashMap<(String, String), i64> = HashMap::new();
+
+    for content in messages.iter().flat_map(|message| &message.content) {
+        let MessageContent::ToolRequest(request) = content else {
+            continue;
+        };
+        let Ok(call) = &request.tool_call else {
+            continue;
+        };
+        if !call.name.ends_with(TEXT_EDITOR_TOOL_SUFFIX) || !succeeded.contains(request.id.as_str())
+        {
+            continue;
+        }
+        let Some(arguments) = &call.arguments else {
+            continue;
+        };
+        let (Some(path), Some(command)) = (
+            arguments.get("path").and_then(|v| v.as_str()),
+            arguments.get("command").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        if arguments.get("diff").is_some() {
+            continue;
+        }
+
+        let delta = match command {
+            c if EDIT_COMMANDS.contains(&c) => 1,
+            UNDO_COMMAND => -1,
+            _ => continue,
+        };
+        let key = (call.name.to_string(), path.to_string());
+        if !counts.contains_key(&key) {
+            edits.push(FileEdits {
+                tool_name: key.0.clone(),
+                path: key.1.clone(),
+                edits: 0,
+            });
+        }
+        let count = counts.entry(key).or_default();
+        *count = (*count + delta).max(0);
+    }
+
+    edits
+        .into_iter()
+        .filter_map(|mut file| {
+            let count = counts[&(file.tool_name.clone(), file.path.clone())];
+            file.edits = count as usize;
+            (count > 0).then_some(file)
+        })
+        .collect()
+}
+
+impl Agent {
+    /// Undo the text editor edits made in `messages`, e.g. the part of a conversation that is
+    /// being rewound. Relies on the editor's in-memory history, so edits made before the
+    /// extension was last restarted cannot be reverted.
+    pub async fn revert_file_edits(&self, messages: &[Message]) -> Vec<FileRevert> {
+        let mut reverts = Vec::new();
+
+        for file in collect_file_edits(messages) {
+            let mut revert = FileRevert {
+                path: file.path.clone(),
+                reverted: 0,
+                error: None,
+            };
+
+            for _ in 0..file.edits {
+                let call = CallToolRequestParam {
+                    name: file.tool_name.clone().into(),
+                    arguments: json!({ "path": file.path, "command": UNDO_COMMAND })
+                        .as_object()
+                        .cloned(),
+                };
+                let result = match self
+                    .extension_manager
+                    .dispatch_tool_call(call, CancellationToken::new())
+                    .await
+                {
+                    Ok(call_result) => call_result.result.await.map_err(|e| e.message.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                match result {
+                    Ok(_) => revert.reverted += 1,
+                    Err(e) => {
+                        revert.error = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            reverts.push(revert);
+        }
+
+        reverts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::message::Message;
+    use rmcp::model::{Content, ErrorCode, ErrorData};
+    use rmcp::object;
+
+    fn edit(id: &str, path: &str, command: &str) -> Message {
+        Message::assistant().with_tool_request(
+            id,
+            Ok(CallToolRequestParam {
+                name: "developer__text_editor".into(),
+                arguments: Some(object!({ "path": path, "command": command })),
+            }),
+        )
+    }
+
+    fn ok(id: &str) -> Message {
+        Message::user().with_tool_response(id, Ok(vec![Content::text("done")]))
+    }
+
+    #[test]
+    fn test_collect_file_edits() {
+        let messages = vec![
+            edit("1", "/a.txt", "write"),
+            ok("1"),
+            edit("2", "/b.txt", "view"),
+            ok("2"),
+            edit("3", "/a.txt", "str_replace"),
+            ok("3"),
+            edit("4", "/b.txt", "insert"),
+            Message::user().with_tool_response(
+                "4",
+                Err(ErrorData::new(ErrorCode::INVALID_PARAMS, "bad", None)),
+            ),
+            edit("5", "/c.txt", "write"),
+            ok("5"),
+            edit("6", "/c.txt", "undo_edit"),
+            ok("6"),
+        ];
+
+        assert_eq!(
+            collect_file_edits(&messages),
+            vec![FileEdits {
+                tool_name: "developer__text_editor".to_string(),
+                path: "/a.txt".to_string(),
+                edits: 2,
+            }]
+        );
+    }
+}
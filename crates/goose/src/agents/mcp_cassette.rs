@@ -0,0 +1,608 @@
+use crate::agents::mcp_client::{Error, McpClientTrait};
+use async_trait::async_trait;
+use base64::Engine;
+use rmcp::model::{
+    CallToolResult, ErrorData, GetPromptResult, InitializeResult, JsonObject, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, ReadResourceResult, ServerNotification, Tool,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+type McpClientBox = Arc<Mutex<Box<dyn McpClientTrait>>>;
+
+/// Traffic with extension servers, recorded so a session can be replayed without them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpCassette {
+    pub extensions: BTreeMap<String, RecordedExtension>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordedExtension {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<InitializeResult>,
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+    /// Tool calls in the order they were made
+    #[serde(default)]
+    pub calls: Vec<RecordedToolCall>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Option<JsonObject>,
+    pub result: Result<CallToolResult, ErrorData>,
+    /// Files the call changed in the working directory, by path relative to it, with their
+    /// new base64 content, or `None` when the call removed them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_changes: BTreeMap<String, Option<String>>,
+}
+
+pub type SharedMcpCassette = Arc<std::sync::Mutex<McpCassette>>;
+/// Tool calls a replay was asked to make that have no recording
+pub type MissedToolCalls = Arc<std::sync::Mutex<Vec<String>>>;
+
+/// Contents of every file under `dir`, keyed by their path relative to it
+pub fn snapshot_dir(dir: &Path) -> std::io::Result<BTreeMap<String, Vec<u8>>> {
+    fn visit(
+        root: &Path,
+        dir: &Path,
+        files: &mut BTreeMap<String, Vec<u8>>,
+    ) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                visit(root, &path, files)?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                files.insert(relative, fs::read(&path)?);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = BTreeMap::new();
+    visit(dir, dir, &mut files)?;
+    Ok(files)
+}
+
+fn file_changes(
+    before: &BTreeMap<String, Vec<u8>>,
+    after: &BTreeMap<String, Vec<u8>>,
+) -> BTreeMap<String, Option<String>> {
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| before.get(*path) != after.get(*path))
+        .map(|path| {
+            let content = after
+                .get(path)
+                .map(|content| base64::prelude::BASE64_STANDARD.encode(content));
+            (path.clone(), content)
+        })
+        .collect()
+}
+
+/// Write the recorded effects of a tool call. Paths that would leave `dir` are ignored.
+fn apply_file_changes(
+    dir: &Path,
+    changes: &BTreeMap<String, Option<String>>,
+) -> std::io::Result<()> {
+    for (relative, content) in changes {
+        let relative = Path::new(relative);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            continue;
+        }
+        let path = dir.join(relative);
+        match content {
+            Some(content) => {
+                let content = base64::prelude::BASE64_STANDARD
+                    .decode(content)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, content)?;
+            }
+            None => match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Passes requests through to an extension's client, recording its tools and tool calls
+pub struct RecordingClient {
+    name: String,
+    inner: McpClientBox,
+    cassette: SharedMcpCassette,
+    working_dir: Option<PathBuf>,
+}
+
+impl RecordingClient {
+    pub fn new(
+        name: &str,
+        inner: McpClientBox,
+        info: Option<&InitializeResult>,
+        cassette: SharedMcpCassette,
+    ) -> Self {
+        cassette
+            .lock()
+            .unwrap()
+            .extensions
+            .entry(name.to_string())
+            .or_default()
+            .info = info.cloned();
+        Self {
+            name: name.to_string(),
+            inner,
+            cassette,
+            working_dir: None,
+        }
+    }
+
+    /// Also record the files each tool call changes in `dir`. Calls that run at the same
+    /// time may have their changes attributed to each other.
+    pub fn with_working_dir(mut self, dir: PathBuf) -> Self {
+        self.working_dir = Some(dir);
+        self
+    }
+
+    fn snapshot(&self) -> Option<BTreeMap<String, Vec<u8>>> {
+        let dir = self.working_dir.as_ref()?;
+        snapshot_dir(dir)
+            .inspect_err(|e| tracing::warn!("Failed to snapshot {}: {}", dir.display(), e))
+            .ok()
+    }
+
+    fn record<F: FnOnce(&mut RecordedExtension)>(&self, f: F) {
+        let mut cassette = self.cassette.lock().unwrap();
+        f(cassette.extensions.entry(self.name.clone()).or_default());
+    }
+}
+
+#[async_trait]
+impl McpClientTrait for RecordingClient {
+    async fn list_resources(
+        &self,
+        next_cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListResourcesResult, Error> {
+        let inner = self.inner.lock().await;
+        inner.list_resources(next_cursor, cancel_token).await
+    }
+
+    async fn read_resource(
+        &self,
+        uri: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<ReadResourceResult, Error> {
+        let inner = self.inner.lock().await;
+        inner.read_resource(uri, cancel_token).await
+    }
+
+    async fn list_tools(
+        &self,
+        next_cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListToolsResult, Error> {
+        let first_page = next_cursor.is_none();
+        let result = {
+            let inner = self.inner.lock().await;
+            inner.list_tools(next_cursor, cancel_token).await?
+        };
+        self.record(|extension| {
+            if first_page {
+                extension.tools.clear();
+            }
+            extension.tools.extend(result.tools.iter().cloned());
+        });
+        Ok(result)
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<JsonObject>,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        let before = self.snapshot();
+        let result = {
+            let inner = self.inner.lock().await;
+            inner.call_tool(name, arguments.clone(), cancel_token).await
+        };
+        let file_changes = before
+            .zip(self.snapshot())
+            .map(|(before, after)| file_changes(&before, &after))
+            .unwrap_or_default();
+        let recorded = match &result {
+            Ok(call_result) => Ok(call_result.clone()),
+            Err(Error::McpError(error)) => Err(error.clone()),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        };
+        self.record(|extension| {
+            extension.calls.push(RecordedToolCall {
+                name: name.to_string(),
+                arguments,
+                result: recorded,
+                file_changes,
+            })
+        });
+        result
+    }
+
+    async fn list_prompts(
+        &self,
+        next_cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListPromptsResult, Error> {
+        let inner = self.inner.lock().await;
+        inner.list_prompts(next_cursor, cancel_token).await
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<GetPromptResult, Error> {
+        let inner = self.inner.lock().await;
+        inner.get_prompt(name, arguments, cancel_token).await
+    }
+
+    async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+        self.inner.lock().await.subscribe().await
+    }
+
+    fn get_info(&self) -> Option<&InitializeResult> {
+        None
+    }
+}
+
+/// Serves an extension's recorded tools and answers tool calls from the recording.
+/// Each recorded call is used once, matching on tool name and arguments.
+pub struct ReplayingClient {
+    extension: RecordedExtension,
+    used: std::sync::Mutex<Vec<bool>>,
+    working_dir: Option<PathBuf>,
+    missed: MissedToolCalls,
+}
+
+impl ReplayingClient {
+    pub fn new(extension: RecordedExtension) -> Self {
+        let used = vec![false; extension.calls.len()];
+        Self {
+            extension,
+            used: std::sync::Mutex::new(used),
+            working_dir: None,
+            missed: MissedToolCalls::default(),
+        }
+    }
+
+    /// Apply the recorded file changes of each replayed call to `dir`
+    pub fn with_working_dir(mut self, dir: PathBuf) -> Self {
+        self.working_dir = Some(dir);
+        self
+    }
+
+    /// Add the names of calls that have no recording to `missed`
+    pub fn with_missed_calls(mut self, missed: MissedToolCalls) -> Self {
+        self.missed = missed;
+        self
+    }
+}
+
+#[async_trait]
+impl McpClientTrait for ReplayingClient {
+    async fn list_resources(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListResourcesResult, Error> {
+        Ok(ListResourcesResult {
+            resources: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<ReadResourceResult, Error> {
+        Err(Error::TransportClosed)
+    }
+
+    async fn list_tools(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListToolsResult, Error> {
+        Ok(ListToolsResult {
+            tools: self.extension.tools.clone(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Option<JsonObject>,
+        _cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        let index = {
+            let mut used = self.used.lock().unwrap();
+            let index = self
+                .extension
+                .calls
+                .iter()
+                .enumerate()
+                .position(|(i, call)| !used[i] && call.name == name && call.arguments == arguments);
+            if let Some(index) = index {
+                used[index] = true;
+            }
+            index
+        };
+        let Some(index) = index else {
+            self.missed.lock().unwrap().push(name.to_string());
+            return Err(Error::McpError(ErrorData::internal_error(
+                format!("No recorded call found for tool {}", name),
+                None,
+            )));
+        };
+
+        let call = &self.extension.calls[index];
+        if let Some(dir) = &self.working_dir {
+            apply_file_changes(dir, &call.file_changes).map_err(|e| {
+                Error::McpError(ErrorData::internal_error(
+                    format!("Failed to replay the file changes of {}: {}", name, e),
+                    None,
+                ))
+            })?;
+        }
+        call.result.clone().map_err(Error::McpError)
+    }
+
+    async fn list_prompts(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListPromptsResult, Error> {
+        Ok(ListPromptsResult {
+            prompts: vec![],
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        _name: &str,
+        _arguments: Value,
+        _cancel_token: CancellationToken,
+    ) -> Result<GetPromptResult, Error> {
+        Err(Error::TransportClosed)
+    }
+
+    async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+        mpsc::channel(1).1
+    }
+
+    fn get_info(&self) -> Option<&InitializeResult> {
+        self.extension.info.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+    use rmcp::object;
+
+    /// Echoes `text`, and writes it to `file` in its directory when given one
+    struct EchoClient(Option<PathBuf>);
+
+    #[async_trait]
+    impl McpClientTrait for EchoClient {
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+            _cancel_token: CancellationToken,
+        ) -> Result<ListResourcesResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn read_resource(
+            &self,
+            _uri: &str,
+            _cancel_token: CancellationToken,
+        ) -> Result<ReadResourceResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn list_tools(
+            &self,
+            _next_cursor: Option<String>,
+            _cancel_token: CancellationToken,
+        ) -> Result<ListToolsResult, Error> {
+            Ok(ListToolsResult {
+                tools: vec![Tool::new("echo", "Echo the text", object!({}))],
+                next_cursor: None,
+            })
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            arguments: Option<JsonObject>,
+            _cancel_token: CancellationToken,
+        ) -> Result<CallToolResult, Error> {
+            let text = arguments
+                .as_ref()
+                .and_then(|args| args.get("text"))
+                .and_then(|text| text.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let file = arguments
+                .as_ref()
+                .and_then(|args| args.get("file"))
+                .and_then(|file| file.as_str());
+            if let (Some(dir), Some(file)) = (&self.0, file) {
+                fs::write(dir.join(file), &text).unwrap();
+            }
+            Ok(CallToolResult::success(vec![Content::text(text)]))
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+            _cancel_token: CancellationToken,
+        ) -> Result<ListPromptsResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+            _cancel_token: CancellationToken,
+        ) -> Result<GetPromptResult, Error> {
+            Err(Error::TransportClosed)
+        }
+
+        async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+            mpsc::channel(1).1
+        }
+
+        fn get_info(&self) -> Option<&InitializeResult> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_tool_calls() {
+        let cassette = SharedMcpCassette::default();
+        let inner: McpClientBox = Arc::new(Mutex::new(Box::new(EchoClient(None))));
+        let recorder = RecordingClient::new("echo", inner, None, cassette.clone());
+
+        recorder
+            .list_tools(None, CancellationToken::new())
+            .await
+            .unwrap();
+        for text in ["first", "second"] {
+            recorder
+                .call_tool(
+                    "echo",
+                    Some(object!({ "text": text })),
+                    CancellationToken::new(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let recorded = cassette.lock().unwrap().extensions["echo"].clone();
+        assert_eq!(recorded.tools.len(), 1);
+        assert_eq!(recorded.calls.len(), 2);
+
+        let replayer = ReplayingClient::new(recorded);
+        let tools = replayer
+            .list_tools(None, CancellationToken::new())
+            .await
+            .unwrap();
+        assert_eq!(tools.tools[0].name, "echo");
+
+        let result = replayer
+            .call_tool(
+                "echo",
+                Some(object!({ "text": "second" })),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.content[0].as_text().unwrap().text, "second");
+
+        let unrecorded = replayer
+            .call_tool(
+                "echo",
+                Some(object!({ "text": "second" })),
+                CancellationToken::new(),
+            )
+            .await;
+        assert!(unrecorded.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_applies_recorded_file_changes() {
+        let recorded_dir = tempfile::tempdir().unwrap();
+        fs::write(recorded_dir.path().join("old.txt"), "old").unwrap();
+        let cassette = SharedMcpCassette::default();
+        let inner: McpClientBox = Arc::new(Mutex::new(Box::new(EchoClient(Some(
+            recorded_dir.path().to_path_buf(),
+        )))));
+        let recorder = RecordingClient::new("echo", inner, None, cassette.clone())
+            .with_working_dir(recorded_dir.path().to_path_buf());
+        recorder
+            .call_tool(
+                "echo",
+                Some(object!({ "text": "hello", "file": "notes.txt" })),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        let mut recorded = cassette.lock().unwrap().extensions["echo"].clone();
+        assert_eq!(
+            recorded.calls[0].file_changes.keys().collect::<Vec<_>>(),
+            vec!["notes.txt"]
+        );
+
+        recorded.calls[0].file_changes.insert(
+            "../outside.txt".to_string(),
+            Some("b3V0c2lkZQ==".to_string()),
+        );
+        recorded.calls[0]
+            .file_changes
+            .insert("old.txt".to_string(), None);
+        let replay_dir = tempfile::tempdir().unwrap();
+        fs::write(replay_dir.path().join("old.txt"), "old").unwrap();
+        let missed = MissedToolCalls::default();
+        let replayer = ReplayingClient::new(recorded)
+            .with_working_dir(replay_dir.path().to_path_buf())
+            .with_missed_calls(missed.clone());
+        replayer
+            .call_tool(
+                "echo",
+                Some(object!({ "text": "hello", "file": "notes.txt" })),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        let files = snapshot_dir(replay_dir.path()).unwrap();
+        assert_eq!(files.keys().collect::<Vec<_>>(), vec!["notes.txt"]);
+        assert_eq!(files["notes.txt"], b"hello");
+        assert!(!replay_dir.path().join("../outside.txt").exists());
+        assert!(missed.lock().unwrap().is_empty());
+
+        let unrecorded = replayer
+            .call_tool(
+                "echo",
+                Some(object!({ "text": "again" })),
+                CancellationToken::new(),
+            )
+            .await;
+        assert!(unrecorded.is_err());
+        assert_eq!(*missed.lock().unwrap(), vec!["echo".to_string()]);
+    }
+}
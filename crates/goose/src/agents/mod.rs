@@ -4,8 +4,10 @@ pub mod extension;
 pub mod extension_malware_check;
 pub mod extension_manager;
 pub mod extension_manager_extension;
+mod file_revert;
 pub mod final_output_tool;
 mod large_response_handler;
+pub mod mcp_cassette;
 pub mod mcp_client;
 pub mod model_selector;
 pub mod platform_tools;
@@ -29,6 +31,7 @@ pub mod types;
 pub use agent::{Agent, AgentEvent, MANUAL_COMPACT_TRIGGER};
 pub use extension::ExtensionConfig;
 pub use extension_manager::ExtensionManager;
+pub use file_revert::{collect_file_edits, FileEdits, FileRevert};
 pub use prompt_manager::PromptManager;
 pub use subagent_task_config::TaskConfig;
 pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
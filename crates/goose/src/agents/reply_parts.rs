@@ -4,20 +4,21 @@ use std::sync::Arc;
 use async_stream::try_stream;
 use futures::stream::StreamExt;
 use serde_json::{json, Value};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use super::super::agents::Agent;
 use crate::conversation::message::{Message, MessageContent, ToolRequest};
 use crate::conversation::Conversation;
 use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
+use crate::providers::pricing::{estimate_cost_usd, initialize_pricing_cache};
 use crate::providers::toolshim::{
     augment_message_with_tool_calls, convert_tool_messages_to_text,
     modify_system_prompt_for_tool_json, OllamaInterpreter,
 };
 
 use crate::agents::recipe_tools::dynamic_task_tools::should_enabled_subagents;
-use crate::session::SessionManager;
+use crate::session::{Budget, BudgetStatus, SessionManager};
 use rmcp::model::Tool;
 
 fn coerce_value(s: &str, schema: &Value) -> Value {
@@ -315,7 +316,8 @@ impl Agent {
         }
 
         let mut filtered_message =
-            Message::new(response.role.clone(), response.created, filtered_content);
+            Message::new(response.role.clone(), response.created, filtered_content)
+                .with_metadata(response.metadata.clone());
 
         // Preserve the ID if it exists
         if let Some(id) = response.id.clone() {
@@ -344,6 +346,7 @@ impl Agent {
 
     pub(crate) async fn update_session_metrics(
         session_config: &crate::agents::types::SessionConfig,
+        provider_name: &str,
         usage: &ProviderUsage,
         is_compaction_usage: bool,
     ) -> Result<()> {
@@ -364,6 +367,23 @@ impl Agent {
         let accumulated_output =
             accumulate(session.accumulated_output_tokens, usage.usage.output_tokens);
 
+        let cost = match (usage.usage.input_tokens, usage.usage.output_tokens) {
+            (None, None) => None,
+            (input, output) => {
+                estimate_cost_usd(
+                    provider_name,
+                    &usage.model,
+                    input.unwrap_or(0).max(0) as usize,
+                    output.unwrap_or(0).max(0) as usize,
+                )
+                .await
+            }
+        };
+        let accumulated_cost = match (session.accumulated_cost, cost) {
+            (Some(x), Some(y)) => Some(x + y),
+            (a, b) => a.or(b),
+        };
+
         let (current_total, current_input, current_output) = if is_compaction_usage {
             // After compaction: summary output becomes new input context
             let new_input = usage.usage.output_tokens;
@@ -384,11 +404,57 @@ impl Agent {
             .accumulated_total_tokens(accumulated_total)
             .accumulated_input_tokens(accumulated_input)
             .accumulated_output_tokens(accumulated_output)
+            .accumulated_cost(accumulated_cost)
             .apply()
             .await?;
 
         Ok(())
     }
+
+    /// A USD budget can only be enforced when the model's price is known. When such a
+    /// budget applies, this loads the pricing data if needed and returns a warning if the
+    /// price is still unknown.
+    pub(crate) async fn unpriced_budget_warning(
+        &self,
+        session_config: &crate::agents::types::SessionConfig,
+    ) -> Result<Option<String>> {
+        let session = SessionManager::get_session(&session_config.id, false).await?;
+        if Budget::for_session(&session).max_cost_usd.is_none() {
+            return Ok(None);
+        }
+
+        let provider = self.provider().await?;
+        let model = provider.get_model_config().model_name;
+        if estimate_cost_usd(provider.get_name(), &model, 0, 0)
+            .await
+            .is_some()
+        {
+            return Ok(None);
+        }
+        if let Err(e) = initialize_pricing_cache().await {
+            warn!("Failed to initialize pricing cache: {}", e);
+        }
+        if estimate_cost_usd(provider.get_name(), &model, 0, 0)
+            .await
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(format!(
+            "No pricing is known for {}/{}, so its USD budget cannot be enforced. Set a token budget to limit this session.",
+            provider.get_name(),
+            model
+        )))
+    }
+
+    /// Where the session stands against the budget that applies to it
+    pub(crate) async fn budget_status(
+        session_config: &crate::agents::types::SessionConfig,
+    ) -> Result<BudgetStatus> {
+        let session = SessionManager::get_session(&session_config.id, false).await?;
+        Ok(Budget::for_session(&session).status(&session))
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
@@ -11,9 +12,13 @@ use crate::agents::types::{
     RetryConfig, SuccessCheck, DEFAULT_ON_FAILURE_TIMEOUT_SECONDS, DEFAULT_RETRY_TIMEOUT_SECONDS,
 };
 use crate::config::Config;
-use crate::conversation::message::Message;
+use crate::conversation::message::{Message, MessageContent};
 use crate::conversation::Conversation;
+use crate::model::ModelConfig;
+use crate::providers::base::Provider;
 use crate::tool_monitor::RepetitionInspector;
+use rmcp::model::Role;
+use serde_json::Value;
 
 /// Result of a retry logic evaluation
 #[derive(Debug, Clone, PartialEq)]
@@ -28,6 +33,62 @@ pub enum RetryResult {
     Retried,
 }
 
+/// Outcome of running the success checks of a retry configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuccessCheckOutcome {
+    /// Every check passed
+    Passed,
+    /// A check failed, for the given reason
+    Failed { reason: String },
+}
+
+/// What the success checks can inspect about the finished attempt
+#[derive(Clone, Default)]
+pub struct SuccessCheckContext {
+    /// The recipe's structured response if it produced one, otherwise the text of the final
+    /// assistant message
+    pub final_output: String,
+    /// The session's provider, used by LLM-judge checks
+    pub provider: Option<Arc<dyn Provider>>,
+    /// The session's working directory, which relative file check paths are resolved against
+    pub working_dir: Option<PathBuf>,
+}
+
+impl SuccessCheckContext {
+    pub fn from_conversation(
+        conversation: &Conversation,
+        final_output: Option<String>,
+        provider: Option<Arc<dyn Provider>>,
+        working_dir: Option<PathBuf>,
+    ) -> Self {
+        let final_output = final_output.unwrap_or_else(|| {
+            conversation
+                .messages()
+                .iter()
+                .rev()
+                .find(|message| message.role == Role::Assistant && has_text(message))
+                .map(|message| message.as_concat_text())
+                .unwrap_or_default()
+        });
+        Self {
+            final_output,
+            provider,
+            working_dir,
+        }
+    }
+}
+
+fn has_text(message: &Message) -> bool {
+    message.content.iter().any(
+        |content| matches!(content, MessageContent::Text(text) if !text.text.trim().is_empty()),
+    )
+}
+
+const LLM_JUDGE_SYSTEM_PROMPT: &str =
+    "You review the output of an automated task against a rubric. \
+    Reply with only a JSON object of the form {\"pass\": true or false, \"reason\": \"...\"}. \
+    The reason must say briefly what is missing or wrong when the output does not pass.";
+
 /// Environment variable for configuring retry timeout globally
 const GOOSE_RECIPE_RETRY_TIMEOUT_SECONDS: &str = "GOOSE_RECIPE_RETRY_TIMEOUT_SECONDS";
 
@@ -108,23 +169,53 @@ impl RetryManager {
         }
     }
 
+    /// Tell the model why the previous attempt was not accepted, so the next one can fix it
+    fn add_failure_feedback(messages: &mut Conversation, reason: &str) {
+        let feedback = format!(
+            "A previous attempt at this task did not pass its success checks: {}\n\
+            Make sure this check passes this time.",
+            reason
+        );
+        let mut updated = messages.messages().clone();
+        match updated.last_mut().filter(|m| m.role == Role::User) {
+            Some(last) => last.content.push(MessageContent::text(feedback)),
+            None => updated.push(Message::user().with_text(feedback)),
+        }
+        *messages = Conversation::new_unvalidated(updated);
+    }
+
     pub async fn handle_retry_logic(
         &self,
         messages: &mut Conversation,
         session_config: &SessionConfig,
         initial_messages: &[Message],
         final_output_tool: &Arc<Mutex<Option<crate::agents::final_output_tool::FinalOutputTool>>>,
+        provider: Option<Arc<dyn Provider>>,
+        working_dir: &Path,
     ) -> Result<RetryResult> {
         let Some(retry_config) = &session_config.retry_config else {
             return Ok(RetryResult::Skipped);
         };
 
-        let success = execute_success_checks(&retry_config.checks, retry_config).await?;
-
-        if success {
-            info!("All success checks passed, no retry needed");
-            return Ok(RetryResult::SuccessChecksPassed);
-        }
+        let final_output = final_output_tool
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|tool| tool.final_output.clone());
+        let context = SuccessCheckContext::from_conversation(
+            messages,
+            final_output,
+            provider,
+            Some(working_dir.to_path_buf()),
+        );
+        let reason =
+            match execute_success_checks(&retry_config.checks, retry_config, &context).await? {
+                SuccessCheckOutcome::Passed => {
+                    info!("All success checks passed, no retry needed");
+                    return Ok(RetryResult::SuccessChecksPassed);
+                }
+                SuccessCheckOutcome::Failed { reason } => reason,
+            };
 
         let current_attempts = self.get_attempts().await;
         if current_attempts >= retry_config.max_retries {
@@ -146,6 +237,7 @@ impl RetryManager {
         }
 
         Self::reset_status_for_retry(messages, initial_messages, final_output_tool).await;
+        Self::add_failure_feedback(messages, &reason);
 
         let new_attempts = self.increment_attempts().await;
         info!("Incrementing retry attempts to {}", new_attempts);
@@ -184,34 +276,207 @@ fn get_on_failure_timeout(retry_config: &RetryConfig) -> Duration {
     Duration::from_secs(timeout_seconds)
 }
 
-/// Execute all success checks and return true if all pass
+/// Execute the success checks in order, stopping at the first one that fails
 pub async fn execute_success_checks(
     checks: &[SuccessCheck],
     retry_config: &RetryConfig,
-) -> Result<bool> {
+    context: &SuccessCheckContext,
+) -> Result<SuccessCheckOutcome> {
     let timeout = get_retry_timeout(retry_config);
 
     for check in checks {
-        match check {
-            SuccessCheck::Shell { command } => {
-                let result = execute_shell_command(command, timeout).await?;
-                if !result.status.success() {
-                    warn!(
-                        "Success check failed: command '{}' exited with status {}, stderr: {}",
-                        command,
-                        result.status,
-                        String::from_utf8_lossy(&result.stderr)
-                    );
-                    return Ok(false);
-                }
-                info!(
-                    "Success check passed: command '{}' completed successfully",
-                    command
-                );
+        if let Some(reason) = execute_success_check(check, timeout, context).await? {
+            warn!("Success check failed: {}", reason);
+            return Ok(SuccessCheckOutcome::Failed { reason });
+        }
+        debug!("Success check passed: {:?}", check);
+    }
+    Ok(SuccessCheckOutcome::Passed)
+}
+
+/// Run a single check, returning why it failed, or `None` if it passed
+async fn execute_success_check(
+    check: &SuccessCheck,
+    timeout: Duration,
+    context: &SuccessCheckContext,
+) -> Result<Option<String>> {
+    match check {
+        SuccessCheck::Shell { command } => {
+            let result = execute_shell_command(command, timeout).await?;
+            if result.status.success() {
+                return Ok(None);
+            }
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            let mut reason = format!("command '{}' exited with {}", command, result.status);
+            if !stderr.trim().is_empty() {
+                reason.push_str(&format!(", stderr: {}", stderr.trim()));
+            }
+            Ok(Some(reason))
+        }
+        SuccessCheck::File { path, contains } => {
+            let full_path = match &context.working_dir {
+                Some(working_dir) => working_dir.join(path),
+                None => PathBuf::from(path),
+            };
+            let content = match tokio::fs::read(&full_path).await {
+                Ok(content) => content,
+                Err(e) => return Ok(Some(format!("file '{}' could not be read: {}", path, e))),
+            };
+            Ok(contains
+                .as_ref()
+                .filter(|text| !String::from_utf8_lossy(&content).contains(text.as_str()))
+                .map(|text| format!("file '{}' does not contain '{}'", path, text)))
+        }
+        SuccessCheck::Regex { pattern } => {
+            let regex = regex::Regex::new(pattern)?;
+            if regex.is_match(&context.final_output) {
+                Ok(None)
+            } else {
+                Ok(Some(format!(
+                    "the final response does not match the pattern '{}'",
+                    pattern
+                )))
+            }
+        }
+        SuccessCheck::JsonSchema { schema } => {
+            let Some(output) = extract_json(&context.final_output) else {
+                return Ok(Some("the final response does not contain JSON".to_string()));
+            };
+            let validator = jsonschema::validator_for(schema)
+                .map_err(|e| anyhow::anyhow!("Invalid JSON schema in success check: {}", e))?;
+            let errors: Vec<String> = validator
+                .iter_errors(&output)
+                .map(|error| format!("{} at '{}'", error, error.instance_path))
+                .collect();
+            if errors.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(format!(
+                    "the JSON output does not match the schema: {}",
+                    errors.join("; ")
+                )))
             }
         }
+        SuccessCheck::Http { url, status } => {
+            let client = reqwest::Client::builder().timeout(timeout).build()?;
+            match client.get(url).send().await {
+                Ok(response) if response.status().as_u16() == *status => Ok(None),
+                Ok(response) => Ok(Some(format!(
+                    "GET {} returned status {}, expected {}",
+                    url,
+                    response.status().as_u16(),
+                    status
+                ))),
+                Err(e) => Ok(Some(format!("GET {} failed: {}", url, e))),
+            }
+        }
+        SuccessCheck::LlmJudge {
+            rubric,
+            provider,
+            model,
+        } => {
+            let verdict = tokio::time::timeout(
+                timeout,
+                judge_output(rubric, provider.as_deref(), model.as_deref(), context),
+            )
+            .await
+            .map_err(|_| anyhow::anyhow!("LLM judge check timed out after {:?}", timeout))??;
+            Ok(verdict)
+        }
+    }
+}
+
+/// Parse the JSON in a response: the whole text, a fenced code block, or the outermost
+/// object or array in it
+fn extract_json(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Ok(value) = serde_json::from_str(text) {
+        return Some(value);
+    }
+
+    let fenced = text.split("```").skip(1).step_by(2).find_map(|block| {
+        let block = block.strip_prefix("json").unwrap_or(block);
+        serde_json::from_str(block.trim()).ok()
+    });
+    if fenced.is_some() {
+        return fenced;
+    }
+
+    ['{', '[']
+        .iter()
+        .zip(['}', ']'])
+        .filter_map(|(open, close)| {
+            let start = text.find(*open)?;
+            let end = text.rfind(close)?;
+            text.get(start..=end)
+        })
+        .find_map(|candidate| serde_json::from_str(candidate).ok())
+}
+
+/// Ask a model whether the final output meets the rubric
+async fn judge_output(
+    rubric: &str,
+    provider_name: Option<&str>,
+    model: Option<&str>,
+    context: &SuccessCheckContext,
+) -> Result<Option<String>> {
+    let provider = match (provider_name, &context.provider) {
+        (Some(name), _) => {
+            let model = model
+                .ok_or_else(|| anyhow::anyhow!("LLM judge check with a provider needs a model"))?;
+            crate::providers::create(name, ModelConfig::new(model)?).await?
+        }
+        (None, Some(provider)) => provider.clone(),
+        (None, None) => anyhow::bail!("LLM judge check needs a provider"),
+    };
+    let model_config = match model {
+        Some(model) => ModelConfig::new(model)?,
+        None => provider.get_model_config(),
+    };
+
+    let prompt = format!(
+        "Rubric:\n{}\n\nOutput to review:\n{}",
+        rubric, context.final_output
+    );
+    let (response, _) = provider
+        .complete_with_model(
+            &model_config,
+            LLM_JUDGE_SYSTEM_PROMPT,
+            &[Message::user().with_text(prompt)],
+            &[],
+        )
+        .await?;
+    parse_verdict(&response.as_concat_text())
+}
+
+/// Read the judge's verdict, returning the reason when the output did not pass
+fn parse_verdict(text: &str) -> Result<Option<String>> {
+    if let Some(verdict) = extract_json(text) {
+        if let Some(pass) = verdict.get("pass").and_then(Value::as_bool) {
+            let reason = verdict
+                .get("reason")
+                .and_then(Value::as_str)
+                .unwrap_or("no reason given");
+            return Ok(
+                (!pass).then(|| format!("the LLM judge did not pass the output: {}", reason))
+            );
+        }
+    }
+
+    let upper = text.trim().to_uppercase();
+    if upper.starts_with("PASS") {
+        Ok(None)
+    } else if upper.starts_with("FAIL") {
+        Ok(Some(format!(
+            "the LLM judge did not pass the output: {}",
+            text.trim()
+        )))
+    } else {
+        Err(anyhow::anyhow!(
+            "Could not read a verdict from the LLM judge: {}",
+            text.trim()
+        ))
     }
-    Ok(true)
 }
 
 /// Execute a shell command with cross-platform compatibility and mandatory timeout
@@ -310,6 +575,58 @@ pub async fn execute_on_failure_command(command: &str, retry_config: &RetryConfi
 mod tests {
     use super::*;
     use crate::agents::types::SuccessCheck;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::errors::ProviderError;
+    use async_trait::async_trait;
+    use rmcp::model::Tool;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    struct JudgeProvider {
+        verdict: String,
+    }
+
+    #[async_trait]
+    impl Provider for JudgeProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "judge"
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("judge-model").unwrap()
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text(&self.verdict),
+                ProviderUsage::new("judge-model".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    fn context(final_output: &str) -> SuccessCheckContext {
+        SuccessCheckContext {
+            final_output: final_output.to_string(),
+            provider: None,
+            working_dir: None,
+        }
+    }
+
+    async fn check(check: SuccessCheck, context: &SuccessCheckContext) -> SuccessCheckOutcome {
+        execute_success_checks(&[check], &create_test_retry_config(), context)
+            .await
+            .unwrap()
+    }
 
     fn create_test_retry_config() -> RetryConfig {
         RetryConfig {
@@ -353,9 +670,9 @@ mod tests {
         ];
         let retry_config = create_test_retry_config();
 
-        let result = execute_success_checks(&checks, &retry_config).await;
-        assert!(result.is_ok());
-        assert!(result.unwrap());
+        let result =
+            execute_success_checks(&checks, &retry_config, &SuccessCheckContext::default()).await;
+        assert_eq!(result.unwrap(), SuccessCheckOutcome::Passed);
     }
 
     #[tokio::test]
@@ -370,9 +687,12 @@ mod tests {
         ];
         let retry_config = create_test_retry_config();
 
-        let result = execute_success_checks(&checks, &retry_config).await;
-        assert!(result.is_ok());
-        assert!(!result.unwrap());
+        let result =
+            execute_success_checks(&checks, &retry_config, &SuccessCheckContext::default()).await;
+        assert!(matches!(
+            result.unwrap(),
+            SuccessCheckOutcome::Failed { reason } if reason.contains("'false'")
+        ));
     }
 
     #[tokio::test]
@@ -494,4 +814,207 @@ mod tests {
         assert_eq!(on_failure_timeout, Duration::from_secs(300));
         assert_ne!(retry_timeout, on_failure_timeout);
     }
+
+    #[tokio::test]
+    async fn test_file_check() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.md");
+        std::fs::write(&path, "# Report\nAll tests passed").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let exists = SuccessCheck::File {
+            path: path.clone(),
+            contains: None,
+        };
+        assert_eq!(
+            check(exists, &context("")).await,
+            SuccessCheckOutcome::Passed
+        );
+
+        let contains = SuccessCheck::File {
+            path: path.clone(),
+            contains: Some("failed".to_string()),
+        };
+        assert!(matches!(
+            check(contains, &context("")).await,
+            SuccessCheckOutcome::Failed { reason } if reason.contains("does not contain 'failed'")
+        ));
+
+        let missing = SuccessCheck::File {
+            path: format!("{}.missing", path),
+            contains: None,
+        };
+        assert!(matches!(
+            check(missing, &context("")).await,
+            SuccessCheckOutcome::Failed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_file_check_is_relative_to_working_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("out")).unwrap();
+        std::fs::write(temp_dir.path().join("out/report.md"), "All tests passed").unwrap();
+        let context = SuccessCheckContext {
+            working_dir: Some(temp_dir.path().to_path_buf()),
+            ..context("")
+        };
+
+        let relative = SuccessCheck::File {
+            path: "out/report.md".to_string(),
+            contains: Some("passed".to_string()),
+        };
+        assert_eq!(check(relative, &context).await, SuccessCheckOutcome::Passed);
+
+        let missing = SuccessCheck::File {
+            path: "report.md".to_string(),
+            contains: None,
+        };
+        assert!(matches!(
+            check(missing, &context).await,
+            SuccessCheckOutcome::Failed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_regex_check() {
+        let regex = SuccessCheck::Regex {
+            pattern: r"(?i)\bdone\b".to_string(),
+        };
+        assert_eq!(
+            check(regex.clone(), &context("All Done.")).await,
+            SuccessCheckOutcome::Passed
+        );
+        assert!(matches!(
+            check(regex, &context("still working")).await,
+            SuccessCheckOutcome::Failed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_json_schema_check() {
+        let schema = SuccessCheck::JsonSchema {
+            schema: json!({
+                "type": "object",
+                "properties": {"count": {"type": "integer"}},
+                "required": ["count"]
+            }),
+        };
+        assert_eq!(
+            check(
+                schema.clone(),
+                &context("Here you go:\n```json\n{\"count\": 3}\n```")
+            )
+            .await,
+            SuccessCheckOutcome::Passed
+        );
+        assert!(matches!(
+            check(schema.clone(), &context(r#"{"count": "three"}"#)).await,
+            SuccessCheckOutcome::Failed { reason } if reason.contains("/count")
+        ));
+        assert!(matches!(
+            check(schema, &context("no json here")).await,
+            SuccessCheckOutcome::Failed { reason } if reason.contains("does not contain JSON")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_check() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = [0u8; 1024];
+                let _ = stream.read(&mut buffer).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\n\r\n")
+                    .await;
+            }
+        });
+
+        let expect_ok = SuccessCheck::Http {
+            url: url.clone(),
+            status: 200,
+        };
+        assert!(matches!(
+            check(expect_ok, &context("")).await,
+            SuccessCheckOutcome::Failed { reason } if reason.contains("returned status 503")
+        ));
+        let expect_unavailable = SuccessCheck::Http { url, status: 503 };
+        assert_eq!(
+            check(expect_unavailable, &context("")).await,
+            SuccessCheckOutcome::Passed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_llm_judge_check() {
+        let judge = SuccessCheck::LlmJudge {
+            rubric: "The summary mentions the number of failing tests".to_string(),
+            provider: None,
+            model: None,
+        };
+
+        let mut ctx = context("3 tests failed");
+        ctx.provider = Some(Arc::new(JudgeProvider {
+            verdict: r#"{"pass": true, "reason": "mentions 3 failures"}"#.to_string(),
+        }));
+        assert_eq!(
+            check(judge.clone(), &ctx).await,
+            SuccessCheckOutcome::Passed
+        );
+
+        ctx.provider = Some(Arc::new(JudgeProvider {
+            verdict: r#"{"pass": false, "reason": "no test count"}"#.to_string(),
+        }));
+        assert!(matches!(
+            check(judge, &ctx).await,
+            SuccessCheckOutcome::Failed { reason } if reason.contains("no test count")
+        ));
+    }
+
+    #[test]
+    fn test_parse_verdict_plain_text() {
+        assert_eq!(parse_verdict("PASS").unwrap(), None);
+        assert!(parse_verdict("FAIL: the file is empty")
+            .unwrap()
+            .unwrap()
+            .contains("the file is empty"));
+        assert!(parse_verdict("maybe").is_err());
+    }
+
+    #[test]
+    fn test_failure_feedback_joins_last_user_message() {
+        let mut messages =
+            Conversation::new_unvalidated(vec![Message::user().with_text("Write the report")]);
+        RetryManager::add_failure_feedback(&mut messages, "file 'report.md' could not be read");
+        assert_eq!(messages.len(), 1);
+        assert!(messages.messages()[0]
+            .as_concat_text()
+            .contains("file 'report.md' could not be read"));
+    }
+
+    #[test]
+    fn test_success_check_validation() {
+        assert!(SuccessCheck::Regex {
+            pattern: "(".to_string()
+        }
+        .validate()
+        .is_err());
+        assert!(SuccessCheck::LlmJudge {
+            rubric: "ok".to_string(),
+            provider: Some("openai".to_string()),
+            model: None,
+        }
+        .validate()
+        .is_err());
+        assert!(SuccessCheck::Http {
+            url: "http://localhost:8080/health".to_string(),
+            status: 200,
+        }
+        .validate()
+        .is_ok());
+    }
 }
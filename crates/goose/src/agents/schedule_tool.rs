@@ -162,6 +162,10 @@ impl Agent {
             paused: false,
             current_session_id: None,
             process_start_time: None,
+            budget: None,
+            triggers: Vec::new(),
+            debounce_seconds: None,
+            run_policy: crate::scheduler::RunPolicy::default(),
         };
 
         match scheduler.add_scheduled_job(job).await {
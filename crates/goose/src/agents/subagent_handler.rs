@@ -135,6 +135,9 @@ fn get_agent_messages(
         agent
             .apply_recipe_components(recipe.sub_recipes.clone(), recipe.response.clone(), true)
             .await;
+        agent
+            .set_context_strategy(recipe.settings.as_ref().and_then(|s| s.context_strategy))
+            .await;
 
         let user_message = Message::user().with_text(text_instruction);
         let mut conversation = Conversation::new_unvalidated(vec![user_message.clone()]);
@@ -2,6 +2,7 @@ use crate::mcp_utils::ToolResult;
 use crate::providers::base::Provider;
 use rmcp::model::{Content, Tool};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
 use utoipa::ToSchema;
@@ -57,6 +58,10 @@ impl RetryConfig {
             }
         }
 
+        for check in &self.checks {
+            check.validate()?;
+        }
+
         Ok(())
     }
 }
@@ -71,6 +76,77 @@ pub enum SuccessCheck {
         /// The shell command to execute
         command: String,
     },
+    /// Check that a file exists, and optionally that it contains some text
+    #[serde(alias = "file")]
+    File {
+        /// Path of the file, relative to the working directory
+        path: String,
+        /// Text the file must contain
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        contains: Option<String>,
+    },
+    /// Match a regular expression against the final assistant message
+    #[serde(alias = "regex")]
+    Regex {
+        /// The regular expression that must match
+        pattern: String,
+    },
+    /// Validate the JSON in the final assistant message, or the recipe's structured
+    /// response, against a JSON schema
+    #[serde(alias = "json_schema")]
+    JsonSchema {
+        /// The JSON schema the output must satisfy
+        #[schema(value_type = Object)]
+        schema: Value,
+    },
+    /// Request a URL, typically a local server started by the recipe, and compare the
+    /// response status
+    #[serde(alias = "http")]
+    Http {
+        /// The URL to request with GET
+        url: String,
+        /// The expected status code (default: 200)
+        #[serde(default = "default_http_status")]
+        status: u16,
+    },
+    /// Ask a model whether the final assistant message meets a rubric
+    #[serde(alias = "llm_judge")]
+    LlmJudge {
+        /// What the model should check the output against
+        rubric: String,
+        /// Provider to use for the verdict (default: the session's provider)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provider: Option<String>,
+        /// Model to use for the verdict (default: the session's model)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+    },
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+impl SuccessCheck {
+    /// Validates the check's settings
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SuccessCheck::Regex { pattern } => regex::Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|e| format!("invalid regex check pattern '{}': {}", pattern, e)),
+            SuccessCheck::JsonSchema { schema } => jsonschema::meta::validate(schema)
+                .map_err(|e| format!("invalid json_schema check schema: {}", e)),
+            SuccessCheck::Http { url, .. } => url::Url::parse(url)
+                .map(|_| ())
+                .map_err(|e| format!("invalid http check url '{}': {}", url, e)),
+            SuccessCheck::LlmJudge {
+                provider: Some(_),
+                model: None,
+                ..
+            } => Err("llm_judge checks that set a provider must also set a model".to_string()),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// A frontend tool that will be executed by the frontend rather than an extension
@@ -27,6 +27,16 @@ pub enum ProviderEngine {
     Anthropic,
 }
 
+/// The OpenAI API a provider sends requests to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenAiApi {
+    ChatCompletions,
+    /// The Responses API, which returns reasoning summaries and carries encrypted reasoning
+    /// over between turns
+    Responses,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DeclarativeProviderConfig {
     pub name: String,
@@ -39,6 +49,9 @@ pub struct DeclarativeProviderConfig {
     pub headers: Option<HashMap<String, String>>,
     pub timeout_seconds: Option<u64>,
     pub supports_streaming: Option<bool>,
+    /// Which OpenAI API to use when `engine` is `openai`; unset uses chat completions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<OpenAiApi>,
 }
 
 impl DeclarativeProviderConfig {
@@ -120,6 +133,7 @@ pub fn create_custom_provider(
         headers: None,
         timeout_seconds: None,
         supports_streaming,
+        api: None,
     };
 
     let custom_providers_dir = custom_providers_dir();
@@ -172,6 +186,7 @@ pub fn update_custom_provider(
             headers: existing_config.headers,
             timeout_seconds: existing_config.timeout_seconds,
             supports_streaming,
+            api: existing_config.api,
         };
 
         let file_path = custom_providers_dir().join(format!("{}.json", id));
@@ -5,6 +5,7 @@ pub mod extensions;
 pub mod goose_mode;
 pub mod paths;
 pub mod permission;
+pub mod permission_rules;
 pub mod search_path;
 pub mod signup_openrouter;
 pub mod signup_tetrate;
@@ -1,5 +1,7 @@
 use crate::config::paths::Paths;
+use crate::config::permission_rules::{deserialize_rules, evaluate_rules, PermissionRule};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -16,10 +18,16 @@ pub enum PermissionLevel {
 
 /// Struct representing the configuration of permissions, categorized by level.
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
+#[serde(default)]
 pub struct PermissionConfig {
     pub always_allow: Vec<String>, // List of tools that are always allowed
     pub ask_before: Vec<String>,   // List of tools that require user consent
     pub never_allow: Vec<String>,  // List of tools that are never allowed
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_rules"
+    )]
+    pub rules: Vec<PermissionRule>, // Argument-aware rules, checked before the lists above
 }
 
 /// PermissionManager manages permission configurations for various tools.
@@ -43,7 +51,10 @@ impl Default for PermissionManager {
             // Load the configuration file
             let file_contents =
                 fs::read_to_string(&config_path).expect("Failed to read permission.yaml");
-            serde_yaml::from_str(&file_contents).unwrap_or_else(|_| HashMap::new())
+            serde_yaml::from_str(&file_contents).unwrap_or_else(|e| {
+                tracing::error!("Ignoring invalid {}: {}", config_path.display(), e);
+                HashMap::new()
+            })
         } else {
             HashMap::new() // No config file, create an empty map
         };
@@ -65,7 +76,10 @@ impl PermissionManager {
             // Load the configuration file
             let file_contents =
                 fs::read_to_string(&config_path).expect("Failed to read permission.yaml");
-            serde_yaml::from_str(&file_contents).unwrap_or_else(|_| HashMap::new())
+            serde_yaml::from_str(&file_contents).unwrap_or_else(|e| {
+                tracing::error!("Ignoring invalid {}: {}", config_path.display(), e);
+                HashMap::new()
+            })
         } else {
             HashMap::new() // No config file, create an empty map
         };
@@ -86,6 +100,27 @@ impl PermissionManager {
         self.get_permission(USER_PERMISSION, principal_name)
     }
 
+    /// Retrieves the user permission level for a specific tool call, taking its arguments
+    /// into account. Matching argument rules take precedence over the plain tool lists.
+    /// Relative path patterns are resolved against `working_dir`.
+    pub fn get_user_permission_for_call(
+        &self,
+        principal_name: &str,
+        arguments: Option<&Map<String, Value>>,
+        working_dir: Option<&Path>,
+    ) -> Option<PermissionLevel> {
+        self.get_rule_permission(USER_PERMISSION, principal_name, arguments, working_dir)
+            .or_else(|| self.get_user_permission(principal_name))
+    }
+
+    /// Returns the argument rules configured for the user.
+    pub fn get_user_rules(&self) -> &[PermissionRule] {
+        self.permission_map
+            .get(USER_PERMISSION)
+            .map(|config| config.rules.as_slice())
+            .unwrap_or_default()
+    }
+
     /// Retrieves the smart approve permission level for a specific tool.
     pub fn get_smart_approve_permission(&self, principal_name: &str) -> Option<PermissionLevel> {
         self.get_permission(SMART_APPROVE_PERMISSION, principal_name)
@@ -121,6 +156,23 @@ impl PermissionManager {
         None // Return None if no matching permission level is found
     }
 
+    /// Helper function to evaluate the argument rules of a permission category for a tool call.
+    fn get_rule_permission(
+        &self,
+        name: &str,
+        principal_name: &str,
+        arguments: Option<&Map<String, Value>>,
+        working_dir: Option<&Path>,
+    ) -> Option<PermissionLevel> {
+        let permission_config = self.permission_map.get(name)?;
+        evaluate_rules(
+            &permission_config.rules,
+            principal_name,
+            arguments,
+            working_dir,
+        )
+    }
+
     /// Updates the user permission level for a specific tool.
     pub fn update_user_permission(&mut self, principal_name: &str, level: PermissionLevel) {
         self.update_permission(USER_PERMISSION, principal_name, level)
@@ -180,6 +232,9 @@ impl PermissionManager {
             permission_config
                 .never_allow
                 .retain(|p| !p.starts_with(extension_name));
+            permission_config
+                .rules
+                .retain(|rule| !rule.tool.starts_with(extension_name));
         }
 
         let yaml_content = serde_yaml::to_string(&self.permission_map)
@@ -281,6 +336,56 @@ mod tests {
         assert!(config.never_allow.contains(&"tool7".to_string()));
     }
 
+    #[test]
+    fn test_rules_take_precedence_over_tool_lists() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            r#"
+user:
+  ask_before:
+    - developer__shell
+  rules:
+    - tool: developer__shell
+      command_prefix: cargo test
+      level: always_allow
+"#,
+        )
+        .unwrap();
+        let manager = PermissionManager::new(temp_file.path());
+
+        let args = serde_json::json!({"command": "cargo test -p goose"});
+        assert_eq!(
+            manager.get_user_permission_for_call("developer__shell", args.as_object(), None),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+
+        let args = serde_json::json!({"command": "cargo publish"});
+        assert_eq!(
+            manager.get_user_permission_for_call("developer__shell", args.as_object(), None),
+            Some(PermissionLevel::AskBefore)
+        );
+    }
+
+    #[test]
+    fn test_rules_survive_permission_updates() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "user:\n  rules:\n    - tool: developer__shell\n      command_prefix: ls\n      level: always_allow\n",
+        )
+        .unwrap();
+        let mut manager = PermissionManager::new(temp_file.path());
+        manager.update_user_permission("tool1", PermissionLevel::AlwaysAllow);
+
+        let reloaded = PermissionManager::new(temp_file.path());
+        assert_eq!(reloaded.get_user_rules().len(), 1);
+        assert_eq!(
+            reloaded.get_user_permission("tool1"),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+    }
+
     #[test]
     fn test_remove_extension() {
         let mut manager = create_test_permission_manager();
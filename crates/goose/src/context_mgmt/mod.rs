@@ -1,4 +1,5 @@
-use crate::conversation::message::MessageMetadata;
+mod strategy;
+
 use crate::conversation::message::{Message, MessageContent};
 use crate::conversation::Conversation;
 use crate::prompt_template::render_global_file;
@@ -10,6 +11,12 @@ use serde::Serialize;
 use std::sync::Arc;
 use tracing::{debug, info};
 
+pub use strategy::{
+    ContextStrategy, ContextStrategyKind, ElideToolOutputsStrategy, HierarchicalStrategy,
+    SlidingWindowStrategy, SummarizeStrategy, CONTEXT_KEEP_TURNS_CONFIG_KEY,
+    CONTEXT_PINNED_TURNS_CONFIG_KEY, CONTEXT_STRATEGY_CONFIG_KEY,
+};
+
 pub const DEFAULT_COMPACTION_THRESHOLD: f64 = 0.8;
 
 #[derive(Serialize)]
@@ -17,136 +24,79 @@ struct SummarizeContext {
     messages: String,
 }
 
-/// Compact messages by summarizing them
+/// Compact messages with the given context strategy
 ///
-/// This function performs the actual compaction by summarizing messages and updating
-/// their visibility metadata. It does not check thresholds - use `check_if_compaction_needed`
-/// first to determine if compaction is necessary.
+/// This function performs the actual compaction and updates the visibility metadata of the
+/// messages. It does not check thresholds - use `check_if_compaction_needed` first to
+/// determine if compaction is necessary. If the strategy cannot make any room, the
+/// conversation is summarized instead.
 ///
 /// # Arguments
 /// * `agent` - The agent to use for context management
+/// * `strategy` - How to compact, see `select_strategy`
 /// * `conversation` - The current conversation history
 /// * `preserve_last_user_message` - If true and last message is not a user message, copy the most recent user message to the end
 ///
 /// # Returns
 /// * A tuple containing:
 ///   - `Conversation`: The compacted messages
-///   - `ProviderUsage`: Provider usage from summarization
+///   - `ProviderUsage`: Provider usage from summarization, if any
 pub async fn compact_messages(
     agent: &Agent,
+    strategy: &dyn ContextStrategy,
     conversation: &Conversation,
     preserve_last_user_message: bool,
 ) -> Result<(Conversation, ProviderUsage)> {
-    info!("Performing message compaction");
-
-    let messages = conversation.messages();
-
-    let has_text_only = |msg: &Message| {
-        let has_text = msg
-            .content
-            .iter()
-            .any(|c| matches!(c, MessageContent::Text(_)));
-        let has_tool_content = msg.content.iter().any(|c| {
-            matches!(
-                c,
-                MessageContent::ToolRequest(_) | MessageContent::ToolResponse(_)
-            )
-        });
-        has_text && !has_tool_content
-    };
-
-    // Helper function to extract text content from a message
-    let extract_text = |msg: &Message| -> Option<String> {
-        let text_parts: Vec<String> = msg
-            .content
-            .iter()
-            .filter_map(|c| {
-                if let MessageContent::Text(text) = c {
-                    Some(text.text.clone())
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if text_parts.is_empty() {
-            None
-        } else {
-            Some(text_parts.join("\n"))
-        }
-    };
-
-    // Check if the most recent message is a user message with text content only
-    let (messages_to_compact, preserved_user_text) = if let Some(last_message) = messages.last() {
-        if matches!(last_message.role, rmcp::model::Role::User) && has_text_only(last_message) {
-            // Remove the last user message before compaction and preserve its text
-            (&messages[..messages.len() - 1], extract_text(last_message))
-        } else if preserve_last_user_message {
-            // Last message is not a user message with text only, but we want to preserve the most recent user message with text only
-            // Find the most recent user message with text content only and extract its text
-            let preserved_text = messages
-                .iter()
-                .rev()
-                .find(|msg| matches!(msg.role, rmcp::model::Role::User) && has_text_only(msg))
-                .and_then(extract_text);
-            (messages.as_slice(), preserved_text)
-        } else {
-            (messages.as_slice(), None)
-        }
-    } else {
-        (messages.as_slice(), None)
-    };
+    info!("Performing message compaction with {:?}", strategy.kind());
 
     let provider = agent.provider().await?;
-    let (summary_message, summarization_usage) =
-        do_compact(provider.clone(), messages_to_compact).await?;
-
-    // Create the final message list with updated visibility metadata:
-    // 1. Original messages become user_visible but not agent_visible
-    // 2. Summary message becomes agent_visible but not user_visible
-    // 3. Assistant messages to continue the conversation remain both user_visible and agent_visible
-
-    let mut final_messages = Vec::new();
+    let (compacted, usage) = strategy
+        .compact(provider.clone(), conversation, preserve_last_user_message)
+        .await?;
 
-    // Add all original messages with updated visibility (preserve user_visible, set agent_visible=false)
-    for msg in messages_to_compact.iter().cloned() {
-        let updated_metadata = msg.metadata.with_agent_invisible();
-        let updated_msg = msg.with_metadata(updated_metadata);
-        final_messages.push(updated_msg);
+    if strategy.kind() != ContextStrategyKind::Summarize
+        && compacted.agent_visible_messages() == conversation.agent_visible_messages()
+    {
+        info!(
+            "{:?} could not reduce the context, summarizing instead",
+            strategy.kind()
+        );
+        return SummarizeStrategy
+            .compact(provider, conversation, preserve_last_user_message)
+            .await;
     }
 
-    // Add the summary message (agent_visible=true, user_visible=false)
-    let summary_msg = summary_message.with_metadata(MessageMetadata::agent_only());
-    final_messages.push(summary_msg);
-
-    // Add an assistant message to continue the conversation (agent_visible=true, user_visible=false)
-    let assistant_message = Message::assistant()
-        .with_text(
-            "The previous message contains a summary that was prepared because a context limit was reached.
-Do not mention that you read a summary or that conversation summarization occurred
-Just continue the conversation naturally based on the summarized context"
-        )
-        .with_metadata(MessageMetadata::agent_only());
-    final_messages.push(assistant_message);
-
-    // Add back the preserved user message if it exists
-    if let Some(user_text) = preserved_user_text {
-        final_messages.push(Message::user().with_text(&user_text));
-    }
+    Ok((compacted, usage))
+}
 
-    Ok((
-        Conversation::new_unvalidated(final_messages),
-        summarization_usage,
-    ))
+/// The context strategy for a session: the agent's override if it has one, then the
+/// strategy of the session's recipe, then `GOOSE_CONTEXT_STRATEGY`.
+pub async fn select_strategy(
+    agent: &Agent,
+    session: &crate::session::Session,
+) -> Box<dyn ContextStrategy> {
+    let recipe_strategy = session
+        .recipe
+        .as_ref()
+        .and_then(|recipe| recipe.settings.as_ref())
+        .and_then(|settings| settings.context_strategy);
+
+    agent
+        .context_strategy()
+        .await
+        .or(recipe_strategy)
+        .unwrap_or_else(ContextStrategyKind::from_config)
+        .build()
 }
 
-/// Check if messages exceed the auto-compaction threshold
+/// Check if messages exceed the auto-compaction threshold, returning the strategy to
+/// compact them with if they do
 pub async fn check_if_compaction_needed(
     agent: &Agent,
     conversation: &Conversation,
     threshold_override: Option<f64>,
     session: &crate::session::Session,
-) -> Result<bool> {
+) -> Result<Option<Box<dyn ContextStrategy>>> {
     let messages = conversation.messages();
     let config = Config::global();
     let threshold = threshold_override.unwrap_or_else(|| {
@@ -193,7 +143,10 @@ pub async fn check_if_compaction_needed(
         token_source
     );
 
-    Ok(needs_compaction)
+    if !needs_compaction {
+        return Ok(None);
+    }
+    Ok(Some(select_strategy(agent, session).await))
 }
 
 async fn do_compact(
@@ -235,6 +188,41 @@ async fn do_compact(
     Ok((response, provider_usage))
 }
 
+/// Whether `msg` is a user prompt: a user message with text and no tool content
+fn is_user_prompt(msg: &Message) -> bool {
+    let has_text = msg
+        .content
+        .iter()
+        .any(|c| matches!(c, MessageContent::Text(_)));
+    let has_tool_content = msg.content.iter().any(|c| {
+        matches!(
+            c,
+            MessageContent::ToolRequest(_) | MessageContent::ToolResponse(_)
+        )
+    });
+    msg.role == Role::User && has_text && !has_tool_content
+}
+
+fn message_text(msg: &Message) -> Option<String> {
+    let text_parts: Vec<String> = msg
+        .content
+        .iter()
+        .filter_map(|c| {
+            if let MessageContent::Text(text) = c {
+                Some(text.text.clone())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if text_parts.is_empty() {
+        None
+    } else {
+        Some(text_parts.join("\n"))
+    }
+}
+
 fn format_message_for_compacting(msg: &Message) -> String {
     let content_parts: Vec<String> = msg
         .content
@@ -0,0 +1,630 @@
+use super::{do_compact, is_user_prompt, message_text};
+use crate::config::Config;
+use crate::conversation::message::{Message, MessageContent, MessageMetadata, ToolResponse};
+use crate::conversation::Conversation;
+use crate::providers::base::{Provider, ProviderUsage, Usage};
+use anyhow::Result;
+use async_trait::async_trait;
+use rmcp::model::Content;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// Which context strategy to use when compacting, e.g. `sliding_window`.
+pub const CONTEXT_STRATEGY_CONFIG_KEY: &str = "GOOSE_CONTEXT_STRATEGY";
+/// Number of most recent turns kept verbatim by the strategies that keep recent turns.
+pub const CONTEXT_KEEP_TURNS_CONFIG_KEY: &str = "GOOSE_CONTEXT_KEEP_TURNS";
+/// Number of initial turns the sliding window always keeps. Only the first turns can be
+/// pinned; there is no way to pin a turn from the middle of the conversation.
+pub const CONTEXT_PINNED_TURNS_CONFIG_KEY: &str = "GOOSE_CONTEXT_PINNED_TURNS";
+
+const DEFAULT_KEEP_TURNS: usize = 3;
+const DEFAULT_PINNED_TURNS: usize = 1;
+
+const SUMMARY_CONTINUATION_TEXT: &str =
+    "The previous message contains a summary that was prepared because a context limit was reached.
+Do not mention that you read a summary or that conversation summarization occurred
+Just continue the conversation naturally based on the summarized context";
+
+const ELIDED_TOOL_OUTPUT: &str =
+    "[Tool output removed to save context. Call the tool again if you need it.]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextStrategyKind {
+    /// Summarize everything except the last user message
+    #[default]
+    Summarize,
+    /// Replace the output of tool calls before the most recent turns with a stub
+    ElideToolOutputs,
+    /// Drop the turns between the pinned first turns and the most recent turns
+    SlidingWindow,
+    /// Summarize everything except the most recent turns, which are kept verbatim
+    Hierarchical,
+}
+
+impl ContextStrategyKind {
+    pub fn from_config() -> Self {
+        Config::global()
+            .get_param(CONTEXT_STRATEGY_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Create the strategy, with its parameters read from the config.
+    pub fn build(self) -> Box<dyn ContextStrategy> {
+        let config = Config::global();
+        let keep_turns = config
+            .get_param(CONTEXT_KEEP_TURNS_CONFIG_KEY)
+            .unwrap_or(DEFAULT_KEEP_TURNS);
+
+        match self {
+            ContextStrategyKind::Summarize => Box::new(SummarizeStrategy),
+            ContextStrategyKind::ElideToolOutputs => {
+                Box::new(ElideToolOutputsStrategy { keep_turns })
+            }
+            ContextStrategyKind::SlidingWindow => Box::new(SlidingWindowStrategy {
+                keep_turns,
+                pinned_turns: config
+                    .get_param(CONTEXT_PINNED_TURNS_CONFIG_KEY)
+                    .unwrap_or(DEFAULT_PINNED_TURNS),
+            }),
+            ContextStrategyKind::Hierarchical => Box::new(HierarchicalStrategy { keep_turns }),
+        }
+    }
+}
+
+/// A way of shrinking a conversation that no longer fits in the context window.
+///
+/// Messages removed from the agent's context stay in the conversation as user-only
+/// messages, so the user still sees the full history.
+#[async_trait]
+pub trait ContextStrategy: Send + Sync {
+    fn kind(&self) -> ContextStrategyKind;
+
+    /// Compact `conversation`. If `preserve_last_user_message` is set, the most recent user
+    /// prompt must still be the last thing the agent sees.
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        conversation: &Conversation,
+        preserve_last_user_message: bool,
+    ) -> Result<(Conversation, ProviderUsage)>;
+}
+
+pub struct SummarizeStrategy;
+
+#[async_trait]
+impl ContextStrategy for SummarizeStrategy {
+    fn kind(&self) -> ContextStrategyKind {
+        ContextStrategyKind::Summarize
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        conversation: &Conversation,
+        preserve_last_user_message: bool,
+    ) -> Result<(Conversation, ProviderUsage)> {
+        let messages = conversation.messages();
+
+        // Check if the most recent message is a user message with text content only
+        let (messages_to_compact, preserved_user_text) = match messages.last() {
+            Some(last_message) if is_user_prompt(last_message) => {
+                // Remove the last user message before compaction and preserve its text
+                (&messages[..messages.len() - 1], message_text(last_message))
+            }
+            Some(_) if preserve_last_user_message => {
+                // Find the most recent user message with text content only and extract its text
+                let preserved_text = messages
+                    .iter()
+                    .rev()
+                    .find(|msg| is_user_prompt(msg))
+                    .and_then(message_text);
+                (messages.as_slice(), preserved_text)
+            }
+            _ => (messages.as_slice(), None),
+        };
+
+        let (summary_message, summarization_usage) =
+            do_compact(provider, messages_to_compact).await?;
+
+        let mut final_messages = with_summary(messages_to_compact, summary_message);
+
+        // Add back the preserved user message if it exists
+        if let Some(user_text) = preserved_user_text {
+            final_messages.push(Message::user().with_text(&user_text));
+        }
+
+        Ok((
+            Conversation::new_unvalidated(final_messages),
+            summarization_usage,
+        ))
+    }
+}
+
+pub struct ElideToolOutputsStrategy {
+    pub keep_turns: usize,
+}
+
+#[async_trait]
+impl ContextStrategy for ElideToolOutputsStrategy {
+    fn kind(&self) -> ContextStrategyKind {
+        ContextStrategyKind::ElideToolOutputs
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        conversation: &Conversation,
+        _preserve_last_user_message: bool,
+    ) -> Result<(Conversation, ProviderUsage)> {
+        let messages = conversation.messages();
+        let cutoff = recent_turns_start(messages, self.keep_turns).unwrap_or(0);
+
+        let mut final_messages = Vec::with_capacity(messages.len());
+        for (index, msg) in messages.iter().enumerate() {
+            if index >= cutoff || !msg.is_agent_visible() || !has_elidable_output(msg) {
+                final_messages.push(msg.clone());
+                continue;
+            }
+
+            let mut stub = msg.clone().with_metadata(MessageMetadata::agent_only());
+            stub.id = None;
+            stub.content = stub.content.into_iter().map(elide_output).collect();
+
+            final_messages.push(
+                msg.clone()
+                    .with_metadata(msg.metadata.clone().with_agent_invisible()),
+            );
+            final_messages.push(stub);
+        }
+
+        Ok((
+            Conversation::new_unvalidated(final_messages),
+            no_usage(provider.as_ref()),
+        ))
+    }
+}
+
+/// Keeps the first `pinned_turns` turns and the last `keep_turns` turns, and hides the
+/// turns in between from the agent. At least the latest turn is always kept, so the
+/// agent still sees the prompt it is working on.
+pub struct SlidingWindowStrategy {
+    pub keep_turns: usize,
+    pub pinned_turns: usize,
+}
+
+#[async_trait]
+impl ContextStrategy for SlidingWindowStrategy {
+    fn kind(&self) -> ContextStrategyKind {
+        ContextStrategyKind::SlidingWindow
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        conversation: &Conversation,
+        _preserve_last_user_message: bool,
+    ) -> Result<(Conversation, ProviderUsage)> {
+        let messages = conversation.messages();
+        let starts = turn_starts(messages);
+        let keep_turns = self.keep_turns.max(1);
+        if starts.len() <= self.pinned_turns + keep_turns {
+            return Ok((conversation.clone(), no_usage(provider.as_ref())));
+        }
+
+        let drop_from = match self.pinned_turns {
+            0 => 0,
+            pinned => starts[pinned],
+        };
+        let drop_to = starts[starts.len() - keep_turns];
+        let dropped = messages[drop_from..drop_to]
+            .iter()
+            .filter(|msg| msg.is_agent_visible())
+            .count();
+
+        let mut final_messages = messages[..drop_from].to_vec();
+        final_messages.extend(messages[drop_from..drop_to].iter().map(|msg| {
+            msg.clone()
+                .with_metadata(msg.metadata.clone().with_agent_invisible())
+        }));
+        final_messages.push(
+            Message::user()
+                .with_text(format!(
+                    "[{} earlier messages of this conversation were removed to stay within the context limit.]",
+                    dropped
+                ))
+                .with_metadata(MessageMetadata::agent_only()),
+        );
+        final_messages.extend(messages[drop_to..].iter().cloned());
+
+        Ok((
+            Conversation::new_unvalidated(final_messages),
+            no_usage(provider.as_ref()),
+        ))
+    }
+}
+
+pub struct HierarchicalStrategy {
+    pub keep_turns: usize,
+}
+
+#[async_trait]
+impl ContextStrategy for HierarchicalStrategy {
+    fn kind(&self) -> ContextStrategyKind {
+        ContextStrategyKind::Hierarchical
+    }
+
+    async fn compact(
+        &self,
+        provider: Arc<dyn Provider>,
+        conversation: &Conversation,
+        preserve_last_user_message: bool,
+    ) -> Result<(Conversation, ProviderUsage)> {
+        let messages = conversation.messages();
+        let Some(cutoff) = recent_turns_start(messages, self.keep_turns) else {
+            // Everything is recent, so summarizing is the only way to make room
+            return SummarizeStrategy
+                .compact(provider, conversation, preserve_last_user_message)
+                .await;
+        };
+
+        let (summary_message, summarization_usage) =
+            do_compact(provider, &messages[..cutoff]).await?;
+
+        let mut final_messages = with_summary(&messages[..cutoff], summary_message);
+        final_messages.extend(messages[cutoff..].iter().cloned());
+
+        Ok((
+            Conversation::new_unvalidated(final_messages),
+            summarization_usage,
+        ))
+    }
+}
+
+/// `messages` hidden from the agent, followed by the agent-only summary that replaces them.
+fn with_summary(messages: &[Message], summary_message: Message) -> Vec<Message> {
+    let mut final_messages: Vec<Message> = messages
+        .iter()
+        .map(|msg| {
+            msg.clone()
+                .with_metadata(msg.metadata.clone().with_agent_invisible())
+        })
+        .collect();
+
+    final_messages.push(summary_message.with_metadata(MessageMetadata::agent_only()));
+    final_messages.push(
+        Message::assistant()
+            .with_text(SUMMARY_CONTINUATION_TEXT)
+            .with_metadata(MessageMetadata::agent_only()),
+    );
+    final_messages
+}
+
+/// Indices of the agent-visible messages that start a turn: user prompts, as opposed to
+/// tool results.
+fn turn_starts(messages: &[Message]) -> Vec<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, msg)| msg.is_agent_visible() && is_user_prompt(msg))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Index of the first message of the last `keep_turns` turns, or `None` if there is
+/// nothing before them.
+fn recent_turns_start(messages: &[Message], keep_turns: usize) -> Option<usize> {
+    if keep_turns == 0 {
+        return Some(messages.len());
+    }
+    let starts = turn_starts(messages);
+    if starts.len() <= keep_turns {
+        return None;
+    }
+    Some(starts[starts.len() - keep_turns])
+}
+
+fn has_elidable_output(msg: &Message) -> bool {
+    msg.content.iter().any(|content| match content {
+        MessageContent::ToolResponse(response) => match &response.tool_result {
+            Ok(contents) => !is_elided(contents),
+            Err(_) => false,
+        },
+        _ => false,
+    })
+}
+
+fn is_elided(contents: &[Content]) -> bool {
+    matches!(contents, [content] if content.as_text().is_some_and(|t| t.text == ELIDED_TOOL_OUTPUT))
+}
+
+fn elide_output(content: MessageContent) -> MessageContent {
+    match content {
+        MessageContent::ToolResponse(response) if response.tool_result.is_ok() => {
+            MessageContent::ToolResponse(ToolResponse {
+                id: response.id,
+                tool_result: Ok(vec![Content::text(ELIDED_TOOL_OUTPUT)]),
+            })
+        }
+        other => other,
+    }
+}
+
+/// Usage of a strategy that does not call the model. Token counts are unknown until the
+/// next reply, so they are left empty.
+fn no_usage(provider: &dyn Provider) -> ProviderUsage {
+    ProviderUsage::new(provider.get_model_config().model_name, Usage::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ModelConfig;
+    use crate::providers::base::ProviderMetadata;
+    use crate::providers::errors::ProviderError;
+    use crate::providers::testprovider::TestProvider;
+    use rmcp::model::{CallToolRequestParam, Tool};
+    use rmcp::object;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct SummaryProvider {
+        system_prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for SummaryProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "summary"
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.system_prompts.lock().unwrap().push(system.to_string());
+            Ok((
+                Message::assistant().with_text("the summary"),
+                ProviderUsage::new("mock".to_string(), Usage::new(Some(100), Some(10), None)),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("mock")
+        }
+    }
+
+    /// `count` turns, each a prompt, a shell call with its output and a final answer
+    fn conversation(count: usize) -> Conversation {
+        let messages = (0..count).flat_map(|i| {
+            let id = format!("call{}", i);
+            vec![
+                Message::user().with_text(format!("task {}", i)),
+                Message::assistant().with_tool_request(
+                    &id,
+                    Ok(CallToolRequestParam {
+                        name: "developer__shell".into(),
+                        arguments: Some(object!({ "command": format!("cat file{}", i) })),
+                    }),
+                ),
+                Message::user()
+                    .with_tool_response(&id, Ok(vec![Content::text(format!("output {}", i))])),
+                Message::assistant().with_text(format!("done {}", i)),
+            ]
+        });
+        Conversation::new_unvalidated(messages)
+    }
+
+    fn user_view(conversation: &Conversation) -> Vec<Vec<MessageContent>> {
+        conversation
+            .user_visible_messages()
+            .into_iter()
+            .map(|msg| msg.content)
+            .collect()
+    }
+
+    fn agent_view(conversation: &Conversation) -> Vec<String> {
+        conversation
+            .agent_visible_messages()
+            .iter()
+            .map(|msg| {
+                msg.content
+                    .iter()
+                    .map(|content| match content {
+                        MessageContent::Text(text) => text.text.clone(),
+                        MessageContent::ToolRequest(_) => "request".to_string(),
+                        MessageContent::ToolResponse(response) => response
+                            .tool_result
+                            .as_ref()
+                            .unwrap()
+                            .iter()
+                            .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+                            .collect(),
+                        _ => "other".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_elide_tool_outputs_keeps_recent_turns() {
+        let dir = TempDir::new().unwrap();
+        let provider = Arc::new(TestProvider::new_recording(
+            Arc::new(SummaryProvider::default()),
+            dir.path().join("records.json").to_string_lossy(),
+        ));
+        let original = conversation(3);
+
+        let strategy = ElideToolOutputsStrategy { keep_turns: 1 };
+        let (compacted, usage) = strategy
+            .compact(provider.clone(), &original, false)
+            .await
+            .unwrap();
+
+        let view = agent_view(&compacted);
+        assert_eq!(view.len(), 12);
+        assert_eq!(view[1], "request");
+        assert_eq!(view[2], ELIDED_TOOL_OUTPUT);
+        assert_eq!(view[6], ELIDED_TOOL_OUTPUT);
+        assert_eq!(view[10], "output 2");
+        assert_eq!(user_view(&compacted), user_view(&original));
+        assert_eq!(usage.usage.total_tokens, None);
+        assert_eq!(provider.get_record_count(), 0);
+
+        // Elided outputs are not elided again
+        let (again, _) = strategy
+            .compact(provider.clone(), &compacted, false)
+            .await
+            .unwrap();
+        assert_eq!(again.messages(), compacted.messages());
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_keeps_pinned_and_recent_turns() {
+        let dir = TempDir::new().unwrap();
+        let provider = Arc::new(TestProvider::new_recording(
+            Arc::new(SummaryProvider::default()),
+            dir.path().join("records.json").to_string_lossy(),
+        ));
+        let original = conversation(5);
+
+        let strategy = SlidingWindowStrategy {
+            keep_turns: 2,
+            pinned_turns: 1,
+        };
+        let (compacted, _) = strategy
+            .compact(provider.clone(), &original, true)
+            .await
+            .unwrap();
+
+        let view = agent_view(&compacted);
+        assert_eq!(
+            view,
+            vec![
+                "task 0",
+                "request",
+                "output 0",
+                "done 0",
+                "[8 earlier messages of this conversation were removed to stay within the context limit.]",
+                "task 3",
+                "request",
+                "output 3",
+                "done 3",
+                "task 4",
+                "request",
+                "output 4",
+                "done 4",
+            ]
+        );
+        assert_eq!(user_view(&compacted), user_view(&original));
+        assert_eq!(provider.get_record_count(), 0);
+
+        let (unchanged, _) = strategy
+            .compact(provider.clone(), &conversation(3), true)
+            .await
+            .unwrap();
+        assert_eq!(unchanged.messages(), conversation(3).messages());
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_always_keeps_latest_turn() {
+        let dir = TempDir::new().unwrap();
+        let provider = Arc::new(TestProvider::new_recording(
+            Arc::new(SummaryProvider::default()),
+            dir.path().join("records.json").to_string_lossy(),
+        ));
+        let mut original = conversation(3);
+        original.push(Message::user().with_text("next task"));
+
+        let strategy = SlidingWindowStrategy {
+            keep_turns: 0,
+            pinned_turns: 0,
+        };
+        let (compacted, _) = strategy
+            .compact(provider.clone(), &original, true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            agent_view(&compacted),
+            vec![
+                "[12 earlier messages of this conversation were removed to stay within the context limit.]",
+                "next task",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hierarchical_summarizes_older_turns_only() {
+        let dir = TempDir::new().unwrap();
+        let inner = Arc::new(SummaryProvider::default());
+        let provider = Arc::new(TestProvider::new_recording(
+            inner.clone(),
+            dir.path().join("records.json").to_string_lossy(),
+        ));
+        let original = conversation(3);
+
+        let strategy = HierarchicalStrategy { keep_turns: 1 };
+        let (compacted, usage) = strategy
+            .compact(provider.clone(), &original, true)
+            .await
+            .unwrap();
+
+        let view = agent_view(&compacted);
+        assert_eq!(view.len(), 6);
+        assert_eq!(view[0], "the summary");
+        assert_eq!(view[1], SUMMARY_CONTINUATION_TEXT);
+        assert_eq!(view[2..], ["task 2", "request", "output 2", "done 2"]);
+        assert_eq!(usage.usage.output_tokens, Some(10));
+        assert_eq!(provider.get_record_count(), 1);
+
+        let system_prompts = inner.system_prompts.lock().unwrap();
+        assert!(system_prompts[0].contains("output 1"));
+        assert!(!system_prompts[0].contains("output 2"));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_preserves_last_user_message() {
+        let dir = TempDir::new().unwrap();
+        let provider = Arc::new(TestProvider::new_recording(
+            Arc::new(SummaryProvider::default()),
+            dir.path().join("records.json").to_string_lossy(),
+        ));
+        let mut original = conversation(2);
+        original.push(Message::user().with_text("next task"));
+
+        let (compacted, _) = SummarizeStrategy
+            .compact(provider.clone(), &original, false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            agent_view(&compacted),
+            vec!["the summary", SUMMARY_CONTINUATION_TEXT, "next task"]
+        );
+        assert_eq!(provider.get_record_count(), 1);
+    }
+
+    #[test]
+    fn test_strategy_kind_names() {
+        assert_eq!(
+            serde_json::from_str::<ContextStrategyKind>("\"elide_tool_outputs\"").unwrap(),
+            ContextStrategyKind::ElideToolOutputs
+        );
+        assert_eq!(
+            serde_json::to_string(&ContextStrategyKind::SlidingWindow).unwrap(),
+            "\"sliding_window\""
+        );
+    }
+}
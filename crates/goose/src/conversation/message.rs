@@ -3,7 +3,7 @@ use chrono::Utc;
 use rmcp::model::{
     AnnotateAble, CallToolRequestParam, Content, ImageContent, JsonObject, PromptMessage,
     PromptMessageContent, PromptMessageRole, RawContent, RawImageContent, RawTextContent,
-    ResourceContents, Role, TextContent,
+    ResourceContents, Role, ServerNotification, TextContent,
 };
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashSet;
@@ -11,6 +11,7 @@ use std::fmt;
 use utoipa::ToSchema;
 
 use crate::conversation::tool_result_serde;
+use crate::conversation::Conversation;
 use crate::utils::sanitize_unicode_tags;
 
 #[derive(ToSchema)]
@@ -386,7 +387,14 @@ impl From<PromptMessage> for Message {
     }
 }
 
-#[derive(ToSchema, Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(ToSchema, Clone, PartialEq, Serialize, Deserialize, Debug)]
+/// The provider and model that generated a message
+pub struct ServedBy {
+    pub provider: String,
+    pub model: String,
+}
+
+#[derive(ToSchema, Clone, PartialEq, Serialize, Deserialize, Debug)]
 /// Metadata for message visibility
 #[serde(rename_all = "camelCase")]
 pub struct MessageMetadata {
@@ -394,6 +402,9 @@ pub struct MessageMetadata {
     pub user_visible: bool,
     /// Whether the message should be included in the agent's context window
     pub agent_visible: bool,
+    /// Which provider generated the message, recorded when a fallback chain is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_by: Option<ServedBy>,
 }
 
 impl Default for MessageMetadata {
@@ -401,6 +412,7 @@ impl Default for MessageMetadata {
         MessageMetadata {
             user_visible: true,
             agent_visible: true,
+            served_by: None,
         }
     }
 }
@@ -411,6 +423,7 @@ impl MessageMetadata {
         MessageMetadata {
             user_visible: false,
             agent_visible: true,
+            served_by: None,
         }
     }
 
@@ -419,6 +432,7 @@ impl MessageMetadata {
         MessageMetadata {
             user_visible: true,
             agent_visible: false,
+            served_by: None,
         }
     }
 
@@ -427,6 +441,7 @@ impl MessageMetadata {
         MessageMetadata {
             user_visible: false,
             agent_visible: false,
+            served_by: None,
         }
     }
 
@@ -722,9 +737,42 @@ pub struct TokenState {
     pub accumulated_total_tokens: i32,
 }
 
+/// An event of a streamed agent reply. goosed sends these to the desktop app as server-sent
+/// events, and `goose run --output-format stream-json` prints one per line.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type")]
+pub enum MessageEvent {
+    Message {
+        message: Message,
+        token_state: TokenState,
+    },
+    Error {
+        error: String,
+    },
+    Finish {
+        reason: String,
+        token_state: TokenState,
+    },
+    ModelChange {
+        model: String,
+        mode: String,
+    },
+    Notification {
+        request_id: String,
+        #[schema(value_type = Object)]
+        message: ServerNotification,
+    },
+    UpdateConversation {
+        conversation: Conversation,
+    },
+    Ping,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::conversation::message::{Message, MessageContent, MessageMetadata};
+    use crate::conversation::message::{
+        Message, MessageContent, MessageEvent, MessageMetadata, TokenState,
+    };
     use crate::conversation::*;
     use rmcp::model::{
         AnnotateAble, CallToolRequestParam, PromptMessage, PromptMessageContent, PromptMessageRole,
@@ -1212,4 +1260,27 @@ mod tests {
         assert!(metadata.user_visible);
         assert!(metadata.agent_visible);
     }
+
+    #[test]
+    fn test_message_event_serialization() {
+        let event = MessageEvent::Message {
+            message: Message::assistant().with_text("Hello"),
+            token_state: TokenState {
+                total_tokens: 10,
+                ..Default::default()
+            },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "Message");
+        assert_eq!(json["message"]["content"][0]["text"], "Hello");
+        assert_eq!(json["token_state"]["totalTokens"], 10);
+
+        let finish: MessageEvent =
+            serde_json::from_str(r#"{"type":"Finish","reason":"stop","token_state":{"inputTokens":1,"outputTokens":2,"totalTokens":3,"accumulatedInputTokens":1,"accumulatedOutputTokens":2,"accumulatedTotalTokens":3}}"#)
+                .unwrap();
+        assert!(matches!(
+            finish,
+            MessageEvent::Finish { reason, token_state } if reason == "stop" && token_state.total_tokens == 3
+        ));
+    }
 }
@@ -14,7 +14,9 @@ pub mod providers;
 pub mod recipe;
 pub mod recipe_deeplink;
 pub mod scheduler;
+pub mod scheduler_history;
 pub mod scheduler_trait;
+pub mod scheduler_triggers;
 pub mod security;
 pub mod session;
 pub mod session_context;
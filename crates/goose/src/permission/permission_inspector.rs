@@ -7,12 +7,14 @@ use crate::tool_inspection::{InspectionAction, InspectionResult, ToolInspector};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 /// Permission Inspector that handles tool permission checking
 pub struct PermissionInspector {
     mode: Arc<Mutex<GooseMode>>,
+    working_dir: Arc<Mutex<Option<PathBuf>>>,
     readonly_tools: HashSet<String>,
     regular_tools: HashSet<String>,
     pub permission_manager: Arc<Mutex<PermissionManager>>,
@@ -26,6 +28,7 @@ impl PermissionInspector {
     ) -> Self {
         Self {
             mode: Arc::new(Mutex::new(mode)),
+            working_dir: Arc::new(Mutex::new(None)),
             readonly_tools,
             regular_tools,
             permission_manager: Arc::new(Mutex::new(PermissionManager::default())),
@@ -40,6 +43,7 @@ impl PermissionInspector {
     ) -> Self {
         Self {
             mode: Arc::new(Mutex::new(mode)),
+            working_dir: Arc::new(Mutex::new(None)),
             readonly_tools,
             regular_tools,
             permission_manager,
@@ -52,6 +56,12 @@ impl PermissionInspector {
         *mode = new_mode;
     }
 
+    /// Update the working directory that relative path rules are resolved against
+    pub async fn update_working_dir(&self, working_dir: PathBuf) {
+        let mut current = self.working_dir.lock().await;
+        *current = Some(working_dir);
+    }
+
     /// Process inspection results into permission decisions
     /// This method takes all inspection results and converts them into a PermissionCheckResult
     /// that can be used by the agent to determine which tools to approve, deny, or ask for approval
@@ -134,6 +144,7 @@ impl ToolInspector for PermissionInspector {
         let mut results = Vec::new();
         let permission_manager = self.permission_manager.lock().await;
         let mode = self.mode.lock().await;
+        let working_dir = self.working_dir.lock().await;
 
         for request in tool_requests {
             if let Ok(tool_call) = &request.tool_call {
@@ -143,8 +154,12 @@ impl ToolInspector for PermissionInspector {
                     GooseMode::Chat => continue,
                     GooseMode::Auto => InspectionAction::Allow,
                     GooseMode::Approve | GooseMode::SmartApprove => {
-                        // 1. Check user-defined permission first
-                        if let Some(level) = permission_manager.get_user_permission(tool_name) {
+                        // 1. Check user-defined argument rules and permissions first
+                        if let Some(level) = permission_manager.get_user_permission_for_call(
+                            tool_name,
+                            tool_call.arguments.as_ref(),
+                            working_dir.as_deref(),
+                        ) {
                             match level {
                                 PermissionLevel::AlwaysAllow => InspectionAction::Allow,
                                 PermissionLevel::NeverAllow => InspectionAction::Deny,
@@ -208,3 +223,95 @@ impl ToolInspector for PermissionInspector {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::CallToolRequestParam;
+    use rmcp::object;
+    use tempfile::NamedTempFile;
+
+    fn shell_request(id: &str, command: &str) -> ToolRequest {
+        ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(CallToolRequestParam {
+                name: "developer__shell".into(),
+                arguments: Some(object!({ "command": command })),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_argument_rules_evaluated_before_mode_default() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_file.path(),
+            r#"
+user:
+  rules:
+    - tool: developer__shell
+      command_prefix: cargo test
+      level: always_allow
+    - tool: developer__shell
+      command_prefix: rm
+      level: never_allow
+"#,
+        )
+        .unwrap();
+        let inspector = PermissionInspector::with_permission_manager(
+            GooseMode::SmartApprove,
+            HashSet::new(),
+            HashSet::new(),
+            Arc::new(Mutex::new(PermissionManager::new(temp_file.path()))),
+        );
+
+        let requests = vec![
+            shell_request("allowed", "cargo test --workspace"),
+            shell_request("denied", "rm -rf target"),
+            shell_request("unmatched", "cargo publish"),
+        ];
+        let results = inspector.inspect(&requests, &[]).await.unwrap();
+
+        assert_eq!(results[0].action, InspectionAction::Allow);
+        assert_eq!(results[1].action, InspectionAction::Deny);
+        assert_eq!(results[2].action, InspectionAction::RequireApproval(None));
+    }
+
+    #[tokio::test]
+    async fn test_relative_path_rules_use_session_working_dir() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_file.path(),
+            r#"
+user:
+  rules:
+    - tool: developer__text_editor
+      path: "src/**"
+      level: always_allow
+"#,
+        )
+        .unwrap();
+        let inspector = PermissionInspector::with_permission_manager(
+            GooseMode::Approve,
+            HashSet::new(),
+            HashSet::new(),
+            Arc::new(Mutex::new(PermissionManager::new(temp_file.path()))),
+        );
+        let request = ToolRequest {
+            id: "edit".to_string(),
+            tool_call: Ok(CallToolRequestParam {
+                name: "developer__text_editor".into(),
+                arguments: Some(object!({ "path": "/work/project/src/lib.rs" })),
+            }),
+        };
+
+        let results = inspector.inspect(&[request.clone()], &[]).await.unwrap();
+        assert_eq!(results[0].action, InspectionAction::RequireApproval(None));
+
+        inspector
+            .update_working_dir(PathBuf::from("/work/project"))
+            .await;
+        let results = inspector.inspect(&[request], &[]).await.unwrap();
+        assert_eq!(results[0].action, InspectionAction::Allow);
+    }
+}
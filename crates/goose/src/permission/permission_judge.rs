@@ -12,6 +12,7 @@ use rmcp::object;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 
 #[derive(Serialize)]
@@ -174,6 +175,7 @@ pub async fn check_tool_permissions(
     tools_without_annotation: HashSet<String>,
     permission_manager: &mut PermissionManager,
     provider: Arc<dyn Provider>,
+    working_dir: Option<&Path>,
 ) -> (PermissionCheckResult, Vec<String>) {
     let mut approved = vec![];
     let mut needs_approval = vec![];
@@ -193,7 +195,11 @@ pub async fn check_tool_permissions(
                 }
 
                 // 1. Check user-defined permission
-                if let Some(level) = permission_manager.get_user_permission(&tool_call.name) {
+                if let Some(level) = permission_manager.get_user_permission_for_call(
+                    &tool_call.name,
+                    tool_call.arguments.as_ref(),
+                    working_dir,
+                ) {
                     match level {
                         PermissionLevel::AlwaysAllow => approved.push(request.clone()),
                         PermissionLevel::AskBefore => needs_approval.push(request.clone()),
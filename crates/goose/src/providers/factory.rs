@@ -8,6 +8,10 @@ use super::{
     claude_code::ClaudeCodeProvider,
     cursor_agent::CursorAgentProvider,
     databricks::DatabricksProvider,
+    fallback::{
+        FallbackEntry, FallbackProvider, DEFAULT_FALLBACK_COOLDOWN, DEFAULT_FALLBACK_RETRIES,
+        FALLBACK_COOLDOWN_CONFIG_KEY, FALLBACK_PROVIDERS_CONFIG_KEY, FALLBACK_RETRIES_CONFIG_KEY,
+    },
     gcpvertexai::GcpVertexAIProvider,
     gemini_cli::GeminiCliProvider,
     githubcopilot::GithubCopilotProvider,
@@ -31,6 +35,7 @@ use crate::{
     providers::provider_registry::ProviderEntry,
 };
 use anyhow::Result;
+use std::time::Duration;
 use tokio::sync::OnceCell;
 
 const DEFAULT_LEAD_TURNS: usize = 3;
@@ -126,13 +131,31 @@ async fn get_from_registry(name: &str) -> Result<ProviderEntry> {
 pub async fn create(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
 
-    if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
+    let provider = if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
         tracing::info!("Creating lead/worker provider from environment variables");
-        return create_lead_worker_from_env(name, &model, &lead_model_name).await;
-    }
+        create_lead_worker_from_env(name, &model, &lead_model_name).await?
+    } else {
+        let constructor = get_from_registry(name).await?.constructor.clone();
+        constructor(model).await?
+    };
 
-    let constructor = get_from_registry(name).await?.constructor.clone();
-    constructor(model).await
+    Ok(provider)
+}
+
+/// Create the main provider of a session: like [`create`], but followed by the
+/// configured fallback providers. Providers used for anything else, such as embeddings
+/// or judging output, should use [`create`] so they never fall back to another model.
+pub async fn create_session_provider(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
+    let provider = create(name, model).await?;
+
+    let config = crate::config::Config::global();
+    match config.get_param::<Vec<FallbackEntry>>(FALLBACK_PROVIDERS_CONFIG_KEY) {
+        Ok(entries) if !entries.is_empty() => {
+            tracing::info!("Creating fallback provider chain from configuration");
+            Ok(create_fallback_chain(provider, &entries).await)
+        }
+        _ => Ok(provider),
+    }
 }
 
 pub async fn create_with_default_model(name: impl AsRef<str>) -> Result<Arc<dyn Provider>> {
@@ -212,6 +235,48 @@ async fn create_lead_worker_from_env(
     )))
 }
 
+/// Put `primary` in front of the configured fallback providers. Fallback providers that
+/// cannot be created, e.g. because they are not configured, are left out.
+async fn create_fallback_chain(
+    primary: Arc<dyn Provider>,
+    entries: &[FallbackEntry],
+) -> Arc<dyn Provider> {
+    let config = crate::config::Config::global();
+
+    let cooldown = config
+        .get_param::<u64>(FALLBACK_COOLDOWN_CONFIG_KEY)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_FALLBACK_COOLDOWN);
+    let retries = config
+        .get_param::<usize>(FALLBACK_RETRIES_CONFIG_KEY)
+        .unwrap_or(DEFAULT_FALLBACK_RETRIES);
+
+    let mut providers = vec![primary];
+    for entry in entries {
+        match create_fallback_member(entry).await {
+            Ok(provider) => providers.push(provider),
+            Err(e) => tracing::warn!(
+                "Skipping fallback provider {}/{}: {}",
+                entry.provider,
+                entry.model,
+                e
+            ),
+        }
+    }
+
+    Arc::new(FallbackProvider::new_with_settings(
+        providers, cooldown, retries,
+    ))
+}
+
+async fn create_fallback_member(entry: &FallbackEntry) -> Result<Arc<dyn Provider>> {
+    let constructor = get_from_registry(&entry.provider)
+        .await?
+        .constructor
+        .clone();
+    constructor(ModelConfig::new(&entry.model)?).await
+}
+
 fn create_worker_model_config(default_model: &ModelConfig) -> Result<ModelConfig> {
     let mut worker_config = ModelConfig::new_or_fail(&default_model.model_name)
         .with_context_limit(default_model.context_limit)
@@ -351,6 +416,40 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_fallback_chain_skips_unavailable_providers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let primary: Arc<dyn Provider> = Arc::new(
+            crate::providers::testprovider::TestProvider::new_replaying(
+                temp_dir.path().join("records.json").to_string_lossy(),
+            )
+            .unwrap(),
+        );
+
+        let chain = create_fallback_chain(
+            primary,
+            &[FallbackEntry {
+                provider: "no-such-provider".to_string(),
+                model: "no-such-model".to_string(),
+            }],
+        )
+        .await;
+
+        assert_eq!(chain.get_name(), "test");
+        assert_eq!(chain.get_model_config().model_name, "test-model");
+    }
+
+    #[test]
+    fn test_fallback_entries_from_yaml() {
+        let entries: Vec<FallbackEntry> = serde_yaml::from_str(
+            "- provider: anthropic\n  model: claude-sonnet-4\n- provider: openai\n  model: gpt-4o\n",
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].provider, "openai");
+        assert_eq!(entries[1].model, "gpt-4o");
+    }
+
     #[test]
     fn test_worker_model_preserves_original_context_limit() {
         let _guard = EnvVarGuard::new(&[
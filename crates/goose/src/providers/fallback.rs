@@ -0,0 +1,574 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::base::{
+    stream_from_single_message, LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata,
+    ProviderUsage,
+};
+use super::errors::ProviderError;
+use crate::conversation::message::{Message, ServedBy};
+use crate::model::ModelConfig;
+use rmcp::model::Tool;
+
+/// Ordered list of `provider`/`model` pairs to fail over to, e.g. in config.yaml:
+///
+/// ```yaml
+/// GOOSE_FALLBACK_PROVIDERS:
+///   - provider: openai
+///     model: gpt-4o
+/// ```
+pub const FALLBACK_PROVIDERS_CONFIG_KEY: &str = "GOOSE_FALLBACK_PROVIDERS";
+/// Seconds a provider is skipped after it failed, doubled for each further failure.
+pub const FALLBACK_COOLDOWN_CONFIG_KEY: &str = "GOOSE_FALLBACK_COOLDOWN";
+/// Times a transient error is retried on the same provider before failing over.
+pub const FALLBACK_RETRIES_CONFIG_KEY: &str = "GOOSE_FALLBACK_RETRIES";
+
+pub const DEFAULT_FALLBACK_COOLDOWN: Duration = Duration::from_secs(60);
+pub const DEFAULT_FALLBACK_RETRIES: usize = 1;
+// Cap the exponential cooldown at 16 times the base cooldown
+const MAX_COOLDOWN_DOUBLINGS: u32 = 4;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackEntry {
+    pub provider: String,
+    pub model: String,
+}
+
+/// How the fallback chain reacts to an error from one of its providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Likely transient, so the same provider is retried before moving on
+    Retryable,
+    /// The provider cannot serve requests right now, so the next one is tried
+    Failover,
+    /// The request itself is at fault and no other provider would do better
+    Fatal,
+}
+
+impl ErrorClass {
+    pub fn of(error: &ProviderError) -> Self {
+        match error {
+            ProviderError::ServerError(_) | ProviderError::RequestFailed(_) => {
+                ErrorClass::Retryable
+            }
+            ProviderError::RateLimitExceeded { .. }
+            | ProviderError::Authentication(_)
+            | ProviderError::ExecutionError(_)
+            | ProviderError::UsageError(_)
+            | ProviderError::NotImplemented(_) => ErrorClass::Failover,
+            // Handled by the agent, which compacts the conversation and tries again
+            ProviderError::ContextLengthExceeded(_) => ErrorClass::Fatal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProviderHealth {
+    pub consecutive_failures: u32,
+    /// The provider is skipped until then, unless every provider is cooling down
+    pub cooldown_until: Option<Instant>,
+    pub last_error: Option<String>,
+}
+
+impl ProviderHealth {
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.cooldown_until.is_none_or(|until| now >= until)
+    }
+}
+
+/// A provider that tries an ordered list of providers, failing over to the next one when a
+/// provider errors and skipping providers that failed recently until their cooldown expires
+pub struct FallbackProvider {
+    providers: Vec<Arc<dyn Provider>>,
+    health: Mutex<Vec<ProviderHealth>>,
+    cooldown: Duration,
+    retries: usize,
+}
+
+impl FallbackProvider {
+    /// Create a new FallbackProvider with the default cooldown and retries
+    ///
+    /// # Arguments
+    /// * `providers` - The providers to try, in order of preference
+    pub fn new(providers: Vec<Arc<dyn Provider>>) -> Self {
+        Self::new_with_settings(
+            providers,
+            DEFAULT_FALLBACK_COOLDOWN,
+            DEFAULT_FALLBACK_RETRIES,
+        )
+    }
+
+    /// Create a new FallbackProvider with custom settings
+    ///
+    /// # Arguments
+    /// * `providers` - The providers to try, in order of preference
+    /// * `cooldown` - How long a provider is skipped after its first failure
+    /// * `retries` - Times a transient error is retried before failing over
+    pub fn new_with_settings(
+        providers: Vec<Arc<dyn Provider>>,
+        cooldown: Duration,
+        retries: usize,
+    ) -> Self {
+        assert!(
+            !providers.is_empty(),
+            "FallbackProvider needs at least one provider"
+        );
+        let health = Mutex::new(vec![ProviderHealth::default(); providers.len()]);
+        Self {
+            providers,
+            health,
+            cooldown,
+            retries,
+        }
+    }
+
+    /// Health of each provider, in order of preference
+    pub fn health(&self) -> Vec<(String, ProviderHealth)> {
+        let health = self.health.lock().unwrap();
+        self.providers
+            .iter()
+            .zip(health.iter())
+            .map(|(provider, health)| (provider.get_name().to_string(), health.clone()))
+            .collect()
+    }
+
+    /// Available providers in order of preference, followed by the ones cooling down,
+    /// soonest available first
+    fn attempt_order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let health = self.health.lock().unwrap();
+        let (mut available, mut cooling): (Vec<usize>, Vec<usize>) =
+            (0..self.providers.len()).partition(|&i| health[i].is_available(now));
+        cooling.sort_by_key(|&i| health[i].cooldown_until);
+        available.append(&mut cooling);
+        available
+    }
+
+    fn preferred(&self) -> &Arc<dyn Provider> {
+        &self.providers[self.attempt_order()[0]]
+    }
+
+    fn record_success(&self, index: usize) {
+        self.health.lock().unwrap()[index] = ProviderHealth::default();
+    }
+
+    fn record_failure(&self, index: usize, error: &ProviderError) {
+        let mut health = self.health.lock().unwrap();
+        let health = &mut health[index];
+        health.consecutive_failures += 1;
+
+        let cooldown = match error {
+            ProviderError::RateLimitExceeded {
+                retry_delay: Some(delay),
+                ..
+            } => *delay,
+            _ => {
+                self.cooldown
+                    * 2u32.pow((health.consecutive_failures - 1).min(MAX_COOLDOWN_DOUBLINGS))
+            }
+        };
+        health.cooldown_until = Some(Instant::now() + cooldown);
+        health.last_error = Some(error.to_string());
+    }
+
+    /// Run `call` against each provider in turn until one succeeds, returning its result
+    /// along with the provider that served it
+    async fn with_failover<'a, T>(
+        &'a self,
+        call: impl Fn(&'a Arc<dyn Provider>) -> BoxFuture<'a, Result<T, ProviderError>>,
+    ) -> Result<(T, ServedBy), ProviderError> {
+        let mut last_error = None;
+
+        for index in self.attempt_order() {
+            let provider = &self.providers[index];
+            let mut attempt = 0;
+
+            let error = loop {
+                match call(provider).await {
+                    Ok(value) => {
+                        self.record_success(index);
+                        let served_by = ServedBy {
+                            provider: provider.get_name().to_string(),
+                            model: provider.get_active_model_name(),
+                        };
+                        return Ok((value, served_by));
+                    }
+                    Err(error) => match ErrorClass::of(&error) {
+                        ErrorClass::Fatal => return Err(error),
+                        ErrorClass::Retryable if attempt < self.retries => {
+                            attempt += 1;
+                            tracing::warn!(
+                                "Provider {} failed with a transient error, retrying ({}/{}): {}",
+                                provider.get_name(),
+                                attempt,
+                                self.retries,
+                                error
+                            );
+                        }
+                        _ => break error,
+                    },
+                }
+            };
+
+            tracing::warn!(
+                "Provider {} failed, failing over to the next provider: {}",
+                provider.get_name(),
+                error
+            );
+            self.record_failure(index, &error);
+            last_error = Some(error);
+        }
+
+        Err(last_error.expect("FallbackProvider has at least one provider"))
+    }
+}
+
+fn with_served_by(mut message: Message, served_by: &ServedBy) -> Message {
+    message.metadata.served_by = Some(served_by.clone());
+    message
+}
+
+#[async_trait]
+impl Provider for FallbackProvider {
+    fn metadata() -> ProviderMetadata {
+        // This is a wrapper provider, so we return minimal metadata
+        ProviderMetadata::new(
+            "fallback",
+            "Fallback Provider",
+            "A provider that fails over to the next of a list of providers when one errors",
+            "",
+            vec![],
+            "",
+            vec![],
+        )
+    }
+
+    fn get_name(&self) -> &str {
+        self.preferred().get_name()
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.preferred().get_model_config()
+    }
+
+    async fn complete_with_model(
+        &self,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // The requested model only applies to the provider it belongs to, the others
+        // use their own model
+        let ((message, usage), served_by) = self
+            .with_failover(|provider| {
+                Box::pin(async move {
+                    if provider.get_model_config().model_name == model_config.model_name {
+                        provider
+                            .complete_with_model(model_config, system, messages, tools)
+                            .await
+                    } else {
+                        provider.complete(system, messages, tools).await
+                    }
+                })
+            })
+            .await?;
+        Ok((with_served_by(message, &served_by), usage))
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let ((message, usage), served_by) = self
+            .with_failover(|provider| Box::pin(provider.complete(system, messages, tools)))
+            .await?;
+        Ok((with_served_by(message, &served_by), usage))
+    }
+
+    async fn complete_fast(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let ((message, usage), served_by) = self
+            .with_failover(|provider| Box::pin(provider.complete_fast(system, messages, tools)))
+            .await?;
+        Ok((with_served_by(message, &served_by), usage))
+    }
+
+    /// Fails over only while the stream is being set up. Once a provider has started
+    /// streaming, its errors are passed through.
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let (stream, served_by) = self
+            .with_failover(|provider| {
+                Box::pin(async move {
+                    if provider.supports_streaming() {
+                        provider.stream(system, messages, tools).await
+                    } else {
+                        let (message, usage) = provider.complete(system, messages, tools).await?;
+                        Ok(stream_from_single_message(message, usage))
+                    }
+                })
+            })
+            .await?;
+
+        Ok(Box::pin(stream.map(move |item| {
+            item.map(|(message, usage)| (message.map(|m| with_served_by(m, &served_by)), usage))
+        })))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.providers.iter().any(|p| p.supports_streaming())
+    }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.providers[0].fetch_supported_models().await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.providers.iter().any(|p| p.supports_embeddings())
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        match self.providers.iter().find(|p| p.supports_embeddings()) {
+            Some(provider) => provider.create_embeddings(texts).await,
+            None => Err(ProviderError::ExecutionError(
+                "None of the fallback providers supports embeddings".to_string(),
+            )),
+        }
+    }
+
+    async fn supports_cache_control(&self) -> bool {
+        self.preferred().supports_cache_control().await
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.providers[0].as_lead_worker()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct MockProvider {
+        name: String,
+        errors: Mutex<Vec<ProviderError>>,
+        calls: AtomicUsize,
+    }
+
+    impl MockProvider {
+        fn new(name: &str, errors: Vec<ProviderError>) -> Arc<Self> {
+            Arc::new(Self {
+                name: name.to_string(),
+                errors: Mutex::new(errors),
+                calls: AtomicUsize::new(0),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail(&format!("{}-model", self.name))
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let mut errors = self.errors.lock().unwrap();
+            if !errors.is_empty() {
+                return Err(errors.remove(0));
+            }
+            Ok((
+                Message::assistant().with_text(format!("Response from {}", self.name)),
+                ProviderUsage::new(self.name.clone(), Usage::default()),
+            ))
+        }
+    }
+
+    fn overloaded() -> ProviderError {
+        ProviderError::ServerError("529 overloaded".to_string())
+    }
+
+    fn served_by(message: &Message) -> (String, String) {
+        let served_by = message.metadata.served_by.clone().unwrap();
+        (served_by.provider, served_by.model)
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert_eq!(ErrorClass::of(&overloaded()), ErrorClass::Retryable);
+        assert_eq!(
+            ErrorClass::of(&ProviderError::RequestFailed("timeout".to_string())),
+            ErrorClass::Retryable
+        );
+        assert_eq!(
+            ErrorClass::of(&ProviderError::RateLimitExceeded {
+                details: "slow down".to_string(),
+                retry_delay: None,
+            }),
+            ErrorClass::Failover
+        );
+        assert_eq!(
+            ErrorClass::of(&ProviderError::Authentication("bad key".to_string())),
+            ErrorClass::Failover
+        );
+        assert_eq!(
+            ErrorClass::of(&ProviderError::ContextLengthExceeded(
+                "too long".to_string()
+            )),
+            ErrorClass::Fatal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_serves_from_primary_when_healthy() {
+        let primary = MockProvider::new("primary", vec![]);
+        let backup = MockProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new(vec![primary.clone(), backup.clone()]);
+
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+
+        assert_eq!(
+            served_by(&message),
+            ("primary".to_string(), "primary-model".to_string())
+        );
+        assert_eq!(backup.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors_then_fails_over() {
+        let primary = MockProvider::new("primary", vec![overloaded(), overloaded()]);
+        let backup = MockProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new_with_settings(
+            vec![primary.clone(), backup.clone()],
+            Duration::from_secs(3600),
+            1,
+        );
+
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(served_by(&message).0, "backup");
+        assert_eq!(primary.calls(), 2);
+
+        let health = provider.health();
+        assert_eq!(health[0].1.consecutive_failures, 1);
+        assert!(health[0].1.last_error.as_ref().unwrap().contains("529"));
+        assert_eq!(provider.get_name(), "backup");
+
+        // The primary is cooling down, so it is skipped
+        provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(primary.calls(), 2);
+        assert_eq!(backup.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_primary_is_used_again_after_cooldown() {
+        let primary = MockProvider::new(
+            "primary",
+            vec![ProviderError::RateLimitExceeded {
+                details: "slow down".to_string(),
+                retry_delay: None,
+            }],
+        );
+        let backup = MockProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new_with_settings(
+            vec![primary.clone(), backup.clone()],
+            Duration::ZERO,
+            1,
+        );
+
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(served_by(&message).0, "backup");
+        assert_eq!(primary.calls(), 1);
+
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(served_by(&message).0, "primary");
+        assert_eq!(provider.health()[0].1.consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fatal_errors_are_not_failed_over() {
+        let primary = MockProvider::new(
+            "primary",
+            vec![ProviderError::ContextLengthExceeded("too long".to_string())],
+        );
+        let backup = MockProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new(vec![primary.clone(), backup.clone()]);
+
+        let result = provider.complete("system", &[], &[]).await;
+        assert!(matches!(
+            result,
+            Err(ProviderError::ContextLengthExceeded(_))
+        ));
+        assert_eq!(backup.calls(), 0);
+        assert_eq!(provider.health()[0].1.consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_returns_last_error_when_all_providers_fail() {
+        let primary = MockProvider::new(
+            "primary",
+            vec![ProviderError::Authentication("bad key".to_string())],
+        );
+        let backup = MockProvider::new(
+            "backup",
+            vec![ProviderError::Authentication("also bad".to_string())],
+        );
+        let provider = FallbackProvider::new(vec![primary, backup]);
+
+        let result = provider.complete("system", &[], &[]).await;
+        assert_eq!(
+            result.unwrap_err(),
+            ProviderError::Authentication("also bad".to_string())
+        );
+
+        // Everything is cooling down, so the chain is tried again in order of availability
+        let (message, _) = provider.complete("system", &[], &[]).await.unwrap();
+        assert_eq!(served_by(&message).0, "primary");
+    }
+
+    #[tokio::test]
+    async fn test_stream_records_serving_provider() {
+        let primary = MockProvider::new("primary", vec![overloaded(), overloaded()]);
+        let backup = MockProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new(vec![primary, backup]);
+
+        let mut stream = provider.stream("system", &[], &[]).await.unwrap();
+        let (message, _) = stream.next().await.unwrap().unwrap();
+        assert_eq!(served_by(&message.unwrap()).0, "backup");
+    }
+}
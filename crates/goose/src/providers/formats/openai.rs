@@ -306,53 +306,11 @@ pub fn response_to_message(response: &Value) -> anyhow::Result<Message> {
                     .unwrap_or_default()
                     .to_string();
 
-                // Get the raw arguments string from the LLM.
                 let arguments_str = tool_call["function"]["arguments"]
                     .as_str()
-                    .unwrap_or_default()
-                    .to_string();
+                    .unwrap_or_default();
 
-                // If arguments_str is empty, default to an empty JSON object string.
-                let arguments_str = if arguments_str.is_empty() {
-                    "{}".to_string()
-                } else {
-                    arguments_str
-                };
-
-                if !is_valid_function_name(&function_name) {
-                    let error = ErrorData {
-                        code: ErrorCode::INVALID_REQUEST,
-                        message: Cow::from(format!(
-                            "The provided function name '{}' had invalid characters, it must match this regex [a-zA-Z0-9_-]+",
-                            function_name
-                        )),
-                        data: None,
-                    };
-                    content.push(MessageContent::tool_request(id, Err(error)));
-                } else {
-                    match safely_parse_json(&arguments_str) {
-                        Ok(params) => {
-                            content.push(MessageContent::tool_request(
-                                id,
-                                Ok(CallToolRequestParam {
-                                    name: function_name.into(),
-                                    arguments: Some(object(params)),
-                                }),
-                            ));
-                        }
-                        Err(e) => {
-                            let error = ErrorData {
-                                code: ErrorCode::INVALID_PARAMS,
-                                message: Cow::from(format!(
-                                    "Could not interpret tool use parameters for id {}: {}. Raw arguments: '{}'",
-                                    id, e, arguments_str
-                                )),
-                                data: None,
-                            };
-                            content.push(MessageContent::tool_request(id, Err(error)));
-                        }
-                    }
-                }
+                content.push(tool_call_to_content(id, function_name, arguments_str));
             }
         }
     }
@@ -364,6 +322,50 @@ pub fn response_to_message(response: &Value) -> anyhow::Result<Message> {
     ))
 }
 
+/// Turn a function call from the model into a tool request, reporting invalid names and
+/// unparseable arguments as errors the model can correct
+pub fn tool_call_to_content(id: String, function_name: String, arguments: &str) -> MessageContent {
+    // If arguments is empty, default to an empty JSON object string.
+    let arguments_str = if arguments.is_empty() {
+        "{}"
+    } else {
+        arguments
+    };
+
+    if !is_valid_function_name(&function_name) {
+        let error = ErrorData {
+            code: ErrorCode::INVALID_REQUEST,
+            message: Cow::from(format!(
+                "The provided function name '{}' had invalid characters, it must match this regex [a-zA-Z0-9_-]+",
+                function_name
+            )),
+            data: None,
+        };
+        return MessageContent::tool_request(id, Err(error));
+    }
+
+    match safely_parse_json(arguments_str) {
+        Ok(params) => MessageContent::tool_request(
+            id,
+            Ok(CallToolRequestParam {
+                name: function_name.into(),
+                arguments: Some(object(params)),
+            }),
+        ),
+        Err(e) => {
+            let error = ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from(format!(
+                    "Could not interpret tool use parameters for id {}: {}. Raw arguments: '{}'",
+                    id, e, arguments_str
+                )),
+                data: None,
+            };
+            MessageContent::tool_request(id, Err(error))
+        }
+    }
+}
+
 pub fn get_usage(usage: &Value) -> Usage {
     let input_tokens = usage
         .get("prompt_tokens")
@@ -604,6 +606,34 @@ where
     }
 }
 
+/// Whether a model is one of OpenAI's reasoning models (o-series and gpt-5)
+pub fn is_reasoning_model(model_name: &str) -> bool {
+    model_name.starts_with("o1")
+        || model_name.starts_with("o2")
+        || model_name.starts_with("o3")
+        || model_name.starts_with("o4")
+        || model_name.starts_with("gpt-5")
+}
+
+/// Split a reasoning model name like `o3-high` into the model and its reasoning effort,
+/// defaulting to medium effort. Other models get no reasoning effort.
+pub fn split_reasoning_effort(model_name: &str) -> (String, Option<String>) {
+    if !is_reasoning_model(model_name) {
+        return (model_name.to_string(), None);
+    }
+
+    let parts: Vec<&str> = model_name.split('-').collect();
+    let last_part = parts.last().unwrap();
+
+    match *last_part {
+        "low" | "medium" | "high" => {
+            let base_name = parts[..parts.len() - 1].join("-");
+            (base_name, Some(last_part.to_string()))
+        }
+        _ => (model_name.to_string(), Some("medium".to_string())),
+    }
+}
+
 pub fn create_request(
     model_config: &ModelConfig,
     system: &str,
@@ -617,31 +647,8 @@ pub fn create_request(
         ));
     }
 
-    let is_ox_model = model_config.model_name.starts_with("o1")
-        || model_config.model_name.starts_with("o2")
-        || model_config.model_name.starts_with("o3")
-        || model_config.model_name.starts_with("o4")
-        || model_config.model_name.starts_with("gpt-5");
-
-    // Only extract reasoning effort for O-series models
-    let (model_name, reasoning_effort) = if is_ox_model {
-        let parts: Vec<&str> = model_config.model_name.split('-').collect();
-        let last_part = parts.last().unwrap();
-
-        match *last_part {
-            "low" | "medium" | "high" => {
-                let base_name = parts[..parts.len() - 1].join("-");
-                (base_name, Some(last_part.to_string()))
-            }
-            _ => (
-                model_config.model_name.to_string(),
-                Some("medium".to_string()),
-            ),
-        }
-    } else {
-        // For non-O family models, use the model name as is and no reasoning effort
-        (model_config.model_name.to_string(), None)
-    };
+    let is_ox_model = is_reasoning_model(&model_config.model_name);
+    let (model_name, reasoning_effort) = split_reasoning_effort(&model_config.model_name);
 
     let system_message = json!({
         "role": if is_ox_model { "developer" } else { "system" },
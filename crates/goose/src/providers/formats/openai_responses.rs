@@ -0,0 +1,780 @@
+//! Request and response format for OpenAI's Responses API (`/v1/responses`).
+//!
+//! Unlike chat completions, the Responses API returns reasoning items alongside the answer.
+//! Their summaries become [`MessageContent::Thinking`]; the item id and encrypted content go
+//! into the thinking signature so the reasoning can be sent back on the next turn without
+//! OpenAI storing the conversation.
+use crate::conversation::message::{Message, MessageContent};
+use crate::model::ModelConfig;
+use crate::providers::base::{ProviderUsage, Usage};
+use crate::providers::formats::openai::{
+    format_tools, is_reasoning_model, split_reasoning_effort, tool_call_to_content,
+    validate_tool_schemas,
+};
+use crate::providers::utils::{detect_image_path, load_image_file, sanitize_function_name};
+use anyhow::{anyhow, Error};
+use async_stream::try_stream;
+use futures::Stream;
+use rmcp::model::{AnnotateAble, ImageContent, RawContent, ResourceContents, Role, Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::ops::Deref;
+
+/// What goose keeps of a reasoning item to send it back on a later turn
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ReasoningCarryOver {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encrypted_content: Option<String>,
+}
+
+/// Input messages, function calls and reasoning items interleave, so text and images are
+/// collected here and flushed as one message item whenever another kind of item follows
+struct InputItems {
+    items: Vec<Value>,
+    role: Role,
+    parts: Vec<Value>,
+}
+
+impl InputItems {
+    fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            role: Role::User,
+            parts: Vec::new(),
+        }
+    }
+
+    fn push_part(&mut self, role: Role, part: Value) {
+        if self.role != role {
+            self.flush();
+            self.role = role;
+        }
+        self.parts.push(part);
+    }
+
+    fn push_text(&mut self, role: Role, text: &str) {
+        let part_type = match role {
+            Role::Assistant => "output_text",
+            Role::User => "input_text",
+        };
+        self.push_part(role, json!({"type": part_type, "text": text}));
+    }
+
+    fn push_item(&mut self, item: Value) {
+        self.flush();
+        self.items.push(item);
+    }
+
+    fn flush(&mut self) {
+        if self.parts.is_empty() {
+            return;
+        }
+        self.items.push(json!({
+            "type": "message",
+            "role": self.role,
+            "content": std::mem::take(&mut self.parts),
+        }));
+    }
+
+    fn finish(mut self) -> Vec<Value> {
+        self.flush();
+        self.items
+    }
+}
+
+fn input_image(image: &ImageContent) -> Value {
+    json!({
+        "type": "input_image",
+        "image_url": format!("data:{};base64,{}", image.mime_type, image.data),
+    })
+}
+
+fn reasoning_item(carry_over: &str, summary: Option<&str>) -> Option<Value> {
+    let carry_over: ReasoningCarryOver = serde_json::from_str(carry_over).ok()?;
+    let summary: Vec<Value> = summary
+        .filter(|text| !text.is_empty())
+        .map(|text| json!({"type": "summary_text", "text": text}))
+        .into_iter()
+        .collect();
+
+    let mut item = json!({
+        "type": "reasoning",
+        "id": carry_over.id,
+        "summary": summary,
+    });
+    if let Some(encrypted_content) = carry_over.encrypted_content {
+        item["encrypted_content"] = json!(encrypted_content);
+    }
+    Some(item)
+}
+
+fn function_call_item(id: &str, name: &str, arguments: &Option<rmcp::model::JsonObject>) -> Value {
+    let arguments_str = match arguments {
+        Some(args) => serde_json::to_string(args).unwrap_or_else(|_| "{}".to_string()),
+        None => "{}".to_string(),
+    };
+    json!({
+        "type": "function_call",
+        "call_id": id,
+        "name": sanitize_function_name(name),
+        "arguments": arguments_str,
+    })
+}
+
+/// Convert goose messages into Responses API input items
+pub fn format_input(messages: &[Message]) -> Vec<Value> {
+    let mut input = InputItems::new();
+    // Tool requests that could not be parsed were never real function calls, so neither
+    // they nor their responses can be sent as function call items
+    let mut invalid_calls = HashSet::new();
+
+    for message in messages.iter().filter(|m| m.is_agent_visible()) {
+        let role = message.role.clone();
+        for content in &message.content {
+            match content {
+                MessageContent::Text(text) => {
+                    if text.text.is_empty() {
+                        continue;
+                    }
+                    input.push_text(role.clone(), &text.text);
+                    if role == Role::User {
+                        if let Some(image_path) = detect_image_path(&text.text) {
+                            if let Ok(image) = load_image_file(image_path) {
+                                input.push_part(Role::User, input_image(&image));
+                            }
+                        }
+                    }
+                }
+                MessageContent::Image(image) => {
+                    input.push_part(Role::User, input_image(image));
+                }
+                MessageContent::Thinking(thinking) => {
+                    if let Some(item) =
+                        reasoning_item(&thinking.signature, Some(&thinking.thinking))
+                    {
+                        input.push_item(item);
+                    }
+                }
+                MessageContent::RedactedThinking(redacted) => {
+                    if let Some(item) = reasoning_item(&redacted.data, None) {
+                        input.push_item(item);
+                    }
+                }
+                MessageContent::ToolRequest(request) => match &request.tool_call {
+                    Ok(tool_call) => input.push_item(function_call_item(
+                        &request.id,
+                        &tool_call.name,
+                        &tool_call.arguments,
+                    )),
+                    Err(e) => {
+                        invalid_calls.insert(request.id.clone());
+                        input.push_text(Role::Assistant, &format!("Error: {}", e));
+                    }
+                },
+                MessageContent::FrontendToolRequest(request) => match &request.tool_call {
+                    Ok(tool_call) => input.push_item(function_call_item(
+                        &request.id,
+                        &tool_call.name,
+                        &tool_call.arguments,
+                    )),
+                    Err(e) => {
+                        invalid_calls.insert(request.id.clone());
+                        input.push_text(Role::Assistant, &format!("Error: {}", e));
+                    }
+                },
+                MessageContent::ToolResponse(response) => {
+                    let mut images = Vec::new();
+                    let output = match &response.tool_result {
+                        Ok(contents) => contents
+                            .iter()
+                            // Send only contents with no audience or with Assistant in the audience
+                            .filter(|content| {
+                                content
+                                    .audience()
+                                    .is_none_or(|audience| audience.contains(&Role::Assistant))
+                            })
+                            .map(|content| match content.deref() {
+                                RawContent::Text(text) => text.text.clone(),
+                                RawContent::Image(image) => {
+                                    images.push(input_image(&image.clone().no_annotation()));
+                                    "This tool result included an image that is uploaded in the next message.".to_string()
+                                }
+                                RawContent::Resource(resource) => match &resource.resource {
+                                    ResourceContents::TextResourceContents { text, .. } => {
+                                        text.clone()
+                                    }
+                                    _ => String::new(),
+                                },
+                                _ => String::new(),
+                            })
+                            .collect::<Vec<String>>()
+                            .join(" "),
+                        // A tool result error is shown as output so the model can interpret the error message
+                        Err(e) => format!("The tool call returned the following error:\n{}", e),
+                    };
+
+                    if invalid_calls.contains(&response.id) {
+                        input.push_text(Role::User, &output);
+                    } else {
+                        input.push_item(json!({
+                            "type": "function_call_output",
+                            "call_id": response.id,
+                            "output": output,
+                        }));
+                    }
+                    for image in images {
+                        input.push_part(Role::User, image);
+                    }
+                }
+                MessageContent::ToolConfirmationRequest(_)
+                | MessageContent::SystemNotification(_) => {}
+            }
+        }
+    }
+
+    input.finish()
+}
+
+/// Convert tools into Responses API function tools, which are not nested under `function`
+pub fn format_response_tools(tools: &[Tool]) -> anyhow::Result<Vec<Value>> {
+    let mut tools_spec = format_tools(tools)?;
+    validate_tool_schemas(&mut tools_spec);
+    Ok(tools_spec
+        .into_iter()
+        .map(|tool| {
+            let mut function = tool["function"].clone();
+            function["type"] = json!("function");
+            function
+        })
+        .collect())
+}
+
+fn reasoning_content(item: &Value) -> Option<MessageContent> {
+    let carry_over = serde_json::to_string(&ReasoningCarryOver {
+        id: item["id"].as_str()?.to_string(),
+        encrypted_content: item["encrypted_content"].as_str().map(str::to_string),
+    })
+    .ok()?;
+    let summary = item["summary"]
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+        .unwrap_or_default();
+
+    if !summary.is_empty() {
+        Some(MessageContent::thinking(summary, carry_over))
+    } else if item["encrypted_content"].is_string() {
+        Some(MessageContent::redacted_thinking(carry_over))
+    } else {
+        None
+    }
+}
+
+fn function_call_content(item: &Value) -> MessageContent {
+    tool_call_to_content(
+        item["call_id"].as_str().unwrap_or_default().to_string(),
+        item["name"].as_str().unwrap_or_default().to_string(),
+        item["arguments"].as_str().unwrap_or_default(),
+    )
+}
+
+fn response_error(response: &Value) -> Option<Error> {
+    let error = response.get("error").filter(|e| !e.is_null())?;
+    Some(anyhow!(
+        "Responses API request failed: {}",
+        error["message"].as_str().unwrap_or("unknown error")
+    ))
+}
+
+/// Convert a Responses API response to internal Message format
+pub fn response_to_message(response: &Value) -> anyhow::Result<Message> {
+    if let Some(error) = response_error(response) {
+        return Err(error);
+    }
+
+    let mut content = Vec::new();
+    for item in response["output"].as_array().into_iter().flatten() {
+        match item["type"].as_str() {
+            Some("reasoning") => content.extend(reasoning_content(item)),
+            Some("message") => {
+                for part in item["content"].as_array().into_iter().flatten() {
+                    match part["type"].as_str() {
+                        Some("output_text") => {
+                            content.push(MessageContent::text(
+                                part["text"].as_str().unwrap_or_default(),
+                            ));
+                        }
+                        Some("refusal") => {
+                            content.push(MessageContent::text(
+                                part["refusal"].as_str().unwrap_or_default(),
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Some("function_call") => content.push(function_call_content(item)),
+            _ => {}
+        }
+    }
+
+    Ok(Message::new(
+        Role::Assistant,
+        chrono::Utc::now().timestamp(),
+        content,
+    ))
+}
+
+pub fn get_usage(usage: &Value) -> Usage {
+    let input_tokens = usage
+        .get("input_tokens")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
+    let output_tokens = usage
+        .get("output_tokens")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+
+    let total_tokens = usage
+        .get("total_tokens")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32)
+        .or_else(|| match (input_tokens, output_tokens) {
+            (Some(input), Some(output)) => Some(input + output),
+            _ => None,
+        });
+
+    Usage::new(input_tokens, output_tokens, total_tokens)
+}
+
+/// Convert the server-sent events of a streamed response. Text is yielded as it arrives,
+/// reasoning once each item is done, and all function calls together with the usage when
+/// the response completes, so they run as one turn like streamed chat completions.
+pub fn response_to_streaming_message<S>(
+    mut stream: S,
+) -> impl Stream<Item = anyhow::Result<(Option<Message>, Option<ProviderUsage>)>> + 'static
+where
+    S: Stream<Item = anyhow::Result<String>> + Unpin + Send + 'static,
+{
+    try_stream! {
+        use futures::StreamExt;
+
+        let mut response_id: Option<String> = None;
+        let mut tool_calls = Vec::new();
+
+        let message = |content: Vec<MessageContent>, id: &Option<String>| {
+            let msg = Message::new(Role::Assistant, chrono::Utc::now().timestamp(), content);
+            match id {
+                Some(id) => msg.with_id(id.clone()),
+                None => msg,
+            }
+        };
+
+        while let Some(line) = stream.next().await {
+            let line = line?;
+            // Event names are repeated in the data, so only data lines matter
+            let Some(data) = line.strip_prefix("data: ").map(str::trim) else {
+                continue
+            };
+            if data.is_empty() || data == "[DONE]" {
+                continue
+            }
+
+            let event: Value = serde_json::from_str(data)
+                .map_err(|e| anyhow!("Failed to parse streaming event: {}: {:?}", e, data))?;
+
+            match event["type"].as_str() {
+                Some("response.created") => {
+                    response_id = event["response"]["id"].as_str().map(str::to_string);
+                }
+                Some("response.output_text.delta") => {
+                    if let Some(delta) = event["delta"].as_str().filter(|d| !d.is_empty()) {
+                        yield (Some(message(vec![MessageContent::text(delta)], &response_id)), None);
+                    }
+                }
+                Some("response.output_item.done") => {
+                    let item = &event["item"];
+                    match item["type"].as_str() {
+                        Some("reasoning") => {
+                            if let Some(content) = reasoning_content(item) {
+                                yield (Some(message(vec![content], &response_id)), None);
+                            }
+                        }
+                        Some("function_call") => tool_calls.push(function_call_content(item)),
+                        _ => {}
+                    }
+                }
+                Some("response.completed") | Some("response.incomplete") => {
+                    let response = &event["response"];
+                    let usage = response["usage"].as_object().map(|usage| ProviderUsage {
+                        usage: get_usage(&Value::Object(usage.clone())),
+                        model: response["model"].as_str().unwrap_or_default().to_string(),
+                    });
+                    let msg = if tool_calls.is_empty() {
+                        None
+                    } else {
+                        Some(message(std::mem::take(&mut tool_calls), &response_id))
+                    };
+                    yield (msg, usage);
+                }
+                Some("response.failed") => {
+                    Err(response_error(&event["response"])
+                        .unwrap_or_else(|| anyhow!("Responses API request failed")))?;
+                }
+                Some("error") => {
+                    Err(anyhow!(
+                        "Responses API stream error: {}",
+                        event["message"].as_str().unwrap_or("unknown error")
+                    ))?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub fn create_request(
+    model_config: &ModelConfig,
+    system: &str,
+    messages: &[Message],
+    tools: &[Tool],
+) -> anyhow::Result<Value, Error> {
+    if model_config.model_name.starts_with("o1-mini") {
+        return Err(anyhow!(
+            "o1-mini model is not currently supported since goose uses tool calling and o1-mini does not support it. Please use o1 or o3 models instead."
+        ));
+    }
+
+    let is_reasoning = is_reasoning_model(&model_config.model_name);
+    let (model_name, reasoning_effort) = split_reasoning_effort(&model_config.model_name);
+
+    let mut payload = json!({
+        "model": model_name,
+        "instructions": system,
+        "input": format_input(messages),
+        // Conversations are not stored by OpenAI; reasoning is carried over in the input
+        "store": false,
+    });
+    let payload_obj = payload.as_object_mut().unwrap();
+
+    let tools_spec = format_response_tools(tools)?;
+    if !tools_spec.is_empty() {
+        payload_obj.insert("tools".to_string(), json!(tools_spec));
+    }
+
+    if let Some(effort) = reasoning_effort {
+        payload_obj.insert(
+            "reasoning".to_string(),
+            json!({"effort": effort, "summary": "auto"}),
+        );
+        payload_obj.insert(
+            "include".to_string(),
+            json!(["reasoning.encrypted_content"]),
+        );
+    }
+
+    // Reasoning models don't support temperature
+    if !is_reasoning {
+        if let Some(temp) = model_config.temperature {
+            payload_obj.insert("temperature".to_string(), json!(temp));
+        }
+    }
+
+    if let Some(tokens) = model_config.max_tokens {
+        payload_obj.insert("max_output_tokens".to_string(), json!(tokens));
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::message::Message;
+    use rmcp::model::CallToolRequestParam;
+    use rmcp::object;
+    use tokio::pin;
+    use tokio_stream::StreamExt;
+
+    const RESPONSE_FIXTURE: &str = include_str!("testdata/openai_responses.json");
+    const STREAM_FIXTURE: &str = include_str!("testdata/openai_responses_stream.txt");
+
+    #[test]
+    fn test_response_to_message_maps_reasoning_to_thinking() -> anyhow::Result<()> {
+        let response: Value = serde_json::from_str(RESPONSE_FIXTURE)?;
+        let message = response_to_message(&response)?;
+
+        assert_eq!(message.content.len(), 3);
+        let MessageContent::Thinking(thinking) = &message.content[0] else {
+            panic!("Expected thinking, got {:?}", message.content[0]);
+        };
+        assert!(thinking.thinking.starts_with("**Listing the directory**"));
+        let carry_over: ReasoningCarryOver = serde_json::from_str(&thinking.signature)?;
+        assert_eq!(carry_over.id, "rs_68a1c2f0e3b08190a4c6e2b1d0f3a9c1");
+        assert_eq!(
+            carry_over.encrypted_content.as_deref(),
+            Some("gAAAAABooy0x-enc")
+        );
+
+        assert_eq!(message.as_concat_text(), "I'll list the files first.");
+
+        let MessageContent::ToolRequest(request) = &message.content[2] else {
+            panic!("Expected tool request, got {:?}", message.content[2]);
+        };
+        assert_eq!(request.id, "call_Qx8bTk3nRZ2dPj6yV1mC0aLs");
+        let tool_call = request.tool_call.as_ref().unwrap();
+        assert_eq!(tool_call.name, "developer__shell");
+        assert_eq!(tool_call.arguments, Some(object!({"command": "ls"})));
+
+        let usage = get_usage(&response["usage"]);
+        assert_eq!(usage.input_tokens, Some(2013));
+        assert_eq!(usage.output_tokens, Some(164));
+        assert_eq!(usage.total_tokens, Some(2177));
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_to_message_error() {
+        let response = json!({
+            "id": "resp_1",
+            "status": "failed",
+            "error": {"code": "server_error", "message": "The server had an error"},
+            "output": []
+        });
+        let err = response_to_message(&response).unwrap_err();
+        assert!(err.to_string().contains("The server had an error"));
+    }
+
+    #[test]
+    fn test_format_input_round_trips_reasoning_and_tool_calls() -> anyhow::Result<()> {
+        let response: Value = serde_json::from_str(RESPONSE_FIXTURE)?;
+        let assistant = response_to_message(&response)?;
+        let messages = vec![
+            Message::user().with_text("What is in this directory?"),
+            assistant,
+            Message::user().with_tool_response(
+                "call_Qx8bTk3nRZ2dPj6yV1mC0aLs",
+                Ok(vec![rmcp::model::Content::text("Cargo.toml\nsrc")]),
+            ),
+        ];
+
+        let input = format_input(&messages);
+        assert_eq!(
+            input,
+            vec![
+                json!({
+                    "type": "message",
+                    "role": "user",
+                    "content": [{"type": "input_text", "text": "What is in this directory?"}]
+                }),
+                json!({
+                    "type": "reasoning",
+                    "id": "rs_68a1c2f0e3b08190a4c6e2b1d0f3a9c1",
+                    "summary": [{
+                        "type": "summary_text",
+                        "text": "**Listing the directory**\n\nThe user wants to know what is here, so I should run ls."
+                    }],
+                    "encrypted_content": "gAAAAABooy0x-enc"
+                }),
+                json!({
+                    "type": "message",
+                    "role": "assistant",
+                    "content": [{"type": "output_text", "text": "I'll list the files first."}]
+                }),
+                json!({
+                    "type": "function_call",
+                    "call_id": "call_Qx8bTk3nRZ2dPj6yV1mC0aLs",
+                    "name": "developer__shell",
+                    "arguments": "{\"command\":\"ls\"}"
+                }),
+                json!({
+                    "type": "function_call_output",
+                    "call_id": "call_Qx8bTk3nRZ2dPj6yV1mC0aLs",
+                    "output": "Cargo.toml\nsrc"
+                }),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_input_skips_foreign_thinking_and_invalid_calls() {
+        let messages = vec![
+            Message::assistant()
+                .with_thinking("Anthropic reasoning", "anthropic-signature")
+                .with_tool_request(
+                    "call_1",
+                    Err(rmcp::model::ErrorData::invalid_params(
+                        "bad arguments",
+                        None,
+                    )),
+                ),
+            Message::user().with_tool_response("call_1", Ok(vec![])),
+        ];
+
+        let input = format_input(&messages);
+        assert_eq!(input.len(), 2);
+        assert_eq!(input[0]["role"], "assistant");
+        assert!(input[0]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("bad arguments"));
+        assert_eq!(input[1]["role"], "user");
+        assert!(input
+            .iter()
+            .all(|item| item["type"] != "function_call_output" && item["type"] != "reasoning"));
+    }
+
+    #[test]
+    fn test_create_request_reasoning_model() -> anyhow::Result<()> {
+        let model_config = ModelConfig {
+            model_name: "o3-high".to_string(),
+            context_limit: None,
+            temperature: Some(0.5),
+            max_tokens: Some(1024),
+            toolshim: false,
+            toolshim_model: None,
+            fast_model: None,
+        };
+        let tool = Tool::new(
+            "developer__shell",
+            "Run a shell command",
+            object!({"type": "object", "properties": {"command": {"type": "string"}}}),
+        );
+        let request = create_request(
+            &model_config,
+            "You are goose",
+            &[Message::user().with_text("Hello")],
+            &[tool],
+        )?;
+
+        assert_eq!(request["model"], "o3");
+        assert_eq!(request["instructions"], "You are goose");
+        assert_eq!(request["store"], false);
+        assert_eq!(
+            request["reasoning"],
+            json!({"effort": "high", "summary": "auto"})
+        );
+        assert_eq!(request["include"], json!(["reasoning.encrypted_content"]));
+        assert_eq!(request["max_output_tokens"], 1024);
+        assert!(request.get("temperature").is_none());
+        assert_eq!(
+            request["tools"][0],
+            json!({
+                "type": "function",
+                "name": "developer__shell",
+                "description": "Run a shell command",
+                "parameters": {
+                    "type": "object",
+                    "properties": {"command": {"type": "string"}},
+                    "required": []
+                }
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_request_chat_model() -> anyhow::Result<()> {
+        let model_config = ModelConfig {
+            model_name: "gpt-4.1".to_string(),
+            context_limit: None,
+            temperature: Some(0.5),
+            max_tokens: None,
+            toolshim: false,
+            toolshim_model: None,
+            fast_model: None,
+        };
+        let request = create_request(&model_config, "system", &[], &[])?;
+
+        assert_eq!(request["model"], "gpt-4.1");
+        assert_eq!(request["temperature"], 0.5);
+        assert!(request.get("reasoning").is_none());
+        assert!(request.get("include").is_none());
+        assert!(request.get("tools").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamed_response_to_messages() -> anyhow::Result<()> {
+        let response_stream =
+            tokio_stream::iter(STREAM_FIXTURE.lines().map(|line| Ok(line.to_string())));
+        let messages = response_to_streaming_message(response_stream);
+        pin!(messages);
+
+        let mut text = String::new();
+        let mut thinking = Vec::new();
+        let mut tool_calls = Vec::new();
+        let mut final_usage = None;
+        while let Some(item) = messages.next().await {
+            let (message, usage) = item?;
+            if let Some(message) = message {
+                assert_eq!(
+                    message.id.as_deref(),
+                    Some("resp_68a1c3b2d5e4819c9f0b7a6e5d4c3b2a")
+                );
+                for content in message.content {
+                    match content {
+                        MessageContent::Text(t) => text.push_str(&t.text),
+                        MessageContent::Thinking(t) => thinking.push(t),
+                        MessageContent::ToolRequest(request) => tool_calls.push(request),
+                        other => panic!("Unexpected content {:?}", other),
+                    }
+                }
+            }
+            if usage.is_some() {
+                final_usage = usage;
+            }
+        }
+
+        assert_eq!(text, "Running both listings now.");
+        assert_eq!(thinking.len(), 1);
+        assert_eq!(
+            thinking[0].thinking,
+            "**Planning the listings**\n\nI'll run ls in both directories."
+        );
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(
+            tool_calls[1].tool_call.as_ref().unwrap(),
+            &CallToolRequestParam {
+                name: "developer__shell".into(),
+                arguments: Some(object!({"command": "ls working_dir"})),
+            }
+        );
+
+        let final_usage = final_usage.expect("usage from response.completed");
+        assert_eq!(final_usage.model, "o4-mini-2025-04-16");
+        assert_eq!(final_usage.usage.total_tokens, Some(5104));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamed_response_failed() {
+        let lines = [
+            "event: response.created",
+            r#"data: {"type":"response.created","response":{"id":"resp_1","status":"in_progress"}}"#,
+            "",
+            "event: response.failed",
+            r#"data: {"type":"response.failed","response":{"id":"resp_1","status":"failed","error":{"code":"rate_limit_exceeded","message":"Rate limit reached"}}}"#,
+        ];
+        let response_stream = tokio_stream::iter(lines.map(|line| Ok(line.to_string())));
+        let messages = response_to_streaming_message(response_stream);
+        pin!(messages);
+
+        let err = loop {
+            match messages.next().await {
+                Some(Err(e)) => break e,
+                Some(Ok(_)) => continue,
+                None => panic!("Expected the stream to fail"),
+            }
+        };
+        assert!(err.to_string().contains("Rate limit reached"));
+    }
+}
@@ -10,6 +10,7 @@ pub mod databricks;
 pub mod embedding;
 pub mod errors;
 mod factory;
+pub mod fallback;
 pub mod formats;
 mod gcpauth;
 pub mod gcpvertexai;
@@ -38,5 +39,6 @@ pub mod venice;
 pub mod xai;
 
 pub use factory::{
-    create, create_with_default_model, create_with_named_model, providers, refresh_custom_providers,
+    create, create_session_provider, create_with_default_model, create_with_named_model, providers,
+    refresh_custom_providers,
 };
@@ -1,5 +1,6 @@
 use super::api_client::{ApiClient, AuthMethod};
 use super::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage};
+use super::embedding::{EmbeddingCapable, EmbeddingRequest, EmbeddingResponse};
 use super::errors::ProviderError;
 use super::retry::ProviderRetry;
 use super::utils::{
@@ -300,6 +301,16 @@ impl Provider for OllamaProvider {
         }))
     }
 
+    fn supports_embeddings(&self) -> bool {
+        true
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        EmbeddingCapable::create_embeddings(self, texts)
+            .await
+            .map_err(|e| ProviderError::ExecutionError(e.to_string()))
+    }
+
     async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
         let response = self
             .api_client
@@ -336,6 +347,38 @@ impl Provider for OllamaProvider {
     }
 }
 
+#[async_trait]
+impl EmbeddingCapable for OllamaProvider {
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Embedding providers are created with the embedding model as their model
+        let request = serde_json::to_value(EmbeddingRequest {
+            input: texts,
+            model: self.model.model_name.clone(),
+        })?;
+
+        let response = self
+            .with_retry(|| async {
+                let response = self
+                    .api_client
+                    .response_post("v1/embeddings", &request)
+                    .await?;
+                handle_response_openai_compat(response).await
+            })
+            .await?;
+
+        let embedding_response: EmbeddingResponse = serde_json::from_value(response)?;
+        Ok(embedding_response
+            .data
+            .into_iter()
+            .map(|d| d.embedding)
+            .collect())
+    }
+}
+
 impl OllamaProvider {
     fn filter_reasoning_tokens(text: &str) -> String {
         let mut filtered = text.to_string();
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use async_stream::try_stream;
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use futures::TryStreamExt;
 use reqwest::StatusCode;
 use serde_json::{json, Value};
@@ -15,12 +16,13 @@ use super::api_client::{ApiClient, AuthMethod};
 use super::base::{ConfigKey, ModelInfo, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::embedding::{EmbeddingCapable, EmbeddingRequest, EmbeddingResponse};
 use super::errors::ProviderError;
-use super::formats::openai::{create_request, get_usage, response_to_message};
+use super::formats::openai::{create_request, get_usage, is_reasoning_model, response_to_message};
+use super::formats::openai_responses;
 use super::retry::ProviderRetry;
 use super::utils::{
     get_model, handle_response_openai_compat, handle_status_openai_compat, ImageFormat,
 };
-use crate::config::declarative_providers::DeclarativeProviderConfig;
+use crate::config::declarative_providers::{DeclarativeProviderConfig, OpenAiApi};
 use crate::conversation::message::Message;
 
 use crate::model::ModelConfig;
@@ -44,6 +46,7 @@ pub const OPEN_AI_KNOWN_MODELS: &[(&str, usize)] = &[
 ];
 
 pub const OPEN_AI_DOC_URL: &str = "https://platform.openai.com/docs/models";
+const OPEN_AI_DEFAULT_HOST: &str = "https://api.openai.com";
 
 #[derive(Debug, serde::Serialize)]
 pub struct OpenAiProvider {
@@ -56,6 +59,8 @@ pub struct OpenAiProvider {
     custom_headers: Option<HashMap<String, String>>,
     supports_streaming: bool,
     name: String,
+    /// The API to send requests to; unset picks the Responses API for reasoning models
+    api: Option<OpenAiApi>,
 }
 
 impl OpenAiProvider {
@@ -66,7 +71,7 @@ impl OpenAiProvider {
         let api_key: String = config.get_secret("OPENAI_API_KEY")?;
         let host: String = config
             .get_param("OPENAI_HOST")
-            .unwrap_or_else(|_| "https://api.openai.com".to_string());
+            .unwrap_or_else(|_| OPEN_AI_DEFAULT_HOST.to_string());
         let base_path: String = config
             .get_param("OPENAI_BASE_PATH")
             .unwrap_or_else(|_| "v1/chat/completions".to_string());
@@ -78,6 +83,12 @@ impl OpenAiProvider {
             .ok()
             .map(parse_custom_headers);
         let timeout_secs: u64 = config.get_param("OPENAI_TIMEOUT").unwrap_or(600);
+        // OpenAI compatible servers rarely implement the Responses API, so only pick it per
+        // model when talking to OpenAI itself
+        let api: Option<OpenAiApi> = config
+            .get_param("OPENAI_API_MODE")
+            .ok()
+            .or((host != OPEN_AI_DEFAULT_HOST).then_some(OpenAiApi::ChatCompletions));
 
         let auth = AuthMethod::BearerToken(api_key);
         let mut api_client =
@@ -110,6 +121,7 @@ impl OpenAiProvider {
             custom_headers,
             supports_streaming: true,
             name: Self::metadata().name,
+            api,
         })
     }
 
@@ -124,6 +136,7 @@ impl OpenAiProvider {
             custom_headers: None,
             supports_streaming: true,
             name: Self::metadata().name,
+            api: Some(OpenAiApi::ChatCompletions),
         }
     }
 
@@ -181,14 +194,48 @@ impl OpenAiProvider {
             custom_headers: config.headers,
             supports_streaming: config.supports_streaming.unwrap_or(true),
             name: config.name.clone(),
+            api: Some(config.api.unwrap_or(OpenAiApi::ChatCompletions)),
         })
     }
 
-    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
-        let response = self
-            .api_client
-            .response_post(&self.base_path, payload)
-            .await?;
+    fn api_for(&self, model_config: &ModelConfig) -> OpenAiApi {
+        match self.api {
+            Some(api) => api,
+            None if is_reasoning_model(&model_config.model_name) => OpenAiApi::Responses,
+            None => OpenAiApi::ChatCompletions,
+        }
+    }
+
+    /// The Responses API lives next to chat completions; a base path pointing elsewhere is
+    /// taken to already be the Responses endpoint
+    fn path_for(&self, api: OpenAiApi) -> String {
+        match (api, self.base_path.strip_suffix("chat/completions")) {
+            (OpenAiApi::Responses, Some(prefix)) => format!("{}responses", prefix),
+            _ => self.base_path.clone(),
+        }
+    }
+
+    fn create_request(
+        &self,
+        api: OpenAiApi,
+        model_config: &ModelConfig,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<Value, ProviderError> {
+        let payload = match api {
+            OpenAiApi::ChatCompletions => {
+                create_request(model_config, system, messages, tools, &ImageFormat::OpenAi)?
+            }
+            OpenAiApi::Responses => {
+                openai_responses::create_request(model_config, system, messages, tools)?
+            }
+        };
+        Ok(payload)
+    }
+
+    async fn post(&self, path: &str, payload: &Value) -> Result<Value, ProviderError> {
+        let response = self.api_client.response_post(path, payload).await?;
         handle_response_openai_compat(response).await
     }
 }
@@ -215,6 +262,7 @@ impl Provider for OpenAiProvider {
                 ConfigKey::new("OPENAI_PROJECT", false, false, None),
                 ConfigKey::new("OPENAI_CUSTOM_HEADERS", false, true, None),
                 ConfigKey::new("OPENAI_TIMEOUT", false, false, Some("600")),
+                ConfigKey::new("OPENAI_API_MODE", false, false, None),
             ],
         )
     }
@@ -238,27 +286,35 @@ impl Provider for OpenAiProvider {
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let payload = create_request(model_config, system, messages, tools, &ImageFormat::OpenAi)?;
+        let api = self.api_for(model_config);
+        let path = self.path_for(api);
+        let payload = self.create_request(api, model_config, system, messages, tools)?;
 
         let mut log = RequestLog::start(&self.model, &payload)?;
         let json_response = self
             .with_retry(|| async {
                 let payload_clone = payload.clone();
-                self.post(&payload_clone).await
+                self.post(&path, &payload_clone).await
             })
             .await
             .inspect_err(|e| {
                 let _ = log.error(e);
             })?;
 
-        let message = response_to_message(&json_response)?;
-        let usage = json_response
-            .get("usage")
-            .map(get_usage)
-            .unwrap_or_else(|| {
-                tracing::debug!("Failed to get usage data");
-                Usage::default()
-            });
+        let (message, usage) = match api {
+            OpenAiApi::ChatCompletions => (
+                response_to_message(&json_response)?,
+                json_response.get("usage").map(get_usage),
+            ),
+            OpenAiApi::Responses => (
+                openai_responses::response_to_message(&json_response)?,
+                json_response.get("usage").map(openai_responses::get_usage),
+            ),
+        };
+        let usage = usage.unwrap_or_else(|| {
+            tracing::debug!("Failed to get usage data");
+            Usage::default()
+        });
 
         let model = get_model(&json_response);
         log.write(&json_response, Some(&usage))?;
@@ -319,20 +375,21 @@ impl Provider for OpenAiProvider {
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<MessageStream, ProviderError> {
-        let mut payload =
-            create_request(&self.model, system, messages, tools, &ImageFormat::OpenAi)?;
+        let api = self.api_for(&self.model);
+        let path = self.path_for(api);
+        let mut payload = self.create_request(api, &self.model, system, messages, tools)?;
         payload["stream"] = serde_json::Value::Bool(true);
-        payload["stream_options"] = json!({
-            "include_usage": true,
-        });
+        // The Responses API always reports usage in its completed event
+        if api == OpenAiApi::ChatCompletions {
+            payload["stream_options"] = json!({
+                "include_usage": true,
+            });
+        }
         let mut log = RequestLog::start(&self.model, &payload)?;
 
         let response = self
             .with_retry(|| async {
-                let resp = self
-                    .api_client
-                    .response_post(&self.base_path, &payload)
-                    .await?;
+                let resp = self.api_client.response_post(&path, &payload).await?;
                 let status = resp.status();
                 if !status.is_success() {
                     return Err(super::utils::map_http_error_to_provider_error(
@@ -353,7 +410,10 @@ impl Provider for OpenAiProvider {
             let stream_reader = StreamReader::new(stream);
             let framed = FramedRead::new(stream_reader, LinesCodec::new()).map_err(anyhow::Error::from);
 
-            let message_stream = response_to_streaming_message(framed);
+            let message_stream: BoxStream<'static, Result<(Option<Message>, Option<ProviderUsage>)>> = match api {
+                OpenAiApi::ChatCompletions => Box::pin(response_to_streaming_message(framed)),
+                OpenAiApi::Responses => Box::pin(openai_responses::response_to_streaming_message(framed)),
+            };
             pin!(message_stream);
             while let Some(message) = message_stream.next().await {
                 let (message, usage) = message.map_err(|e| ProviderError::RequestFailed(format!("Stream decode error: {}", e)))?;
@@ -382,7 +442,8 @@ impl EmbeddingCapable for OpenAiProvider {
             return Ok(vec![]);
         }
 
-        let embedding_model = std::env::var("GOOSE_EMBEDDING_MODEL")
+        let embedding_model = crate::config::Config::global()
+            .get_param("GOOSE_EMBEDDING_MODEL")
             .unwrap_or_else(|_| "text-embedding-3-small".to_string());
 
         let request = EmbeddingRequest {
@@ -427,3 +488,53 @@ impl EmbeddingCapable for OpenAiProvider {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(api: Option<OpenAiApi>, base_path: &str) -> OpenAiProvider {
+        let api_client = ApiClient::new(
+            OPEN_AI_DEFAULT_HOST.to_string(),
+            AuthMethod::BearerToken("test-key".to_string()),
+        )
+        .unwrap();
+        OpenAiProvider {
+            api,
+            base_path: base_path.to_string(),
+            ..OpenAiProvider::new(api_client, ModelConfig::new_or_fail("gpt-4o"))
+        }
+    }
+
+    #[test]
+    fn test_api_selected_per_model_unless_configured() {
+        let auto = provider(None, "v1/chat/completions");
+        assert_eq!(
+            auto.api_for(&ModelConfig::new_or_fail("o3")),
+            OpenAiApi::Responses
+        );
+        assert_eq!(
+            auto.api_for(&ModelConfig::new_or_fail("gpt-4o")),
+            OpenAiApi::ChatCompletions
+        );
+
+        let configured = provider(Some(OpenAiApi::ChatCompletions), "v1/chat/completions");
+        assert_eq!(
+            configured.api_for(&ModelConfig::new_or_fail("o3")),
+            OpenAiApi::ChatCompletions
+        );
+    }
+
+    #[test]
+    fn test_responses_path() {
+        let openai = provider(None, "openai/v1/chat/completions");
+        assert_eq!(openai.path_for(OpenAiApi::Responses), "openai/v1/responses");
+        assert_eq!(
+            openai.path_for(OpenAiApi::ChatCompletions),
+            "openai/v1/chat/completions"
+        );
+
+        let custom = provider(Some(OpenAiApi::Responses), "api/respond");
+        assert_eq!(custom.path_for(OpenAiApi::Responses), "api/respond");
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -333,6 +334,65 @@ pub fn convert_pricing(price_str: &str) -> Option<f64> {
     price_str.parse::<f64>().ok()
 }
 
+/// Strip date and `-latest` suffixes and dot version numbers so a model name matches the
+/// pricing data, e.g. `claude-3-7-sonnet-20250219` -> `claude-3.7-sonnet`
+pub fn normalize_model_name(model: &str) -> String {
+    let mut result = model.to_string();
+
+    // Remove "-latest" suffix
+    if result.ends_with("-latest") {
+        result = result.strip_suffix("-latest").unwrap().to_string();
+    }
+
+    // Remove date-like suffixes: -YYYYMMDD
+    let re_date = Regex::new(r"-\d{8}$").unwrap();
+    if re_date.is_match(&result) {
+        result = re_date.replace(&result, "").to_string();
+    }
+
+    // Convert version numbers like -3-7- to -3.7- (e.g., claude-3-7-sonnet -> claude-3.7-sonnet)
+    let re_version = Regex::new(r"-(\d+)-(\d+)-").unwrap();
+    if re_version.is_match(&result) {
+        result = re_version.replace(&result, "-$1.$2-").to_string();
+    }
+
+    result
+}
+
+/// Estimated cost in USD of a call using `input_tokens` and `output_tokens`, if price data
+/// is available for the model
+pub async fn estimate_cost_usd(
+    provider: &str,
+    model: &str,
+    input_tokens: usize,
+    output_tokens: usize,
+) -> Option<f64> {
+    // For OpenRouter, parse the model name to extract real provider/model
+    let openrouter_data = if provider == "openrouter" {
+        parse_model_id(model)
+    } else {
+        None
+    };
+
+    let (provider_to_use, model_to_use) = match &openrouter_data {
+        Some((real_provider, real_model)) => (real_provider.as_str(), real_model.as_str()),
+        None => (provider, model),
+    };
+
+    // Use the pricing module's get_model_pricing which handles model name mapping internally
+    let cleaned_model = normalize_model_name(model_to_use);
+    let pricing_info = get_model_pricing(provider_to_use, &cleaned_model).await;
+
+    match pricing_info {
+        Some(pricing) => {
+            let input_cost = pricing.input_cost * input_tokens as f64;
+            let output_cost = pricing.output_cost * output_tokens as f64;
+            Some(input_cost + output_cost)
+        }
+        None => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -35,6 +35,7 @@ struct TestRecord {
 pub struct TestProvider {
     inner: Option<Arc<dyn Provider>>,
     records: Arc<Mutex<HashMap<String, TestRecord>>>,
+    missing: Mutex<Vec<String>>,
     file_path: String,
     name: String,
 }
@@ -44,6 +45,7 @@ impl TestProvider {
         Self {
             inner: Some(inner),
             records: Arc::new(Mutex::new(HashMap::new())),
+            missing: Mutex::new(Vec::new()),
             file_path: file_path.into(),
             name: Self::metadata().name,
         }
@@ -56,11 +58,30 @@ impl TestProvider {
         Ok(Self {
             inner: None,
             records: Arc::new(Mutex::new(records)),
+            missing: Mutex::new(Vec::new()),
             file_path,
             name: Self::metadata().name,
         })
     }
 
+    /// Replay records saved by `records_json`. Records are keyed by their recorded input, so
+    /// they still match after the recorded messages have been edited, e.g. to fill in paths.
+    pub fn new_replaying_from_str(content: &str) -> Result<Self> {
+        let records: HashMap<String, TestRecord> = serde_json::from_str(content)?;
+        let records = records
+            .into_values()
+            .map(|record| (Self::hash_input(&record.input.messages), record))
+            .collect();
+
+        Ok(Self {
+            inner: None,
+            records: Arc::new(Mutex::new(records)),
+            missing: Mutex::new(Vec::new()),
+            file_path: String::new(),
+            name: Self::metadata().name,
+        })
+    }
+
     pub fn finish_recording(self) -> Result<()> {
         if self.inner.is_some() {
             self.save_records()?;
@@ -96,9 +117,20 @@ impl TestProvider {
         Ok(())
     }
 
+    /// The recorded interactions, in the format read by `new_replaying_from_str`
+    pub fn records_json(&self) -> Result<String> {
+        let records = self.records.lock().unwrap();
+        Ok(serde_json::to_string_pretty(&*records)?)
+    }
+
     pub fn get_record_count(&self) -> usize {
         self.records.lock().unwrap().len()
     }
+
+    /// Input hashes a replay was asked to complete that have no recorded response
+    pub fn missing_records(&self) -> Vec<String> {
+        self.missing.lock().unwrap().clone()
+    }
 }
 
 #[async_trait]
@@ -154,6 +186,7 @@ impl Provider for TestProvider {
             if let Some(record) = records.get(&hash) {
                 Ok((record.output.message.clone(), record.output.usage.clone()))
             } else {
+                self.missing.lock().unwrap().push(hash.clone());
                 Err(ProviderError::ExecutionError(format!(
                     "No recorded response found for input hash: {}",
                     hash
@@ -273,6 +306,39 @@ mod tests {
         let _ = fs::remove_file(temp_file);
     }
 
+    #[tokio::test]
+    async fn test_replay_from_edited_records() {
+        let mock = Arc::new(MockProvider {
+            model_config: ModelConfig::new_or_fail("mock-model"),
+            response: "Read /tmp/recorded/notes.txt".to_string(),
+        });
+        let recorder = TestProvider::new_recording(mock, "");
+        recorder
+            .complete(
+                "system",
+                &[Message::user().with_text("Open /tmp/recorded")],
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let edited = recorder
+            .records_json()
+            .unwrap()
+            .replace("/tmp/recorded", "/tmp/replayed");
+        let replay_provider = TestProvider::new_replaying_from_str(&edited).unwrap();
+
+        let (message, _) = replay_provider
+            .complete(
+                "system",
+                &[Message::user().with_text("Open /tmp/replayed")],
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(message.as_concat_text(), "Read /tmp/replayed/notes.txt");
+    }
+
     #[tokio::test]
     async fn test_replay_missing_record() {
         let temp_file = format!(
@@ -292,6 +358,7 @@ mod tests {
             .unwrap_err()
             .to_string()
             .contains("No recorded response found"));
+        assert_eq!(replay_provider.missing_records().len(), 1);
 
         let _ = fs::remove_file(temp_file);
     }
@@ -6,8 +6,10 @@ use std::path::Path;
 
 use crate::agents::extension::ExtensionConfig;
 use crate::agents::types::RetryConfig;
+use crate::context_mgmt::ContextStrategyKind;
 use crate::recipe::read_recipe_file_content::read_recipe_file;
 use crate::recipe::yaml_format_utils::reformat_fields_with_multiline_values;
+use crate::session::Budget;
 use crate::utils::contains_unicode_tags;
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
@@ -94,6 +96,14 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+
+    /// How to compact the conversation when it no longer fits in the context window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_strategy: Option<ContextStrategyKind>,
+
+    /// Spending limits for sessions running this recipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Local, Utc};
+use futures::future::BoxFuture;
+use globset::GlobMatcher;
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::Instant;
 use tokio_cron_scheduler::{job::JobId, Job, JobScheduler as TokioJobScheduler};
 use tokio_util::sync::CancellationToken;
 
@@ -18,14 +22,38 @@ use crate::config::paths::Paths;
 use crate::config::Config;
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
-use crate::providers::create;
+use crate::providers::create_session_provider;
+use crate::recipe::build_recipe::build_recipe_from_template;
 use crate::recipe::Recipe;
+use crate::scheduler_history::{JobRun, RetentionPolicy, RunHistory, RunStatus, RunTrigger};
 use crate::scheduler_trait::SchedulerTrait;
+use crate::scheduler_triggers::{
+    changed_files, file_matcher, snapshot_files, webhook_params, JobTrigger, TriggerEvent,
+    DEFAULT_MAX_TRIGGERED_RUNS, DEFAULT_TRIGGER_DEBOUNCE_SECONDS, FILE_WATCH_POLL_INTERVAL,
+    MAX_TRIGGERED_RUNS_CONFIG_KEY,
+};
 use crate::session::session_manager::SessionType;
-use crate::session::{Session, SessionManager};
+use crate::session::{Budget, Session, SessionManager};
 
 type RunningTasksMap = HashMap<String, CancellationToken>;
-type JobsMap = HashMap<String, (JobId, ScheduledJob)>;
+type JobsMap = HashMap<String, (Option<JobId>, ScheduledJob)>;
+/// Runs one attempt of a job; `execute_job` outside of tests
+type JobExecutor = fn(
+    ScheduledJob,
+    Arc<Mutex<JobsMap>>,
+    String,
+    Vec<(String, String)>,
+    CancellationToken,
+) -> BoxFuture<'static, Result<String>>;
+
+/// How often a queued run checks whether an earlier run of its job has finished
+const RUN_WAIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a cancelled or timed out run gets to stop before it is dropped
+const RUN_STOP_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+const DEFAULT_RETRY_BACKOFF_SECONDS: u64 = 60;
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60 * 60);
 
 pub fn get_default_scheduler_storage_path() -> Result<PathBuf, io::Error> {
     let data_dir = Paths::data_dir();
@@ -49,6 +77,7 @@ pub enum SchedulerError {
     AgentSetupError(String),
     PersistError(String),
     CronParseError(String),
+    TriggerError(String),
     SchedulerInternalError(String),
     AnyhowError(anyhow::Error),
 }
@@ -63,6 +92,7 @@ impl std::fmt::Display for SchedulerError {
             SchedulerError::AgentSetupError(e) => write!(f, "Agent setup error: {}", e),
             SchedulerError::PersistError(e) => write!(f, "Failed to persist schedules: {}", e),
             SchedulerError::CronParseError(e) => write!(f, "Invalid cron string: {}", e),
+            SchedulerError::TriggerError(e) => write!(f, "Invalid trigger: {}", e),
             SchedulerError::SchedulerInternalError(e) => {
                 write!(f, "Scheduler internal error: {}", e)
             }
@@ -103,6 +133,8 @@ impl From<anyhow::Error> for SchedulerError {
 pub struct ScheduledJob {
     pub id: String,
     pub source: String,
+    /// Cron expression for the job, empty for jobs that only run on triggers
+    #[serde(default)]
     pub cron: String,
     pub last_run: Option<DateTime<Utc>>,
     #[serde(default)]
@@ -113,6 +145,118 @@ pub struct ScheduledJob {
     pub current_session_id: Option<String>,
     #[serde(default)]
     pub process_start_time: Option<DateTime<Utc>>,
+    /// Spending limits for each session the job runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// Events that run the job in addition to its cron expression
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<JobTrigger>,
+    /// Seconds a trigger waits for further events before running the job (default: 2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debounce_seconds: Option<u64>,
+    #[serde(default)]
+    pub run_policy: RunPolicy,
+}
+
+/// What a cron tick does when the previous run of the job is still going
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    /// Leave the running run alone and skip this tick
+    #[default]
+    Skip,
+    /// Run once the running run finishes; further ticks while waiting are skipped
+    Queue,
+    /// Kill the running run and start a new one
+    CancelPrevious,
+}
+
+impl std::str::FromStr for OverlapPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.replace('-', "_").as_str() {
+            "skip" => Ok(OverlapPolicy::Skip),
+            "queue" => Ok(OverlapPolicy::Queue),
+            "cancel_previous" => Ok(OverlapPolicy::CancelPrevious),
+            _ => Err(format!(
+                "unknown overlap policy '{}', expected skip, queue or cancel-previous",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OverlapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = match self {
+            OverlapPolicy::Skip => "skip",
+            OverlapPolicy::Queue => "queue",
+            OverlapPolicy::CancelPrevious => "cancel-previous",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+/// Limits on how long a job runs, how often a failed run is retried and how overlapping
+/// runs are handled
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, utoipa::ToSchema)]
+pub struct RunPolicy {
+    /// Seconds a run may take before it is stopped and recorded as timed out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runtime_seconds: Option<u64>,
+    /// How many times a failed or timed out run is retried
+    #[serde(default)]
+    pub retries: u32,
+    /// Seconds before the first retry, doubling for each further retry (default: 60)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_backoff_seconds: Option<u64>,
+    #[serde(default)]
+    pub overlap: OverlapPolicy,
+}
+
+impl RunPolicy {
+    fn max_runtime(&self) -> Option<Duration> {
+        self.max_runtime_seconds.map(Duration::from_secs)
+    }
+
+    /// How long to wait before retrying after the given attempt, counting from 1
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let initial = Duration::from_secs(
+            self.retry_backoff_seconds
+                .unwrap_or(DEFAULT_RETRY_BACKOFF_SECONDS),
+        );
+        initial
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(MAX_RETRY_BACKOFF)
+    }
+}
+
+impl ScheduledJob {
+    fn has_cron(&self) -> bool {
+        !self.cron.trim().is_empty()
+    }
+
+    fn upstream_jobs(&self) -> impl Iterator<Item = &str> {
+        self.triggers.iter().filter_map(|trigger| match trigger {
+            JobTrigger::JobCompleted { job_id, .. } => Some(job_id.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// Who started a run, which decides what happens when the job is paused or already running
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RunKind {
+    Cron,
+    Manual,
+    Triggered,
+}
+
+/// A triggered run waiting for its debounce period to pass
+struct PendingTrigger {
+    event: TriggerEvent,
+    last_event_at: Instant,
 }
 
 async fn persist_jobs(
@@ -134,22 +278,51 @@ pub struct Scheduler {
     jobs: Arc<Mutex<JobsMap>>,
     storage_path: PathBuf,
     running_tasks: Arc<Mutex<RunningTasksMap>>,
+    history: RunHistory,
+    pending_triggers: Mutex<HashMap<String, PendingTrigger>>,
+    queued_runs: Mutex<HashSet<String>>,
+    watchers: Mutex<HashMap<String, CancellationToken>>,
+    triggered_runs: Arc<Semaphore>,
+    executor: JobExecutor,
+    weak_self: Weak<Scheduler>,
 }
 
 impl Scheduler {
     pub async fn new(storage_path: PathBuf) -> Result<Arc<Self>, SchedulerError> {
+        Self::with_executor(storage_path, |job, jobs, job_id, params, cancel_token| {
+            Box::pin(execute_job(job, jobs, job_id, params, cancel_token))
+        })
+        .await
+    }
+
+    async fn with_executor(
+        storage_path: PathBuf,
+        executor: JobExecutor,
+    ) -> Result<Arc<Self>, SchedulerError> {
         let internal_scheduler = TokioJobScheduler::new()
             .await
             .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
 
         let jobs = Arc::new(Mutex::new(HashMap::new()));
         let running_tasks = Arc::new(Mutex::new(HashMap::new()));
+        let max_triggered_runs = Config::global()
+            .get_param::<usize>(MAX_TRIGGERED_RUNS_CONFIG_KEY)
+            .unwrap_or(DEFAULT_MAX_TRIGGERED_RUNS)
+            .max(1);
+        let history = RunHistory::new(RunHistory::path_for(&storage_path));
 
-        let arc_self = Arc::new(Self {
+        let arc_self = Arc::new_cyclic(|weak_self| Self {
             tokio_scheduler: internal_scheduler,
             jobs,
             storage_path,
             running_tasks,
+            history,
+            pending_triggers: Mutex::new(HashMap::new()),
+            queued_runs: Mutex::new(HashSet::new()),
+            watchers: Mutex::new(HashMap::new()),
+            triggered_runs: Arc::new(Semaphore::new(max_triggered_runs)),
+            executor,
+            weak_self: weak_self.clone(),
         });
 
         arc_self.load_jobs_from_storage().await;
@@ -163,10 +336,8 @@ impl Scheduler {
     }
 
     fn create_cron_task(&self, job: ScheduledJob) -> Result<Job, SchedulerError> {
-        let job_for_task = job.clone();
-        let jobs_arc = self.jobs.clone();
-        let storage_path = self.storage_path.clone();
-        let running_tasks_arc = self.running_tasks.clone();
+        let job_id = job.id.clone();
+        let scheduler = self.weak_self.clone();
 
         let cron_parts: Vec<&str> = job.cron.split_whitespace().collect();
         let cron = match cron_parts.len() {
@@ -191,79 +362,433 @@ impl Scheduler {
         let local_tz = Local::now().timezone();
 
         Job::new_async_tz(&cron, local_tz, move |_uuid, _l| {
-            tracing::info!("Cron task triggered for job '{}'", job_for_task.id);
-            let task_job_id = job_for_task.id.clone();
-            let current_jobs_arc = jobs_arc.clone();
-            let local_storage_path = storage_path.clone();
-            let job_to_execute = job_for_task.clone();
-            let running_tasks = running_tasks_arc.clone();
+            tracing::info!("Cron task triggered for job '{}'", job_id);
+            let task_job_id = job_id.clone();
+            let scheduler = scheduler.clone();
 
             Box::pin(async move {
-                let should_execute = {
-                    let jobs_guard = current_jobs_arc.lock().await;
-                    jobs_guard
-                        .get(&task_job_id)
-                        .map(|(_, j)| !j.paused)
-                        .unwrap_or(false)
+                let Some(scheduler) = scheduler.upgrade() else {
+                    return;
                 };
+                match scheduler.begin_cron_run(&task_job_id).await {
+                    Ok(Some(job)) => {
+                        let _ = scheduler.run_job(job, RunTrigger::Cron, Vec::new()).await;
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::error!("Failed to start job '{}': {}", task_job_id, e),
+                }
+            })
+        })
+        .map_err(|e| SchedulerError::CronParseError(e.to_string()))
+    }
 
-                if !should_execute {
-                    return;
+    /// Mark the job as running and return it, or `None` when the job should not run now:
+    /// paused jobs only run manually, and cron and triggered runs do not start while the
+    /// job is running
+    async fn begin_run(
+        &self,
+        job_id: &str,
+        kind: RunKind,
+    ) -> Result<Option<ScheduledJob>, SchedulerError> {
+        let job = {
+            let mut jobs_guard = self.jobs.lock().await;
+            let Some((_, job)) = jobs_guard.get_mut(job_id) else {
+                return Err(SchedulerError::JobNotFound(job_id.to_string()));
+            };
+            match kind {
+                RunKind::Manual if job.currently_running => {
+                    return Err(SchedulerError::AnyhowError(anyhow!(
+                        "Job '{}' is already running",
+                        job_id
+                    )));
                 }
+                RunKind::Cron | RunKind::Triggered if job.paused => return Ok(None),
+                RunKind::Cron | RunKind::Triggered if job.currently_running => return Ok(None),
+                _ => {}
+            }
+            let now = Utc::now();
+            job.last_run = Some(now);
+            job.currently_running = true;
+            job.process_start_time = Some(now);
+            job.clone()
+        };
 
-                let current_time = Utc::now();
-                {
-                    let mut jobs_guard = current_jobs_arc.lock().await;
-                    if let Some((_, job)) = jobs_guard.get_mut(&task_job_id) {
-                        job.last_run = Some(current_time);
-                        job.currently_running = true;
-                        job.process_start_time = Some(current_time);
+        persist_jobs(&self.storage_path, &self.jobs).await?;
+        Ok(Some(job))
+    }
+
+    /// Start a run for a cron tick, applying the job's overlap policy when the previous
+    /// run is still going
+    async fn begin_cron_run(&self, job_id: &str) -> Result<Option<ScheduledJob>, SchedulerError> {
+        let mut queued = false;
+        let result = loop {
+            if let Some(job) = self.begin_run(job_id, RunKind::Cron).await? {
+                break Ok(Some(job));
+            }
+            let state = {
+                let jobs_guard = self.jobs.lock().await;
+                jobs_guard
+                    .get(job_id)
+                    .map(|(_, job)| (job.paused, job.currently_running, job.run_policy.overlap))
+            };
+            match state {
+                Some((false, true, overlap)) if !queued => match overlap {
+                    OverlapPolicy::Skip => {
+                        tracing::info!("Job '{}' is still running, skipping this run", job_id);
+                        return Ok(None);
                     }
-                }
+                    OverlapPolicy::Queue | OverlapPolicy::CancelPrevious => {
+                        if !self.queued_runs.lock().await.insert(job_id.to_string()) {
+                            tracing::info!("Job '{}' already has a queued run", job_id);
+                            return Ok(None);
+                        }
+                        queued = true;
+                        if overlap == OverlapPolicy::CancelPrevious {
+                            tracing::info!("Cancelling the previous run of job '{}'", job_id);
+                            if let Err(e) = self.kill_running_job(job_id).await {
+                                tracing::warn!("Failed to cancel job '{}': {}", job_id, e);
+                            }
+                        }
+                    }
+                },
+                Some((false, _, _)) => {}
+                _ => break Ok(None),
+            }
+            tokio::time::sleep(RUN_WAIT_INTERVAL).await;
+        };
 
-                if let Err(e) = persist_jobs(&local_storage_path, &current_jobs_arc).await {
-                    tracing::error!("Failed to persist job status: {}", e);
-                }
+        if queued {
+            self.queued_runs.lock().await.remove(job_id);
+        }
+        result
+    }
+
+    /// Execute a job marked as running by `begin_run`, retrying failed attempts as its run
+    /// policy allows. Every attempt is recorded in the job's history. Once the job is done
+    /// the jobs chained to it are triggered.
+    async fn run_job(
+        &self,
+        job: ScheduledJob,
+        trigger: RunTrigger,
+        params: Vec<(String, String)>,
+    ) -> Result<String> {
+        let job_id = job.id.clone();
+        let policy = job.run_policy;
+        let cancel_token = CancellationToken::new();
+        {
+            let mut tasks = self.running_tasks.lock().await;
+            tasks.insert(job_id.clone(), cancel_token.clone());
+        }
 
-                let cancel_token = CancellationToken::new();
-                {
-                    let mut tasks = running_tasks.lock().await;
-                    tasks.insert(task_job_id.clone(), cancel_token.clone());
+        let mut attempt = 1;
+        let (result, session_id) = loop {
+            let mut run = JobRun::start(&job_id, trigger, Utc::now()).with_attempt(attempt);
+            self.record_run(&run).await;
+
+            // A timeout cancels only this attempt; killing the job also stops its retries
+            let attempt_token = cancel_token.child_token();
+            let execution = (self.executor)(
+                job.clone(),
+                self.jobs.clone(),
+                job_id.clone(),
+                params.clone(),
+                attempt_token.clone(),
+            );
+            let (result, status) =
+                run_attempt(execution, policy.max_runtime(), &attempt_token).await;
+
+            let session_id = {
+                let mut jobs_guard = self.jobs.lock().await;
+                jobs_guard
+                    .get_mut(&job_id)
+                    .and_then(|(_, job)| job.current_session_id.take())
+            };
+            if let Some(session_id) = &session_id {
+                match SessionManager::get_session(session_id, false).await {
+                    Ok(session) => run.record_usage(&session),
+                    Err(e) => tracing::warn!("Failed to load session of job '{}': {}", job_id, e),
                 }
+            }
+            let error = match (&result, status) {
+                (Err(e), RunStatus::Failure | RunStatus::Timeout) => Some(e.to_string()),
+                _ => None,
+            };
+            run.finish(status, error);
+            self.record_run(&run).await;
 
-                let result = execute_job(
-                    job_to_execute,
-                    current_jobs_arc.clone(),
-                    task_job_id.clone(),
-                    cancel_token.clone(),
-                )
-                .await;
+            let retry = matches!(status, RunStatus::Failure | RunStatus::Timeout)
+                && attempt <= policy.retries;
+            if !retry {
+                break (result, session_id);
+            }
+
+            let delay = policy.retry_delay(attempt);
+            tracing::warn!(
+                "Job '{}' attempt {} ended with status {}, retrying in {}s",
+                job_id,
+                attempt,
+                status,
+                delay.as_secs()
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel_token.cancelled() => break (result, session_id),
+            }
+            attempt += 1;
+        };
+
+        {
+            let mut tasks = self.running_tasks.lock().await;
+            tasks.remove(&job_id);
+        }
+
+        {
+            let mut jobs_guard = self.jobs.lock().await;
+            if let Some((_, job)) = jobs_guard.get_mut(&job_id) {
+                job.currently_running = false;
+                job.process_start_time = None;
+            }
+        }
+
+        if let Err(e) = persist_jobs(&self.storage_path, &self.jobs).await {
+            tracing::error!("Failed to persist job completion: {}", e);
+        }
+
+        match &result {
+            Ok(_) => tracing::info!("Job '{}' completed", job_id),
+            Err(e) => tracing::error!("Job '{}' failed: {}", job_id, e),
+        }
+
+        self.trigger_chained_jobs(&job_id, result.is_ok(), session_id)
+            .await;
+        result
+    }
+
+    /// Save a run to the history and delete the sessions of the runs retention pruned
+    async fn record_run(&self, run: &JobRun) {
+        let pruned = match self
+            .history
+            .record(run, RetentionPolicy::from_config())
+            .await
+        {
+            Ok(pruned) => pruned,
+            Err(e) => {
+                tracing::error!("Failed to record run of job '{}': {}", run.job_id, e);
+                return;
+            }
+        };
+
+        for session_id in pruned.iter().filter_map(|run| run.session_id.as_deref()) {
+            if let Err(e) = SessionManager::delete_session(session_id).await {
+                tracing::warn!("Failed to delete pruned session '{}': {}", session_id, e);
+            }
+        }
+    }
+
+    async fn trigger_chained_jobs(
+        &self,
+        job_id: &str,
+        succeeded: bool,
+        session_id: Option<String>,
+    ) {
+        let chained: Vec<String> = {
+            let jobs_guard = self.jobs.lock().await;
+            jobs_guard
+                .values()
+                .filter(|(_, job)| {
+                    job.triggers.iter().any(|trigger| {
+                        matches!(trigger, JobTrigger::JobCompleted { job_id: upstream, on }
+                            if upstream == job_id && on.matches(succeeded))
+                    })
+                })
+                .map(|(_, job)| job.id.clone())
+                .collect()
+        };
+
+        for chained_id in chained {
+            tracing::info!("Job '{}' finished, triggering '{}'", job_id, chained_id);
+            self.fire_trigger(
+                &chained_id,
+                TriggerEvent::JobCompleted {
+                    job_id: job_id.to_string(),
+                    succeeded,
+                    session_id: session_id.clone(),
+                },
+            )
+            .await;
+        }
+    }
+
+    /// Queue a triggered run of the job. Events that arrive before the run starts are
+    /// merged into it, so a burst of events runs the job once.
+    async fn fire_trigger(&self, job_id: &str, event: TriggerEvent) {
+        {
+            let mut pending = self.pending_triggers.lock().await;
+            if let Some(run) = pending.get_mut(job_id) {
+                run.event.merge(event);
+                run.last_event_at = Instant::now();
+                return;
+            }
+            pending.insert(
+                job_id.to_string(),
+                PendingTrigger {
+                    event,
+                    last_event_at: Instant::now(),
+                },
+            );
+        }
+
+        if let Some(scheduler) = self.weak_self.upgrade() {
+            let job_id = job_id.to_string();
+            tokio::spawn(async move { scheduler.dispatch_trigger(job_id).await });
+        }
+    }
 
-                {
-                    let mut tasks = running_tasks.lock().await;
-                    tasks.remove(&task_job_id);
+    /// Run a pending trigger once no new events arrived for the job's debounce period,
+    /// the job is not running and a triggered run slot is free
+    fn dispatch_trigger(self: Arc<Self>, job_id: String) -> BoxFuture<'static, ()> {
+        // Boxed because a run can trigger chained jobs, which dispatch again
+        Box::pin(async move {
+            let debounce = {
+                let jobs_guard = self.jobs.lock().await;
+                jobs_guard.get(&job_id).map(|(_, job)| {
+                    Duration::from_secs(
+                        job.debounce_seconds
+                            .unwrap_or(DEFAULT_TRIGGER_DEBOUNCE_SECONDS),
+                    )
+                })
+            };
+            let Some(debounce) = debounce else {
+                self.pending_triggers.lock().await.remove(&job_id);
+                return;
+            };
+
+            loop {
+                let last_event_at = match self.pending_triggers.lock().await.get(&job_id) {
+                    Some(run) => run.last_event_at,
+                    None => return,
+                };
+                let quiet_until = last_event_at + debounce;
+                if Instant::now() >= quiet_until {
+                    break;
                 }
+                tokio::time::sleep_until(quiet_until).await;
+            }
 
-                {
-                    let mut jobs_guard = current_jobs_arc.lock().await;
-                    if let Some((_, job)) = jobs_guard.get_mut(&task_job_id) {
-                        job.currently_running = false;
-                        job.current_session_id = None;
-                        job.process_start_time = None;
+            let (job, _permit) = loop {
+                let state = {
+                    let jobs_guard = self.jobs.lock().await;
+                    jobs_guard
+                        .get(&job_id)
+                        .map(|(_, job)| (job.paused, job.currently_running))
+                };
+                match state {
+                    None | Some((true, _)) => {
+                        tracing::info!("Dropping trigger for missing or paused job '{}'", job_id);
+                        self.pending_triggers.lock().await.remove(&job_id);
+                        return;
                     }
+                    Some((false, true)) => {
+                        tokio::time::sleep(RUN_WAIT_INTERVAL).await;
+                        continue;
+                    }
+                    Some((false, false)) => {}
                 }
 
-                if let Err(e) = persist_jobs(&local_storage_path, &current_jobs_arc).await {
-                    tracing::error!("Failed to persist job completion: {}", e);
+                let Ok(permit) = self.triggered_runs.clone().acquire_owned().await else {
+                    return;
+                };
+                match self.begin_run(&job_id, RunKind::Triggered).await {
+                    Ok(Some(job)) => break (job, permit),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::error!("Failed to start triggered job '{}': {}", job_id, e);
+                        self.pending_triggers.lock().await.remove(&job_id);
+                        return;
+                    }
                 }
+            };
 
-                match result {
-                    Ok(_) => tracing::info!("Job '{}' completed", task_job_id),
-                    Err(e) => tracing::error!("Job '{}' failed: {}", task_job_id, e),
-                }
-            })
+            let Some(run) = self.pending_triggers.lock().await.remove(&job_id) else {
+                return;
+            };
+            let _ = self
+                .run_job(job, RunTrigger::from(&run.event), run.event.params())
+                .await;
         })
-        .map_err(|e| SchedulerError::CronParseError(e.to_string()))
+    }
+
+    /// Start polling the directories of the job's file-watch triggers
+    async fn start_watchers(&self, job: &ScheduledJob) {
+        let watches: Vec<(PathBuf, GlobMatcher)> = job
+            .triggers
+            .iter()
+            .filter_map(|trigger| match trigger {
+                JobTrigger::FileWatch { path, pattern } => match file_matcher(pattern) {
+                    Ok(matcher) => Some((PathBuf::from(path), matcher)),
+                    Err(e) => {
+                        tracing::error!("Not watching files for job '{}': {}", job.id, e);
+                        None
+                    }
+                },
+                _ => None,
+            })
+            .collect();
+        if watches.is_empty() {
+            return;
+        }
+
+        let cancel_token = CancellationToken::new();
+        for (dir, matcher) in watches {
+            tokio::spawn(watch_files(
+                self.weak_self.clone(),
+                job.id.clone(),
+                dir,
+                matcher,
+                cancel_token.clone(),
+            ));
+        }
+
+        let mut watchers = self.watchers.lock().await;
+        if let Some(previous) = watchers.insert(job.id.clone(), cancel_token) {
+            previous.cancel();
+        }
+    }
+
+    async fn stop_watchers(&self, job_id: &str) {
+        if let Some(cancel_token) = self.watchers.lock().await.remove(job_id) {
+            cancel_token.cancel();
+        }
+    }
+
+    async fn validate_triggers(&self, job: &ScheduledJob) -> Result<(), SchedulerError> {
+        if !job.has_cron() && job.triggers.is_empty() {
+            return Err(SchedulerError::TriggerError(format!(
+                "job '{}' needs a cron expression or at least one trigger",
+                job.id
+            )));
+        }
+        for trigger in &job.triggers {
+            trigger.validate(&job.id)?;
+        }
+
+        let jobs_guard = self.jobs.lock().await;
+        let mut seen = HashSet::new();
+        let mut upstream: Vec<&str> = job.upstream_jobs().collect();
+        while let Some(upstream_id) = upstream.pop() {
+            if upstream_id == job.id {
+                return Err(SchedulerError::TriggerError(format!(
+                    "chaining job '{}' would create a cycle",
+                    job.id
+                )));
+            }
+            if seen.insert(upstream_id) {
+                if let Some((_, upstream_job)) = jobs_guard.get(upstream_id) {
+                    upstream.extend(upstream_job.upstream_jobs());
+                }
+            }
+        }
+        Ok(())
     }
 
     pub async fn add_scheduled_job(
@@ -276,6 +801,7 @@ impl Scheduler {
                 return Err(SchedulerError::JobIdExists(original_job_spec.id.clone()));
             }
         }
+        self.validate_triggers(&original_job_spec).await?;
 
         let original_recipe_path = Path::new(&original_job_spec.source);
         if !original_recipe_path.is_file() {
@@ -301,14 +827,19 @@ impl Scheduler {
         stored_job.current_session_id = None;
         stored_job.process_start_time = None;
 
-        let cron_task = self.create_cron_task(stored_job.clone())?;
-
-        let job_uuid = self
-            .tokio_scheduler
-            .add(cron_task)
-            .await
-            .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        let job_uuid = if stored_job.has_cron() {
+            let cron_task = self.create_cron_task(stored_job.clone())?;
+            Some(
+                self.tokio_scheduler
+                    .add(cron_task)
+                    .await
+                    .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?,
+            )
+        } else {
+            None
+        };
 
+        self.start_watchers(&stored_job).await;
         {
             let mut jobs_guard = self.jobs.lock().await;
             jobs_guard.insert(stored_job.id.clone(), (job_uuid, stored_job));
@@ -357,30 +888,35 @@ impl Scheduler {
                 continue;
             }
 
-            let cron_task = match self.create_cron_task(job_to_load.clone()) {
-                Ok(task) => task,
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to create cron task for job '{}': {}. Skipping.",
-                        job_to_load.id,
-                        e
-                    );
-                    continue;
-                }
-            };
+            let job_uuid = if job_to_load.has_cron() {
+                let cron_task = match self.create_cron_task(job_to_load.clone()) {
+                    Ok(task) => task,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to create cron task for job '{}': {}. Skipping.",
+                            job_to_load.id,
+                            e
+                        );
+                        continue;
+                    }
+                };
 
-            let job_uuid = match self.tokio_scheduler.add(cron_task).await {
-                Ok(uuid) => uuid,
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to add job '{}' to scheduler: {}. Skipping.",
-                        job_to_load.id,
-                        e
-                    );
-                    continue;
+                match self.tokio_scheduler.add(cron_task).await {
+                    Ok(uuid) => Some(uuid),
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to add job '{}' to scheduler: {}. Skipping.",
+                            job_to_load.id,
+                            e
+                        );
+                        continue;
+                    }
                 }
+            } else {
+                None
             };
 
+            self.start_watchers(&job_to_load).await;
             let mut jobs_guard = self.jobs.lock().await;
             jobs_guard.insert(job_to_load.id.clone(), (job_uuid, job_to_load));
         }
@@ -404,10 +940,15 @@ impl Scheduler {
             }
         };
 
-        self.tokio_scheduler
-            .remove(&job_uuid)
-            .await
-            .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        if let Some(job_uuid) = job_uuid {
+            self.tokio_scheduler
+                .remove(&job_uuid)
+                .await
+                .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        }
+        self.stop_watchers(id).await;
+        self.pending_triggers.lock().await.remove(id);
+        self.history.remove_job(id).await?;
 
         let path = Path::new(&recipe_path);
         if path.exists() {
@@ -439,59 +980,23 @@ impl Scheduler {
         Ok(schedule_sessions)
     }
 
-    pub async fn run_now(&self, sched_id: &str) -> Result<String, SchedulerError> {
-        let job_to_run = {
-            let mut jobs_guard = self.jobs.lock().await;
-            match jobs_guard.get_mut(sched_id) {
-                Some((_, job)) => {
-                    if job.currently_running {
-                        return Err(SchedulerError::AnyhowError(anyhow!(
-                            "Job '{}' is already running",
-                            sched_id
-                        )));
-                    }
-                    job.currently_running = true;
-                    job.process_start_time = Some(Utc::now());
-                    job.clone()
-                }
-                None => return Err(SchedulerError::JobNotFound(sched_id.to_string())),
-            }
-        };
-
-        persist_jobs(&self.storage_path, &self.jobs).await?;
-
-        let cancel_token = CancellationToken::new();
-        {
-            let mut tasks = self.running_tasks.lock().await;
-            tasks.insert(sched_id.to_string(), cancel_token.clone());
+    /// The most recent runs of a job, newest first
+    pub async fn runs(&self, sched_id: &str, limit: usize) -> Result<Vec<JobRun>, SchedulerError> {
+        if !self.jobs.lock().await.contains_key(sched_id) {
+            return Err(SchedulerError::JobNotFound(sched_id.to_string()));
         }
+        self.history.runs(sched_id, limit).await
+    }
 
-        let result = execute_job(
-            job_to_run,
-            self.jobs.clone(),
-            sched_id.to_string(),
-            cancel_token.clone(),
-        )
-        .await;
-
-        {
-            let mut tasks = self.running_tasks.lock().await;
-            tasks.remove(sched_id);
-        }
+    pub async fn run_now(&self, sched_id: &str) -> Result<String, SchedulerError> {
+        let Some(job_to_run) = self.begin_run(sched_id, RunKind::Manual).await? else {
+            return Err(SchedulerError::JobNotFound(sched_id.to_string()));
+        };
 
+        match self
+            .run_job(job_to_run, RunTrigger::Manual, Vec::new())
+            .await
         {
-            let mut jobs_guard = self.jobs.lock().await;
-            if let Some((_, job)) = jobs_guard.get_mut(sched_id) {
-                job.currently_running = false;
-                job.current_session_id = None;
-                job.process_start_time = None;
-                job.last_run = Some(Utc::now());
-            }
-        }
-
-        persist_jobs(&self.storage_path, &self.jobs).await?;
-
-        match result {
             Ok(session_id) => Ok(session_id),
             Err(e) => Err(SchedulerError::AnyhowError(anyhow!(
                 "Job '{}' failed: {}",
@@ -501,6 +1006,39 @@ impl Scheduler {
         }
     }
 
+    /// Run a job from its webhook trigger, passing the fields of the payload as recipe
+    /// parameters. The run starts once the job's debounce period has passed.
+    pub async fn fire_webhook(
+        &self,
+        sched_id: &str,
+        payload: Option<serde_json::Value>,
+    ) -> Result<(), SchedulerError> {
+        {
+            let jobs_guard = self.jobs.lock().await;
+            match jobs_guard.get(sched_id) {
+                None => return Err(SchedulerError::JobNotFound(sched_id.to_string())),
+                Some((_, job)) if !job.triggers.contains(&JobTrigger::Webhook) => {
+                    return Err(SchedulerError::TriggerError(format!(
+                        "job '{}' has no webhook trigger",
+                        sched_id
+                    )));
+                }
+                Some((_, job)) if job.paused => {
+                    return Err(SchedulerError::AnyhowError(anyhow!(
+                        "Schedule '{}' is paused",
+                        sched_id
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let params = webhook_params(payload)?;
+        self.fire_trigger(sched_id, TriggerEvent::Webhook(params))
+            .await;
+        Ok(())
+    }
+
     pub async fn pause_schedule(&self, sched_id: &str) -> Result<(), SchedulerError> {
         {
             let mut jobs_guard = self.jobs.lock().await;
@@ -551,6 +1089,12 @@ impl Scheduler {
                     if new_cron == job.cron {
                         return Ok(());
                     }
+                    if new_cron.trim().is_empty() && job.triggers.is_empty() {
+                        return Err(SchedulerError::TriggerError(format!(
+                            "job '{}' needs a cron expression or at least one trigger",
+                            sched_id
+                        )));
+                    }
                     job.cron = new_cron.clone();
                     (*uuid, job.clone())
                 }
@@ -558,17 +1102,24 @@ impl Scheduler {
             }
         };
 
-        self.tokio_scheduler
-            .remove(&old_uuid)
-            .await
-            .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        if let Some(old_uuid) = old_uuid {
+            self.tokio_scheduler
+                .remove(&old_uuid)
+                .await
+                .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        }
 
-        let cron_task = self.create_cron_task(updated_job)?;
-        let new_uuid = self
-            .tokio_scheduler
-            .add(cron_task)
-            .await
-            .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        let new_uuid = if updated_job.has_cron() {
+            let cron_task = self.create_cron_task(updated_job)?;
+            Some(
+                self.tokio_scheduler
+                    .add(cron_task)
+                    .await
+                    .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?,
+            )
+        } else {
+            None
+        };
 
         {
             let mut jobs_guard = self.jobs.lock().await;
@@ -623,31 +1174,62 @@ impl Scheduler {
     }
 }
 
+/// Run one attempt of a job, stopping it once it exceeds `max_runtime`. An attempt that
+/// does not stop within a grace period after it was cancelled is dropped.
+async fn run_attempt<F>(
+    execution: F,
+    max_runtime: Option<Duration>,
+    cancel_token: &CancellationToken,
+) -> (Result<String>, RunStatus)
+where
+    F: std::future::Future<Output = Result<String>>,
+{
+    let mut execution = std::pin::pin!(execution);
+    let deadline = async {
+        match max_runtime {
+            Some(max_runtime) => tokio::time::sleep(max_runtime).await,
+            None => std::future::pending().await,
+        }
+    };
+
+    let timed_out = tokio::select! {
+        result = &mut execution => {
+            let status = match &result {
+                _ if cancel_token.is_cancelled() => RunStatus::Killed,
+                Ok(_) => RunStatus::Success,
+                Err(_) => RunStatus::Failure,
+            };
+            return (result, status);
+        }
+        _ = deadline => true,
+        _ = cancel_token.cancelled() => false,
+    };
+
+    cancel_token.cancel();
+    let stopped = tokio::time::timeout(RUN_STOP_GRACE_PERIOD, &mut execution).await;
+    if timed_out {
+        let max_runtime = max_runtime.unwrap_or_default().as_secs();
+        return (
+            Err(anyhow!("Job exceeded its max runtime of {}s", max_runtime)),
+            RunStatus::Timeout,
+        );
+    }
+    let result = stopped.unwrap_or_else(|_| Err(anyhow!("Job did not stop after it was killed")));
+    (result, RunStatus::Killed)
+}
+
 async fn execute_job(
     job: ScheduledJob,
     jobs: Arc<Mutex<JobsMap>>,
     job_id: String,
+    params: Vec<(String, String)>,
     cancel_token: CancellationToken,
 ) -> Result<String> {
     if job.source.is_empty() {
         return Ok(job.id.to_string());
     }
 
-    let recipe_path = Path::new(&job.source);
-    let recipe_content = fs::read_to_string(recipe_path)?;
-
-    let recipe: Recipe = {
-        let extension = recipe_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("yaml")
-            .to_lowercase();
-
-        match extension.as_str() {
-            "json" | "jsonl" => serde_json::from_str(&recipe_content)?,
-            _ => serde_yaml::from_str(&recipe_content)?,
-        }
-    };
+    let recipe = load_job_recipe(Path::new(&job.source), params)?;
 
     let agent = Agent::new();
 
@@ -656,7 +1238,7 @@ async fn execute_job(
     let model_name = config.get_goose_model()?;
     let model_config = crate::model::ModelConfig::new(&model_name)?;
 
-    let agent_provider = create(&provider_name, model_config).await?;
+    let agent_provider = create_session_provider(&provider_name, model_config).await?;
 
     if let Some(ref extensions) = recipe.extensions {
         for ext in extensions {
@@ -673,6 +1255,13 @@ async fn execute_job(
     )
     .await?;
 
+    SessionManager::update_session(&session.id)
+        .schedule_id(Some(job.id.clone()))
+        .recipe(Some(recipe.clone()))
+        .budget(job.budget)
+        .apply()
+        .await?;
+
     let mut jobs_guard = jobs.lock().await;
     if let Some((_, job_def)) = jobs_guard.get_mut(job_id.as_str()) {
         job_def.current_session_id = Some(session.id.clone());
@@ -718,19 +1307,86 @@ async fn execute_job(
             Ok(_) => {}
             Err(e) => {
                 tracing::error!("Error in agent stream: {}", e);
-                break;
+                return Err(e);
             }
         }
     }
 
-    SessionManager::update_session(&session.id)
-        .schedule_id(Some(job.id.clone()))
-        .recipe(Some(recipe))
-        .apply()
-        .await?;
     Ok(session.id)
 }
 
+/// Load the job's recipe. Parameters from a trigger are rendered into the recipe
+/// template; runs without parameters use the recipe as it is stored.
+fn load_job_recipe(recipe_path: &Path, params: Vec<(String, String)>) -> Result<Recipe> {
+    let recipe_content = fs::read_to_string(recipe_path)?;
+
+    if !params.is_empty() {
+        let recipe_dir = recipe_path.parent().unwrap_or_else(|| Path::new("."));
+        return Ok(build_recipe_from_template(
+            recipe_content,
+            recipe_dir,
+            params,
+            None::<fn(&str, &str) -> Result<String>>,
+        )?);
+    }
+
+    let extension = recipe_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("yaml")
+        .to_lowercase();
+
+    Ok(match extension.as_str() {
+        "json" | "jsonl" => serde_json::from_str(&recipe_content)?,
+        _ => serde_yaml::from_str(&recipe_content)?,
+    })
+}
+
+async fn watch_files(
+    scheduler: Weak<Scheduler>,
+    job_id: String,
+    dir: PathBuf,
+    matcher: GlobMatcher,
+    cancel_token: CancellationToken,
+) {
+    let scan = |dir: PathBuf, matcher: GlobMatcher| async move {
+        tokio::task::spawn_blocking(move || snapshot_files(&dir, &matcher))
+            .await
+            .unwrap_or_default()
+    };
+
+    let mut snapshot = scan(dir.clone(), matcher.clone()).await;
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return,
+            _ = tokio::time::sleep(FILE_WATCH_POLL_INTERVAL) => {}
+        }
+        if scheduler.strong_count() == 0 {
+            return;
+        }
+
+        let current = scan(dir.clone(), matcher.clone()).await;
+        let changed = changed_files(&snapshot, &current);
+        snapshot = current;
+        if changed.is_empty() {
+            continue;
+        }
+
+        let Some(scheduler) = scheduler.upgrade() else {
+            return;
+        };
+        tracing::info!(
+            "{} watched file(s) changed in {}, triggering job '{}'",
+            changed.len(),
+            dir.display(),
+            job_id
+        );
+        scheduler
+            .fire_trigger(&job_id, TriggerEvent::FilesChanged(changed))
+            .await;
+    }
+}
+
 #[async_trait]
 impl SchedulerTrait for Scheduler {
     async fn add_scheduled_job(&self, job: ScheduledJob) -> Result<(), SchedulerError> {
@@ -757,6 +1413,14 @@ impl SchedulerTrait for Scheduler {
         self.run_now(id).await
     }
 
+    async fn fire_webhook(
+        &self,
+        id: &str,
+        payload: Option<serde_json::Value>,
+    ) -> Result<(), SchedulerError> {
+        self.fire_webhook(id, payload).await
+    }
+
     async fn sessions(
         &self,
         sched_id: &str,
@@ -765,6 +1429,10 @@ impl SchedulerTrait for Scheduler {
         self.sessions(sched_id, limit).await
     }
 
+    async fn runs(&self, sched_id: &str, limit: usize) -> Result<Vec<JobRun>, SchedulerError> {
+        self.runs(sched_id, limit).await
+    }
+
     async fn update_schedule(
         &self,
         sched_id: &str,
@@ -788,6 +1456,7 @@ impl SchedulerTrait for Scheduler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scheduler_triggers::ChainCondition;
     use tempfile::tempdir;
     use tokio::time::{sleep, Duration};
 
@@ -813,6 +1482,10 @@ mod tests {
             paused: false,
             current_session_id: None,
             process_start_time: None,
+            budget: None,
+            triggers: Vec::new(),
+            debounce_seconds: None,
+            run_policy: RunPolicy::default(),
         };
 
         scheduler.add_scheduled_job(job).await.unwrap();
@@ -838,6 +1511,10 @@ mod tests {
             paused: false,
             current_session_id: None,
             process_start_time: None,
+            budget: None,
+            triggers: Vec::new(),
+            debounce_seconds: None,
+            run_policy: RunPolicy::default(),
         };
 
         scheduler.add_scheduled_job(job).await.unwrap();
@@ -847,4 +1524,370 @@ mod tests {
         let jobs = scheduler.list_scheduled_jobs().await;
         assert!(jobs[0].last_run.is_none(), "Paused job should not run");
     }
+
+    fn create_triggered_job(
+        id: &str,
+        recipe_path: &Path,
+        triggers: Vec<JobTrigger>,
+    ) -> ScheduledJob {
+        ScheduledJob {
+            id: id.to_string(),
+            source: recipe_path.to_string_lossy().to_string(),
+            cron: String::new(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            current_session_id: None,
+            process_start_time: None,
+            budget: None,
+            triggers,
+            debounce_seconds: Some(0),
+            run_policy: RunPolicy::default(),
+        }
+    }
+
+    async fn wait_for_run(scheduler: &Scheduler, id: &str) -> bool {
+        for _ in 0..50 {
+            let jobs = scheduler.list_scheduled_jobs().await;
+            if jobs
+                .iter()
+                .any(|job| job.id == id && job.last_run.is_some() && !job.currently_running)
+            {
+                return true;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_job_needs_cron_or_trigger() {
+        let temp_dir = tempdir().unwrap();
+        let recipe_path = create_test_recipe(temp_dir.path(), "no_trigger_job");
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        let job = create_triggered_job("no_trigger_job", &recipe_path, Vec::new());
+        assert!(matches!(
+            scheduler.add_scheduled_job(job).await,
+            Err(SchedulerError::TriggerError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_trigger_runs_job() {
+        let temp_dir = tempdir().unwrap();
+        let recipe_path = create_test_recipe(temp_dir.path(), "webhook_job");
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        let job = create_triggered_job("webhook_job", &recipe_path, vec![JobTrigger::Webhook]);
+        scheduler.add_scheduled_job(job).await.unwrap();
+        assert!(matches!(
+            scheduler
+                .fire_webhook(
+                    "webhook_job",
+                    Some(serde_json::json!(["not", "an", "object"]))
+                )
+                .await,
+            Err(SchedulerError::TriggerError(_))
+        ));
+
+        scheduler
+            .fire_webhook("webhook_job", Some(serde_json::json!({"branch": "main"})))
+            .await
+            .unwrap();
+        assert!(wait_for_run(&scheduler, "webhook_job").await);
+
+        let runs = scheduler.runs("webhook_job", 10).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].trigger, RunTrigger::Webhook);
+        assert_ne!(runs[0].status, RunStatus::Running);
+        assert!(runs[0].ended_at.is_some());
+
+        scheduler.remove_scheduled_job("webhook_job").await.unwrap();
+        assert!(matches!(
+            scheduler.runs("webhook_job", 10).await,
+            Err(SchedulerError::JobNotFound(_))
+        ));
+        assert!(scheduler
+            .history
+            .runs("webhook_job", 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_chained_job_runs_after_upstream() {
+        let temp_dir = tempdir().unwrap();
+        let upstream_recipe = create_test_recipe(temp_dir.path(), "chain_upstream");
+        let downstream_recipe = create_test_recipe(temp_dir.path(), "chain_downstream");
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        let upstream = create_triggered_job(
+            "chain_upstream",
+            &upstream_recipe,
+            vec![JobTrigger::Webhook],
+        );
+        let downstream = create_triggered_job(
+            "chain_downstream",
+            &downstream_recipe,
+            vec![JobTrigger::JobCompleted {
+                job_id: "chain_upstream".to_string(),
+                on: ChainCondition::Any,
+            }],
+        );
+        scheduler.add_scheduled_job(upstream).await.unwrap();
+        scheduler.add_scheduled_job(downstream).await.unwrap();
+
+        let _ = scheduler.run_now("chain_upstream").await;
+        assert!(wait_for_run(&scheduler, "chain_downstream").await);
+
+        let cycle = create_triggered_job(
+            "chain_cycle",
+            &upstream_recipe,
+            vec![JobTrigger::JobCompleted {
+                job_id: "chain_downstream".to_string(),
+                on: ChainCondition::Success,
+            }],
+        );
+        scheduler.add_scheduled_job(cycle).await.unwrap();
+        scheduler
+            .remove_scheduled_job("chain_upstream")
+            .await
+            .unwrap();
+        let looping = create_triggered_job(
+            "chain_upstream",
+            &upstream_recipe,
+            vec![JobTrigger::JobCompleted {
+                job_id: "chain_cycle".to_string(),
+                on: ChainCondition::Success,
+            }],
+        );
+        assert!(matches!(
+            scheduler.add_scheduled_job(looping).await,
+            Err(SchedulerError::TriggerError(_))
+        ));
+
+        scheduler.remove_scheduled_job("chain_cycle").await.unwrap();
+        scheduler
+            .remove_scheduled_job("chain_downstream")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_watch_trigger_runs_job() {
+        let temp_dir = tempdir().unwrap();
+        let watched_dir = temp_dir.path().join("watched");
+        fs::create_dir(&watched_dir).unwrap();
+        let recipe_path = create_test_recipe(temp_dir.path(), "file_watch_job");
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        let job = create_triggered_job(
+            "file_watch_job",
+            &recipe_path,
+            vec![JobTrigger::FileWatch {
+                path: watched_dir.to_string_lossy().to_string(),
+                pattern: "*.md".to_string(),
+            }],
+        );
+        scheduler.add_scheduled_job(job).await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        fs::write(watched_dir.join("notes.txt"), "ignored").unwrap();
+        sleep(FILE_WATCH_POLL_INTERVAL * 2).await;
+        assert!(scheduler.list_scheduled_jobs().await[0].last_run.is_none());
+
+        fs::write(watched_dir.join("notes.md"), "changed").unwrap();
+        sleep(FILE_WATCH_POLL_INTERVAL).await;
+        assert!(wait_for_run(&scheduler, "file_watch_job").await);
+        scheduler
+            .remove_scheduled_job("file_watch_job")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_attempt_enforces_max_runtime() {
+        let token = CancellationToken::new();
+        let started = Instant::now();
+        let stops_when_cancelled = {
+            let token = token.clone();
+            async move {
+                token.cancelled().await;
+                Ok("stopped".to_string())
+            }
+        };
+        let (result, status) =
+            run_attempt(stops_when_cancelled, Some(Duration::from_secs(30)), &token).await;
+        assert_eq!(status, RunStatus::Timeout);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("max runtime of 30s"));
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+
+        let (result, status) = run_attempt(
+            async { Ok("done".to_string()) },
+            Some(Duration::from_secs(30)),
+            &CancellationToken::new(),
+        )
+        .await;
+        assert_eq!(status, RunStatus::Success);
+        assert_eq!(result.unwrap(), "done");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_attempt_drops_killed_run_after_grace_period() {
+        let token = CancellationToken::new();
+        let started = Instant::now();
+        tokio::spawn({
+            let token = token.clone();
+            async move {
+                sleep(Duration::from_secs(5)).await;
+                token.cancel();
+            }
+        });
+
+        let (result, status) =
+            run_attempt(std::future::pending::<Result<String>>(), None, &token).await;
+        assert_eq!(status, RunStatus::Killed);
+        assert!(result.is_err());
+        assert_eq!(
+            started.elapsed(),
+            Duration::from_secs(5) + RUN_STOP_GRACE_PERIOD
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        let policy = RunPolicy {
+            retry_backoff_seconds: Some(10),
+            ..RunPolicy::default()
+        };
+        assert_eq!(policy.retry_delay(1), Duration::from_secs(10));
+        assert_eq!(policy.retry_delay(2), Duration::from_secs(20));
+        assert_eq!(policy.retry_delay(3), Duration::from_secs(40));
+        assert_eq!(policy.retry_delay(30), MAX_RETRY_BACKOFF);
+        assert_eq!(
+            RunPolicy::default().retry_delay(1),
+            Duration::from_secs(DEFAULT_RETRY_BACKOFF_SECONDS)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_run_is_retried_with_backoff() {
+        let temp_dir = tempdir().unwrap();
+        let recipe_path = create_test_recipe(temp_dir.path(), "retried_job");
+        let scheduler =
+            Scheduler::with_executor(temp_dir.path().join("schedules.json"), |_, _, _, _, _| {
+                Box::pin(async { Err(anyhow!("provider error")) })
+            })
+            .await
+            .unwrap();
+
+        let mut job = create_triggered_job("retried_job", &recipe_path, vec![JobTrigger::Webhook]);
+        job.run_policy = RunPolicy {
+            retries: 2,
+            retry_backoff_seconds: Some(5),
+            ..RunPolicy::default()
+        };
+        scheduler.add_scheduled_job(job).await.unwrap();
+
+        let started = Instant::now();
+        assert!(scheduler.run_now("retried_job").await.is_err());
+        assert!(started.elapsed() >= Duration::from_secs(15));
+
+        let runs = scheduler.runs("retried_job", 10).await.unwrap();
+        assert_eq!(
+            runs.iter().map(|run| run.attempt).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert!(runs.iter().all(|run| run.status == RunStatus::Failure
+            && run.error.as_deref() == Some("provider error")));
+        assert!(!scheduler.list_scheduled_jobs().await[0].currently_running);
+    }
+
+    /// Register a job that looks like it is in the middle of a run
+    async fn insert_running_job(
+        scheduler: &Scheduler,
+        id: &str,
+        overlap: OverlapPolicy,
+    ) -> CancellationToken {
+        let mut job = create_triggered_job(id, Path::new(""), Vec::new());
+        job.cron = "0 0 0 1 1 *".to_string();
+        job.currently_running = true;
+        job.run_policy.overlap = overlap;
+        scheduler
+            .jobs
+            .lock()
+            .await
+            .insert(id.to_string(), (None, job));
+
+        let token = CancellationToken::new();
+        scheduler
+            .running_tasks
+            .lock()
+            .await
+            .insert(id.to_string(), token.clone());
+        token
+    }
+
+    async fn finish_running_job(scheduler: &Scheduler, id: &str) {
+        if let Some((_, job)) = scheduler.jobs.lock().await.get_mut(id) {
+            job.currently_running = false;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overlap_policies() {
+        let temp_dir = tempdir().unwrap();
+        let scheduler = Scheduler::new(temp_dir.path().join("schedules.json"))
+            .await
+            .unwrap();
+
+        let skip_token = insert_running_job(&scheduler, "skip_job", OverlapPolicy::Skip).await;
+        assert!(scheduler
+            .begin_cron_run("skip_job")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!skip_token.is_cancelled());
+
+        insert_running_job(&scheduler, "queue_job", OverlapPolicy::Queue).await;
+        let queued = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.begin_cron_run("queue_job").await }
+        });
+        sleep(RUN_WAIT_INTERVAL * 3).await;
+        assert!(!queued.is_finished());
+        // A second tick while one is queued is skipped
+        assert!(scheduler
+            .begin_cron_run("queue_job")
+            .await
+            .unwrap()
+            .is_none());
+        finish_running_job(&scheduler, "queue_job").await;
+        assert!(queued.await.unwrap().unwrap().is_some());
+
+        let cancel_token =
+            insert_running_job(&scheduler, "cancel_job", OverlapPolicy::CancelPrevious).await;
+        let replacement = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.begin_cron_run("cancel_job").await }
+        });
+        sleep(RUN_WAIT_INTERVAL).await;
+        assert!(cancel_token.is_cancelled());
+        finish_running_job(&scheduler, "cancel_job").await;
+        let job = replacement.await.unwrap().unwrap().unwrap();
+        assert!(job.currently_running);
+    }
 }
@@ -0,0 +1,416 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use tokio::sync::Mutex;
+
+use crate::config::Config;
+use crate::scheduler::SchedulerError;
+use crate::scheduler_triggers::TriggerEvent;
+use crate::session::Session;
+
+/// Config key for the number of runs kept per job
+pub const HISTORY_MAX_RUNS_CONFIG_KEY: &str = "GOOSE_SCHEDULER_HISTORY_MAX_RUNS";
+pub const DEFAULT_HISTORY_MAX_RUNS: usize = 100;
+/// Config key for the number of days runs are kept; unset keeps runs regardless of age
+pub const HISTORY_MAX_AGE_DAYS_CONFIG_KEY: &str = "GOOSE_SCHEDULER_HISTORY_MAX_AGE_DAYS";
+/// Longer max ages are treated as this many days
+const MAX_HISTORY_MAX_AGE_DAYS: i64 = 36_500;
+
+/// How a run of a scheduled job ended
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Success,
+    Failure,
+    /// Stopped through `kill`
+    Killed,
+    /// Stopped because it ran longer than the job allows
+    Timeout,
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            RunStatus::Running => "running",
+            RunStatus::Success => "success",
+            RunStatus::Failure => "failure",
+            RunStatus::Killed => "killed",
+            RunStatus::Timeout => "timeout",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// What started a run
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    Cron,
+    Manual,
+    FileWatch,
+    Webhook,
+    JobCompleted,
+}
+
+impl From<&TriggerEvent> for RunTrigger {
+    fn from(event: &TriggerEvent) -> Self {
+        match event {
+            TriggerEvent::FilesChanged(_) => RunTrigger::FileWatch,
+            TriggerEvent::Webhook(_) => RunTrigger::Webhook,
+            TriggerEvent::JobCompleted { .. } => RunTrigger::JobCompleted,
+        }
+    }
+}
+
+fn first_attempt() -> u32 {
+    1
+}
+
+/// One run of a scheduled job
+#[derive(Clone, Serialize, Deserialize, Debug, utoipa::ToSchema)]
+pub struct JobRun {
+    pub id: String,
+    pub job_id: String,
+    pub trigger: RunTrigger,
+    /// Which attempt of the run this was, counting from 1; later attempts are retries
+    #[serde(default = "first_attempt")]
+    pub attempt: u32,
+    pub status: RunStatus,
+    pub started_at: DateTime<Utc>,
+    /// When the run ended, unset while it is running
+    pub ended_at: Option<DateTime<Utc>>,
+    /// The session the run used
+    pub session_id: Option<String>,
+    /// Why the run failed
+    pub error: Option<String>,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+    /// Estimated cost in USD of the run's provider calls
+    pub cost_usd: Option<f64>,
+}
+
+impl JobRun {
+    pub fn start(job_id: &str, trigger: RunTrigger, started_at: DateTime<Utc>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            job_id: job_id.to_string(),
+            trigger,
+            attempt: first_attempt(),
+            status: RunStatus::Running,
+            started_at,
+            ended_at: None,
+            session_id: None,
+            error: None,
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cost_usd: None,
+        }
+    }
+
+    pub fn with_attempt(mut self, attempt: u32) -> Self {
+        self.attempt = attempt;
+        self
+    }
+
+    pub fn finish(&mut self, status: RunStatus, error: Option<String>) {
+        self.status = status;
+        self.error = error;
+        self.ended_at = Some(Utc::now());
+    }
+
+    /// Copy the usage of the run's session
+    pub fn record_usage(&mut self, session: &Session) {
+        self.session_id = Some(session.id.clone());
+        self.input_tokens = session.accumulated_input_tokens;
+        self.output_tokens = session.accumulated_output_tokens;
+        self.total_tokens = session.accumulated_total_tokens;
+        self.cost_usd = session.accumulated_cost;
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.ended_at.map(|ended_at| ended_at - self.started_at)
+    }
+}
+
+/// How many runs of each job to keep
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetentionPolicy {
+    pub max_runs: usize,
+    pub max_age: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    pub fn from_config() -> Self {
+        let config = Config::global();
+        Self {
+            max_runs: config
+                .get_param::<usize>(HISTORY_MAX_RUNS_CONFIG_KEY)
+                .unwrap_or(DEFAULT_HISTORY_MAX_RUNS),
+            max_age: config
+                .get_param::<i64>(HISTORY_MAX_AGE_DAYS_CONFIG_KEY)
+                .ok()
+                .and_then(max_age_from_days),
+        }
+    }
+
+    /// Split a job's runs, newest first, into those to keep and those to prune. Runs that
+    /// are still going are always kept.
+    fn apply(&self, runs: Vec<JobRun>, now: DateTime<Utc>) -> (Vec<JobRun>, Vec<JobRun>) {
+        let mut kept = Vec::new();
+        let mut pruned = Vec::new();
+        for run in runs {
+            let too_many = kept.len() >= self.max_runs;
+            let too_old = self
+                .max_age
+                .is_some_and(|max_age| now - run.started_at > max_age);
+            if run.status != RunStatus::Running && (too_many || too_old) {
+                pruned.push(run);
+            } else {
+                kept.push(run);
+            }
+        }
+        (kept, pruned)
+    }
+}
+
+/// The max age for a configured number of days. Values that are not positive are ignored,
+/// since they would prune every run as soon as it finishes.
+fn max_age_from_days(days: i64) -> Option<Duration> {
+    if days <= 0 {
+        tracing::warn!(
+            "Ignoring {} of {}: it must be a positive number of days",
+            HISTORY_MAX_AGE_DAYS_CONFIG_KEY,
+            days
+        );
+        return None;
+    }
+    Duration::try_days(days.min(MAX_HISTORY_MAX_AGE_DAYS))
+}
+
+/// The runs of all scheduled jobs, stored as JSON next to the jobs. The file is read and
+/// written on every change, so other goose processes running jobs see each other's runs.
+pub struct RunHistory {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl RunHistory {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The history file that belongs to a jobs store
+    pub fn path_for(storage_path: &Path) -> PathBuf {
+        storage_path.with_file_name("schedule_runs.json")
+    }
+
+    /// Hold an exclusive lock on the file next to the history while reading, merging and
+    /// writing it, so runs recorded by another goose process are not lost
+    fn lock_file(&self) -> Result<fs::File, SchedulerError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        file.lock_exclusive()?;
+        Ok(file)
+    }
+
+    fn read(&self) -> Result<Vec<JobRun>, SchedulerError> {
+        let data = match fs::read_to_string(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        if data.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&data).map_err(|e| {
+            SchedulerError::PersistError(format!("Failed to parse {}: {}", self.path.display(), e))
+        })
+    }
+
+    /// Write to a temporary file and rename it over the history, so readers never see a
+    /// partly written file
+    fn write(&self, runs: &[JobRun]) -> Result<(), SchedulerError> {
+        let parent = self.path.parent().unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent)?;
+        let mut file = NamedTempFile::new_in(parent)?;
+        file.write_all(serde_json::to_string_pretty(runs)?.as_bytes())?;
+        file.as_file().sync_all()?;
+        file.persist(&self.path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Add or update a run, apply `retention` to the runs of its job and return the runs
+    /// that were pruned
+    pub async fn record(
+        &self,
+        run: &JobRun,
+        retention: RetentionPolicy,
+    ) -> Result<Vec<JobRun>, SchedulerError> {
+        let _guard = self.lock.lock().await;
+        let _file_lock = self.lock_file()?;
+        let (mut job_runs, mut other_runs): (Vec<JobRun>, Vec<JobRun>) = self
+            .read()?
+            .into_iter()
+            .filter(|existing| existing.id != run.id)
+            .partition(|existing| existing.job_id == run.job_id);
+
+        job_runs.push(run.clone());
+        job_runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        let (kept, pruned) = retention.apply(job_runs, Utc::now());
+
+        other_runs.extend(kept);
+        self.write(&other_runs)?;
+        Ok(pruned)
+    }
+
+    /// The most recent runs of a job, newest first
+    pub async fn runs(&self, job_id: &str, limit: usize) -> Result<Vec<JobRun>, SchedulerError> {
+        let _guard = self.lock.lock().await;
+        let mut runs: Vec<JobRun> = self
+            .read()?
+            .into_iter()
+            .filter(|run| run.job_id == job_id)
+            .collect();
+        runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        runs.truncate(limit);
+        Ok(runs)
+    }
+
+    pub async fn remove_job(&self, job_id: &str) -> Result<(), SchedulerError> {
+        let _guard = self.lock.lock().await;
+        let _file_lock = self.lock_file()?;
+        let runs: Vec<JobRun> = self
+            .read()?
+            .into_iter()
+            .filter(|run| run.job_id != job_id)
+            .collect();
+        self.write(&runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn finished_run(job_id: &str, hours_ago: i64) -> JobRun {
+        let mut run = JobRun::start(
+            job_id,
+            RunTrigger::Cron,
+            Utc::now() - Duration::hours(hours_ago),
+        );
+        run.finish(RunStatus::Success, None);
+        run
+    }
+
+    #[test]
+    fn test_max_age_from_days() {
+        assert_eq!(max_age_from_days(7), Some(Duration::days(7)));
+        assert_eq!(max_age_from_days(0), None);
+        assert_eq!(max_age_from_days(-1), None);
+        assert_eq!(
+            max_age_from_days(i64::MAX),
+            Some(Duration::days(MAX_HISTORY_MAX_AGE_DAYS))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_keeps_newest_runs() {
+        let temp_dir = tempdir().unwrap();
+        let history = RunHistory::new(temp_dir.path().join("schedule_runs.json"));
+        let retention = RetentionPolicy {
+            max_runs: 2,
+            max_age: None,
+        };
+
+        let oldest = finished_run("nightly", 3);
+        history.record(&oldest, retention).await.unwrap();
+        history
+            .record(&finished_run("nightly", 2), retention)
+            .await
+            .unwrap();
+        history
+            .record(&finished_run("other", 5), retention)
+            .await
+            .unwrap();
+        let pruned = history
+            .record(&finished_run("nightly", 1), retention)
+            .await
+            .unwrap();
+
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].id, oldest.id);
+        assert_eq!(history.runs("nightly", 10).await.unwrap().len(), 2);
+        assert_eq!(history.runs("other", 10).await.unwrap().len(), 1);
+
+        history.remove_job("nightly").await.unwrap();
+        assert!(history.runs("nightly", 10).await.unwrap().is_empty());
+        assert_eq!(history.runs("other", 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_record_updates_run_and_prunes_by_age() {
+        let temp_dir = tempdir().unwrap();
+        let history = RunHistory::new(temp_dir.path().join("schedule_runs.json"));
+        let retention = RetentionPolicy {
+            max_runs: 10,
+            max_age: Some(Duration::days(1)),
+        };
+
+        let stale = finished_run("nightly", 48);
+        let keep_all = RetentionPolicy {
+            max_runs: 10,
+            max_age: None,
+        };
+        history.record(&stale, keep_all).await.unwrap();
+
+        let mut run = JobRun::start("nightly", RunTrigger::Manual, Utc::now());
+        let pruned = history.record(&run, retention).await.unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].id, stale.id);
+
+        run.finish(RunStatus::Failure, Some("provider error".to_string()));
+        history.record(&run, retention).await.unwrap();
+        let runs = history.runs("nightly", 10).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, RunStatus::Failure);
+        assert!(runs[0].duration().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_history_is_an_error_and_left_alone() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("schedule_runs.json");
+        fs::write(&path, "not json").unwrap();
+        let history = RunHistory::new(path.clone());
+        let retention = RetentionPolicy {
+            max_runs: 10,
+            max_age: None,
+        };
+
+        assert!(history.runs("nightly", 10).await.is_err());
+        assert!(history
+            .record(&finished_run("nightly", 1), retention)
+            .await
+            .is_err());
+        assert!(history.remove_job("nightly").await.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "not json");
+    }
+}
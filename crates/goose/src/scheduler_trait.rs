@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
 use crate::scheduler::{ScheduledJob, SchedulerError};
+use crate::scheduler_history::JobRun;
 use crate::session::Session;
 
 #[async_trait]
@@ -12,11 +13,17 @@ pub trait SchedulerTrait: Send + Sync {
     async fn pause_schedule(&self, id: &str) -> Result<(), SchedulerError>;
     async fn unpause_schedule(&self, id: &str) -> Result<(), SchedulerError>;
     async fn run_now(&self, id: &str) -> Result<String, SchedulerError>;
+    async fn fire_webhook(
+        &self,
+        id: &str,
+        payload: Option<serde_json::Value>,
+    ) -> Result<(), SchedulerError>;
     async fn sessions(
         &self,
         sched_id: &str,
         limit: usize,
     ) -> Result<Vec<(String, Session)>, SchedulerError>;
+    async fn runs(&self, sched_id: &str, limit: usize) -> Result<Vec<JobRun>, SchedulerError>;
     async fn update_schedule(&self, sched_id: &str, new_cron: String)
         -> Result<(), SchedulerError>;
     async fn kill_running_job(&self, sched_id: &str) -> Result<(), SchedulerError>;
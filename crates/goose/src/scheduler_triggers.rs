@@ -0,0 +1,346 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::SchedulerError;
+
+/// How often file-watch triggers scan their directory for changes
+pub const FILE_WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a trigger waits for further events before running the job, unless the job
+/// sets `debounce_seconds`
+pub const DEFAULT_TRIGGER_DEBOUNCE_SECONDS: u64 = 2;
+
+/// Config key for the number of triggered runs that may execute at the same time
+pub const MAX_TRIGGERED_RUNS_CONFIG_KEY: &str = "GOOSE_SCHEDULER_MAX_TRIGGERED_RUNS";
+pub const DEFAULT_MAX_TRIGGERED_RUNS: usize = 2;
+
+fn default_file_pattern() -> String {
+    "**/*".to_string()
+}
+
+/// An event that runs a scheduled job, in addition to its cron expression
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobTrigger {
+    /// Run when files matching `pattern` are created, modified or removed under `path`.
+    /// The changed paths are passed as the `changed_files` recipe parameter.
+    FileWatch {
+        /// The directory to watch, including its subdirectories
+        path: String,
+        /// Glob matched against paths relative to `path` (default: every file)
+        #[serde(default = "default_file_pattern")]
+        pattern: String,
+    },
+    /// Run when `POST /schedule/{id}/webhook` is called on goose-server. The fields of
+    /// the JSON payload are passed as recipe parameters.
+    Webhook,
+    /// Run when another scheduled job finishes. The upstream job is passed as the
+    /// `upstream_job_id`, `upstream_status` and `upstream_session_id` recipe parameters.
+    JobCompleted {
+        /// The job to follow
+        job_id: String,
+        #[serde(default)]
+        on: ChainCondition,
+    },
+}
+
+/// Which outcomes of the upstream job run a chained job
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainCondition {
+    #[default]
+    Success,
+    Failure,
+    Any,
+}
+
+impl ChainCondition {
+    pub fn matches(self, succeeded: bool) -> bool {
+        match self {
+            ChainCondition::Success => succeeded,
+            ChainCondition::Failure => !succeeded,
+            ChainCondition::Any => true,
+        }
+    }
+}
+
+impl fmt::Display for JobTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobTrigger::FileWatch { path, pattern } => {
+                write!(f, "files matching '{}' change in {}", pattern, path)
+            }
+            JobTrigger::Webhook => write!(f, "its webhook is called"),
+            JobTrigger::JobCompleted { job_id, on } => match on {
+                ChainCondition::Success => write!(f, "job '{}' succeeds", job_id),
+                ChainCondition::Failure => write!(f, "job '{}' fails", job_id),
+                ChainCondition::Any => write!(f, "job '{}' finishes", job_id),
+            },
+        }
+    }
+}
+
+impl JobTrigger {
+    pub fn validate(&self, job_id: &str) -> Result<(), SchedulerError> {
+        match self {
+            JobTrigger::FileWatch { path, pattern } => {
+                if !Path::new(path).is_dir() {
+                    return Err(SchedulerError::TriggerError(format!(
+                        "watched path '{}' is not a directory",
+                        path
+                    )));
+                }
+                file_matcher(pattern)?;
+            }
+            JobTrigger::Webhook => {}
+            JobTrigger::JobCompleted {
+                job_id: upstream, ..
+            } => {
+                if upstream == job_id {
+                    return Err(SchedulerError::TriggerError(format!(
+                        "job '{}' cannot be chained to itself",
+                        job_id
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn file_matcher(pattern: &str) -> Result<GlobMatcher, SchedulerError> {
+    Glob::new(pattern)
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| SchedulerError::TriggerError(format!("invalid glob '{}': {}", pattern, e)))
+}
+
+/// What fired a trigger. Events that arrive while a run is pending are merged into it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TriggerEvent {
+    FilesChanged(BTreeSet<PathBuf>),
+    Webhook(BTreeMap<String, String>),
+    JobCompleted {
+        job_id: String,
+        succeeded: bool,
+        session_id: Option<String>,
+    },
+}
+
+impl TriggerEvent {
+    /// Fold a later event into this one: changed files accumulate, later webhook fields
+    /// and upstream results replace earlier ones
+    pub fn merge(&mut self, later: TriggerEvent) {
+        match (self, later) {
+            (TriggerEvent::FilesChanged(files), TriggerEvent::FilesChanged(more)) => {
+                files.extend(more);
+            }
+            (TriggerEvent::Webhook(fields), TriggerEvent::Webhook(more)) => {
+                fields.extend(more);
+            }
+            (current, later) => *current = later,
+        }
+    }
+
+    /// The recipe parameters the run receives
+    pub fn params(&self) -> Vec<(String, String)> {
+        match self {
+            TriggerEvent::FilesChanged(files) => vec![(
+                "changed_files".to_string(),
+                files
+                    .iter()
+                    .map(|path| path.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )],
+            TriggerEvent::Webhook(fields) => fields
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            TriggerEvent::JobCompleted {
+                job_id,
+                succeeded,
+                session_id,
+            } => vec![
+                ("upstream_job_id".to_string(), job_id.clone()),
+                (
+                    "upstream_status".to_string(),
+                    if *succeeded { "success" } else { "failure" }.to_string(),
+                ),
+                (
+                    "upstream_session_id".to_string(),
+                    session_id.clone().unwrap_or_default(),
+                ),
+            ],
+        }
+    }
+}
+
+/// Turn a webhook payload into recipe parameters. String fields are passed as they are,
+/// other values as JSON.
+pub fn webhook_params(
+    payload: Option<serde_json::Value>,
+) -> Result<BTreeMap<String, String>, SchedulerError> {
+    match payload {
+        None | Some(serde_json::Value::Null) => Ok(BTreeMap::new()),
+        Some(serde_json::Value::Object(fields)) => Ok(fields
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(text) => text,
+                    other => other.to_string(),
+                };
+                (key, value)
+            })
+            .collect()),
+        Some(_) => Err(SchedulerError::TriggerError(
+            "webhook payload must be a JSON object".to_string(),
+        )),
+    }
+}
+
+/// Modification time and size of each watched file
+pub type FileSnapshot = HashMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Scan `dir` for files matching `matcher`, skipping hidden and git-ignored files
+pub fn snapshot_files(dir: &Path, matcher: &GlobMatcher) -> FileSnapshot {
+    ignore::WalkBuilder::new(dir)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+        .filter(|entry| {
+            entry
+                .path()
+                .strip_prefix(dir)
+                .is_ok_and(|relative| matcher.is_match(relative))
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((
+                entry.into_path(),
+                (metadata.modified().ok(), metadata.len()),
+            ))
+        })
+        .collect()
+}
+
+/// Files that were created, modified or removed between two snapshots
+pub fn changed_files(before: &FileSnapshot, after: &FileSnapshot) -> BTreeSet<PathBuf> {
+    let mut changed: BTreeSet<PathBuf> = after
+        .iter()
+        .filter(|(path, state)| before.get(*path) != Some(*state))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_changed_files_detects_create_modify_remove() {
+        let dir = tempdir().unwrap();
+        let matcher = file_matcher("**/*.md").unwrap();
+        fs::write(dir.path().join("keep.md"), "a").unwrap();
+        fs::write(dir.path().join("edit.md"), "a").unwrap();
+        fs::write(dir.path().join("remove.md"), "a").unwrap();
+        fs::write(dir.path().join("ignored.txt"), "a").unwrap();
+        let before = snapshot_files(dir.path(), &matcher);
+        assert_eq!(before.len(), 3);
+
+        fs::write(dir.path().join("edit.md"), "longer").unwrap();
+        fs::remove_file(dir.path().join("remove.md")).unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs").join("new.md"), "a").unwrap();
+        fs::write(dir.path().join("ignored.txt"), "changed").unwrap();
+        let after = snapshot_files(dir.path(), &matcher);
+
+        let changed: Vec<PathBuf> = changed_files(&before, &after).into_iter().collect();
+        assert_eq!(
+            changed,
+            vec![
+                dir.path().join("docs").join("new.md"),
+                dir.path().join("edit.md"),
+                dir.path().join("remove.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_events() {
+        let mut files = TriggerEvent::FilesChanged(BTreeSet::from([PathBuf::from("a.md")]));
+        files.merge(TriggerEvent::FilesChanged(BTreeSet::from([PathBuf::from(
+            "b.md",
+        )])));
+        assert_eq!(files.params()[0].1, "a.md\nb.md");
+
+        let mut webhook = TriggerEvent::Webhook(BTreeMap::from([
+            ("branch".to_string(), "main".to_string()),
+            ("sha".to_string(), "abc".to_string()),
+        ]));
+        webhook.merge(TriggerEvent::Webhook(BTreeMap::from([(
+            "sha".to_string(),
+            "def".to_string(),
+        )])));
+        assert_eq!(
+            webhook.params(),
+            vec![
+                ("branch".to_string(), "main".to_string()),
+                ("sha".to_string(), "def".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_webhook_params() {
+        let params = webhook_params(Some(serde_json::json!({
+            "branch": "main",
+            "commits": 3,
+            "draft": false
+        })))
+        .unwrap();
+        assert_eq!(params["branch"], "main");
+        assert_eq!(params["commits"], "3");
+        assert_eq!(params["draft"], "false");
+        assert!(webhook_params(None).unwrap().is_empty());
+        assert!(webhook_params(Some(serde_json::json!(["main"]))).is_err());
+    }
+
+    #[test]
+    fn test_trigger_serialization() {
+        let triggers: Vec<JobTrigger> = serde_json::from_value(serde_json::json!([
+            {"type": "file_watch", "path": "/tmp"},
+            {"type": "webhook"},
+            {"type": "job_completed", "job_id": "build", "on": "failure"}
+        ]))
+        .unwrap();
+        assert_eq!(
+            triggers,
+            vec![
+                JobTrigger::FileWatch {
+                    path: "/tmp".to_string(),
+                    pattern: "**/*".to_string(),
+                },
+                JobTrigger::Webhook,
+                JobTrigger::JobCompleted {
+                    job_id: "build".to_string(),
+                    on: ChainCondition::Failure,
+                },
+            ]
+        );
+        assert!(triggers[2].validate("build").is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::config::Config;
+use crate::session::session_manager::Session;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Maximum estimated cost in USD of each session, unless a lower limit applies
+pub const BUDGET_MAX_COST_CONFIG_KEY: &str = "GOOSE_BUDGET_MAX_COST_USD";
+/// Maximum number of tokens used by each session, unless a lower limit applies
+pub const BUDGET_MAX_TOKENS_CONFIG_KEY: &str = "GOOSE_BUDGET_MAX_TOKENS";
+/// Fraction of a budget at which a warning is shown, `0.8` by default
+pub const BUDGET_WARNING_THRESHOLD_CONFIG_KEY: &str = "GOOSE_BUDGET_WARNING_THRESHOLD";
+
+const DEFAULT_WARNING_THRESHOLD: f64 = 0.8;
+
+/// Spending limits for a session. Unset limits do not apply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Budget {
+    /// Maximum estimated cost in USD, based on the provider's published pricing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_usd: Option<f64>,
+    /// Maximum number of input and output tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i64>,
+}
+
+/// Where a session stands against its budget
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetStatus {
+    Within,
+    /// Spend has passed the warning threshold of a limit
+    Warning(String),
+    /// Spend has reached a limit
+    Exceeded(String),
+}
+
+impl Budget {
+    pub fn is_empty(&self) -> bool {
+        self.max_cost_usd.is_none() && self.max_tokens.is_none()
+    }
+
+    /// The global budget from `GOOSE_BUDGET_MAX_COST_USD` and `GOOSE_BUDGET_MAX_TOKENS`
+    pub fn from_config() -> Self {
+        let config = Config::global();
+        Self {
+            max_cost_usd: config.get_param(BUDGET_MAX_COST_CONFIG_KEY).ok(),
+            max_tokens: config.get_param(BUDGET_MAX_TOKENS_CONFIG_KEY).ok(),
+        }
+        .without_invalid_limits()
+    }
+
+    /// Drop limits that are not positive, which no spend could be measured against
+    fn without_invalid_limits(self) -> Self {
+        let max_cost_usd = self.max_cost_usd.filter(|max_cost| {
+            let valid = *max_cost > 0.0 && max_cost.is_finite();
+            if !valid {
+                tracing::warn!("Ignoring budget of ${}: it must be positive", max_cost);
+            }
+            valid
+        });
+        let max_tokens = self.max_tokens.filter(|max_tokens| {
+            let valid = *max_tokens > 0;
+            if !valid {
+                tracing::warn!(
+                    "Ignoring budget of {} tokens: it must be positive",
+                    max_tokens
+                );
+            }
+            valid
+        });
+        Self {
+            max_cost_usd,
+            max_tokens,
+        }
+    }
+
+    /// Combine two budgets, keeping the lower of each limit
+    pub fn merge(self, other: Budget) -> Self {
+        fn lower<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            max_cost_usd: lower(self.max_cost_usd, other.max_cost_usd),
+            max_tokens: lower(self.max_tokens, other.max_tokens),
+        }
+    }
+
+    /// The budget that applies to `session`: the global budget, the session's own budget
+    /// (also set by scheduled jobs) and its recipe's budget, whichever limits are lowest
+    pub fn for_session(session: &Session) -> Self {
+        let recipe_budget = session
+            .recipe
+            .as_ref()
+            .and_then(|recipe| recipe.settings.as_ref())
+            .and_then(|settings| settings.budget);
+
+        [session.budget, recipe_budget]
+            .into_iter()
+            .flatten()
+            .map(Self::without_invalid_limits)
+            .fold(Self::from_config(), Self::merge)
+    }
+
+    /// Check the cost and token spend of `session` against this budget
+    pub fn status(&self, session: &Session) -> BudgetStatus {
+        let threshold = Config::global()
+            .get_param::<f64>(BUDGET_WARNING_THRESHOLD_CONFIG_KEY)
+            .unwrap_or(DEFAULT_WARNING_THRESHOLD);
+        self.status_with_threshold(session, threshold)
+    }
+
+    fn status_with_threshold(&self, session: &Session, threshold: f64) -> BudgetStatus {
+        let cost = session.accumulated_cost.unwrap_or(0.0);
+        let tokens = session_tokens(session);
+
+        let mut usage = Vec::new();
+        if let Some(max_cost) = self.max_cost_usd {
+            usage.push((
+                cost / max_cost,
+                format!("${:.4} of the ${:.2} budget", cost, max_cost),
+            ));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            usage.push((
+                tokens as f64 / max_tokens as f64,
+                format!("{} of {} budgeted tokens", tokens, max_tokens),
+            ));
+        }
+
+        let Some((fraction, spent)) = usage.into_iter().max_by(|(a, _), (b, _)| a.total_cmp(b))
+        else {
+            return BudgetStatus::Within;
+        };
+
+        if fraction >= 1.0 {
+            BudgetStatus::Exceeded(format!("Budget reached: spent {}.", spent))
+        } else if fraction >= threshold {
+            BudgetStatus::Warning(format!(
+                "Approaching budget: spent {} ({:.0}%).",
+                spent,
+                fraction * 100.0
+            ))
+        } else {
+            BudgetStatus::Within
+        }
+    }
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut limits = Vec::new();
+        if let Some(max_cost) = self.max_cost_usd {
+            limits.push(format!("${:.2}", max_cost));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            limits.push(format!("{} tokens", max_tokens));
+        }
+        write!(f, "{}", limits.join(", "))
+    }
+}
+
+fn session_tokens(session: &Session) -> i64 {
+    session
+        .accumulated_total_tokens
+        .or(session.total_tokens)
+        .unwrap_or(0) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(cost: f64, tokens: i32) -> Session {
+        Session {
+            accumulated_cost: Some(cost),
+            accumulated_total_tokens: Some(tokens),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_lowest_limits() {
+        let global = Budget {
+            max_cost_usd: Some(5.0),
+            max_tokens: None,
+        };
+        let recipe = Budget {
+            max_cost_usd: Some(10.0),
+            max_tokens: Some(1000),
+        };
+        assert_eq!(
+            global.merge(recipe),
+            Budget {
+                max_cost_usd: Some(5.0),
+                max_tokens: Some(1000),
+            }
+        );
+        assert!(Budget::default().merge(Budget::default()).is_empty());
+    }
+
+    #[test]
+    fn test_non_positive_limits_are_ignored() {
+        let budget = Budget {
+            max_cost_usd: Some(0.0),
+            max_tokens: Some(-5),
+        }
+        .without_invalid_limits();
+        assert!(budget.is_empty());
+        assert_eq!(
+            budget.status_with_threshold(&session(0.0, 0), 0.8),
+            BudgetStatus::Within
+        );
+
+        let budget = Budget {
+            max_cost_usd: Some(f64::NAN),
+            max_tokens: Some(1000),
+        }
+        .without_invalid_limits();
+        assert_eq!(
+            budget,
+            Budget {
+                max_cost_usd: None,
+                max_tokens: Some(1000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_status_reports_closest_limit() {
+        let budget = Budget {
+            max_cost_usd: Some(1.0),
+            max_tokens: Some(1000),
+        };
+
+        assert_eq!(
+            budget.status_with_threshold(&session(0.1, 100), 0.8),
+            BudgetStatus::Within
+        );
+        assert_eq!(
+            budget.status_with_threshold(&session(0.1, 900), 0.8),
+            BudgetStatus::Warning(
+                "Approaching budget: spent 900 of 1000 budgeted tokens (90%).".to_string()
+            )
+        );
+        assert_eq!(
+            budget.status_with_threshold(&session(1.25, 900), 0.8),
+            BudgetStatus::Exceeded(
+                "Budget reached: spent $1.2500 of the $1.00 budget.".to_string()
+            )
+        );
+        assert_eq!(
+            Budget::default().status_with_threshold(&session(100.0, 100_000), 0.8),
+            BudgetStatus::Within
+        );
+    }
+}
@@ -1,9 +1,18 @@
 use crate::conversation::message::MessageContent;
+use crate::session::embedding_index::{cosine_similarity, decode_embedding};
+use crate::session::session_search::{fts_available, fts_match_query};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use sqlx::query::QueryAs;
+use sqlx::sqlite::SqliteArguments;
 use sqlx::{Pool, Sqlite};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+// Messages less similar to the query than this are not considered semantic matches
+const MIN_SEMANTIC_SIMILARITY: f32 = 0.35;
+// Damping constant for reciprocal rank fusion
+const RRF_K: f32 = 60.0;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ChatRecallResult {
@@ -29,6 +38,7 @@ pub struct ChatRecallResults {
 }
 
 type SqlQueryRow = (
+    i64,
     String,
     String,
     String,
@@ -52,6 +62,7 @@ pub struct ChatHistorySearch<'a> {
     after_date: Option<DateTime<Utc>>,
     before_date: Option<DateTime<Utc>>,
     exclude_session_id: Option<String>,
+    query_embedding: Option<(Vec<f32>, String)>,
 }
 
 impl<'a> ChatHistorySearch<'a> {
@@ -70,27 +81,56 @@ impl<'a> ChatHistorySearch<'a> {
             after_date,
             before_date,
             exclude_session_id,
+            query_embedding: None,
         }
     }
 
+    /// Rank results by combining keyword matches with similarity to this embedding
+    /// of the query, computed with `model`.
+    pub fn with_query_embedding(mut self, embedding: Vec<f32>, model: String) -> Self {
+        self.query_embedding = Some((embedding, model));
+        self
+    }
+
     pub async fn execute(self) -> Result<ChatRecallResults> {
         let keywords = self.parse_keywords();
-        if keywords.is_empty() {
+        let keyword_rows = if keywords.is_empty() {
+            Vec::new()
+        } else {
+            self.fetch_rows(&keywords).await?
+        };
+
+        let (rows, session_ranks) = match &self.query_embedding {
+            Some((embedding, model)) => {
+                let keyword_ids: Vec<i64> = keyword_rows.iter().map(|row| row.0).collect();
+                let semantic_ids = self.fetch_semantic_ids(embedding, model).await?;
+                let ids = fuse_rankings(&[keyword_ids, semantic_ids], self.limit);
+                let rows = self.fetch_rows_by_id(&ids, keyword_rows).await?;
+                let session_ranks = Self::session_ranks(&rows);
+                (rows, Some(session_ranks))
+            }
+            None => (keyword_rows, None),
+        };
+
+        if rows.is_empty() {
             return Ok(ChatRecallResults {
                 results: vec![],
                 total_matches: 0,
             });
         }
 
-        let rows = self.fetch_rows(&keywords).await?;
         let session_messages = self.process_rows(rows);
         let session_totals = self.get_session_totals(&session_messages).await?;
-        let results = self.convert_to_results(session_messages, session_totals);
+        let results = self.convert_to_results(session_messages, session_totals, session_ranks);
 
         Ok(results)
     }
 
     async fn fetch_rows(&self, keywords: &[String]) -> Result<Vec<SqlQueryRow>> {
+        if fts_available(self.pool).await? {
+            return self.fetch_fts_rows().await;
+        }
+
         let sql = self.build_sql(keywords);
         let mut query_builder = sqlx::query_as::<_, SqlQueryRow>(&sql);
 
@@ -98,6 +138,150 @@ impl<'a> ChatHistorySearch<'a> {
             query_builder = query_builder.bind(keyword);
         }
 
+        query_builder = self.bind_filters(query_builder);
+        query_builder = query_builder.bind(self.limit as i64);
+
+        Ok(query_builder.fetch_all(self.pool).await?)
+    }
+
+    /// Messages matching any of the query words in the full-text index, best match first.
+    async fn fetch_fts_rows(&self) -> Result<Vec<SqlQueryRow>> {
+        let Some(match_query) = fts_match_query(self.query, "OR") else {
+            return Ok(Vec::new());
+        };
+
+        let mut sql = String::from(
+            r#"
+            SELECT
+                m.id,
+                s.id as session_id,
+                s.description as session_description,
+                s.working_dir as session_working_dir,
+                s.created_at as session_created_at,
+                m.role,
+                m.content_json,
+                m.timestamp
+            FROM messages_fts
+            INNER JOIN messages m ON m.id = messages_fts.rowid
+            INNER JOIN sessions s ON m.session_id = s.id
+            WHERE messages_fts MATCH ?
+        "#,
+        );
+        self.push_filters(&mut sql);
+        sql.push_str(" ORDER BY messages_fts.rank LIMIT ?");
+
+        let query_builder = sqlx::query_as::<_, SqlQueryRow>(&sql).bind(match_query);
+        Ok(self
+            .bind_filters(query_builder)
+            .bind(self.limit as i64)
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    /// Ids of the messages most similar to the query embedding, best first.
+    async fn fetch_semantic_ids(&self, query_embedding: &[f32], model: &str) -> Result<Vec<i64>> {
+        let mut sql = String::from(
+            r#"
+            SELECT e.message_id, e.embedding
+            FROM message_embeddings e
+            INNER JOIN messages m ON m.id = e.message_id
+            INNER JOIN sessions s ON m.session_id = s.id
+            WHERE e.model = ?
+        "#,
+        );
+        self.push_filters(&mut sql);
+
+        let query_builder = sqlx::query_as::<_, (i64, Vec<u8>)>(&sql).bind(model);
+        let rows = self
+            .bind_filters(query_builder)
+            .fetch_all(self.pool)
+            .await?;
+
+        let mut scored: Vec<(i64, f32)> = rows
+            .into_iter()
+            .filter_map(|(id, blob)| {
+                cosine_similarity(query_embedding, &decode_embedding(&blob))
+                    .filter(|score| *score >= MIN_SEMANTIC_SIMILARITY)
+                    .map(|score| (id, score))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(self.limit);
+
+        Ok(scored.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// Rows for the given message ids in the same order, reusing rows already fetched.
+    async fn fetch_rows_by_id(
+        &self,
+        ids: &[i64],
+        known_rows: Vec<SqlQueryRow>,
+    ) -> Result<Vec<SqlQueryRow>> {
+        let mut rows_by_id: HashMap<i64, SqlQueryRow> =
+            known_rows.into_iter().map(|row| (row.0, row)).collect();
+
+        let missing: Vec<i64> = ids
+            .iter()
+            .copied()
+            .filter(|id| !rows_by_id.contains_key(id))
+            .collect();
+        if !missing.is_empty() {
+            let placeholders = vec!["?"; missing.len()].join(", ");
+            let sql = format!(
+                r#"
+                SELECT
+                    m.id,
+                    s.id as session_id,
+                    s.description as session_description,
+                    s.working_dir as session_working_dir,
+                    s.created_at as session_created_at,
+                    m.role,
+                    m.content_json,
+                    m.timestamp
+                FROM messages m
+                INNER JOIN sessions s ON m.session_id = s.id
+                WHERE m.id IN ({})
+            "#,
+                placeholders
+            );
+            let mut query_builder = sqlx::query_as::<_, SqlQueryRow>(&sql);
+            for id in &missing {
+                query_builder = query_builder.bind(id);
+            }
+            for row in query_builder.fetch_all(self.pool).await? {
+                rows_by_id.insert(row.0, row);
+            }
+        }
+
+        Ok(ids.iter().filter_map(|id| rows_by_id.remove(id)).collect())
+    }
+
+    /// Position of each session's best ranked message.
+    fn session_ranks(rows: &[SqlQueryRow]) -> HashMap<String, usize> {
+        let mut ranks = HashMap::new();
+        for (rank, row) in rows.iter().enumerate() {
+            ranks.entry(row.1.clone()).or_insert(rank);
+        }
+        ranks
+    }
+
+    fn push_filters(&self, sql: &mut String) {
+        if self.exclude_session_id.is_some() {
+            sql.push_str(" AND s.id != ?");
+        }
+
+        if self.after_date.is_some() {
+            sql.push_str(" AND m.timestamp >= ?");
+        }
+        if self.before_date.is_some() {
+            sql.push_str(" AND m.timestamp <= ?");
+        }
+    }
+
+    fn bind_filters<'q, O>(
+        &'q self,
+        mut query_builder: QueryAs<'q, Sqlite, O, SqliteArguments<'q>>,
+    ) -> QueryAs<'q, Sqlite, O, SqliteArguments<'q>> {
         if let Some(exclude_id) = &self.exclude_session_id {
             query_builder = query_builder.bind(exclude_id);
         }
@@ -108,10 +292,7 @@ impl<'a> ChatHistorySearch<'a> {
         if let Some(before) = self.before_date {
             query_builder = query_builder.bind(before);
         }
-
-        query_builder = query_builder.bind(self.limit as i64);
-
-        Ok(query_builder.fetch_all(self.pool).await?)
+        query_builder
     }
 
     fn parse_keywords(&self) -> Vec<String> {
@@ -125,6 +306,7 @@ impl<'a> ChatHistorySearch<'a> {
         let mut sql = String::from(
             r#"
             SELECT 
+                m.id,
                 s.id as session_id,
                 s.description as session_description,
                 s.working_dir as session_working_dir,
@@ -155,16 +337,7 @@ impl<'a> ChatHistorySearch<'a> {
         "#,
         );
 
-        if self.exclude_session_id.is_some() {
-            sql.push_str(" AND s.id != ?");
-        }
-
-        if self.after_date.is_some() {
-            sql.push_str(" AND m.timestamp >= ?");
-        }
-        if self.before_date.is_some() {
-            sql.push_str(" AND m.timestamp <= ?");
-        }
+        self.push_filters(&mut sql);
 
         sql.push_str(" ORDER BY m.timestamp DESC LIMIT ?");
 
@@ -175,6 +348,7 @@ impl<'a> ChatHistorySearch<'a> {
         let mut session_messages: HashMap<String, SessionMessageGroup> = HashMap::new();
 
         for (
+            _message_id,
             session_id,
             session_description,
             session_working_dir,
@@ -240,6 +414,7 @@ impl<'a> ChatHistorySearch<'a> {
         &self,
         session_messages: HashMap<String, SessionMessageGroup>,
         session_totals: HashMap<String, usize>,
+        session_ranks: Option<HashMap<String, usize>>,
     ) -> ChatRecallResults {
         let mut results: Vec<ChatRecallResult> = session_messages
             .into_iter()
@@ -275,7 +450,10 @@ impl<'a> ChatHistorySearch<'a> {
             )
             .collect();
 
-        results.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+        match session_ranks {
+            Some(ranks) => results.sort_by_key(|r| ranks.get(&r.session_id).copied()),
+            None => results.sort_by(|a, b| b.last_activity.cmp(&a.last_activity)),
+        }
 
         let total_matches = results.iter().map(|r| r.messages.len()).sum();
         ChatRecallResults {
@@ -284,3 +462,42 @@ impl<'a> ChatHistorySearch<'a> {
         }
     }
 }
+
+/// Combine rankings of message ids with reciprocal rank fusion, returning the best `limit` ids.
+fn fuse_rankings(rankings: &[Vec<i64>], limit: usize) -> Vec<i64> {
+    let mut scores: HashMap<i64, f32> = HashMap::new();
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+
+    for ranking in rankings {
+        for (rank, id) in ranking.iter().enumerate() {
+            *scores.entry(*id).or_default() += 1.0 / (RRF_K + rank as f32 + 1.0);
+            if seen.insert(*id) {
+                order.push(*id);
+            }
+        }
+    }
+
+    // Stable sort keeps the earlier ranking first on ties
+    order.sort_by(|a, b| scores[b].total_cmp(&scores[a]));
+    order.truncate(limit);
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuse_rankings_prefers_ids_in_both_rankings() {
+        let keyword = vec![1, 2, 3];
+        let semantic = vec![4, 3, 5];
+        assert_eq!(fuse_rankings(&[keyword, semantic], 3), vec![3, 1, 4]);
+    }
+
+    #[test]
+    fn test_fuse_rankings_with_single_ranking() {
+        assert_eq!(fuse_rankings(&[vec![7, 8, 9], vec![]], 2), vec![7, 8]);
+        assert!(fuse_rankings(&[vec![], vec![]], 5).is_empty());
+    }
+}
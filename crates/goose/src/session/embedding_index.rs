@@ -0,0 +1,262 @@
+use crate::config::Config;
+use crate::conversation::message::MessageContent;
+use crate::model::ModelConfig;
+use crate::providers::base::Provider;
+use anyhow::{anyhow, Result};
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Provider used to embed messages, e.g. `ollama`. The index is disabled when unset.
+pub const EMBEDDING_PROVIDER_CONFIG_KEY: &str = "GOOSE_EMBEDDING_PROVIDER";
+/// Embedding model to use with the embedding provider, e.g. `nomic-embed-text`.
+pub const EMBEDDING_MODEL_CONFIG_KEY: &str = "GOOSE_EMBEDDING_MODEL";
+
+const EMBEDDING_BATCH_SIZE: i64 = 32;
+// Keep inputs well within the context of small local embedding models
+const MAX_EMBEDDING_INPUT_CHARS: usize = 4000;
+
+/// Optional vector index over message text, stored in the `message_embeddings` table
+/// next to the messages it describes.
+pub struct EmbeddingIndex {
+    provider: Arc<dyn Provider>,
+    model: String,
+    // Sessions with a running indexing worker, and whether messages arrived during its pass
+    workers: Mutex<HashMap<String, bool>>,
+}
+
+impl EmbeddingIndex {
+    pub fn new(provider: Arc<dyn Provider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            workers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create the index from `GOOSE_EMBEDDING_PROVIDER` and `GOOSE_EMBEDDING_MODEL`.
+    /// Returns `None` if no embedding provider is configured.
+    pub async fn from_config() -> Result<Option<Self>> {
+        Ok(configured_embedding_provider()
+            .await?
+            .map(|(provider, model)| Self::new(provider, model)))
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        self.provider
+            .create_embeddings(vec![query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("Embedding provider returned no embedding"))
+    }
+
+    /// Index a session's new messages in the background. At most one worker runs per
+    /// session; messages added while it is busy are picked up by another pass.
+    pub fn index_session_in_background(self: &Arc<Self>, pool: Pool<Sqlite>, session_id: &str) {
+        {
+            let mut workers = self.workers.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(rerun) = workers.get_mut(session_id) {
+                *rerun = true;
+                return;
+            }
+            workers.insert(session_id.to_string(), false);
+        }
+
+        let index = Arc::clone(self);
+        let session_id = session_id.to_string();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = index.index_pending(&pool, Some(&session_id)).await {
+                    warn!("Failed to index message embeddings: {}", e);
+                }
+                let mut workers = index.workers.lock().unwrap_or_else(|e| e.into_inner());
+                match workers.get_mut(&session_id) {
+                    Some(rerun) if *rerun => *rerun = false,
+                    _ => {
+                        workers.remove(&session_id);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Embed all messages that have text but no embedding for the current model yet,
+    /// optionally limited to one session. Returns the number of messages indexed.
+    pub async fn index_pending(
+        &self,
+        pool: &Pool<Sqlite>,
+        session_id: Option<&str>,
+    ) -> Result<usize> {
+        let mut indexed = 0;
+
+        loop {
+            let mut sql = String::from(
+                r#"
+                SELECT m.id, m.content_json
+                FROM messages m
+                LEFT JOIN message_embeddings e ON e.message_id = m.id
+                WHERE (e.message_id IS NULL OR e.model != ?)
+                AND EXISTS (
+                    SELECT 1 FROM json_each(m.content_json)
+                    WHERE json_extract(value, '$.type') = 'text'
+                )
+            "#,
+            );
+            if session_id.is_some() {
+                sql.push_str(" AND m.session_id = ?");
+            }
+            sql.push_str(" ORDER BY m.id LIMIT ?");
+
+            let mut query = sqlx::query_as::<_, (i64, String)>(&sql).bind(&self.model);
+            if let Some(session_id) = session_id {
+                query = query.bind(session_id);
+            }
+            let rows = query.bind(EMBEDDING_BATCH_SIZE).fetch_all(pool).await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let (empty, pending): (Vec<_>, Vec<_>) = rows
+                .into_iter()
+                .map(|(id, content_json)| (id, embedding_text(&content_json)))
+                .partition(|(_, text)| text.trim().is_empty());
+
+            let embeddings = if pending.is_empty() {
+                Vec::new()
+            } else {
+                let texts = pending.iter().map(|(_, text)| text.clone()).collect();
+                let embeddings = self.provider.create_embeddings(texts).await?;
+                if embeddings.len() != pending.len() {
+                    anyhow::bail!(
+                        "Embedding provider returned {} embeddings for {} messages",
+                        embeddings.len(),
+                        pending.len()
+                    );
+                }
+                embeddings
+            };
+
+            // Messages without text get an empty embedding so they are not picked up again
+            let mut tx = pool.begin().await?;
+            let entries = pending
+                .iter()
+                .map(|(id, _)| *id)
+                .zip(embeddings.iter().map(|e| encode_embedding(e)))
+                .chain(empty.iter().map(|(id, _)| (*id, Vec::new())));
+            for (message_id, blob) in entries {
+                sqlx::query(
+                    "INSERT OR REPLACE INTO message_embeddings (message_id, model, embedding) VALUES (?, ?, ?)",
+                )
+                .bind(message_id)
+                .bind(&self.model)
+                .bind(blob)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+
+            indexed += pending.len() + empty.len();
+        }
+
+        Ok(indexed)
+    }
+}
+
+/// The provider set with `GOOSE_EMBEDDING_PROVIDER` and the model set with
+/// `GOOSE_EMBEDDING_MODEL`. Returns `None` if no embedding provider is configured.
+pub async fn configured_embedding_provider() -> Result<Option<(Arc<dyn Provider>, String)>> {
+    let config = Config::global();
+    let Ok(provider_name) = config.get_param::<String>(EMBEDDING_PROVIDER_CONFIG_KEY) else {
+        return Ok(None);
+    };
+    let model: String = config.get_param(EMBEDDING_MODEL_CONFIG_KEY).map_err(|_| {
+        anyhow!(
+            "{} must be set when {} is configured",
+            EMBEDDING_MODEL_CONFIG_KEY,
+            EMBEDDING_PROVIDER_CONFIG_KEY
+        )
+    })?;
+
+    let provider = crate::providers::create(&provider_name, ModelConfig::new(&model)?).await?;
+    if !provider.supports_embeddings() {
+        anyhow::bail!("Provider '{}' does not support embeddings", provider_name);
+    }
+
+    Ok(Some((provider, model)))
+}
+
+/// The text of a stored message that is worth embedding: its text content only.
+fn embedding_text(content_json: &str) -> String {
+    let Ok(content) = serde_json::from_str::<Vec<MessageContent>>(content_json) else {
+        return String::new();
+    };
+    let text = content
+        .iter()
+        .filter_map(|c| c.as_text())
+        .collect::<Vec<_>>()
+        .join("\n");
+    crate::utils::safe_truncate(&text, MAX_EMBEDDING_INPUT_CHARS)
+}
+
+pub fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn decode_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+/// Cosine similarity of two vectors, or `None` if they cannot be compared.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.is_empty() || a.len() != b.len() {
+        return None;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a * norm_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_roundtrip() {
+        let embedding = vec![0.5, -1.25, 3.0];
+        assert_eq!(decode_embedding(&encode_embedding(&embedding)), embedding);
+        assert!(decode_embedding(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), Some(1.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), Some(0.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0]), None);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), None);
+        assert_eq!(cosine_similarity(&[], &[]), None);
+    }
+
+    #[test]
+    fn test_embedding_text_skips_tool_content() {
+        let content = vec![
+            MessageContent::text("first"),
+            MessageContent::thinking("hmm", "sig"),
+            MessageContent::text("second"),
+        ];
+        let json = serde_json::to_string(&content).unwrap();
+        assert_eq!(embedding_text(&json), "first\nsecond");
+        assert_eq!(embedding_text("not json"), "");
+    }
+}
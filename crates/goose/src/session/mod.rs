@@ -1,9 +1,16 @@
+pub mod budget;
 mod chat_history_search;
 mod diagnostics;
+pub mod embedding_index;
 pub mod extension_data;
 mod legacy;
 pub mod session_manager;
+mod session_search;
+pub mod turn_journal;
 
+pub use budget::{Budget, BudgetStatus};
 pub use diagnostics::generate_diagnostics;
 pub use extension_data::{EnabledExtensionsState, ExtensionData, ExtensionState, TodoState};
 pub use session_manager::{Session, SessionInsights, SessionManager, SessionType};
+pub use session_search::{SessionSearchMatch, SessionSearchQuery, SessionSearchResult};
+pub use turn_journal::{TurnJournal, TurnPhase, TurnRecovery, INTERRUPTED_TOOL_CALL_REASON};
@@ -1,15 +1,21 @@
 use crate::config::paths::Paths;
-use crate::conversation::message::Message;
+use crate::conversation::message::{Message, TokenState};
 use crate::conversation::Conversation;
 use crate::providers::base::{Provider, MSG_COUNT_FOR_SESSION_NAME_GENERATION};
 use crate::recipe::Recipe;
+use crate::session::budget::Budget;
+use crate::session::embedding_index::{EmbeddingIndex, EMBEDDING_PROVIDER_CONFIG_KEY};
 use crate::session::extension_data::ExtensionData;
+use crate::session::session_search::{
+    create_fts_index, SessionSearch, SessionSearchQuery, SessionSearchResult,
+};
+use crate::session::turn_journal::{TurnJournal, TurnRecovery};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rmcp::model::Role;
 use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqliteConnectOptions;
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Sqlite, SqliteConnection};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -18,7 +24,7 @@ use tokio::sync::OnceCell;
 use tracing::{info, warn};
 use utoipa::ToSchema;
 
-const CURRENT_SCHEMA_VERSION: i32 = 5;
+const CURRENT_SCHEMA_VERSION: i32 = 11;
 pub const SESSIONS_FOLDER: &str = "sessions";
 pub const DB_NAME: &str = "sessions.db";
 
@@ -84,9 +90,21 @@ pub struct Session {
     pub accumulated_total_tokens: Option<i32>,
     pub accumulated_input_tokens: Option<i32>,
     pub accumulated_output_tokens: Option<i32>,
+    /// Estimated cost in USD of all provider calls in the session
+    #[serde(default)]
+    pub accumulated_cost: Option<f64>,
+    /// Spending limits set for this session or the scheduled job that started it
+    #[serde(default)]
+    pub budget: Option<Budget>,
     pub schedule_id: Option<String>,
     pub recipe: Option<Recipe>,
     pub user_recipe_values: Option<HashMap<String, String>>,
+    /// The session this one was forked from, if any
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+    /// Name of the API key that created the session, when the server runs with named keys
+    #[serde(default)]
+    pub owner: Option<String>,
     pub conversation: Option<Conversation>,
     pub message_count: usize,
 }
@@ -104,9 +122,12 @@ pub struct SessionUpdateBuilder {
     accumulated_total_tokens: Option<Option<i32>>,
     accumulated_input_tokens: Option<Option<i32>>,
     accumulated_output_tokens: Option<Option<i32>>,
+    accumulated_cost: Option<Option<f64>>,
+    budget: Option<Option<Budget>>,
     schedule_id: Option<Option<String>>,
     recipe: Option<Option<Recipe>>,
     user_recipe_values: Option<Option<HashMap<String, String>>>,
+    owner: Option<Option<String>>,
 }
 
 #[derive(Serialize, ToSchema, Debug)]
@@ -114,6 +135,8 @@ pub struct SessionUpdateBuilder {
 pub struct SessionInsights {
     total_sessions: usize,
     total_tokens: i64,
+    /// Estimated cost in USD across all sessions
+    total_cost: f64,
 }
 
 impl SessionUpdateBuilder {
@@ -131,9 +154,12 @@ impl SessionUpdateBuilder {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            accumulated_cost: None,
+            budget: None,
             schedule_id: None,
             recipe: None,
             user_recipe_values: None,
+            owner: None,
         }
     }
 
@@ -200,6 +226,16 @@ impl SessionUpdateBuilder {
         self
     }
 
+    pub fn accumulated_cost(mut self, cost: Option<f64>) -> Self {
+        self.accumulated_cost = Some(cost);
+        self
+    }
+
+    pub fn budget(mut self, budget: Option<Budget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     pub fn schedule_id(mut self, schedule_id: Option<String>) -> Self {
         self.schedule_id = Some(schedule_id);
         self
@@ -218,6 +254,11 @@ impl SessionUpdateBuilder {
         self
     }
 
+    pub fn owner(mut self, owner: Option<String>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
     pub async fn apply(self) -> Result<()> {
         SessionManager::apply_update(self).await
     }
@@ -251,6 +292,22 @@ impl SessionManager {
             .await
     }
 
+    /// The session's token counts, or zeros if the session can't be read
+    pub async fn get_token_state(id: &str) -> TokenState {
+        Self::get_session(id, false)
+            .await
+            .map(|session| TokenState {
+                input_tokens: session.input_tokens.unwrap_or(0),
+                output_tokens: session.output_tokens.unwrap_or(0),
+                total_tokens: session.total_tokens.unwrap_or(0),
+                accumulated_input_tokens: session.accumulated_input_tokens.unwrap_or(0),
+                accumulated_output_tokens: session.accumulated_output_tokens.unwrap_or(0),
+                accumulated_total_tokens: session.accumulated_total_tokens.unwrap_or(0),
+            })
+            .inspect_err(|e| warn!("Failed to fetch session token state for {}: {}", id, e))
+            .unwrap_or_default()
+    }
+
     pub fn update_session(id: &str) -> SessionUpdateBuilder {
         SessionUpdateBuilder::new(id.to_string())
     }
@@ -278,8 +335,9 @@ impl SessionManager {
         Self::instance().await?.delete_session(id).await
     }
 
-    pub async fn get_insights() -> Result<SessionInsights> {
-        Self::instance().await?.get_insights().await
+    /// Totals across all sessions, or only those of `owner` if given
+    pub async fn get_insights(owner: Option<&str>) -> Result<SessionInsights> {
+        Self::instance().await?.get_insights(owner).await
     }
 
     pub async fn export_session(id: &str) -> Result<String> {
@@ -330,10 +388,56 @@ impl SessionManager {
             .search_chat_history(query, limit, after_date, before_date, exclude_session_id)
             .await
     }
+
+    /// Embed all messages missing from the embedding index. Returns the number of messages indexed.
+    pub async fn index_embeddings() -> Result<usize> {
+        Self::instance().await?.index_embeddings().await
+    }
+
+    pub async fn search_sessions(query: &SessionSearchQuery) -> Result<Vec<SessionSearchResult>> {
+        Self::instance().await?.search_sessions(query).await
+    }
+
+    pub async fn save_turn_journal(id: &str, journal: &TurnJournal) -> Result<()> {
+        Self::instance().await?.save_turn_journal(id, journal).await
+    }
+
+    /// The journal of the session's unfinished turn, if its last turn was interrupted
+    pub async fn get_turn_journal(id: &str) -> Result<Option<TurnJournal>> {
+        Self::instance().await?.get_turn_journal(id).await
+    }
+
+    pub async fn clear_turn_journal(id: &str) -> Result<()> {
+        Self::instance().await?.clear_turn_journal(id).await
+    }
+
+    /// Resolve an interrupted turn by keeping its work and failing the interrupted tool calls
+    /// with `reason`, or by rolling it back. Rolling back returns the user message that
+    /// started the turn. Rerunning needs an agent, see `Agent::rerun_interrupted_turn`.
+    pub async fn resolve_interrupted_turn(
+        id: &str,
+        recovery: TurnRecovery,
+        reason: &str,
+    ) -> Result<Option<Message>> {
+        Self::instance()
+            .await?
+            .resolve_interrupted_turn(id, recovery, reason)
+            .await
+    }
+
+    /// Create a new session with the first `message_index` messages of session `id`, leaving
+    /// the original untouched. The new session records `id` as its parent.
+    pub async fn fork_session(id: &str, message_index: usize) -> Result<Session> {
+        Self::instance()
+            .await?
+            .fork_session(id, message_index)
+            .await
+    }
 }
 
 pub struct SessionStorage {
     pool: Pool<Sqlite>,
+    embedding_index: OnceCell<Option<Arc<EmbeddingIndex>>>,
 }
 
 pub fn ensure_session_dir() -> Result<PathBuf> {
@@ -370,9 +474,13 @@ impl Default for Session {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            accumulated_cost: None,
+            budget: None,
             schedule_id: None,
             recipe: None,
             user_recipe_values: None,
+            parent_session_id: None,
+            owner: None,
             conversation: None,
             message_count: 0,
         }
@@ -406,6 +514,9 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Session {
             }
         };
 
+        let budget_json: Option<String> = row.try_get("budget_json").unwrap_or(None);
+        let budget = budget_json.and_then(|json| serde_json::from_str(&json).ok());
+
         let user_set_name = row.try_get("user_set_name").unwrap_or(false);
 
         let session_type_str: String = row
@@ -429,9 +540,13 @@ impl sqlx::FromRow<'_, sqlx::sqlite::SqliteRow> for Session {
             accumulated_total_tokens: row.try_get("accumulated_total_tokens")?,
             accumulated_input_tokens: row.try_get("accumulated_input_tokens")?,
             accumulated_output_tokens: row.try_get("accumulated_output_tokens")?,
+            accumulated_cost: row.try_get("accumulated_cost").unwrap_or(None),
+            budget,
             schedule_id: row.try_get("schedule_id")?,
             recipe,
             user_recipe_values,
+            parent_session_id: row.try_get("parent_session_id").unwrap_or(None),
+            owner: row.try_get("owner").unwrap_or(None),
             conversation: None,
             message_count: row.try_get("message_count").unwrap_or(0) as usize,
         })
@@ -476,7 +591,10 @@ impl SessionStorage {
     async fn open(db_path: &Path) -> Result<Self> {
         let pool = Self::get_pool(db_path, false).await?;
 
-        let storage = Self { pool };
+        let storage = Self {
+            pool,
+            embedding_index: OnceCell::new(),
+        };
         storage.run_migrations().await?;
         Ok(storage)
     }
@@ -520,7 +638,11 @@ impl SessionStorage {
                 accumulated_output_tokens INTEGER,
                 schedule_id TEXT,
                 recipe_json TEXT,
-                user_recipe_values_json TEXT
+                user_recipe_values_json TEXT,
+                parent_session_id TEXT,
+                accumulated_cost REAL,
+                budget_json TEXT,
+                owner TEXT
             )
         "#,
         )
@@ -557,7 +679,38 @@ impl SessionStorage {
             .execute(&pool)
             .await?;
 
-        Ok(Self { pool })
+        sqlx::query(
+            r#"
+            CREATE TABLE message_embeddings (
+                message_id INTEGER PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+                model TEXT NOT NULL,
+                embedding BLOB NOT NULL
+            )
+        "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE turn_journal (
+                session_id TEXT PRIMARY KEY REFERENCES sessions(id),
+                journal_json TEXT NOT NULL,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            )
+        "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        if let Err(e) = create_fts_index(&pool).await {
+            warn!("Full-text search unavailable: {}", e);
+        }
+
+        Ok(Self {
+            pool,
+            embedding_index: OnceCell::new(),
+        })
     }
 
     async fn import_legacy(&self, session_dir: &PathBuf) -> Result<()> {
@@ -766,6 +919,72 @@ impl SessionStorage {
                     .execute(&self.pool)
                     .await?;
             }
+            6 => {
+                sqlx::query(
+                    r#"
+                    CREATE TABLE message_embeddings (
+                        message_id INTEGER PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+                        model TEXT NOT NULL,
+                        embedding BLOB NOT NULL
+                    )
+                "#,
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            7 => {
+                // Search falls back to keyword matching if SQLite lacks FTS5
+                if let Err(e) = create_fts_index(&self.pool).await {
+                    warn!("Full-text search unavailable: {}", e);
+                }
+            }
+            8 => {
+                sqlx::query(
+                    r#"
+                    ALTER TABLE sessions ADD COLUMN parent_session_id TEXT
+                "#,
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            9 => {
+                sqlx::query(
+                    r#"
+                    ALTER TABLE sessions ADD COLUMN accumulated_cost REAL
+                "#,
+                )
+                .execute(&self.pool)
+                .await?;
+                sqlx::query(
+                    r#"
+                    ALTER TABLE sessions ADD COLUMN budget_json TEXT
+                "#,
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            10 => {
+                sqlx::query(
+                    r#"
+                    ALTER TABLE sessions ADD COLUMN owner TEXT
+                "#,
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            11 => {
+                sqlx::query(
+                    r#"
+                    CREATE TABLE turn_journal (
+                        session_id TEXT PRIMARY KEY REFERENCES sessions(id),
+                        journal_json TEXT NOT NULL,
+                        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                    )
+                "#,
+                )
+                .execute(&self.pool)
+                .await?;
+            }
             _ => {
                 anyhow::bail!("Unknown migration version: {}", version);
             }
@@ -779,6 +998,16 @@ impl SessionStorage {
         working_dir: PathBuf,
         name: String,
         session_type: SessionType,
+    ) -> Result<Session> {
+        let mut conn = self.pool.acquire().await?;
+        Self::insert_session(&mut conn, working_dir, name, session_type).await
+    }
+
+    async fn insert_session(
+        conn: &mut SqliteConnection,
+        working_dir: PathBuf,
+        name: String,
+        session_type: SessionType,
     ) -> Result<Session> {
         let today = chrono::Utc::now().format("%Y%m%d").to_string();
         Ok(sqlx::query_as(
@@ -804,7 +1033,7 @@ impl SessionStorage {
             .bind(&name)
             .bind(session_type.to_string())
             .bind(working_dir.to_string_lossy().as_ref())
-            .fetch_one(&self.pool)
+            .fetch_one(conn)
             .await?)
     }
 
@@ -814,7 +1043,8 @@ impl SessionStorage {
         SELECT id, working_dir, name, description, user_set_name, session_type, created_at, updated_at, extension_data,
                total_tokens, input_tokens, output_tokens,
                accumulated_total_tokens, accumulated_input_tokens, accumulated_output_tokens,
-               schedule_id, recipe_json, user_recipe_values_json
+               accumulated_cost, budget_json,
+               schedule_id, recipe_json, user_recipe_values_json, parent_session_id, owner
         FROM sessions
         WHERE id = ?
     "#,
@@ -841,6 +1071,14 @@ impl SessionStorage {
     }
 
     async fn apply_update(&self, builder: SessionUpdateBuilder) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_session_row(&mut conn, builder).await
+    }
+
+    async fn update_session_row(
+        conn: &mut SqliteConnection,
+        builder: SessionUpdateBuilder,
+    ) -> Result<()> {
         let mut updates = Vec::new();
         let mut query = String::from("UPDATE sessions SET ");
 
@@ -871,9 +1109,12 @@ impl SessionStorage {
             builder.accumulated_output_tokens,
             "accumulated_output_tokens"
         );
+        add_update!(builder.accumulated_cost, "accumulated_cost");
+        add_update!(builder.budget, "budget_json");
         add_update!(builder.schedule_id, "schedule_id");
         add_update!(builder.recipe, "recipe_json");
         add_update!(builder.user_recipe_values, "user_recipe_values_json");
+        add_update!(builder.owner, "owner");
 
         if updates.is_empty() {
             return Ok(());
@@ -917,6 +1158,13 @@ impl SessionStorage {
         if let Some(aot) = builder.accumulated_output_tokens {
             q = q.bind(aot);
         }
+        if let Some(cost) = builder.accumulated_cost {
+            q = q.bind(cost);
+        }
+        if let Some(budget) = builder.budget {
+            let budget_json = budget.map(|b| serde_json::to_string(&b)).transpose()?;
+            q = q.bind(budget_json);
+        }
         if let Some(sid) = builder.schedule_id {
             q = q.bind(sid);
         }
@@ -930,9 +1178,12 @@ impl SessionStorage {
                 .transpose()?;
             q = q.bind(user_recipe_values_json);
         }
+        if let Some(owner) = builder.owner {
+            q = q.bind(owner);
+        }
 
         q = q.bind(&builder.session_id);
-        q.execute(&self.pool).await?;
+        q.execute(conn).await?;
 
         Ok(())
     }
@@ -970,6 +1221,22 @@ impl SessionStorage {
     }
 
     async fn add_message(&self, session_id: &str, message: &Message) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::insert_message(&mut conn, session_id, message).await?;
+        drop(conn);
+
+        if let Some(index) = self.embedding_index().await {
+            index.index_session_in_background(self.pool.clone(), session_id);
+        }
+
+        Ok(())
+    }
+
+    async fn insert_message(
+        conn: &mut SqliteConnection,
+        session_id: &str,
+        message: &Message,
+    ) -> Result<()> {
         let metadata_json = serde_json::to_string(&message.metadata)?;
 
         sqlx::query(
@@ -983,27 +1250,68 @@ impl SessionStorage {
         .bind(serde_json::to_string(&message.content)?)
         .bind(message.created)
         .bind(metadata_json)
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query("UPDATE sessions SET updated_at = datetime('now') WHERE id = ?")
             .bind(session_id)
-            .execute(&self.pool)
+            .execute(&mut *conn)
             .await?;
 
         Ok(())
     }
 
+    async fn embedding_index(&self) -> Option<Arc<EmbeddingIndex>> {
+        self.embedding_index
+            .get_or_init(|| async {
+                match EmbeddingIndex::from_config().await {
+                    Ok(index) => index.map(Arc::new),
+                    Err(e) => {
+                        warn!("Embedding index disabled: {}", e);
+                        None
+                    }
+                }
+            })
+            .await
+            .clone()
+    }
+
+    async fn index_embeddings(&self) -> Result<usize> {
+        let index = self.embedding_index().await.ok_or_else(|| {
+            anyhow::anyhow!(
+                "No embedding provider configured. Set {} to enable the embedding index.",
+                EMBEDDING_PROVIDER_CONFIG_KEY
+            )
+        })?;
+        index.index_pending(&self.pool, None).await
+    }
+
     async fn replace_conversation(
         &self,
         session_id: &str,
         conversation: &Conversation,
     ) -> Result<()> {
         let mut tx = self.pool.begin().await?;
+        Self::write_conversation(&mut tx, session_id, conversation).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn write_conversation(
+        conn: &mut SqliteConnection,
+        session_id: &str,
+        conversation: &Conversation,
+    ) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM message_embeddings WHERE message_id IN (SELECT id FROM messages WHERE session_id = ?)",
+        )
+        .bind(session_id)
+        .execute(&mut *conn)
+        .await?;
 
         sqlx::query("DELETE FROM messages WHERE session_id = ?")
             .bind(session_id)
-            .execute(&mut *tx)
+            .execute(&mut *conn)
             .await?;
 
         for message in conversation.messages() {
@@ -1020,11 +1328,10 @@ impl SessionStorage {
             .bind(serde_json::to_string(&message.content)?)
             .bind(message.created)
             .bind(metadata_json)
-            .execute(&mut *tx)
+            .execute(&mut *conn)
             .await?;
         }
 
-        tx.commit().await?;
         Ok(())
     }
 
@@ -1034,7 +1341,8 @@ impl SessionStorage {
         SELECT s.id, s.working_dir, s.name, s.description, s.user_set_name, s.session_type, s.created_at, s.updated_at, s.extension_data,
                s.total_tokens, s.input_tokens, s.output_tokens,
                s.accumulated_total_tokens, s.accumulated_input_tokens, s.accumulated_output_tokens,
-               s.schedule_id, s.recipe_json, s.user_recipe_values_json,
+               s.accumulated_cost, s.budget_json,
+               s.schedule_id, s.recipe_json, s.user_recipe_values_json, s.parent_session_id, s.owner,
                COUNT(m.id) as message_count
         FROM sessions s
         INNER JOIN messages m ON s.id = m.session_id
@@ -1059,11 +1367,23 @@ impl SessionStorage {
             return Err(anyhow::anyhow!("Session not found"));
         }
 
+        sqlx::query(
+            "DELETE FROM message_embeddings WHERE message_id IN (SELECT id FROM messages WHERE session_id = ?)",
+        )
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
         sqlx::query("DELETE FROM messages WHERE session_id = ?")
             .bind(session_id)
             .execute(&self.pool)
             .await?;
 
+        sqlx::query("DELETE FROM turn_journal WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+
         sqlx::query("DELETE FROM sessions WHERE id = ?")
             .bind(session_id)
             .execute(&self.pool)
@@ -1072,20 +1392,25 @@ impl SessionStorage {
         Ok(())
     }
 
-    async fn get_insights(&self) -> Result<SessionInsights> {
-        let row = sqlx::query_as::<_, (i64, Option<i64>)>(
+    async fn get_insights(&self, owner: Option<&str>) -> Result<SessionInsights> {
+        let row = sqlx::query_as::<_, (i64, Option<i64>, Option<f64>)>(
             r#"
             SELECT COUNT(*) as total_sessions,
-                   COALESCE(SUM(COALESCE(accumulated_total_tokens, total_tokens, 0)), 0) as total_tokens
+                   COALESCE(SUM(COALESCE(accumulated_total_tokens, total_tokens, 0)), 0) as total_tokens,
+                   COALESCE(SUM(accumulated_cost), 0.0) as total_cost
             FROM sessions
+            WHERE ? IS NULL OR owner = ?
             "#,
         )
+            .bind(owner)
+            .bind(owner)
             .fetch_one(&self.pool)
             .await?;
 
         Ok(SessionInsights {
             total_sessions: row.0 as usize,
             total_tokens: row.1.unwrap_or(0),
+            total_cost: row.2.unwrap_or(0.0),
         })
     }
 
@@ -1113,6 +1438,8 @@ impl SessionStorage {
             .accumulated_total_tokens(import.accumulated_total_tokens)
             .accumulated_input_tokens(import.accumulated_input_tokens)
             .accumulated_output_tokens(import.accumulated_output_tokens)
+            .accumulated_cost(import.accumulated_cost)
+            .budget(import.budget)
             .schedule_id(import.schedule_id)
             .recipe(import.recipe)
             .user_recipe_values(import.user_recipe_values);
@@ -1141,16 +1468,198 @@ impl SessionStorage {
     ) -> Result<crate::session::chat_history_search::ChatRecallResults> {
         use crate::session::chat_history_search::ChatHistorySearch;
 
-        ChatHistorySearch::new(
+        let mut search = ChatHistorySearch::new(
             &self.pool,
             query,
             limit,
             after_date,
             before_date,
             exclude_session_id,
+        );
+
+        // Rank by meaning as well as keywords when an embedding index is available
+        if let Some(index) = self.embedding_index().await {
+            match index.embed_query(query).await {
+                Ok(embedding) => {
+                    search = search.with_query_embedding(embedding, index.model().to_string());
+                }
+                Err(e) => warn!("Falling back to keyword search: {}", e),
+            }
+        }
+
+        search.execute().await
+    }
+
+    async fn fork_session(&self, id: &str, message_index: usize) -> Result<Session> {
+        let source = self.get_session(id, true).await?;
+        let messages = source
+            .conversation
+            .map(|conversation| conversation.messages().clone())
+            .unwrap_or_default();
+        if message_index > messages.len() {
+            anyhow::bail!(
+                "Message index {} is out of range, session {} has {} messages",
+                message_index,
+                id,
+                messages.len()
+            );
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let fork = Self::insert_session(
+            &mut tx,
+            source.working_dir.clone(),
+            source.name.clone(),
+            source.session_type,
         )
-        .execute()
-        .await
+        .await?;
+
+        let mut builder = SessionUpdateBuilder::new(fork.id.clone())
+            .extension_data(source.extension_data)
+            .budget(source.budget)
+            .recipe(source.recipe)
+            .user_recipe_values(source.user_recipe_values)
+            .owner(source.owner);
+        if source.user_set_name {
+            builder = builder.user_provided_name(source.name);
+        }
+        Self::update_session_row(&mut tx, builder).await?;
+
+        sqlx::query("UPDATE sessions SET parent_session_id = ? WHERE id = ?")
+            .bind(id)
+            .bind(&fork.id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::write_conversation(
+            &mut tx,
+            &fork.id,
+            &Conversation::new_unvalidated(messages[..message_index].to_vec()),
+        )
+        .await?;
+        tx.commit().await?;
+
+        self.get_session(&fork.id, true).await
+    }
+
+    async fn save_turn_journal(&self, session_id: &str, journal: &TurnJournal) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO turn_journal (session_id, journal_json, updated_at)
+            VALUES (?, ?, datetime('now'))
+            ON CONFLICT(session_id) DO UPDATE SET
+                journal_json = excluded.journal_json,
+                updated_at = excluded.updated_at
+        "#,
+        )
+        .bind(session_id)
+        .bind(serde_json::to_string(journal)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_turn_journal(&self, session_id: &str) -> Result<Option<TurnJournal>> {
+        let journal_json = sqlx::query_scalar::<_, String>(
+            "SELECT journal_json FROM turn_journal WHERE session_id = ?",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match journal_json {
+            Some(json) => match serde_json::from_str(&json) {
+                Ok(journal) => Ok(Some(journal)),
+                Err(e) => {
+                    warn!(
+                        "Ignoring unreadable turn journal of session {}: {}",
+                        session_id, e
+                    );
+                    Ok(None)
+                }
+            },
+            None => Ok(None),
+        }
+    }
+
+    async fn clear_turn_journal(&self, session_id: &str) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_turn_journal(&mut conn, session_id).await
+    }
+
+    async fn delete_turn_journal(conn: &mut SqliteConnection, session_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM turn_journal WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn resolve_interrupted_turn(
+        &self,
+        session_id: &str,
+        recovery: TurnRecovery,
+        reason: &str,
+    ) -> Result<Option<Message>> {
+        let journal = self
+            .get_turn_journal(session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Session {} has no interrupted turn", session_id))?;
+
+        // The conversation change and the journal removal are applied together, so a crash
+        // in between can't resolve the same turn twice
+        let mut tx = self.pool.begin().await?;
+        let rolled_back = match recovery {
+            TurnRecovery::Rerun => {
+                anyhow::bail!("Rerunning an interrupted turn needs an agent")
+            }
+            TurnRecovery::MarkFailed => {
+                for message in journal.complete_as_failed(reason) {
+                    Self::insert_message(&mut tx, session_id, &message).await?;
+                }
+                None
+            }
+            TurnRecovery::Rollback => {
+                let conversation = self.get_conversation(session_id).await?;
+                let messages = conversation.messages();
+                let turn_start = journal.turn_start(messages).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Cannot roll back: the conversation of session {} no longer contains the interrupted turn",
+                        session_id
+                    )
+                })?;
+                Self::write_conversation(
+                    &mut tx,
+                    session_id,
+                    &Conversation::new_unvalidated(messages[..turn_start].to_vec()),
+                )
+                .await?;
+                Some(journal.user_message)
+            }
+        };
+        Self::delete_turn_journal(&mut tx, session_id).await?;
+        tx.commit().await?;
+
+        if rolled_back.is_none() {
+            if let Some(index) = self.embedding_index().await {
+                index.index_session_in_background(self.pool.clone(), session_id);
+            }
+        }
+        Ok(rolled_back)
+    }
+
+    async fn search_sessions(
+        &self,
+        query: &SessionSearchQuery,
+    ) -> Result<Vec<SessionSearchResult>> {
+        let hits = SessionSearch::new(&self.pool, query).execute().await?;
+
+        let mut results = Vec::with_capacity(hits.len());
+        for (session_id, matches) in hits {
+            let session = self.get_session(&session_id, false).await?;
+            results.push(SessionSearchResult { session, matches });
+        }
+        Ok(results)
     }
 }
 
@@ -1158,6 +1667,7 @@ impl SessionStorage {
 mod tests {
     use super::*;
     use crate::conversation::message::{Message, MessageContent};
+    use crate::session::chat_history_search::ChatHistorySearch;
     use tempfile::TempDir;
 
     const NUM_CONCURRENT_SESSIONS: i32 = 10;
@@ -1249,7 +1759,7 @@ mod tests {
             assert!(session.name.starts_with("Updated session"));
         }
 
-        let insights = storage.get_insights().await.unwrap();
+        let insights = storage.get_insights(None).await.unwrap();
         assert_eq!(insights.total_sessions, NUM_CONCURRENT_SESSIONS as usize);
         let expected_tokens = 100 * NUM_CONCURRENT_SESSIONS * (NUM_CONCURRENT_SESSIONS - 1) / 2;
         assert_eq!(insights.total_tokens, expected_tokens as i64);
@@ -1358,4 +1868,506 @@ mod tests {
         assert!(imported.user_set_name);
         assert_eq!(imported.working_dir, PathBuf::from("/tmp/test"));
     }
+
+    /// Embeds text by counting words about vehicles and cooking, tracking how many
+    /// embedding requests run at once
+    #[derive(Default)]
+    struct TopicEmbeddingProvider {
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for TopicEmbeddingProvider {
+        fn metadata() -> crate::providers::base::ProviderMetadata {
+            crate::providers::base::ProviderMetadata::empty()
+        }
+
+        fn get_name(&self) -> &str {
+            "topic-embeddings"
+        }
+
+        fn get_model_config(&self) -> crate::model::ModelConfig {
+            crate::model::ModelConfig::new_or_fail("topic")
+        }
+
+        async fn complete_with_model(
+            &self,
+            _model_config: &crate::model::ModelConfig,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[rmcp::model::Tool],
+        ) -> Result<
+            (Message, crate::providers::base::ProviderUsage),
+            crate::providers::errors::ProviderError,
+        > {
+            Err(crate::providers::errors::ProviderError::ExecutionError(
+                "topic-embeddings only supports embeddings".to_string(),
+            ))
+        }
+
+        fn supports_embeddings(&self) -> bool {
+            true
+        }
+
+        async fn create_embeddings(
+            &self,
+            texts: Vec<String>,
+        ) -> Result<Vec<Vec<f32>>, crate::providers::errors::ProviderError> {
+            use std::sync::atomic::Ordering;
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let count = |text: &str, words: &[&str]| {
+                text.to_lowercase()
+                    .split_whitespace()
+                    .filter(|w| words.contains(w))
+                    .count() as f32
+            };
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    vec![
+                        count(text, &["car", "vehicle", "highway"]),
+                        count(text, &["recipe", "lasagna", "cooking"]),
+                        0.1,
+                    ]
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_finds_differently_phrased_messages() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_embeddings.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let add = |session_id: String, role: Role, text: &'static str| {
+            let storage = &storage;
+            async move {
+                let message = Message::new(role, chrono::Utc::now().timestamp_millis(), vec![])
+                    .with_text(text);
+                storage.add_message(&session_id, &message).await.unwrap();
+            }
+        };
+
+        let index = EmbeddingIndex::new(Arc::new(TopicEmbeddingProvider::default()), "topic");
+        assert!(storage.embedding_index.set(Some(Arc::new(index))).is_ok());
+
+        let cars = storage
+            .create_session(
+                PathBuf::from("/tmp/cars"),
+                "Cars".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        add(
+            cars.id.clone(),
+            Role::User,
+            "My car broke down on the highway",
+        )
+        .await;
+        add(cars.id.clone(), Role::Assistant, "Sorry to hear that").await;
+
+        let food = storage
+            .create_session(
+                PathBuf::from("/tmp/food"),
+                "Food".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        add(food.id.clone(), Role::User, "Share a lasagna recipe").await;
+
+        let keyword_only = ChatHistorySearch::new(&storage.pool, "vehicle", None, None, None, None)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(keyword_only.total_matches, 0);
+
+        // Messages are also indexed in the background as they are added
+        storage.index_embeddings().await.unwrap();
+        assert_eq!(storage.index_embeddings().await.unwrap(), 0);
+        let indexed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM message_embeddings")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(indexed, 3);
+
+        let results = storage
+            .search_chat_history("vehicle", None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(results.total_matches, 1);
+        assert_eq!(results.results[0].session_id, cars.id);
+        assert_eq!(
+            results.results[0].messages[0].content,
+            "My car broke down on the highway"
+        );
+
+        storage.delete_session(&cars.id).await.unwrap();
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM message_embeddings")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_background_indexing_runs_one_worker_per_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_index_worker.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let provider = Arc::new(TopicEmbeddingProvider::default());
+        let index = EmbeddingIndex::new(provider.clone(), "topic");
+        assert!(storage.embedding_index.set(Some(Arc::new(index))).is_ok());
+
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp/worker"),
+                "Worker".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        for i in 0..10 {
+            let message = Message::user().with_text(format!("car number {}", i));
+            storage.add_message(&session.id, &message).await.unwrap();
+        }
+
+        let mut indexed = 0;
+        for _ in 0..200 {
+            indexed = sqlx::query_scalar("SELECT COUNT(*) FROM message_embeddings")
+                .fetch_one(&storage.pool)
+                .await
+                .unwrap();
+            if indexed == 10 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(indexed, 10);
+        assert_eq!(
+            provider
+                .max_in_flight
+                .load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_text_session_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_search.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let mut session_ids = Vec::new();
+        for (dir, text) in [
+            ("/work/api", "The deployment failed with a timeout error"),
+            ("/work/web", "Fix the flaky deployment script"),
+            ("/work/web", "Unrelated chatter about lunch"),
+        ] {
+            let session = storage
+                .create_session(PathBuf::from(dir), text.to_string(), SessionType::User)
+                .await
+                .unwrap();
+            storage
+                .add_message(&session.id, &Message::user().with_text(text))
+                .await
+                .unwrap();
+            session_ids.push(session.id);
+        }
+
+        let search = |query: &str, working_dir: Option<&str>| SessionSearchQuery {
+            query: query.to_string(),
+            working_dir: working_dir.map(str::to_string),
+            ..Default::default()
+        };
+
+        let results = storage
+            .search_sessions(&search("deploy", None))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        let results = storage
+            .search_sessions(&search("deployment timeout", None))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].session.id, session_ids[0]);
+        assert_eq!(
+            results[0].matches[0].snippet,
+            "The [deployment] failed with a [timeout] error"
+        );
+
+        let results = storage
+            .search_sessions(&search("deployment", Some("WEB")))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].session.id, session_ids[1]);
+
+        // The index follows conversation rewrites and deletions
+        storage
+            .replace_conversation(
+                &session_ids[2],
+                &Conversation::new_unvalidated(vec![
+                    Message::user().with_text("Now about the deployment")
+                ]),
+            )
+            .await
+            .unwrap();
+        storage.delete_session(&session_ids[0]).await.unwrap();
+        let results = storage
+            .search_sessions(&search("deployment", None))
+            .await
+            .unwrap();
+        let mut found: Vec<_> = results.iter().map(|r| r.session.id.clone()).collect();
+        found.sort();
+        assert_eq!(found, vec![session_ids[1].clone(), session_ids[2].clone()]);
+
+        let recall = ChatHistorySearch::new(&storage.pool, "lunch timeout", None, None, None, None)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(recall.total_matches, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_search_working_dir_is_matched_literally() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_search_dir.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let mut session_ids = Vec::new();
+        for dir in ["/work/my_app", "/work/myxapp", "/work/100%"] {
+            let session = storage
+                .create_session(PathBuf::from(dir), dir.to_string(), SessionType::User)
+                .await
+                .unwrap();
+            storage
+                .add_message(&session.id, &Message::user().with_text("deployment notes"))
+                .await
+                .unwrap();
+            session_ids.push(session.id);
+        }
+
+        for (working_dir, expected) in [("my_app", &session_ids[0]), ("0%", &session_ids[2])] {
+            let query = SessionSearchQuery {
+                query: "deployment".to_string(),
+                working_dir: Some(working_dir.to_string()),
+                ..Default::default()
+            };
+            let results = storage.search_sessions(&query).await.unwrap();
+            assert_eq!(results.len(), 1, "{}", working_dir);
+            assert_eq!(&results[0].session.id, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_budget_and_cost_persist() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_budget.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp/budget"),
+                "Budgeted".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        let budget = Budget {
+            max_cost_usd: Some(2.5),
+            max_tokens: None,
+        };
+        storage
+            .apply_update(
+                SessionUpdateBuilder::new(session.id.clone())
+                    .accumulated_cost(Some(0.75))
+                    .budget(Some(budget)),
+            )
+            .await
+            .unwrap();
+
+        let session = storage.get_session(&session.id, false).await.unwrap();
+        assert_eq!(session.accumulated_cost, Some(0.75));
+        assert_eq!(session.budget, Some(budget));
+
+        let insights = storage.get_insights(None).await.unwrap();
+        assert_eq!(insights.total_cost, 0.75);
+    }
+
+    #[tokio::test]
+    async fn test_fork_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_fork.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp/fork"),
+                "Original".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+        for text in ["first", "reply", "second", "another reply"] {
+            let message = if text.contains("reply") {
+                Message::assistant().with_text(text)
+            } else {
+                Message::user().with_text(text)
+            };
+            storage.add_message(&session.id, &message).await.unwrap();
+        }
+
+        let fork = storage.fork_session(&session.id, 2).await.unwrap();
+        assert_ne!(fork.id, session.id);
+        assert_eq!(fork.parent_session_id.as_deref(), Some(session.id.as_str()));
+        assert_eq!(fork.name, "Original");
+        assert_eq!(fork.working_dir, PathBuf::from("/tmp/fork"));
+        let texts: Vec<String> = fork
+            .conversation
+            .unwrap()
+            .messages()
+            .iter()
+            .map(|m| m.as_concat_text())
+            .collect();
+        assert_eq!(texts, vec!["first", "reply"]);
+
+        let original = storage.get_session(&session.id, true).await.unwrap();
+        assert_eq!(original.message_count, 4);
+        assert_eq!(original.parent_session_id, None);
+
+        assert!(storage.fork_session(&session.id, 5).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_owner() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_owner.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+
+        let mut ids = Vec::new();
+        for (owner, cost) in [(Some("alice"), 1.0), (Some("bob"), 2.0), (None, 4.0)] {
+            let session = storage
+                .create_session(
+                    PathBuf::from("/tmp/owner"),
+                    "Owned".to_string(),
+                    SessionType::User,
+                )
+                .await
+                .unwrap();
+            storage
+                .apply_update(
+                    SessionUpdateBuilder::new(session.id.clone())
+                        .owner(owner.map(str::to_string))
+                        .accumulated_cost(Some(cost)),
+                )
+                .await
+                .unwrap();
+            ids.push(session.id);
+        }
+
+        let session = storage.get_session(&ids[0], false).await.unwrap();
+        assert_eq!(session.owner.as_deref(), Some("alice"));
+
+        let insights = storage.get_insights(Some("bob")).await.unwrap();
+        assert_eq!(insights.total_sessions, 1);
+        assert_eq!(insights.total_cost, 2.0);
+        assert_eq!(storage.get_insights(None).await.unwrap().total_sessions, 3);
+
+        storage
+            .add_message(&ids[0], &Message::user().with_text("hello"))
+            .await
+            .unwrap();
+        let fork = storage.fork_session(&ids[0], 1).await.unwrap();
+        assert_eq!(fork.owner.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_interrupted_turn() {
+        use rmcp::model::CallToolRequestParam;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_turn_journal.db");
+        let storage = SessionStorage::create(&db_path).await.unwrap();
+        let session = storage
+            .create_session(
+                PathBuf::from("/tmp/journal"),
+                "Journal".to_string(),
+                SessionType::User,
+            )
+            .await
+            .unwrap();
+
+        let user_message = Message::user().with_text("run ls");
+        storage
+            .add_message(&session.id, &Message::user().with_text("hello"))
+            .await
+            .unwrap();
+        storage
+            .add_message(&session.id, &user_message)
+            .await
+            .unwrap();
+
+        let mut journal = TurnJournal::new(user_message);
+        journal.record_tool_progress(vec![Message::assistant().with_tool_request(
+            "call_1",
+            Ok(CallToolRequestParam {
+                name: "developer__shell".into(),
+                arguments: None,
+            }),
+        )]);
+        storage
+            .save_turn_journal(&session.id, &journal)
+            .await
+            .unwrap();
+        let saved = storage
+            .get_turn_journal(&session.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(saved.interrupted_tool_requests().len(), 1);
+
+        storage
+            .resolve_interrupted_turn(&session.id, TurnRecovery::MarkFailed, "interrupted")
+            .await
+            .unwrap();
+        assert!(storage
+            .get_turn_journal(&session.id)
+            .await
+            .unwrap()
+            .is_none());
+        let conversation = storage.get_conversation(&session.id).await.unwrap();
+        assert_eq!(conversation.messages().len(), 4);
+        assert!(matches!(
+            conversation.messages()[3].content.first(),
+            Some(MessageContent::ToolResponse(response)) if response.tool_result.is_err()
+        ));
+
+        storage
+            .save_turn_journal(&session.id, &journal)
+            .await
+            .unwrap();
+        let rolled_back = storage
+            .resolve_interrupted_turn(&session.id, TurnRecovery::Rollback, "interrupted")
+            .await
+            .unwrap();
+        assert_eq!(rolled_back.unwrap().as_concat_text(), "run ls");
+        let conversation = storage.get_conversation(&session.id).await.unwrap();
+        assert_eq!(conversation.messages().len(), 1);
+        assert!(storage
+            .get_turn_journal(&session.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
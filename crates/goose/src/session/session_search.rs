@@ -0,0 +1,246 @@
+use crate::session::session_manager::Session;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use utoipa::ToSchema;
+
+const DEFAULT_SESSION_LIMIT: usize = 20;
+const MATCHES_PER_SESSION: usize = 3;
+// Upper bound on matching messages considered before grouping them by session
+const MAX_MATCHED_MESSAGES: i64 = 1000;
+const SNIPPET_TOKENS: i64 = 16;
+
+/// Text of a stored message's text content, as an SQL expression over `<row>.content_json`
+macro_rules! message_text_sql {
+    ($row:literal) => {
+        concat!(
+            "SELECT group_concat(json_extract(value, '$.text'), char(10)) AS text FROM json_each(",
+            $row,
+            ".content_json) WHERE json_extract(value, '$.type') = 'text'"
+        )
+    };
+}
+
+/// Create the `messages_fts` full-text index over message text, kept up to date by triggers,
+/// and fill it with the existing messages.
+pub(crate) async fn create_fts_index(pool: &Pool<Sqlite>) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("CREATE VIRTUAL TABLE messages_fts USING fts5(text)")
+        .execute(&mut *tx)
+        .await?;
+
+    let statements = [
+        concat!(
+            "CREATE TRIGGER messages_fts_insert AFTER INSERT ON messages BEGIN ",
+            "INSERT INTO messages_fts (rowid, text) SELECT new.id, text FROM (",
+            message_text_sql!("new"),
+            ") WHERE text IS NOT NULL; END"
+        ),
+        concat!(
+            "CREATE TRIGGER messages_fts_delete AFTER DELETE ON messages BEGIN ",
+            "DELETE FROM messages_fts WHERE rowid = old.id; END"
+        ),
+        concat!(
+            "CREATE TRIGGER messages_fts_update AFTER UPDATE OF content_json ON messages BEGIN ",
+            "DELETE FROM messages_fts WHERE rowid = old.id; ",
+            "INSERT INTO messages_fts (rowid, text) SELECT new.id, text FROM (",
+            message_text_sql!("new"),
+            ") WHERE text IS NOT NULL; END"
+        ),
+        concat!(
+            "INSERT INTO messages_fts (rowid, text) SELECT id, text FROM (SELECT m.id AS id, (",
+            message_text_sql!("m"),
+            ") AS text FROM messages m) WHERE text IS NOT NULL"
+        ),
+    ];
+    for statement in statements {
+        sqlx::query(statement).execute(&mut *tx).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Whether the sessions database has the full-text index. It is missing if the
+/// SQLite library was built without FTS5.
+pub(crate) async fn fts_available(pool: &Pool<Sqlite>) -> Result<bool> {
+    Ok(sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'messages_fts')",
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+/// Build an FTS5 query matching the words of `query` as prefixes, joined with `operator`
+/// (`AND` or `OR`). Words are quoted so user input is never parsed as FTS5 syntax.
+pub(crate) fn fts_match_query(query: &str, operator: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(&format!(" {} ", operator)))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionSearchQuery {
+    /// Words that must all appear in a message, matched as prefixes
+    pub query: String,
+    /// Maximum number of sessions to return
+    pub limit: Option<usize>,
+    pub after_date: Option<DateTime<Utc>>,
+    pub before_date: Option<DateTime<Utc>>,
+    /// Only sessions whose working directory contains this text, ignoring case
+    pub working_dir: Option<String>,
+    /// Markers placed around matched words in snippets, `[` and `]` by default
+    pub highlight: Option<(String, String)>,
+    /// Only sessions owned by this API key
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionSearchMatch {
+    pub role: String,
+    /// Excerpt of the message with matched words wrapped in the highlight markers
+    pub snippet: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionSearchResult {
+    pub session: Session,
+    /// Best matching messages in the session, most relevant first
+    pub matches: Vec<SessionSearchMatch>,
+}
+
+/// Full-text search over message text, grouping matches by session in order of relevance.
+/// Escape the wildcards of a `LIKE ... ESCAPE '\'` pattern so the text matches literally
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub struct SessionSearch<'a> {
+    pool: &'a Pool<Sqlite>,
+    query: &'a SessionSearchQuery,
+}
+
+impl<'a> SessionSearch<'a> {
+    pub fn new(pool: &'a Pool<Sqlite>, query: &'a SessionSearchQuery) -> Self {
+        Self { pool, query }
+    }
+
+    /// Matching session ids with their best matches, most relevant session first.
+    pub async fn execute(self) -> Result<Vec<(String, Vec<SessionSearchMatch>)>> {
+        if !fts_available(self.pool).await? {
+            anyhow::bail!("Full-text search is not available for this sessions database");
+        }
+        let Some(match_query) = fts_match_query(&self.query.query, "AND") else {
+            return Ok(Vec::new());
+        };
+
+        let (highlight_start, highlight_end) = self
+            .query
+            .highlight
+            .clone()
+            .unwrap_or_else(|| ("[".to_string(), "]".to_string()));
+
+        let mut sql = String::from(
+            r#"
+            SELECT m.session_id, m.role, m.timestamp,
+                   snippet(messages_fts, 0, ?, ?, '…', ?) AS snippet
+            FROM messages_fts
+            INNER JOIN messages m ON m.id = messages_fts.rowid
+            INNER JOIN sessions s ON s.id = m.session_id
+            WHERE messages_fts MATCH ?
+            AND (s.session_type = 'user' OR s.session_type = 'scheduled')
+        "#,
+        );
+        if self.query.working_dir.is_some() {
+            sql.push_str(r" AND LOWER(s.working_dir) LIKE ? ESCAPE '\'");
+        }
+        if self.query.owner.is_some() {
+            sql.push_str(" AND s.owner = ?");
+        }
+        if self.query.after_date.is_some() {
+            sql.push_str(" AND m.timestamp >= ?");
+        }
+        if self.query.before_date.is_some() {
+            sql.push_str(" AND m.timestamp <= ?");
+        }
+        sql.push_str(" ORDER BY messages_fts.rank LIMIT ?");
+
+        let mut query_builder = sqlx::query_as::<_, (String, String, DateTime<Utc>, String)>(&sql)
+            .bind(highlight_start)
+            .bind(highlight_end)
+            .bind(SNIPPET_TOKENS)
+            .bind(match_query);
+        if let Some(working_dir) = &self.query.working_dir {
+            query_builder =
+                query_builder.bind(format!("%{}%", escape_like(&working_dir.to_lowercase())));
+        }
+        if let Some(owner) = &self.query.owner {
+            query_builder = query_builder.bind(owner);
+        }
+        if let Some(after) = self.query.after_date {
+            query_builder = query_builder.bind(after);
+        }
+        if let Some(before) = self.query.before_date {
+            query_builder = query_builder.bind(before);
+        }
+        let rows = query_builder
+            .bind(MAX_MATCHED_MESSAGES)
+            .fetch_all(self.pool)
+            .await?;
+
+        let limit = self.query.limit.unwrap_or(DEFAULT_SESSION_LIMIT);
+        let mut results: Vec<(String, Vec<SessionSearchMatch>)> = Vec::new();
+        for (session_id, role, timestamp, snippet) in rows {
+            let search_match = SessionSearchMatch {
+                role,
+                snippet,
+                timestamp,
+            };
+            match results.iter().position(|(id, _)| *id == session_id) {
+                Some(index) => {
+                    let matches = &mut results[index].1;
+                    if matches.len() < MATCHES_PER_SESSION {
+                        matches.push(search_match);
+                    }
+                }
+                None if results.len() < limit => results.push((session_id, vec![search_match])),
+                None => {}
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_match_query_quotes_terms() {
+        assert_eq!(
+            fts_match_query("rust AND error", "AND"),
+            Some(r#""rust"* AND "AND"* AND "error"*"#.to_string())
+        );
+        assert_eq!(
+            fts_match_query(r#"say "hi""#, "OR"),
+            Some(r#""say"* OR """hi"""*"#.to_string())
+        );
+        assert_eq!(fts_match_query("  -- ** ", "AND"), None);
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("my_app"), r"my\_app");
+        assert_eq!(escape_like(r"100%\done"), r"100\%\\done");
+    }
+}
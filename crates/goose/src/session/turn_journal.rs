@@ -0,0 +1,230 @@
+use crate::conversation::message::{Message, MessageContent, ToolRequest};
+use crate::mcp_utils::ToolResult;
+use chrono::{DateTime, Utc};
+use rmcp::model::{Content, ErrorCode, ErrorData, Role};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// The error recorded for tool calls of an interrupted turn that is not rerun
+pub const INTERRUPTED_TOOL_CALL_REASON: &str =
+    "The tool call was interrupted before it finished and did not return a result";
+
+/// What the agent loop was doing when the journal was last written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnPhase {
+    /// Waiting on the provider for the next response
+    AwaitingResponse,
+    /// Running the tool calls of a response
+    RunningTools,
+}
+
+/// How to continue a session whose last turn was interrupted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnRecovery {
+    /// Run the interrupted tool calls again and let the agent continue the turn
+    Rerun,
+    /// Keep the work done so far and record the interrupted tool calls as failed
+    MarkFailed,
+    /// Remove the turn from the conversation, including the user's message
+    Rollback,
+}
+
+/// The state of an agent turn that is still in progress. The agent loop only saves messages
+/// to the conversation once an iteration finishes, so the journal holds what the current
+/// iteration produced so far. A journal that is still present when a session is resumed
+/// means the process died, or the turn was dropped, before the turn completed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnJournal {
+    /// The user message that started the turn
+    pub user_message: Message,
+    pub phase: TurnPhase,
+    /// Messages of the current iteration that are not in the conversation yet: the
+    /// model's responses followed by the tool results collected so far
+    pub pending_messages: Vec<Message>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TurnJournal {
+    pub fn new(user_message: Message) -> Self {
+        let now = Utc::now();
+        Self {
+            user_message,
+            phase: TurnPhase::AwaitingResponse,
+            pending_messages: Vec::new(),
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Start a new iteration of the agent loop; the previous one has been saved
+    pub fn begin_request(&mut self) {
+        self.phase = TurnPhase::AwaitingResponse;
+        self.pending_messages.clear();
+        self.updated_at = Utc::now();
+    }
+
+    /// Record the iteration's messages while its tool calls run
+    pub fn record_tool_progress(&mut self, pending_messages: Vec<Message>) {
+        self.phase = TurnPhase::RunningTools;
+        self.pending_messages = pending_messages;
+        self.updated_at = Utc::now();
+    }
+
+    /// Tool calls requested by the model that have no result yet
+    pub fn interrupted_tool_requests(&self) -> Vec<ToolRequest> {
+        let answered: HashSet<&str> = self
+            .pending_messages
+            .iter()
+            .flat_map(|message| message.content.iter())
+            .filter_map(|content| match content {
+                MessageContent::ToolResponse(response) => Some(response.id.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        self.pending_messages
+            .iter()
+            .flat_map(|message| message.content.iter())
+            .filter_map(|content| match content {
+                MessageContent::ToolRequest(request) if !answered.contains(request.id.as_str()) => {
+                    Some(request.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The pending messages completed with `results` for the interrupted tool calls, ready
+    /// to be appended to the conversation. The results join the tool results already
+    /// collected, so every tool request is answered by the message that follows it.
+    pub fn complete_with(&self, results: Vec<(String, ToolResult<Vec<Content>>)>) -> Vec<Message> {
+        let mut messages = self.pending_messages.clone();
+        if results.is_empty() {
+            return messages;
+        }
+
+        let partial_results = messages
+            .last()
+            .filter(|message| message.role == Role::User)
+            .is_some();
+        let mut tool_results = if partial_results {
+            messages.pop().unwrap_or_else(Message::user)
+        } else {
+            Message::user().with_id(format!("msg_{}", Uuid::new_v4()))
+        };
+        for (id, result) in results {
+            tool_results = tool_results.with_tool_response(id, result);
+        }
+        messages.push(tool_results);
+        messages
+    }
+
+    /// The pending messages with every interrupted tool call answered by an error
+    pub fn complete_as_failed(&self, reason: &str) -> Vec<Message> {
+        let results = self
+            .interrupted_tool_requests()
+            .into_iter()
+            .map(|request| {
+                (
+                    request.id,
+                    Err(ErrorData {
+                        code: ErrorCode::INTERNAL_ERROR,
+                        message: std::borrow::Cow::from(reason.to_string()),
+                        data: None,
+                    }),
+                )
+            })
+            .collect();
+        self.complete_with(results)
+    }
+
+    /// Where the turn starts in `messages`, i.e. the position of its user message
+    pub fn turn_start(&self, messages: &[Message]) -> Option<usize> {
+        messages.iter().rposition(|message| {
+            message.role == self.user_message.role
+                && message.created == self.user_message.created
+                && message.content == self.user_message.content
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::CallToolRequestParam;
+    use rmcp::object;
+
+    fn tool_request(id: &str) -> Message {
+        Message::assistant().with_tool_request(
+            id,
+            Ok(CallToolRequestParam {
+                name: "developer__shell".into(),
+                arguments: Some(object!({"command": "ls"})),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_interrupted_tool_requests_skip_answered_calls() {
+        let mut journal = TurnJournal::new(Message::user().with_text("list files"));
+        let request = tool_request("call_1").with_tool_request(
+            "call_2",
+            Ok(CallToolRequestParam {
+                name: "developer__shell".into(),
+                arguments: Some(object!({"command": "pwd"})),
+            }),
+        );
+        let partial = Message::user().with_tool_response("call_1", Ok(vec![Content::text("a")]));
+        journal.record_tool_progress(vec![request, partial]);
+
+        let interrupted = journal.interrupted_tool_requests();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, "call_2");
+
+        let messages = journal.complete_as_failed("interrupted");
+        assert_eq!(messages.len(), 2);
+        let responses: Vec<_> = messages[1]
+            .content
+            .iter()
+            .filter_map(|content| match content {
+                MessageContent::ToolResponse(response) => Some(response),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert!(responses[0].tool_result.is_ok());
+        assert!(responses[1].tool_result.is_err());
+    }
+
+    #[test]
+    fn test_complete_with_adds_results_message() {
+        let mut journal = TurnJournal::new(Message::user().with_text("list files"));
+        journal.record_tool_progress(vec![tool_request("call_1")]);
+
+        let messages =
+            journal.complete_with(vec![("call_1".to_string(), Ok(vec![Content::text("a")]))]);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].role, Role::User);
+        assert!(journal.complete_with(Vec::new()).len() == 1);
+
+        journal.begin_request();
+        assert_eq!(journal.phase, TurnPhase::AwaitingResponse);
+        assert!(journal.interrupted_tool_requests().is_empty());
+    }
+
+    #[test]
+    fn test_turn_start_finds_user_message() {
+        let user_message = Message::user().with_text("list files");
+        let journal = TurnJournal::new(user_message.clone());
+        let messages = vec![
+            Message::user().with_text("hello"),
+            Message::assistant().with_text("hi"),
+            user_message,
+        ];
+        assert_eq!(journal.turn_start(&messages), Some(2));
+        assert_eq!(journal.turn_start(&messages[..2]), None);
+    }
+}
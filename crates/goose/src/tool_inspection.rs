@@ -132,6 +132,23 @@ impl ToolInspectionManager {
         tracing::warn!("Permission inspector not found for mode update");
     }
 
+    /// Update the working directory the permission inspector resolves path rules against
+    pub async fn update_permission_inspector_working_dir(&self, working_dir: &std::path::Path) {
+        for inspector in &self.inspectors {
+            if inspector.name() == "permission" {
+                if let Some(permission_inspector) =
+                    inspector.as_any().downcast_ref::<PermissionInspector>()
+                {
+                    permission_inspector
+                        .update_working_dir(working_dir.to_path_buf())
+                        .await;
+                    return;
+                }
+            }
+        }
+        tracing::warn!("Permission inspector not found for working directory update");
+    }
+
     /// Update the permission manager for a specific tool
     pub async fn update_permission_manager(
         &self,
@@ -16,6 +16,7 @@ mod tests {
         use chrono::{DateTime, Utc};
         use goose::agents::platform_tools::PLATFORM_MANAGE_SCHEDULE_TOOL_NAME;
         use goose::scheduler::{ScheduledJob, SchedulerError};
+        use goose::scheduler_history::JobRun;
         use goose::scheduler_trait::SchedulerTrait;
         use goose::session::Session;
         use std::sync::Arc;
@@ -67,6 +68,14 @@ mod tests {
                 Ok("test_session_123".to_string())
             }
 
+            async fn fire_webhook(
+                &self,
+                _id: &str,
+                _payload: Option<serde_json::Value>,
+            ) -> Result<(), SchedulerError> {
+                Ok(())
+            }
+
             async fn sessions(
                 &self,
                 _sched_id: &str,
@@ -75,6 +84,14 @@ mod tests {
                 Ok(vec![])
             }
 
+            async fn runs(
+                &self,
+                _sched_id: &str,
+                _limit: usize,
+            ) -> Result<Vec<JobRun>, SchedulerError> {
+                Ok(vec![])
+            }
+
             async fn update_schedule(
                 &self,
                 _sched_id: &str,
@@ -206,7 +223,9 @@ mod tests {
 
         #[tokio::test]
         async fn test_retry_success_check_execution() -> Result<()> {
-            use goose::agents::retry::execute_success_checks;
+            use goose::agents::retry::{
+                execute_success_checks, SuccessCheckContext, SuccessCheckOutcome,
+            };
 
             let retry_config = RetryConfig {
                 max_retries: 3,
@@ -220,17 +239,25 @@ mod tests {
                 command: "echo 'test'".to_string(),
             }];
 
-            let result = execute_success_checks(&success_checks, &retry_config).await;
+            let context = SuccessCheckContext::default();
+            let result = execute_success_checks(&success_checks, &retry_config, &context).await;
             assert!(result.is_ok(), "Success check should pass");
-            assert!(result.unwrap(), "Command should succeed");
+            assert_eq!(
+                result.unwrap(),
+                SuccessCheckOutcome::Passed,
+                "Command should succeed"
+            );
 
             let fail_checks = vec![SuccessCheck::Shell {
                 command: "false".to_string(),
             }];
 
-            let result = execute_success_checks(&fail_checks, &retry_config).await;
+            let result = execute_success_checks(&fail_checks, &retry_config, &context).await;
             assert!(result.is_ok(), "Success check execution should not error");
-            assert!(!result.unwrap(), "Command should fail");
+            assert!(
+                matches!(result.unwrap(), SuccessCheckOutcome::Failed { .. }),
+                "Command should fail"
+            );
 
             Ok(())
         }
@@ -423,6 +450,284 @@ mod tests {
         }
     }
 
+    #[cfg(test)]
+    mod budget_tests {
+        use super::*;
+        use async_trait::async_trait;
+        use goose::agents::SessionConfig;
+        use goose::conversation::message::{Message, MessageContent};
+        use goose::model::ModelConfig;
+        use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+        use goose::providers::errors::ProviderError;
+        use goose::session::session_manager::SessionType;
+        use goose::session::{Budget, SessionManager};
+        use rmcp::model::{CallToolRequestParam, Tool};
+        use rmcp::object;
+        use std::path::PathBuf;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Keeps calling a tool, so only a limit can end the run
+        #[derive(Default)]
+        struct PricedToolProvider {
+            agent_calls: AtomicUsize,
+        }
+
+        #[async_trait]
+        impl Provider for PricedToolProvider {
+            async fn complete_with_model(
+                &self,
+                _model_config: &ModelConfig,
+                _system_prompt: &str,
+                _messages: &[Message],
+                tools: &[Tool],
+            ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+                // Session naming runs in the background and offers no tools
+                if tools.is_empty() {
+                    return Ok((
+                        Message::assistant().with_text("Budget test"),
+                        ProviderUsage::new("priced-model".to_string(), Usage::default()),
+                    ));
+                }
+                let call = self.agent_calls.fetch_add(1, Ordering::SeqCst);
+                let tool_call = CallToolRequestParam {
+                    name: "test_tool".into(),
+                    arguments: Some(object!({"param": "value"})),
+                };
+                let message =
+                    Message::assistant().with_tool_request(format!("call_{}", call), Ok(tool_call));
+                let usage = ProviderUsage::new(
+                    "priced-model".to_string(),
+                    Usage::new(Some(1000), Some(500), Some(1500)),
+                );
+                Ok((message, usage))
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                ModelConfig::new("priced-model").unwrap()
+            }
+
+            fn metadata() -> ProviderMetadata {
+                ProviderMetadata::empty()
+            }
+
+            fn get_name(&self) -> &str {
+                "priced-test"
+            }
+        }
+
+        #[tokio::test]
+        async fn test_usd_budget_stops_run() -> Result<()> {
+            // Price the model at $0.001 per token, so the first call costs $1.50
+            let cache_dir = tempfile::tempdir()?;
+            let fetched_at = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs();
+            std::fs::write(
+                cache_dir.path().join("pricing_cache.json"),
+                serde_json::json!({
+                    "pricing": {
+                        "priced-test": {
+                            "priced-model": {
+                                "input_cost": 0.001,
+                                "output_cost": 0.001,
+                                "context_length": null
+                            }
+                        }
+                    },
+                    "fetched_at": fetched_at
+                })
+                .to_string(),
+            )?;
+            std::env::set_var("GOOSE_CACHE_DIR", cache_dir.path());
+
+            let agent = Agent::new();
+            let provider = Arc::new(PricedToolProvider::default());
+            agent.update_provider(provider.clone()).await?;
+
+            let session = SessionManager::create_session(
+                PathBuf::default(),
+                "budget-test".to_string(),
+                SessionType::Hidden,
+            )
+            .await?;
+            SessionManager::update_session(&session.id)
+                .budget(Some(Budget {
+                    max_cost_usd: Some(1.0),
+                    max_tokens: None,
+                }))
+                .apply()
+                .await?;
+            let session_config = SessionConfig {
+                id: session.id,
+                schedule_id: None,
+                max_turns: None,
+                retry_config: None,
+            };
+
+            let reply_stream = agent
+                .reply(Message::user().with_text("Hello"), session_config, None)
+                .await?;
+            tokio::pin!(reply_stream);
+
+            let mut notifications = Vec::new();
+            while let Some(event) = reply_stream.next().await {
+                if let AgentEvent::Message(message) = event? {
+                    for content in &message.content {
+                        match content {
+                            MessageContent::ToolConfirmationRequest(req) => {
+                                agent
+                                    .handle_confirmation(
+                                        req.id.clone(),
+                                        goose::permission::PermissionConfirmation {
+                                            principal_type: goose::permission::permission_confirmation::PrincipalType::Tool,
+                                            permission: goose::permission::Permission::AllowOnce,
+                                        },
+                                    )
+                                    .await;
+                            }
+                            MessageContent::SystemNotification(notification) => {
+                                notifications.push(notification.msg.clone());
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(provider.agent_calls.load(Ordering::SeqCst), 1);
+            assert!(
+                notifications
+                    .iter()
+                    .any(|msg| msg.starts_with("Budget reached: spent $1.5000")),
+                "{:?}",
+                notifications
+            );
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod interrupted_turn_tests {
+        use super::*;
+        use async_trait::async_trait;
+        use goose::agents::SessionConfig;
+        use goose::conversation::message::{Message, MessageContent};
+        use goose::model::ModelConfig;
+        use goose::permission::permission_confirmation::PrincipalType;
+        use goose::permission::{Permission, PermissionConfirmation};
+        use goose::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
+        use goose::providers::errors::ProviderError;
+        use goose::session::session_manager::SessionType;
+        use goose::session::{SessionManager, TurnJournal};
+        use rmcp::model::{CallToolRequestParam, Tool};
+        use rmcp::object;
+        use std::path::PathBuf;
+
+        /// Finishes the turn without calling tools
+        struct TextProvider;
+
+        #[async_trait]
+        impl Provider for TextProvider {
+            async fn complete_with_model(
+                &self,
+                _model_config: &ModelConfig,
+                _system_prompt: &str,
+                _messages: &[Message],
+                _tools: &[Tool],
+            ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+                Ok((
+                    Message::assistant().with_text("Done"),
+                    ProviderUsage::new("mock-model".to_string(), Usage::default()),
+                ))
+            }
+
+            fn get_model_config(&self) -> ModelConfig {
+                ModelConfig::new("mock-model").unwrap()
+            }
+
+            fn metadata() -> ProviderMetadata {
+                ProviderMetadata::empty()
+            }
+
+            fn get_name(&self) -> &str {
+                "mock-test"
+            }
+        }
+
+        #[tokio::test]
+        async fn test_rerun_asks_before_running_interrupted_tool_calls() -> Result<()> {
+            std::env::set_var("GOOSE_MODE", "approve");
+            let agent = Agent::new();
+            agent.update_provider(Arc::new(TextProvider)).await?;
+
+            let session = SessionManager::create_session(
+                PathBuf::default(),
+                "rerun-test".to_string(),
+                SessionType::Hidden,
+            )
+            .await?;
+            let user_message = Message::user().with_text("Clean up the build");
+            SessionManager::add_message(&session.id, &user_message).await?;
+            let mut journal = TurnJournal::new(user_message);
+            journal.record_tool_progress(vec![Message::assistant().with_tool_request(
+                "call_1",
+                Ok(CallToolRequestParam {
+                    name: "developer__shell".into(),
+                    arguments: Some(object!({"command": "rm -rf build"})),
+                }),
+            )]);
+            SessionManager::save_turn_journal(&session.id, &journal).await?;
+
+            let session_config = SessionConfig {
+                id: session.id.clone(),
+                schedule_id: None,
+                max_turns: None,
+                retry_config: None,
+            };
+            let reply_stream = agent.rerun_interrupted_turn(session_config, None).await?;
+            tokio::pin!(reply_stream);
+
+            let mut confirmations = Vec::new();
+            let mut tool_results = Vec::new();
+            while let Some(event) = reply_stream.next().await {
+                if let AgentEvent::Message(message) = event? {
+                    for content in &message.content {
+                        match content {
+                            MessageContent::ToolConfirmationRequest(req) => {
+                                confirmations.push(req.id.clone());
+                                agent
+                                    .handle_confirmation(
+                                        req.id.clone(),
+                                        PermissionConfirmation {
+                                            principal_type: PrincipalType::Tool,
+                                            permission: Permission::DenyOnce,
+                                        },
+                                    )
+                                    .await;
+                            }
+                            MessageContent::ToolResponse(response) => {
+                                tool_results.push(response.clone());
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            std::env::remove_var("GOOSE_MODE");
+
+            assert_eq!(confirmations, vec!["call_1".to_string()]);
+            assert_eq!(tool_results.len(), 1);
+            let output = tool_results[0].tool_result.as_ref().unwrap();
+            assert!(output[0]
+                .as_text()
+                .is_some_and(|text| text.text.contains("declined")));
+            assert!(SessionManager::get_turn_journal(&session.id)
+                .await?
+                .is_none());
+            Ok(())
+        }
+    }
+
     #[cfg(test)]
     mod extension_manager_tests {
         use super::*;
@@ -25,6 +25,25 @@ export type AuthorRequest = {
     metadata?: string | null;
 };
 
+/**
+ * Spending limits for a session. Unset limits do not apply.
+ */
+export type Budget = {
+    /**
+     * Maximum estimated cost in USD, based on the provider's published pricing
+     */
+    max_cost_usd?: number | null;
+    /**
+     * Maximum number of input and output tokens
+     */
+    max_tokens?: number | null;
+};
+
+/**
+ * Which outcomes of the upstream job run a chained job
+ */
+export type ChainCondition = 'success' | 'failure' | 'any';
+
 export type ChatRequest = {
     messages: Array<Message>;
     recipe_name?: string | null;
@@ -76,6 +95,8 @@ export type ConfigResponse = {
 
 export type Content = RawTextContent | RawImageContent | RawEmbeddedResource | RawAudioContent | RawResource;
 
+export type ContextStrategyKind = 'summarize' | 'elide_tool_outputs' | 'sliding_window' | 'hierarchical';
+
 export type Conversation = Array<Message>;
 
 export type CreateRecipeRequest = {
@@ -89,12 +110,27 @@ export type CreateRecipeResponse = {
 };
 
 export type CreateScheduleRequest = {
+    budget?: Budget | null;
     cron: string;
+    /**
+     * Cron expression for the job, can be empty when the job has triggers
+     */
+    cron?: string;
+    /**
+     * Seconds a trigger waits for further events before running the job
+     */
+    debounce_seconds?: number | null;
     id: string;
     recipe_source: string;
+    run_policy?: RunPolicy;
+    /**
+     * Events that run the job in addition to its cron expression
+     */
+    triggers?: Array<JobTrigger>;
 };
 
 export type DeclarativeProviderConfig = {
+    api?: OpenAiApi | null;
     api_key_env: string;
     base_url: string;
     description?: string | null;
@@ -274,6 +310,37 @@ export type ExtensionResponse = {
     extensions: Array<ExtensionEntry>;
 };
 
+export type FileRevert = {
+    /**
+     * Why the file could not be fully reverted, if it could not
+     */
+    error?: string | null;
+    path: string;
+    /**
+     * Number of edits that were undone
+     */
+    reverted: number;
+};
+
+export type ForkSessionRequest = {
+    /**
+     * Number of messages to keep; the new session ends right before this message
+     */
+    messageIndex: number;
+    /**
+     * Undo text editor changes made in the messages that are left out
+     */
+    revertFileEdits?: boolean;
+};
+
+export type ForkSessionResponse = {
+    /**
+     * Files that were reverted, empty unless requested
+     */
+    revertedFiles: Array<FileRevert>;
+    session: Session;
+};
+
 export type FrontendToolRequest = {
     id: string;
     toolCall: {
@@ -313,6 +380,64 @@ export type InspectJobResponse = {
     sessionId?: string | null;
 };
 
+/**
+ * An event that runs a scheduled job, in addition to its cron expression
+ */
+/**
+ * One run of a scheduled job
+ */
+export type JobRun = {
+    /**
+     * Which attempt of the run this was, counting from 1; later attempts are retries
+     */
+    attempt?: number;
+    /**
+     * Estimated cost in USD of the run's provider calls
+     */
+    cost_usd?: number | null;
+    /**
+     * When the run ended, unset while it is running
+     */
+    ended_at?: string | null;
+    /**
+     * Why the run failed
+     */
+    error?: string | null;
+    id: string;
+    input_tokens?: number | null;
+    job_id: string;
+    output_tokens?: number | null;
+    /**
+     * The session the run used
+     */
+    session_id?: string | null;
+    started_at: string;
+    status: RunStatus;
+    total_tokens?: number | null;
+    trigger: RunTrigger;
+};
+
+export type JobTrigger = {
+    /**
+     * The directory to watch, including its subdirectories
+     */
+    path: string;
+    /**
+     * Glob matched against paths relative to `path` (default: every file)
+     */
+    pattern?: string;
+    type: 'file_watch';
+} | {
+    type: 'webhook';
+} | {
+    /**
+     * The job to follow
+     */
+    job_id: string;
+    on?: ChainCondition;
+    type: 'job_completed';
+};
+
 export type JsonObject = {
     [key: string]: unknown;
 };
@@ -368,6 +493,10 @@ export type MessageContent = (TextContent & {
     type: 'systemNotification';
 });
 
+/**
+ * An event of a streamed agent reply. goosed sends these to the desktop app as server-sent
+ * events, and `goose run --output-format stream-json` prints one per line.
+ */
 export type MessageEvent = {
     message: Message;
     token_state: TokenState;
@@ -404,6 +533,7 @@ export type MessageMetadata = {
      * Whether the message should be included in the agent's context window
      */
     agentVisible: boolean;
+    servedBy?: ServedBy | null;
     /**
      * Whether the message should be visible to the user in the UI
      */
@@ -440,6 +570,16 @@ export type ModelInfo = {
     supports_cache_control?: boolean | null;
 };
 
+/**
+ * The OpenAI API a provider sends requests to
+ */
+export type OpenAiApi = 'chat_completions' | 'responses';
+
+/**
+ * What a cron tick does when the previous run of the job is still going
+ */
+export type OverlapPolicy = 'skip' | 'queue' | 'cancel_previous';
+
 export type ParseRecipeRequest = {
     content: string;
 };
@@ -649,6 +789,36 @@ export type RunNowResponse = {
     session_id: string;
 };
 
+/**
+ * Limits on how long a job runs, how often a failed run is retried and how overlapping
+ * runs are handled
+ */
+export type RunPolicy = {
+    /**
+     * Seconds a run may take before it is stopped and recorded as timed out
+     */
+    max_runtime_seconds?: number | null;
+    overlap?: OverlapPolicy;
+    /**
+     * How many times a failed or timed out run is retried
+     */
+    retries?: number;
+    /**
+     * Seconds before the first retry, doubling for each further retry (default: 60)
+     */
+    retry_backoff_seconds?: number | null;
+};
+
+/**
+ * How a run of a scheduled job ended
+ */
+export type RunStatus = 'running' | 'success' | 'failure' | 'killed' | 'timeout';
+
+/**
+ * What started a run
+ */
+export type RunTrigger = 'cron' | 'manual' | 'file_watch' | 'webhook' | 'job_completed';
+
 export type SaveRecipeRequest = {
     id?: string | null;
     recipe: Recipe;
@@ -667,20 +837,47 @@ export type ScanRecipeResponse = {
 };
 
 export type ScheduledJob = {
+    budget?: Budget | null;
     cron: string;
+    /**
+     * Cron expression for the job, empty for jobs that only run on triggers
+     */
+    cron?: string;
     current_session_id?: string | null;
     currently_running?: boolean;
+    /**
+     * Seconds a trigger waits for further events before running the job (default: 2)
+     */
+    debounce_seconds?: number | null;
     id: string;
     last_run?: string | null;
     paused?: boolean;
     process_start_time?: string | null;
+    run_policy?: RunPolicy;
     source: string;
+    /**
+     * Events that run the job in addition to its cron expression
+     */
+    triggers?: Array<JobTrigger>;
+};
+
+/**
+ * The provider and model that generated a message
+ */
+export type ServedBy = {
+    model: string;
+    provider: string;
 };
 
 export type Session = {
+    /**
+     * Estimated cost in USD of all provider calls in the session
+     */
+    accumulated_cost?: number | null;
     accumulated_input_tokens?: number | null;
     accumulated_output_tokens?: number | null;
     accumulated_total_tokens?: number | null;
+    budget?: Budget | null;
     conversation?: Conversation | null;
     created_at: string;
     extension_data: ExtensionData;
@@ -689,6 +886,14 @@ export type Session = {
     message_count: number;
     name: string;
     output_tokens?: number | null;
+    /**
+     * Name of the API key that created the session, when the server runs with named keys
+     */
+    owner?: string | null;
+    /**
+     * The session this one was forked from, if any
+     */
+    parent_session_id?: string | null;
     recipe?: Recipe | null;
     schedule_id?: string | null;
     session_type?: SessionType;
@@ -717,6 +922,10 @@ export type SessionDisplayInfo = {
 };
 
 export type SessionInsights = {
+    /**
+     * Estimated cost in USD across all sessions
+     */
+    totalCost: number;
     totalSessions: number;
     totalTokens: number;
 };
@@ -728,6 +937,30 @@ export type SessionListResponse = {
     sessions: Array<Session>;
 };
 
+export type SessionSearchMatch = {
+    role: string;
+    /**
+     * Excerpt of the message with matched words wrapped in the highlight markers
+     */
+    snippet: string;
+    timestamp: string;
+};
+
+export type SessionSearchResponse = {
+    /**
+     * Matching sessions, most relevant first. Matched words in snippets are wrapped in <mark> tags.
+     */
+    results: Array<SessionSearchResult>;
+};
+
+export type SessionSearchResult = {
+    /**
+     * Best matching messages in the session, most relevant first
+     */
+    matches: Array<SessionSearchMatch>;
+    session: Session;
+};
+
 export type SessionType = 'user' | 'scheduled' | 'sub_agent' | 'hidden';
 
 export type SessionsQuery = {
@@ -740,6 +973,8 @@ export type SetProviderRequest = {
 };
 
 export type Settings = {
+    budget?: Budget | null;
+    context_strategy?: ContextStrategyKind | null;
     goose_model?: string | null;
     goose_provider?: string | null;
     temperature?: number | null;
@@ -776,6 +1011,54 @@ export type SuccessCheck = {
      */
     command: string;
     type: 'Shell';
+} | {
+    /**
+     * Text the file must contain
+     */
+    contains?: string | null;
+    /**
+     * Path of the file, relative to the working directory
+     */
+    path: string;
+    type: 'File';
+} | {
+    /**
+     * The regular expression that must match
+     */
+    pattern: string;
+    type: 'Regex';
+} | {
+    /**
+     * The JSON schema the output must satisfy
+     */
+    schema: {
+        [key: string]: unknown;
+    };
+    type: 'JsonSchema';
+} | {
+    /**
+     * The expected status code (default: 200)
+     */
+    status?: number;
+    type: 'Http';
+    /**
+     * The URL to request with GET
+     */
+    url: string;
+} | {
+    /**
+     * Model to use for the verdict (default: the session's model)
+     */
+    model?: string | null;
+    /**
+     * Provider to use for the verdict (default: the session's provider)
+     */
+    provider?: string | null;
+    /**
+     * What the model should check the output against
+     */
+    rubric: string;
+    type: 'LlmJudge';
 };
 
 export type SystemNotificationContent = {
@@ -1971,7 +2254,7 @@ export type CreateScheduleData = {
 
 export type CreateScheduleErrors = {
     /**
-     * Invalid cron expression or recipe file
+     * Invalid cron expression, trigger or recipe file
      */
     400: unknown;
     /**
@@ -2200,6 +2483,43 @@ export type RunNowHandlerResponses = {
 
 export type RunNowHandlerResponse = RunNowHandlerResponses[keyof RunNowHandlerResponses];
 
+export type RunsHandlerData = {
+    body?: never;
+    path: {
+        /**
+         * ID of the schedule
+         */
+        id: string;
+    };
+    query?: {
+        /**
+         * Maximum number of runs to return, newest first
+         */
+        limit?: number | null;
+    };
+    url: '/schedule/{id}/runs';
+};
+
+export type RunsHandlerErrors = {
+    /**
+     * Scheduled job not found
+     */
+    404: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type RunsHandlerResponses = {
+    /**
+     * The job's run history, newest first
+     */
+    200: Array<JobRun>;
+};
+
+export type RunsHandlerResponse = RunsHandlerResponses[keyof RunsHandlerResponses];
+
 export type SessionsHandlerData = {
     body?: never;
     path: {
@@ -2262,6 +2582,49 @@ export type UnpauseScheduleResponses = {
 
 export type UnpauseScheduleResponse = UnpauseScheduleResponses[keyof UnpauseScheduleResponses];
 
+export type WebhookHandlerData = {
+    /**
+     * JSON object whose fields are passed to the recipe as parameters
+     */
+    body?: {
+        [key: string]: unknown;
+    } | null;
+    path: {
+        /**
+         * ID of the schedule to trigger
+         */
+        id: string;
+    };
+    query?: never;
+    url: '/schedule/{id}/webhook';
+};
+
+export type WebhookHandlerErrors = {
+    /**
+     * The job has no webhook trigger or the payload is not a JSON object
+     */
+    400: unknown;
+    /**
+     * Scheduled job not found
+     */
+    404: unknown;
+    /**
+     * The schedule is paused
+     */
+    409: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type WebhookHandlerResponses = {
+    /**
+     * Run queued; it starts once the job's debounce period has passed
+     */
+    202: unknown;
+};
+
 export type ListSessionsData = {
     body?: never;
     path?: never;
@@ -2347,6 +2710,54 @@ export type GetSessionInsightsResponses = {
 
 export type GetSessionInsightsResponse = GetSessionInsightsResponses[keyof GetSessionInsightsResponses];
 
+export type SearchSessionsData = {
+    body?: never;
+    path?: never;
+    query: {
+        /**
+         * Words that must all appear in a matching message
+         */
+        query: string;
+        /**
+         * Maximum number of sessions to return (default 20)
+         */
+        limit?: number | null;
+        /**
+         * Only match messages at or after this time (RFC 3339)
+         */
+        after?: string | null;
+        /**
+         * Only match messages at or before this time (RFC 3339)
+         */
+        before?: string | null;
+        /**
+         * Only sessions whose working directory contains this text
+         */
+        workingDir?: string | null;
+    };
+    url: '/sessions/search';
+};
+
+export type SearchSessionsErrors = {
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type SearchSessionsResponses = {
+    /**
+     * Sessions matching the search
+     */
+    200: SessionSearchResponse;
+};
+
+export type SearchSessionsResponse = SearchSessionsResponses[keyof SearchSessionsResponses];
+
 export type DeleteSessionData = {
     body?: never;
     path: {
@@ -2453,6 +2864,48 @@ export type ExportSessionResponses = {
 
 export type ExportSessionResponse = ExportSessionResponses[keyof ExportSessionResponses];
 
+export type ForkSessionData = {
+    body: ForkSessionRequest;
+    path: {
+        /**
+         * Unique identifier for the session to fork
+         */
+        session_id: string;
+    };
+    query?: never;
+    url: '/sessions/{session_id}/fork';
+};
+
+export type ForkSessionErrors = {
+    /**
+     * Bad request - Message index out of range
+     */
+    400: ErrorResponse;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Session not found
+     */
+    404: ErrorResponse;
+    /**
+     * Internal server error
+     */
+    500: ErrorResponse;
+};
+
+export type ForkSessionError = ForkSessionErrors[keyof ForkSessionErrors];
+
+export type ForkSessionResponses = {
+    /**
+     * Session forked successfully
+     */
+    200: ForkSessionResponse;
+};
+
+export type ForkSessionResponse2 = ForkSessionResponses[keyof ForkSessionResponses];
+
 export type UpdateSessionNameData = {
     body: UpdateSessionNameRequest;
     path: {
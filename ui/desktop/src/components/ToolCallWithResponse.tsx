@@ -385,6 +385,9 @@ function ToolCallView({
       }
 
       case 'web_scrape':
+        if (args.url && args.crawl) {
+          return `crawling ${getStringValue(args.url)}`;
+        }
         if (args.url) {
           return `scraping ${getStringValue(args.url)}`;
         }
@@ -402,6 +405,18 @@ function ToolCallView({
         }
         break;
 
+      case 'search_memories':
+        if (args.query) {
+          return `searching memories for ${getStringValue(args.query)}`;
+        }
+        return `listing recent memories`;
+
+      case 'update_memory':
+        if (args.id) {
+          return `updating memory ${getStringValue(args.id)}`;
+        }
+        break;
+
       case 'screen_capture':
         if (args.window_title) {
           return `capturing window "${getStringValue(args.window_title)}"`;
@@ -39,7 +39,10 @@ export const getToolIcon = (toolName: string): React.ComponentType<ToolIconProps
     case 'remember_memory':
       return Save;
     case 'retrieve_memories':
+    case 'search_memories':
       return Brain;
+    case 'update_memory':
+      return Save;
 
     // Computer Controller Extension Tools
     case 'automation_script':